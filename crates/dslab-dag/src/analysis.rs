@@ -0,0 +1,194 @@
+//! Analysis of DAG execution using the trace log.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use simcore::EPSILON;
+
+use crate::trace_log::{Event, TraceLog};
+
+/// Execution of a task on a resource recovered from the trace log.
+#[derive(Clone, Debug)]
+pub struct TaskExecution {
+    pub task_id: usize,
+    /// Name of the resource.
+    pub location: String,
+    pub cores: u32,
+    pub start: f64,
+    pub finish: f64,
+    /// Whether the execution is completed, otherwise the task failed.
+    pub completed: bool,
+}
+
+/// Returns the task executions in the order of their completion or failure.
+pub fn task_executions(trace_log: &TraceLog) -> Vec<TaskExecution> {
+    let mut locations: HashMap<usize, (String, u32)> = HashMap::new();
+    let mut starts: HashMap<usize, f64> = HashMap::new();
+    let mut executions = Vec::new();
+    for event in trace_log.events.iter() {
+        match event {
+            Event::TaskScheduled {
+                task_id,
+                location,
+                cores,
+                ..
+            } => {
+                locations.insert(*task_id, (location.clone(), *cores));
+            }
+            Event::TaskStarted { time, task_id, .. } => {
+                starts.insert(*task_id, *time);
+            }
+            Event::TaskCompleted { time, task_id, .. } | Event::TaskFailed { time, task_id, .. } => {
+                if let (Some(start), Some((location, cores))) = (starts.remove(task_id), locations.get(task_id)) {
+                    executions.push(TaskExecution {
+                        task_id: *task_id,
+                        location: location.clone(),
+                        cores: *cores,
+                        start,
+                        finish: *time,
+                        completed: matches!(event, Event::TaskCompleted { .. }),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    executions
+}
+
+/// Returns the critical path of DAG execution, i.e. the chain of tasks whose delay would directly extend
+/// the makespan, in the order of execution.
+///
+/// The path is built backwards from the task completed last. For each task on the path, the next (preceding) task
+/// is the one which was the last to enable the task start: either the producer of the task input which was the last
+/// to become available on the task resource (accounting for the data transfer time), or the task which was the last
+/// to complete on the same resource before the task start (accounting for resource contention). The data dependencies
+/// are preferred in case of a tie. If some task was executed several times, its last execution is considered.
+pub fn critical_path(trace_log: &TraceLog) -> Vec<usize> {
+    let executions = task_executions(trace_log)
+        .into_iter()
+        .filter(|execution| execution.completed)
+        .map(|execution| (execution.task_id, execution))
+        .collect::<BTreeMap<_, _>>();
+    let producers = trace_log
+        .graph
+        .tasks
+        .iter()
+        .enumerate()
+        .flat_map(|(task_id, task)| task.outputs.iter().map(move |&data_item_id| (data_item_id, task_id)))
+        .collect::<HashMap<_, _>>();
+    // arrival times of data items at locations
+    let mut transfers: HashMap<usize, usize> = HashMap::new();
+    let mut arrivals: HashMap<(usize, &str), Vec<f64>> = HashMap::new();
+    for event in trace_log.events.iter() {
+        match event {
+            Event::StartUploading {
+                data_id, data_item_id, ..
+            } => {
+                transfers.insert(*data_id, *data_item_id);
+            }
+            Event::FinishUploading { time, to, data_id, .. } => {
+                if let Some(&data_item_id) = transfers.get(data_id) {
+                    arrivals.entry((data_item_id, to.as_str())).or_default().push(*time);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut current = match executions
+        .values()
+        .max_by(|a, b| a.finish.total_cmp(&b.finish).then(b.task_id.cmp(&a.task_id)))
+    {
+        Some(execution) => execution,
+        None => return Vec::new(),
+    };
+    let mut path = vec![current.task_id];
+    let mut visited = HashSet::from([current.task_id]);
+    loop {
+        let start = current.start;
+        // (time when the task start was enabled, is data dependency, task)
+        let mut candidates: Vec<(f64, bool, &TaskExecution)> = Vec::new();
+        for &data_item_id in trace_log.graph.tasks[current.task_id].inputs.iter() {
+            let Some(producer) = producers.get(&data_item_id).and_then(|p| executions.get(p)) else {
+                continue;
+            };
+            let ready_time = if producer.location == current.location {
+                producer.finish
+            } else {
+                arrivals
+                    .get(&(data_item_id, current.location.as_str()))
+                    .and_then(|times| {
+                        times
+                            .iter()
+                            .filter(|&&t| t <= start + EPSILON)
+                            .cloned()
+                            .reduce(f64::max)
+                    })
+                    .unwrap_or(producer.finish)
+            };
+            candidates.push((ready_time, true, producer));
+        }
+        for execution in executions.values() {
+            if execution.location == current.location && execution.task_id != current.task_id {
+                candidates.push((execution.finish, false, execution));
+            }
+        }
+        let mut best: Option<(f64, bool, &TaskExecution)> = None;
+        for (time, is_data, execution) in candidates {
+            if visited.contains(&execution.task_id) || time > start + EPSILON {
+                continue;
+            }
+            let better = match best {
+                None => true,
+                Some((best_time, best_is_data, _)) => {
+                    time > best_time + EPSILON || ((time - best_time).abs() <= EPSILON && is_data && !best_is_data)
+                }
+            };
+            if better {
+                best = Some((time, is_data, execution));
+            }
+        }
+        match best {
+            Some((_, _, execution)) => {
+                current = execution;
+                path.push(current.task_id);
+                visited.insert(current.task_id);
+            }
+            None => break,
+        }
+    }
+    path.reverse();
+    path
+}
+
+/// Returns the intervals when no tasks were running on each resource, until the end of DAG execution.
+///
+/// The resources are indexed as in the trace log, each interval is given as (start, finish) pair.
+pub fn idle_intervals(trace_log: &TraceLog) -> Vec<Vec<(f64, f64)>> {
+    let end_time = trace_log.events.last().map(|event| event.time()).unwrap_or(0.);
+    let executions = task_executions(trace_log);
+    trace_log
+        .resources
+        .iter()
+        .map(|resource| {
+            let mut busy = executions
+                .iter()
+                .filter(|execution| execution.location == resource.name)
+                .map(|execution| (execution.start, execution.finish))
+                .collect::<Vec<_>>();
+            busy.sort_by(|a, b| a.0.total_cmp(&b.0));
+            let mut intervals = Vec::new();
+            let mut idle_start = 0.;
+            for (start, finish) in busy {
+                if start > idle_start {
+                    intervals.push((idle_start, start));
+                }
+                idle_start = f64::max(idle_start, finish);
+            }
+            if end_time > idle_start {
+                intervals.push((idle_start, end_time));
+            }
+            intervals
+        })
+        .collect()
+}
@@ -8,8 +8,40 @@ use dslab_compute::multicore::CoresDependency;
 use crate::dag_stats::DagStats;
 use crate::data_item::*;
 use crate::parsers::config::ParserConfig;
+use crate::resource::ResourceConfig;
 use crate::task::*;
 
+/// Bounded loop which re-executes a subgraph of the DAG.
+///
+/// When all body tasks are completed, the body is executed again until it has run `max_iterations` times
+/// or the convergence predicate returns true. Each iteration starts after the previous one is completed,
+/// the body inputs produced outside the loop are reused by all iterations. Tasks outside the body which consume
+/// the body outputs wait for the last iteration, so the DAG makespan includes the execution of all iterations.
+#[derive(Clone, Debug)]
+pub struct Loop {
+    /// Tasks of the loop body.
+    pub body: BTreeSet<usize>,
+    /// Maximum number of body executions.
+    pub max_iterations: u32,
+    /// Optional convergence predicate, which is called with the number of completed iterations
+    /// and returns true if the loop must stop.
+    pub converged: Option<fn(u32) -> bool>,
+    completed_iterations: u32,
+    finished: bool,
+}
+
+impl Loop {
+    /// Returns the number of completed iterations.
+    pub fn completed_iterations(&self) -> u32 {
+        self.completed_iterations
+    }
+
+    /// Checks whether the last iteration is completed.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+}
+
 /// Represents a computation consisting of multiple tasks with data dependencies
 /// modeled as a directed acyclic graph (DAG).
 ///
@@ -25,6 +57,8 @@ pub struct DAG {
     completed_task_count: usize,
     inputs: BTreeSet<usize>,
     outputs: BTreeSet<usize>,
+    deadline: Option<f64>,
+    loops: Vec<Loop>,
 }
 
 impl DAG {
@@ -51,6 +85,8 @@ impl DAG {
     }
 
     /// Adds new [task](crate::task::Task) with provided parameters and returns its id.
+    ///
+    /// The task must require at least one core.
     pub fn add_task(
         &mut self,
         name: &str,
@@ -60,6 +96,7 @@ impl DAG {
         max_cores: u32,
         cores_dependency: CoresDependency,
     ) -> usize {
+        assert!(min_cores >= 1, "Task {} must require at least one core", name);
         let task = Task::new(name, flops, memory, min_cores, max_cores, cores_dependency);
         let task_id = self.tasks.len();
         self.tasks.push(task);
@@ -67,6 +104,61 @@ impl DAG {
         task_id
     }
 
+    /// Appends tasks, data items and loops of another DAG to this DAG, returns the id of the first appended task.
+    ///
+    /// The ids of appended tasks and data items are shifted by the number of tasks and data items in this DAG.
+    /// The deadline of the other DAG is carried over to its exit tasks (the tasks whose outputs are not consumed),
+    /// whose completion completes the other DAG, unless they have earlier deadlines.
+    /// The other DAG must not be executed yet.
+    pub fn append(&mut self, other: &DAG) -> usize {
+        let task_offset = self.tasks.len();
+        let data_offset = self.data_items.len();
+        for task in other.tasks.iter() {
+            let task_id = self.add_task(
+                &task.name,
+                task.flops,
+                task.memory,
+                task.min_cores,
+                task.max_cores,
+                task.cores_dependency,
+            );
+            self.tasks[task_id].resource_restriction = task.resource_restriction.clone();
+            self.tasks[task_id].deadline = task.deadline;
+            self.tasks[task_id].anti_affinity = task.anti_affinity.iter().map(|&t| t + task_offset).collect();
+        }
+        for data_item in other.data_items.iter() {
+            let data_id = match data_item.producer {
+                Some(producer) => self.add_task_output(producer + task_offset, &data_item.name, data_item.size),
+                None => self.add_data_item(&data_item.name, data_item.size),
+            };
+            self.data_items[data_id].size_function = data_item.size_function;
+            self.data_items[data_id].replicas = data_item.replicas.clone();
+        }
+        for (data_id, data_item) in other.data_items.iter().enumerate() {
+            for &consumer in data_item.consumers.iter() {
+                self.add_data_dependency(data_id + data_offset, consumer + task_offset);
+            }
+        }
+        for l in other.loops.iter() {
+            let body = l.body.iter().map(|&task_id| task_id + task_offset).collect::<Vec<_>>();
+            let loop_id = self.add_loop(&body, l.max_iterations);
+            self.loops[loop_id].converged = l.converged;
+        }
+        if let Some(deadline) = other.deadline {
+            for (task_id, task) in other.tasks.iter().enumerate() {
+                if task
+                    .outputs
+                    .iter()
+                    .all(|&data_id| other.data_items[data_id].consumers.is_empty())
+                {
+                    let task = &mut self.tasks[task_id + task_offset];
+                    task.deadline = Some(task.deadline.map_or(deadline, |d| d.min(deadline)));
+                }
+            }
+        }
+        task_offset
+    }
+
     /// Returns task by id.
     pub fn get_task(&self, task_id: usize) -> &Task {
         self.tasks.get(task_id).unwrap()
@@ -125,6 +217,52 @@ impl DAG {
         data_item_id
     }
 
+    /// Places a replica of DAG input on the resource with given index.
+    ///
+    /// Replicated inputs are not transferred from the runner, instead each consumer receives the data item
+    /// from the replica with the minimal transfer time.
+    pub fn add_replica(&mut self, data_item_id: usize, resource_id: usize) {
+        let data_item = &mut self.data_items[data_item_id];
+        assert!(
+            data_item.producer.is_none(),
+            "Data item {} is not a DAG input",
+            data_item.name
+        );
+        data_item.replicas.insert(resource_id);
+    }
+
+    /// Restricts execution of the task to the resources holding replicas of the given data item.
+    ///
+    /// The replicas must be added before calling this method.
+    pub fn pin_to_replicas(&mut self, task_id: usize, data_item_id: usize) {
+        let replicas = self.data_items[data_item_id].replicas.clone();
+        assert!(
+            !replicas.is_empty(),
+            "Data item {} has no replicas",
+            self.data_items[data_item_id].name
+        );
+        let task = &mut self.tasks[task_id];
+        let allowed = replicas
+            .into_iter()
+            .filter(|&resource_id| task.is_allowed_on(resource_id))
+            .collect();
+        task.resource_restriction = Some(ResourceRestriction::Only(allowed));
+    }
+
+    /// Makes the size of task output depend on the total size of the producer task inputs.
+    ///
+    /// The size is computed when the producer task is completed, the size passed to [`Self::add_task_output`]
+    /// is used only before that (e.g. by static schedulers for estimating transfer times).
+    pub fn set_output_size_function(&mut self, data_item_id: usize, size_function: fn(f64) -> f64) {
+        let data_item = &mut self.data_items[data_item_id];
+        assert!(
+            data_item.producer.is_some(),
+            "Data item {} is not a task output",
+            data_item.name
+        );
+        data_item.size_function = Some(size_function);
+    }
+
     /// Adds a dependency between [data item](crate::data_item::DataItem) and [task](crate::task::Task).
     pub fn add_data_dependency(&mut self, data_item_id: usize, consumer_id: usize) {
         let data_item = self.data_items.get_mut(data_item_id).unwrap();
@@ -149,7 +287,14 @@ impl DAG {
         }
         if task.state == TaskState::Done {
             self.completed_task_count += 1;
-            for &data_item in task.outputs.clone().iter() {
+            let input_size = task.inputs.iter().map(|&id| self.data_items[id].size).sum::<f64>();
+            for &data_item in task.outputs.iter() {
+                let data_item = &mut self.data_items[data_item];
+                if let Some(size_function) = data_item.size_function {
+                    data_item.size = size_function(input_size);
+                }
+            }
+            for &data_item in self.tasks[task_id].outputs.clone().iter() {
                 self.update_data_item_state(data_item, DataItemState::Ready);
             }
         }
@@ -160,22 +305,34 @@ impl DAG {
         let data_item = self.data_items.get_mut(data_id).unwrap();
         data_item.state = state;
         if data_item.state == DataItemState::Ready {
-            for t in data_item.consumers.iter() {
-                let consumer = self.tasks.get_mut(*t).unwrap();
-                consumer.ready_inputs += 1;
-                if consumer.ready_inputs == consumer.inputs.len() {
-                    if consumer.state == TaskState::Pending {
-                        consumer.state = TaskState::Ready;
-                        self.ready_tasks.insert(*t);
-                    } else if consumer.state == TaskState::Scheduled {
-                        consumer.state = TaskState::Runnable;
-                    } else {
-                        panic!(
-                            "Error: task {} reached needed number of ready inputs in state {:?}",
-                            consumer.name, consumer.state
-                        );
-                    }
+            // consumers outside of unfinished loop must wait for its last iteration
+            let running_loop = data_item
+                .producer
+                .and_then(|producer| self.tasks[producer].loop_id)
+                .filter(|&loop_id| !self.loops[loop_id].finished);
+            for t in data_item.consumers.clone() {
+                if running_loop.is_some_and(|loop_id| !self.loops[loop_id].body.contains(&t)) {
+                    continue;
                 }
+                self.on_task_input_ready(t);
+            }
+        }
+    }
+
+    fn on_task_input_ready(&mut self, task_id: usize) {
+        let consumer = self.tasks.get_mut(task_id).unwrap();
+        consumer.ready_inputs += 1;
+        if consumer.ready_inputs == consumer.inputs.len() {
+            if consumer.state == TaskState::Pending {
+                consumer.state = TaskState::Ready;
+                self.ready_tasks.insert(task_id);
+            } else if consumer.state == TaskState::Scheduled {
+                consumer.state = TaskState::Runnable;
+            } else {
+                panic!(
+                    "Error: task {} reached needed number of ready inputs in state {:?}",
+                    consumer.name, consumer.state
+                );
             }
         }
     }
@@ -193,6 +350,325 @@ impl DAG {
         self.tasks[task_id].resource_restriction = Some(restriction);
     }
 
+    /// Declares that the given tasks must not run on the same resource, i.e. each pair of them is anti-affine.
+    ///
+    /// The constraints are taken into account by all schedulers, which panic if some task can't be placed
+    /// without violating them, and schedule actions violating them are rejected.
+    pub fn add_anti_affinity(&mut self, tasks: &[usize]) {
+        for (i, &task1) in tasks.iter().enumerate() {
+            for &task2 in tasks[i + 1..].iter() {
+                assert_ne!(task1, task2, "Task {} can't be anti-affine with itself", task1);
+                self.tasks[task1].anti_affinity.insert(task2);
+                self.tasks[task2].anti_affinity.insert(task1);
+            }
+        }
+    }
+
+    /// Checks whether the task can be placed on the resource without violating its anti-affinity constraints.
+    ///
+    /// Takes into account the tasks which are already scheduled in the simulation, and the tasks for which
+    /// `is_placed_on` returns true, i.e. tasks placed on this resource by the scheduler during the current call.
+    pub fn anti_affinity_allows(
+        &self,
+        task_id: usize,
+        resource_id: usize,
+        is_placed_on: impl Fn(usize) -> bool,
+    ) -> bool {
+        self.tasks[task_id]
+            .anti_affinity
+            .iter()
+            .all(|&other| self.tasks[other].resource != Some(resource_id) && !is_placed_on(other))
+    }
+
+    /// Sets soft deadline for the whole DAG completion.
+    ///
+    /// Deadlines do not affect the execution, the tardiness is reported in [run stats](crate::run_stats::RunStats).
+    pub fn set_deadline(&mut self, deadline: f64) {
+        self.deadline = Some(deadline);
+    }
+
+    /// Returns DAG deadline.
+    pub fn deadline(&self) -> Option<f64> {
+        self.deadline
+    }
+
+    /// Sets soft deadline for the task completion.
+    pub fn set_task_deadline(&mut self, task_id: usize, deadline: f64) {
+        self.tasks[task_id].deadline = Some(deadline);
+    }
+
+    /// Adds a bounded [loop](Loop) over the given tasks and returns its id.
+    ///
+    /// The body tasks are executed up to `max_iterations` times, see [`Self::set_loop_convergence`] for
+    /// stopping the loop earlier.
+    pub fn add_loop(&mut self, body: &[usize], max_iterations: u32) -> usize {
+        assert!(max_iterations > 0, "Loop must have at least one iteration");
+        let loop_id = self.loops.len();
+        for &task_id in body.iter() {
+            assert!(
+                self.tasks[task_id].loop_id.is_none(),
+                "Task {} already belongs to a loop",
+                self.tasks[task_id].name
+            );
+            self.tasks[task_id].loop_id = Some(loop_id);
+        }
+        self.loops.push(Loop {
+            body: body.iter().cloned().collect(),
+            max_iterations,
+            converged: None,
+            completed_iterations: 0,
+            finished: false,
+        });
+        loop_id
+    }
+
+    /// Sets loop convergence predicate, which is called with the number of completed iterations
+    /// and returns true if the loop must stop before reaching the maximum number of iterations.
+    pub fn set_loop_convergence(&mut self, loop_id: usize, converged: fn(u32) -> bool) {
+        self.loops[loop_id].converged = Some(converged);
+    }
+
+    /// Returns loop by id.
+    pub fn get_loop(&self, loop_id: usize) -> &Loop {
+        &self.loops[loop_id]
+    }
+
+    /// Returns all loops.
+    pub fn get_loops(&self) -> &Vec<Loop> {
+        &self.loops
+    }
+
+    /// Returns id of the loop containing the task, if any.
+    pub fn task_loop(&self, task_id: usize) -> Option<usize> {
+        self.tasks[task_id].loop_id
+    }
+
+    /// Returns the earliest finish time of the task when the DAG is executed on the given resources.
+    ///
+    /// The task and its ancestors are list-scheduled in topological order, each task is assigned to the resource
+    /// where it finishes the earliest using `min_cores` cores. Data transfers and memory usage are ignored,
+    /// so the value is an optimistic estimate useful for checking the feasibility of deadlines.
+    pub fn earliest_finish(&self, task_id: usize, resources: &[ResourceConfig]) -> f64 {
+        let mut order = Vec::new();
+        let mut visited = vec![false; self.tasks.len()];
+        self.collect_ancestors(task_id, &mut visited, &mut order);
+
+        let mut finish_times = vec![0.; self.tasks.len()];
+        let mut core_free_times: Vec<Vec<f64>> = resources.iter().map(|r| vec![0.; r.cores as usize]).collect();
+        for &t in order.iter() {
+            let task = &self.tasks[t];
+            let ready_time = task
+                .inputs
+                .iter()
+                .filter_map(|&data_id| self.data_items[data_id].producer)
+                .map(|producer| finish_times[producer])
+                .fold(0., f64::max);
+            let (resource, finish) = (0..resources.len())
+                .filter(|&r| self.can_run_on(t, r, &resources[r]))
+                .map(|r| {
+                    let mut free = core_free_times[r].clone();
+                    free.sort_by(|a, b| a.total_cmp(b));
+                    let start = f64::max(ready_time, free[task.min_cores as usize - 1]);
+                    (r, start + self.exec_time(t, &resources[r]))
+                })
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .unwrap_or_else(|| panic!("No suitable resource for task {}", task.name));
+            let free = &mut core_free_times[resource];
+            let mut cores = (0..free.len()).collect::<Vec<_>>();
+            cores.sort_by(|&a, &b| free[a].total_cmp(&free[b]));
+            for &core in cores.iter().take(task.min_cores as usize) {
+                free[core] = finish;
+            }
+            finish_times[t] = finish;
+        }
+        finish_times[task_id]
+    }
+
+    /// Returns the latest finish time of the task which allows to complete the DAG by the given deadline.
+    ///
+    /// Each descendant of the task is assumed to start right after its inputs are produced and to run
+    /// on the resource where it executes the fastest using `min_cores` cores, ignoring data transfers and
+    /// contention for resources. Task deadlines are taken into account as well.
+    /// Finishing the task later than the returned time makes meeting the deadline impossible.
+    pub fn latest_finish(&self, task_id: usize, resources: &[ResourceConfig], deadline: f64) -> f64 {
+        let mut latest = vec![None; self.tasks.len()];
+        self.calc_latest_finish(task_id, resources, deadline, &mut latest)
+    }
+
+    fn collect_ancestors(&self, task_id: usize, visited: &mut Vec<bool>, order: &mut Vec<usize>) {
+        visited[task_id] = true;
+        for &data_id in self.tasks[task_id].inputs.iter() {
+            if let Some(producer) = self.data_items[data_id].producer {
+                if !visited[producer] {
+                    self.collect_ancestors(producer, visited, order);
+                }
+            }
+        }
+        order.push(task_id);
+    }
+
+    fn calc_latest_finish(
+        &self,
+        task_id: usize,
+        resources: &[ResourceConfig],
+        deadline: f64,
+        latest: &mut Vec<Option<f64>>,
+    ) -> f64 {
+        if let Some(time) = latest[task_id] {
+            return time;
+        }
+        let mut time = self.tasks[task_id].deadline.map_or(deadline, |d| d.min(deadline));
+        for &data_id in self.tasks[task_id].outputs.iter() {
+            for &consumer in self.data_items[data_id].consumers.iter() {
+                let exec_time = (0..resources.len())
+                    .filter(|&r| self.can_run_on(consumer, r, &resources[r]))
+                    .map(|r| self.exec_time(consumer, &resources[r]))
+                    .min_by(|a, b| a.total_cmp(b))
+                    .unwrap_or_else(|| panic!("No suitable resource for task {}", self.tasks[consumer].name));
+                time = time.min(self.calc_latest_finish(consumer, resources, deadline, latest) - exec_time);
+            }
+        }
+        latest[task_id] = Some(time);
+        time
+    }
+
+    fn can_run_on(&self, task_id: usize, resource_id: usize, resource: &ResourceConfig) -> bool {
+        let task = &self.tasks[task_id];
+        task.is_allowed_on(resource_id) && resource.cores >= task.min_cores && resource.memory >= task.memory
+    }
+
+    fn exec_time(&self, task_id: usize, resource: &ResourceConfig) -> f64 {
+        let task = &self.tasks[task_id];
+        task.flops / resource.speed / task.cores_dependency.speedup(task.min_cores)
+    }
+
+    /// Checks whether all body tasks of the loop are completed in the current iteration.
+    pub(crate) fn is_loop_iteration_completed(&self, loop_id: usize) -> bool {
+        self.loops[loop_id]
+            .body
+            .iter()
+            .all(|&task_id| self.tasks[task_id].state == TaskState::Done)
+    }
+
+    /// Completes the current loop iteration.
+    ///
+    /// Returns true if the next iteration is started, in which case the body tasks are reset to the
+    /// Pending or Ready state. Otherwise the loop is finished and the tasks consuming its outputs are notified.
+    pub(crate) fn complete_loop_iteration(&mut self, loop_id: usize) -> bool {
+        let lp = &mut self.loops[loop_id];
+        lp.completed_iterations += 1;
+        let converged = lp.converged.is_some_and(|f| f(lp.completed_iterations));
+        let body = lp.body.clone();
+        if lp.completed_iterations >= lp.max_iterations || converged {
+            lp.finished = true;
+            for &task_id in body.iter() {
+                for data_id in self.tasks[task_id].outputs.clone() {
+                    for consumer in self.data_items[data_id].consumers.clone() {
+                        if !body.contains(&consumer) {
+                            self.on_task_input_ready(consumer);
+                        }
+                    }
+                }
+            }
+            return false;
+        }
+
+        for &task_id in body.iter() {
+            self.completed_task_count -= 1;
+            for &data_id in self.tasks[task_id].outputs.iter() {
+                self.data_items[data_id].state = DataItemState::Pending;
+            }
+        }
+        for &task_id in body.iter() {
+            let ready_inputs = self.tasks[task_id]
+                .inputs
+                .iter()
+                .filter(|&&data_id| self.data_items[data_id].state == DataItemState::Ready)
+                .count();
+            let task = &mut self.tasks[task_id];
+            task.ready_inputs = ready_inputs;
+            task.start_time = None;
+            task.finish_time = None;
+            if ready_inputs == task.inputs.len() {
+                task.state = TaskState::Ready;
+                self.ready_tasks.insert(task_id);
+            } else {
+                task.state = TaskState::Pending;
+            }
+        }
+        true
+    }
+
+    /// Returns the task which is scheduled but not started yet back to unscheduled state,
+    /// returns false if the task is not in one of these states.
+    pub(crate) fn unschedule_task(&mut self, task_id: usize) -> bool {
+        let task = &mut self.tasks[task_id];
+        match task.state {
+            TaskState::Scheduled => task.state = TaskState::Pending,
+            TaskState::Runnable => {
+                task.state = TaskState::Ready;
+                self.ready_tasks.insert(task_id);
+            }
+            _ => return false,
+        }
+        task.resource = None;
+        true
+    }
+
+    /// Returns the running or completed task to the Ready or Pending state, so that it can be executed again.
+    ///
+    /// The outputs of the completed task are reset to the Pending state, and their consumers which have not started
+    /// yet wait for the outputs to be produced again.
+    pub(crate) fn reset_task(&mut self, task_id: usize) {
+        match self.tasks[task_id].state {
+            TaskState::Running => {}
+            TaskState::Done => {
+                self.completed_task_count -= 1;
+                for data_id in self.tasks[task_id].outputs.clone() {
+                    self.invalidate_data_item(data_id);
+                }
+            }
+            state => panic!("Can't reset task {} with state {:?}", task_id, state),
+        }
+        let ready_inputs = self.tasks[task_id]
+            .inputs
+            .iter()
+            .filter(|&&data_id| self.data_items[data_id].state == DataItemState::Ready)
+            .count();
+        let task = &mut self.tasks[task_id];
+        task.ready_inputs = ready_inputs;
+        task.resource = None;
+        task.cores.clear();
+        task.start_time = None;
+        task.finish_time = None;
+        if ready_inputs == task.inputs.len() {
+            task.state = TaskState::Ready;
+            self.ready_tasks.insert(task_id);
+        } else {
+            task.state = TaskState::Pending;
+        }
+    }
+
+    fn invalidate_data_item(&mut self, data_id: usize) {
+        if self.data_items[data_id].state != DataItemState::Ready {
+            return;
+        }
+        self.data_items[data_id].state = DataItemState::Pending;
+        for consumer_id in self.data_items[data_id].consumers.clone() {
+            let consumer = &mut self.tasks[consumer_id];
+            match consumer.state {
+                TaskState::Ready => {
+                    consumer.state = TaskState::Pending;
+                    self.ready_tasks.remove(&consumer_id);
+                }
+                TaskState::Runnable => consumer.state = TaskState::Scheduled,
+                TaskState::Pending | TaskState::Scheduled => {}
+                TaskState::Running | TaskState::Done => continue,
+            }
+            consumer.ready_inputs -= 1;
+        }
+    }
+
     /// Sets data item as output of the specified task.
     ///
     /// The data item must not have producer, i.e. it must be among the DAG inputs.
@@ -211,10 +687,15 @@ impl DAG {
     }
 
     pub(crate) fn set_inputs(&mut self, inputs: BTreeSet<usize>) {
-        assert!(self.inputs.is_empty());
+        assert!(self.inputs.is_subset(&inputs));
         self.inputs = inputs
     }
 
+    /// Removes the replica located on the failed resource.
+    pub(crate) fn remove_replica(&mut self, data_item_id: usize, resource_id: usize) {
+        self.data_items[data_item_id].replicas.remove(&resource_id);
+    }
+
     pub(crate) fn set_outputs(&mut self, outputs: BTreeSet<usize>) {
         assert!(self.outputs.is_empty());
         self.outputs = outputs
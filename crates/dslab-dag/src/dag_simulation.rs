@@ -1,25 +1,38 @@
 //! Simulation configuration and execution.
 
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::rc::Rc;
 
 use dslab_compute::multicore::{Compute, CoresDependency};
+use dslab_network::Network;
+use rand::SeedableRng;
+use rand_pcg::Pcg64;
 use simcore::simulation::Simulation;
 
 use crate::dag::DAG;
+use crate::failure::{FailureModel, RetryPolicy};
 use crate::network::NetworkConfig;
-use crate::resource::{Resource, ResourceConfig};
-use crate::runner::{Config, DAGRunner, Start};
+use crate::power::PowerModel;
+use crate::resource::{Provisioning, Resource, ResourceConfig};
+use crate::robustness::{CostPerturbation, RobustnessStats};
+use crate::runner::{Config, DAGRunner, DagSharingPolicy, Start};
 use crate::scheduler::Scheduler;
+use crate::schedulers::replay::ReplaySchedule;
 use crate::task::ResourceRestriction;
 
 /// Provides a convenient API for configuring and running simulations of DAG execution.
 pub struct DagSimulation {
     pub sim: Simulation,
+    seed: u64,
     resource_configs: Vec<ResourceConfig>,
     network_config: NetworkConfig,
     scheduler: Rc<RefCell<dyn Scheduler>>,
     config: Config,
+    failure_model: Option<(FailureModel, RetryPolicy)>,
+    power_models: HashMap<String, Rc<dyn PowerModel>>,
+    runner: Option<Rc<RefCell<DAGRunner>>>,
+    network: Option<Rc<RefCell<Network>>>,
 }
 
 impl DagSimulation {
@@ -33,23 +46,129 @@ impl DagSimulation {
     ) -> Self {
         DagSimulation {
             sim: Simulation::new(seed),
+            seed,
             resource_configs: resources,
             network_config,
             scheduler,
             config,
+            failure_model: None,
+            power_models: HashMap::new(),
+            runner: None,
+            network: None,
         }
     }
 
     /// Adds a resource with provided parameters.
     pub fn add_resource(&mut self, name: &str, speed: f64, cores: u32, memory: u64) {
-        self.resource_configs.push(ResourceConfig {
+        self.add_resource_with_price(name, speed, cores, memory, 0.);
+    }
+
+    /// Adds a resource with provided parameters and price of using one core per unit of time.
+    ///
+    /// If the simulation is already initialized, the resource joins the running DAG execution.
+    pub fn add_resource_with_price(&mut self, name: &str, speed: f64, cores: u32, memory: u64, price: f64) {
+        self.add_resource_config(ResourceConfig {
             name: name.to_string(),
             speed,
             cores,
             memory,
+            price,
+            cost: 0.,
+            provisioning: None,
         });
     }
 
+    /// Adds an on-demand resource, which has to be provisioned before executing tasks.
+    ///
+    /// See [Provisioning].
+    pub fn add_on_demand_resource(
+        &mut self,
+        name: &str,
+        speed: f64,
+        cores: u32,
+        memory: u64,
+        price: f64,
+        provisioning: Provisioning,
+    ) {
+        self.add_resource_config(ResourceConfig {
+            name: name.to_string(),
+            speed,
+            cores,
+            memory,
+            price,
+            cost: 0.,
+            provisioning: Some(provisioning),
+        });
+    }
+
+    fn add_resource_config(&mut self, config: ResourceConfig) {
+        if let Some(runner) = self.runner.clone() {
+            let resource = self.make_resource(&config);
+            let mut runner = runner.borrow_mut();
+            self.network_config
+                .add_resource(self.network.clone().unwrap(), &resource, runner.resources());
+            runner.add_resource(resource);
+        }
+        self.resource_configs.push(config);
+    }
+
+    /// Enables injection of task and resource failures, the failed tasks are executed again according to the policy.
+    ///
+    /// Must be called before [init](DagSimulation::init). See [DAGRunner::set_failure_model()].
+    pub fn set_failure_model(&mut self, model: FailureModel, policy: RetryPolicy) {
+        assert!(self.runner.is_none(), "Simulation is already initialized");
+        self.failure_model = Some((model, policy));
+    }
+
+    /// Sets the power consumption model of the resource with the given name,
+    /// the consumed energy is reported in [RunStats](crate::run_stats::RunStats).
+    ///
+    /// The model can change the resource speed (e.g. to model DVFS). Must be called before the resource is created,
+    /// i.e. before [init](DagSimulation::init) or, for resources added later, before adding the resource.
+    pub fn set_power_model(&mut self, resource: &str, model: impl PowerModel + 'static) {
+        self.power_models.insert(resource.to_string(), Rc::new(model));
+    }
+
+    /// Runs the simulation until the specified time and re-plans the tasks which have not started yet
+    /// (e.g. after a resource has joined). Completed and running tasks are not affected.
+    ///
+    /// See [DAGRunner::reschedule()].
+    pub fn reschedule_from(&mut self, time: f64) {
+        let runner = self.runner.clone().expect("Simulation is not initialized");
+        assert!(time >= self.sim.time(), "Can't reschedule in the past");
+        self.sim.step_for_duration(time - self.sim.time());
+        runner.borrow_mut().reschedule();
+    }
+
+    fn make_resource(&mut self, config: &ResourceConfig) -> Resource {
+        let power_model = self.power_models.get(&config.name).cloned();
+        let speed = power_model
+            .as_ref()
+            .map(|model| model.speed(config.speed))
+            .unwrap_or(config.speed);
+        let compute = Rc::new(RefCell::new(Compute::new(
+            speed,
+            config.cores,
+            config.memory,
+            self.sim.create_context(&config.name),
+        )));
+        let id = self.sim.add_handler(&config.name, compute.clone());
+        Resource {
+            id,
+            name: config.name.clone(),
+            compute,
+            speed,
+            cores: config.cores,
+            cores_available: config.cores,
+            memory: config.memory,
+            memory_available: config.memory,
+            price: config.price,
+            cost: config.cost,
+            provisioning: config.provisioning,
+            power_model,
+        }
+    }
+
     /// Initializes DAG simulation.
     pub fn init(&mut self, mut dag: DAG) -> Rc<RefCell<DAGRunner>> {
         let net_ctx = self.sim.create_context("net");
@@ -60,26 +179,9 @@ impl DagSimulation {
         }
         let resources = self
             .resource_configs
+            .clone()
             .iter()
-            .map(|r| {
-                let compute = Rc::new(RefCell::new(Compute::new(
-                    r.speed,
-                    r.cores,
-                    r.memory,
-                    self.sim.create_context(&r.name),
-                )));
-                let id = self.sim.add_handler(&r.name, compute.clone());
-                Resource {
-                    id,
-                    name: r.name.clone(),
-                    compute,
-                    speed: r.speed,
-                    cores: r.cores,
-                    cores_available: r.cores,
-                    memory: r.memory,
-                    memory_available: r.memory,
-                }
-            })
+            .map(|r| self.make_resource(r))
             .collect::<Vec<_>>();
 
         self.add_input_output_tasks(&mut dag);
@@ -92,13 +194,47 @@ impl DagSimulation {
             self.config.clone(),
             self.sim.create_context("runner"),
         )));
+        if let Some((model, policy)) = self.failure_model.clone() {
+            runner.borrow_mut().set_failure_model(model, policy);
+        }
         let runner_id = self.sim.add_handler("runner", runner.clone());
-        self.network_config.init_network(network, runner_id, &resources);
+        self.network_config.init_network(network.clone(), runner_id, &resources);
         let client = self.sim.create_context("client");
         client.emit_now(Start {}, runner_id);
+        self.runner = Some(runner.clone());
+        self.network = Some(network);
         runner
     }
 
+    /// Initializes simulation of several DAGs executed together on the same resources.
+    ///
+    /// The DAGs are merged into a single DAG, which is passed to the scheduler, so the tasks of different DAGs
+    /// compete for the resources. The order of competing tasks is defined by the sharing policy.
+    /// The task ids of each DAG are shifted by the total number of tasks in the previous DAGs,
+    /// the completion time of each DAG is available via [DAGRunner::dag_completion_time()].
+    pub fn init_multiple(&mut self, dags: Vec<DAG>, policy: DagSharingPolicy) -> Rc<RefCell<DAGRunner>> {
+        let mut merged = DAG::new();
+        let mut task_dags = Vec::new();
+        for (i, dag) in dags.iter().enumerate() {
+            merged.append(dag);
+            task_dags.resize(merged.get_tasks().len(), i);
+        }
+        let runner = self.init(merged);
+        runner.borrow_mut().set_dag_sharing(task_dags, dags.len(), policy);
+        runner
+    }
+
+    /// Submits a new DAG which arrives to the running simulation at the specified time.
+    ///
+    /// The arrived DAG is executed together with the previous ones on the same resources,
+    /// the scheduler can re-plan the tasks which have not started yet. See [DAGRunner::submit_dag()].
+    pub fn submit_dag_at(&mut self, mut dag: DAG, time: f64) {
+        let runner = self.runner.clone().expect("Simulation is not initialized");
+        assert!(time >= self.sim.time(), "Can't submit DAG in the past");
+        self.add_input_output_tasks(&mut dag);
+        runner.borrow_mut().submit_dag(dag, time - self.sim.time());
+    }
+
     /// Performs the specified number of steps through the simulation.
     ///
     /// See [Simulation::steps()](simcore::simulation::Simulation::steps).
@@ -134,17 +270,82 @@ impl DagSimulation {
         self.sim.time()
     }
 
+    /// Estimates robustness of a schedule to errors in task cost estimates by Monte Carlo simulation.
+    ///
+    /// In each trial the amounts of computations of DAG tasks are multiplied by factors sampled from the perturbation
+    /// distribution, and the DAG is executed according to the schedule in a separate simulation with the same
+    /// resources, network and config. The schedule task ids must correspond to the DAG extended with input and output
+    /// tasks, e.g. the schedule recorded from a run of this DAG with
+    /// [ScheduleRecorder](crate::schedulers::replay::ScheduleRecorder). The trials are deterministic for a fixed seed.
+    pub fn robustness_analysis(
+        &self,
+        dag: &DAG,
+        schedule: &ReplaySchedule,
+        perturbation: CostPerturbation,
+        trials: usize,
+    ) -> RobustnessStats {
+        assert!(trials > 0, "At least one trial is required");
+        let run = |dag: DAG| {
+            let mut sim = DagSimulation::new(
+                self.seed,
+                self.resource_configs.clone(),
+                self.network_config.clone(),
+                Rc::new(RefCell::new(schedule.clone())),
+                self.config.clone(),
+            );
+            let runner = sim.init(dag);
+            sim.step_until_no_events();
+            assert!(
+                runner.borrow().is_completed(),
+                "DAG is not completed with the given schedule"
+            );
+            sim.time()
+        };
+        let mut rng = Pcg64::seed_from_u64(self.seed);
+        let makespans = (0..trials)
+            .map(|_| {
+                let mut perturbed = dag.clone();
+                for task_id in 0..perturbed.get_tasks().len() {
+                    perturbed.get_task_mut(task_id).flops *= perturbation.sample_factor(&mut rng);
+                }
+                run(perturbed)
+            })
+            .collect();
+        RobustnessStats {
+            nominal_makespan: run(dag.clone()),
+            makespans,
+        }
+    }
+
+    /// Returns the busy fraction of cores of each resource over time, see
+    /// [DAGRunner::resource_utilization_series()].
+    ///
+    /// Resources are identified by their indices in the order they were added (including the master resource).
+    pub fn resource_utilization_series(&self) -> HashMap<usize, Vec<(f64, f64)>> {
+        self.runner
+            .as_ref()
+            .expect("Simulation is not initialized")
+            .borrow()
+            .resource_utilization_series()
+    }
+
     fn add_input_output_tasks(&mut self, dag: &mut DAG) {
         let master_resource = self.resource_configs.iter().position(|r| r.name == "master").unwrap();
 
         for task in 0..dag.get_tasks().len() {
-            dag.set_resource_restriction(task, ResourceRestriction::Except([master_resource].into()));
+            dag.get_task_mut(task).exclude_resource(master_resource);
         }
-        if !dag.get_inputs().is_empty() {
-            let inputs = dag.get_inputs().clone();
+        // replicated inputs are transferred directly from the replicas
+        let inputs = dag.get_inputs().clone();
+        let master_inputs = inputs
+            .iter()
+            .filter(|&&input| dag.get_data_item(input).replicas.is_empty())
+            .cloned()
+            .collect::<Vec<_>>();
+        if !master_inputs.is_empty() {
             let input_task = dag.add_task("input", 0., 0, 1, 1, CoresDependency::Linear);
             dag.set_resource_restriction(input_task, ResourceRestriction::Only([master_resource].into()));
-            for &input in inputs.iter() {
+            for &input in master_inputs.iter() {
                 dag.set_as_task_output(input, input_task);
             }
             dag.set_inputs(inputs);
@@ -1,5 +1,6 @@
 //! Data item.
 
+use std::collections::BTreeSet;
 use std::str::FromStr;
 
 use serde::Deserialize;
@@ -7,6 +8,8 @@ use serde::Deserialize;
 use dslab_network::Network;
 use simcore::component::Id;
 
+use crate::resource::Resource;
+
 /// Represents a data item state.
 #[derive(Eq, PartialEq, Clone)]
 pub enum DataItemState {
@@ -25,6 +28,16 @@ pub struct DataItem {
     /// The size of data item in MB.
     pub size: f64,
     pub producer: Option<usize>,
+    /// Optional function which computes the size of the task output from the total size of the task inputs.
+    ///
+    /// If set, the size is updated when the producer task is completed, so that the transfers of the data item
+    /// use the actual size instead of the nominal one.
+    pub size_function: Option<fn(f64) -> f64>,
+    /// Indices of resources holding the replicas of DAG input.
+    ///
+    /// Replicated input is transferred to its consumers from the replica with the minimal transfer time,
+    /// other inputs are located at the runner.
+    pub replicas: BTreeSet<usize>,
     pub(crate) consumers: Vec<usize>,
     pub(crate) state: DataItemState,
 }
@@ -36,6 +49,8 @@ impl DataItem {
             name: name.to_string(),
             size,
             producer,
+            size_function: None,
+            replicas: BTreeSet::new(),
             consumers: Vec::new(),
             state,
         }
@@ -45,6 +60,24 @@ impl DataItem {
     pub fn add_consumer(&mut self, consumer: usize) {
         self.consumers.push(consumer);
     }
+
+    /// Returns the replica with the minimal time of transferring the data item to the given resource,
+    /// along with this time, or None if the data item has no replicas.
+    pub fn closest_replica(&self, resource: usize, resources: &[Resource], network: &Network) -> Option<(usize, f64)> {
+        let target = resources[resource].id;
+        self.replicas
+            .iter()
+            .map(|&replica| {
+                let source = resources[replica].id;
+                let time = if source == target {
+                    0.
+                } else {
+                    network.latency(source, target) + self.size / network.bandwidth(source, target)
+                };
+                (replica, time)
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+    }
 }
 
 /// Defines how data items are transferred during the DAG execution.
@@ -0,0 +1,119 @@
+//! Estimation of task costs from historical execution traces.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::dag::DAG;
+use crate::trace_log::{Event, TraceLog};
+
+/// Estimates task costs using the execution history of similar DAGs.
+///
+/// Each observation is the amount of computations performed by some task, which is recovered from the task runtime,
+/// the speed of the resource it was executed on and the number of used cores (assuming linear speedup).
+/// Observations are grouped by task type, the estimate for a task is the mean over the observations of its type.
+///
+/// By default the task type is the task name, which matches the same tasks in different runs of the same DAG.
+/// A custom mapping can be set with [`with_task_type`](TaskCostEstimator::with_task_type).
+pub struct TaskCostEstimator {
+    observations: BTreeMap<String, (f64, usize)>,
+    task_type: Box<dyn Fn(&str) -> String>,
+}
+
+impl Default for TaskCostEstimator {
+    fn default() -> Self {
+        Self {
+            observations: BTreeMap::new(),
+            task_type: Box::new(|name| name.to_string()),
+        }
+    }
+}
+
+impl TaskCostEstimator {
+    /// Creates estimator with empty history.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the function which maps task name to task type.
+    pub fn with_task_type<F: Fn(&str) -> String + 'static>(mut self, task_type: F) -> Self {
+        self.task_type = Box::new(task_type);
+        self
+    }
+
+    /// Returns the type of task with given name.
+    pub fn task_type(&self, task_name: &str) -> String {
+        (self.task_type)(task_name)
+    }
+
+    /// Adds a single observation of the amount of computations (in Gflops) performed by the task.
+    pub fn add_observation(&mut self, task_name: &str, flops: f64) {
+        let entry = self.observations.entry(self.task_type(task_name)).or_insert((0., 0));
+        entry.0 += flops;
+        entry.1 += 1;
+    }
+
+    /// Adds observations for all tasks completed in the given execution trace.
+    pub fn add_trace(&mut self, trace: &TraceLog) {
+        let resource_speed: HashMap<&str, f64> = trace.resources.iter().map(|r| (r.name.as_str(), r.speed)).collect();
+        let mut locations: HashMap<usize, (f64, u32)> = HashMap::new();
+        let mut start_times: HashMap<usize, f64> = HashMap::new();
+        for event in trace.events.iter() {
+            match event {
+                Event::TaskScheduled {
+                    task_id,
+                    location,
+                    cores,
+                    ..
+                } => {
+                    if let Some(&speed) = resource_speed.get(location.as_str()) {
+                        locations.insert(*task_id, (speed, *cores));
+                    }
+                }
+                Event::TaskStarted { time, task_id, .. } => {
+                    start_times.insert(*task_id, *time);
+                }
+                Event::TaskCompleted {
+                    time,
+                    task_id,
+                    task_name,
+                } => {
+                    if let (Some(start_time), Some((speed, cores))) =
+                        (start_times.remove(task_id), locations.get(task_id))
+                    {
+                        self.add_observation(task_name, (time - start_time) * speed * *cores as f64);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Returns the number of observations for the type of task with given name.
+    pub fn observation_count(&self, task_name: &str) -> usize {
+        self.observations
+            .get(&self.task_type(task_name))
+            .map(|(_, count)| *count)
+            .unwrap_or(0)
+    }
+
+    /// Returns the estimated amount of computations (in Gflops) for the task with given name,
+    /// or None if there are no observations for its type.
+    pub fn estimate(&self, task_name: &str) -> Option<f64> {
+        self.observations
+            .get(&self.task_type(task_name))
+            .map(|(sum, count)| sum / *count as f64)
+    }
+
+    /// Replaces the amounts of computations of DAG tasks with their estimates.
+    ///
+    /// Tasks without observations keep their original values. Returns the number of updated tasks.
+    pub fn apply(&self, dag: &mut DAG) -> usize {
+        let mut updated = 0;
+        for task_id in 0..dag.get_tasks().len() {
+            if let Some(flops) = self.estimate(&dag.get_task(task_id).name) {
+                dag.get_task_mut(task_id).flops = flops;
+                updated += 1;
+            }
+        }
+        updated
+    }
+}
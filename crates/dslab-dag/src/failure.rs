@@ -0,0 +1,28 @@
+//! Failures of tasks and resources during DAG execution.
+
+/// Defines when the running tasks fail.
+#[derive(Clone, Debug)]
+pub enum FailureModel {
+    /// Each task execution fails with the given probability at a uniformly distributed moment of its execution.
+    Random { probability: f64 },
+    /// Resources fail at the given times, specified as (time, resource index) pairs.
+    ///
+    /// All tasks running on the resource at the failure time fail, and the data items stored on the resource are lost.
+    /// The resource is available again right after the failure.
+    Scripted(Vec<(f64, usize)>),
+}
+
+/// Defines how the failed tasks are executed again.
+///
+/// Besides the failed tasks, the completed tasks whose outputs are lost and still needed are executed again
+/// according to the same policy.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RetryPolicy {
+    /// The task is restarted on the same resource, before the other queued tasks.
+    SameResource,
+    /// The failed resource is excluded from the resources allowed for the task,
+    /// and the scheduler is invoked to re-plan the tasks which have not started yet.
+    OtherResource,
+    /// DAG execution is aborted.
+    Abort,
+}
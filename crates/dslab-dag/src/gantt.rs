@@ -0,0 +1,338 @@
+//! Gantt chart of DAG execution built from the trace log.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+
+use crate::analysis::task_executions;
+use crate::trace_log::{Event, TraceLog};
+
+const ROW_LABEL_WIDTH: f64 = 100.;
+const CHART_WIDTH: f64 = 1000.;
+const LANE_HEIGHT: f64 = 20.;
+const TRANSFER_HEIGHT: f64 = 6.;
+const AXIS_HEIGHT: f64 = 30.;
+const MARGIN: f64 = 10.;
+
+/// Execution of a task on a resource.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GanttTask {
+    pub task_id: usize,
+    pub task_name: String,
+    pub start: f64,
+    pub finish: f64,
+    pub cores: u32,
+    /// Whether the execution is completed, otherwise the task failed.
+    pub completed: bool,
+}
+
+/// Transfer of a data item between two locations.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GanttTransfer {
+    pub data_item_id: usize,
+    pub data_name: String,
+    pub from: String,
+    pub to: String,
+    pub start: f64,
+    pub finish: f64,
+}
+
+/// Timeline of a resource.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GanttResource {
+    pub name: String,
+    pub cores: u32,
+    /// Task executions in the order of their start.
+    pub tasks: Vec<GanttTask>,
+}
+
+/// Gantt chart with the task execution intervals on each resource and the data transfers.
+///
+/// Serialized to JSON as is, so that it can be used by external plotting tools.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GanttChart {
+    /// Time of the last event in the trace log.
+    pub makespan: f64,
+    /// Resources in the same order as in the trace log.
+    pub resources: Vec<GanttResource>,
+    /// Completed data transfers in the order of their start.
+    pub transfers: Vec<GanttTransfer>,
+}
+
+impl GanttChart {
+    pub fn from_trace_log(trace_log: &TraceLog) -> Self {
+        let makespan = trace_log.events.last().map(|event| event.time()).unwrap_or(0.);
+        let mut resources = trace_log
+            .resources
+            .iter()
+            .map(|resource| GanttResource {
+                name: resource.name.clone(),
+                cores: resource.cores,
+                tasks: Vec::new(),
+            })
+            .collect::<Vec<_>>();
+        let resource_indexes = resources
+            .iter()
+            .enumerate()
+            .map(|(i, resource)| (resource.name.clone(), i))
+            .collect::<HashMap<_, _>>();
+        for execution in task_executions(trace_log) {
+            if let Some(&i) = resource_indexes.get(&execution.location) {
+                resources[i].tasks.push(GanttTask {
+                    task_id: execution.task_id,
+                    task_name: trace_log.graph.tasks[execution.task_id].name.clone(),
+                    start: execution.start,
+                    finish: execution.finish,
+                    cores: execution.cores,
+                    completed: execution.completed,
+                });
+            }
+        }
+        for resource in resources.iter_mut() {
+            resource.tasks.sort_by(|a, b| a.start.total_cmp(&b.start));
+        }
+
+        let mut transfer_starts: HashMap<usize, (usize, f64)> = HashMap::new();
+        let mut transfers = Vec::new();
+        for event in trace_log.events.iter() {
+            match event {
+                Event::StartUploading {
+                    time,
+                    data_id,
+                    data_item_id,
+                    ..
+                } => {
+                    transfer_starts.insert(*data_id, (*data_item_id, *time));
+                }
+                Event::FinishUploading {
+                    time,
+                    from,
+                    to,
+                    data_id,
+                    data_name,
+                } => {
+                    if let Some((data_item_id, start)) = transfer_starts.remove(data_id) {
+                        transfers.push(GanttTransfer {
+                            data_item_id,
+                            data_name: data_name.clone(),
+                            from: from.clone(),
+                            to: to.clone(),
+                            start,
+                            finish: *time,
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+        transfers.sort_by(|a, b| a.start.total_cmp(&b.start));
+
+        Self {
+            makespan,
+            resources,
+            transfers,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    /// Renders the chart as SVG image.
+    ///
+    /// Each resource is shown as a row with the task executions packed into lanes, so that the tasks running
+    /// at the same time don't overlap. The tasks are colored by their ids, failed executions are shown with
+    /// red dashed border. The incoming data transfers of the resource are shown as thin bars below its tasks,
+    /// the transfers to other locations (e.g. to the runner) are shown in the row of their source.
+    pub fn to_svg(&self) -> String {
+        let scale = if self.makespan > 0. {
+            CHART_WIDTH / self.makespan
+        } else {
+            1.
+        };
+        let x = |time: f64| ROW_LABEL_WIDTH + time * scale;
+
+        let resource_indexes = self
+            .resources
+            .iter()
+            .enumerate()
+            .map(|(i, resource)| (resource.name.as_str(), i))
+            .collect::<HashMap<_, _>>();
+        let mut resource_transfers: Vec<Vec<&GanttTransfer>> = vec![Vec::new(); self.resources.len()];
+        for transfer in self.transfers.iter() {
+            if let Some(&i) = resource_indexes
+                .get(transfer.to.as_str())
+                .or_else(|| resource_indexes.get(transfer.from.as_str()))
+            {
+                resource_transfers[i].push(transfer);
+            }
+        }
+
+        let mut body = String::new();
+        let mut y = MARGIN;
+        for (resource, transfers) in self.resources.iter().zip(resource_transfers.iter()) {
+            let task_lanes = assign_lanes(resource.tasks.iter().map(|task| (task.start, task.finish)));
+            let transfer_lanes = assign_lanes(transfers.iter().map(|transfer| (transfer.start, transfer.finish)));
+            let task_lane_count = task_lanes.iter().max().map(|&lane| lane + 1).unwrap_or(1);
+            let transfer_lane_count = transfer_lanes.iter().max().map(|&lane| lane + 1).unwrap_or(0);
+            let row_height = task_lane_count as f64 * LANE_HEIGHT + transfer_lane_count as f64 * TRANSFER_HEIGHT;
+
+            writeln!(
+                body,
+                r#"<text x="{}" y="{}" font-size="12" dominant-baseline="middle">{}</text>"#,
+                MARGIN,
+                y + row_height / 2.,
+                escape(&resource.name)
+            )
+            .unwrap();
+            for (task, &lane) in resource.tasks.iter().zip(task_lanes.iter()) {
+                let task_y = y + lane as f64 * LANE_HEIGHT;
+                let stroke = if task.completed {
+                    r#"stroke="black""#
+                } else {
+                    r#"stroke="red" stroke-dasharray="3,2""#
+                };
+                writeln!(
+                    body,
+                    r#"<rect x="{:.3}" y="{:.3}" width="{:.3}" height="{}" fill="{}" {}><title>{} ({:.3} - {:.3}, {} cores)</title></rect>"#,
+                    x(task.start),
+                    task_y + 1.,
+                    (task.finish - task.start) * scale,
+                    LANE_HEIGHT - 2.,
+                    task_color(task.task_id),
+                    stroke,
+                    escape(&task.task_name),
+                    task.start,
+                    task.finish,
+                    task.cores
+                )
+                .unwrap();
+                writeln!(
+                    body,
+                    r#"<text x="{:.3}" y="{:.3}" font-size="10" dominant-baseline="middle">{}</text>"#,
+                    x(task.start) + 2.,
+                    task_y + LANE_HEIGHT / 2.,
+                    escape(&task.task_name)
+                )
+                .unwrap();
+            }
+            let transfers_y = y + task_lane_count as f64 * LANE_HEIGHT;
+            for (transfer, &lane) in transfers.iter().zip(transfer_lanes.iter()) {
+                writeln!(
+                    body,
+                    r#"<rect x="{:.3}" y="{:.3}" width="{:.3}" height="{}" fill="gray"><title>{}: {} -> {} ({:.3} - {:.3})</title></rect>"#,
+                    x(transfer.start),
+                    transfers_y + lane as f64 * TRANSFER_HEIGHT + 1.,
+                    (transfer.finish - transfer.start) * scale,
+                    TRANSFER_HEIGHT - 2.,
+                    escape(&transfer.data_name),
+                    escape(&transfer.from),
+                    escape(&transfer.to),
+                    transfer.start,
+                    transfer.finish
+                )
+                .unwrap();
+            }
+            y += row_height;
+            writeln!(
+                body,
+                r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="lightgray"/>"#,
+                ROW_LABEL_WIDTH,
+                y,
+                ROW_LABEL_WIDTH + CHART_WIDTH,
+                y
+            )
+            .unwrap();
+        }
+
+        // time axis
+        writeln!(
+            body,
+            r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="black"/>"#,
+            ROW_LABEL_WIDTH,
+            y,
+            ROW_LABEL_WIDTH + CHART_WIDTH,
+            y
+        )
+        .unwrap();
+        let step = tick_step(self.makespan);
+        for i in 0..=(self.makespan / step + 1e-9).floor() as usize {
+            // rounding removes the floating point errors of the fractional steps
+            let tick = (i as f64 * step * 1e6).round() / 1e6;
+            writeln!(
+                body,
+                r#"<line x1="{:.3}" y1="{}" x2="{:.3}" y2="{}" stroke="black"/><text x="{:.3}" y="{}" font-size="10" text-anchor="middle">{}</text>"#,
+                x(tick),
+                y,
+                x(tick),
+                y + 5.,
+                x(tick),
+                y + 17.,
+                tick
+            )
+            .unwrap();
+        }
+
+        format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" font-family="sans-serif">
+{}</svg>
+"#,
+            ROW_LABEL_WIDTH + CHART_WIDTH + MARGIN,
+            y + AXIS_HEIGHT,
+            body
+        )
+    }
+}
+
+impl TraceLog {
+    /// Saves the Gantt chart of DAG execution in JSON format, see [GanttChart].
+    pub fn save_gantt_json(&self, filename: &str) -> Result<(), std::io::Error> {
+        std::fs::write(filename, GanttChart::from_trace_log(self).to_json())
+    }
+
+    /// Saves the Gantt chart of DAG execution as SVG image, see [GanttChart::to_svg()].
+    pub fn save_gantt_svg(&self, filename: &str) -> Result<(), std::io::Error> {
+        std::fs::write(filename, GanttChart::from_trace_log(self).to_svg())
+    }
+}
+
+/// Assigns the intervals sorted by start time to the lowest lanes where they don't overlap with other intervals.
+fn assign_lanes(intervals: impl Iterator<Item = (f64, f64)>) -> Vec<usize> {
+    let mut lane_ends: Vec<f64> = Vec::new();
+    intervals
+        .map(|(start, finish)| match lane_ends.iter().position(|&end| end <= start) {
+            Some(lane) => {
+                lane_ends[lane] = finish;
+                lane
+            }
+            None => {
+                lane_ends.push(finish);
+                lane_ends.len() - 1
+            }
+        })
+        .collect()
+}
+
+/// Returns the step between time axis ticks from 1, 2 or 5 multiplied by a power of 10,
+/// so that there are at most 10 ticks.
+fn tick_step(makespan: f64) -> f64 {
+    if makespan <= 0. {
+        return 1.;
+    }
+    let magnitude = 10f64.powf((makespan / 10.).log10().floor());
+    [1., 2., 5., 10.]
+        .into_iter()
+        .map(|factor| factor * magnitude)
+        .find(|&step| makespan / step <= 10.)
+        .unwrap()
+}
+
+/// Returns a distinct color for the task by rotating the hue with the golden angle.
+fn task_color(task_id: usize) -> String {
+    format!("hsl({:.1}, 60%, 70%)", (task_id as f64 * 137.508) % 360.)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
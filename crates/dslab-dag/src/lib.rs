@@ -1,14 +1,20 @@
 #![doc = include_str!("../README.md")]
 
+pub mod analysis;
 pub mod dag;
 pub mod dag_simulation;
 pub mod dag_stats;
 pub mod data_item;
+pub mod estimation;
 pub mod experiment;
+pub mod failure;
+pub mod gantt;
 pub mod lower_bound;
 pub mod network;
 pub mod parsers;
+pub mod power;
 pub mod resource;
+pub mod robustness;
 pub mod run_stats;
 pub mod runner;
 pub mod scheduler;
@@ -150,6 +150,39 @@ impl NetworkConfig {
             network.init_topology();
         }
     }
+
+    /// Adds network node and links (in case of topology-aware network model) for a resource added
+    /// after the network initialization. `resources` should contain the previously added resources.
+    pub fn add_resource(&self, network: Rc<RefCell<Network>>, resource: &Resource, resources: &[Resource]) {
+        let mut network = network.borrow_mut();
+        network.add_node(
+            resource.name.as_str(),
+            Box::new(ConstantBandwidthNetworkModel::new(100000., 0.)),
+        );
+        network.set_location(resource.id, &resource.name);
+
+        if let NetworkConfig::TopologyAware {
+            topology_type,
+            link_bandwidth,
+            link_latency,
+        } = self
+        {
+            let link_latency = link_latency * 1e-6; // convert to seconds
+
+            match topology_type {
+                TopologyType::Star => {
+                    network.add_full_duplex_link("master", &resource.name, Link::shared(*link_bandwidth, link_latency));
+                }
+                TopologyType::FullMesh => {
+                    for host in resources.iter().map(|r| r.name.as_str()).chain(["master"]) {
+                        network.add_full_duplex_link(host, &resource.name, Link::shared(*link_bandwidth, link_latency));
+                    }
+                }
+            }
+
+            network.init_topology();
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 
 use serde::{Deserialize, Serialize};
@@ -22,20 +22,39 @@ struct Job {
     id: String,
     name: String,
     runtime: f64,
-    #[serde(rename = "uses")]
+    #[serde(rename = "uses", default)]
     files: Vec<File>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+struct Parent {
+    #[serde(rename = "ref")]
+    id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Child {
+    #[serde(rename = "ref")]
+    id: String,
+    #[serde(rename = "parent", default)]
+    parents: Vec<Parent>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename = "adag")]
 #[allow(clippy::upper_case_acronyms)]
 struct DAX {
     #[serde(rename = "job")]
     jobs: Vec<Job>,
+    #[serde(rename = "child", default)]
+    children: Vec<Child>,
 }
 
 impl DAG {
     /// Reads DAG from a file in [DAX format](https://pegasus.isi.edu/documentation/development/schemas.html).
+    ///
+    /// Files used by several jobs become shared data items. Dependencies between jobs which are listed in
+    /// `<child>` elements but don't share any file are represented as data items of zero size.
     pub fn from_dax<P: AsRef<Path>>(file: P, config: &ParserConfig) -> Self {
         let dax: DAX = from_str(
             &std::fs::read_to_string(&file).unwrap_or_else(|_| panic!("Can't read file {}", file.as_ref().display())),
@@ -43,6 +62,7 @@ impl DAG {
         .unwrap_or_else(|_| panic!("Can't parse DAX from file {}", file.as_ref().display()));
         let mut dag = DAG::new();
         let mut data_items: HashMap<String, usize> = HashMap::new();
+        let mut task_ids: HashMap<String, usize> = HashMap::new();
         for job in dax.jobs.iter() {
             let task_id = dag.add_task(
                 &format!("{}_{}", job.name, job.id),
@@ -52,6 +72,7 @@ impl DAG {
                 1, // assuming all tasks require a single core
                 CoresDependency::Linear,
             );
+            task_ids.insert(job.id.clone(), task_id);
             for file in job.files.iter() {
                 if file.link == "output" {
                     data_items.insert(
@@ -74,6 +95,23 @@ impl DAG {
                 }
             }
         }
+        for child in dax.children.iter() {
+            let task_id = task_ids[&child.id];
+            let mut predecessors: HashSet<usize> = dag
+                .get_task(task_id)
+                .inputs
+                .iter()
+                .filter_map(|&data_item_id| dag.get_data_item(data_item_id).producer)
+                .collect();
+            for parent in child.parents.iter() {
+                let parent_id = task_ids[&parent.id];
+                if !predecessors.contains(&parent_id) {
+                    let data_item_id = dag.add_task_output(parent_id, &format!("{} -> {}", parent.id, child.id), 0.);
+                    dag.add_data_dependency(data_item_id, task_id);
+                    predecessors.insert(parent_id);
+                }
+            }
+        }
         dag
     }
 }
@@ -145,7 +145,8 @@ impl DAG {
                     cores
                 }
             } else {
-                task.cores.unwrap_or(1.) as u32
+                // fractional number of cores is rounded down, but at least one core is required
+                (task.cores.unwrap_or(1.) as u32).max(1)
             };
 
             let memory = if config.ignore_memory {
@@ -39,6 +39,7 @@ struct Task {
     #[serde(default)]
     inputs: Vec<String>,
     outputs: Vec<DataItem>,
+    deadline: Option<f64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -46,6 +47,7 @@ struct Yaml {
     tasks: Vec<Task>,
     #[serde(default = "Vec::new")]
     inputs: Vec<DataItem>,
+    deadline: Option<f64>,
 }
 
 impl DAG {
@@ -78,6 +80,9 @@ impl DAG {
                     _ => CoresDependency::Linear,
                 },
             );
+            if let Some(deadline) = task.deadline {
+                dag.set_task_deadline(task_id, deadline);
+            }
             for output in task.outputs.iter() {
                 data_items.insert(
                     output.name.clone(),
@@ -90,6 +95,9 @@ impl DAG {
                 dag.add_data_dependency(*data_items.get(input).unwrap(), task_id);
             }
         }
+        if let Some(deadline) = yaml.deadline {
+            dag.set_deadline(deadline);
+        }
         dag
     }
 }
@@ -0,0 +1,81 @@
+//! Power consumption of resources.
+
+/// Defines the power consumption of a resource depending on its load.
+///
+/// Can also change the speed at which the resource operates, which allows to model dynamic voltage and frequency
+/// scaling (DVFS) trading the execution time for the energy consumption.
+pub trait PowerModel {
+    /// Returns the power consumption (in W) of the resource operating at the given speed (in Gflop/s)
+    /// with `busy_cores` cores executing tasks.
+    fn power(&self, speed: f64, busy_cores: u32) -> f64;
+
+    /// Returns the speed (in Gflop/s) at which the resource with the given nominal speed operates.
+    fn speed(&self, nominal_speed: f64) -> f64 {
+        nominal_speed
+    }
+}
+
+/// Power model with constant idle power and dynamic power proportional to the computation rate.
+///
+/// The idle power is consumed while the resource is powered on regardless of its load,
+/// and each busy core additionally consumes the energy per flop for each Gflop it executes.
+#[derive(Clone, Debug)]
+pub struct LinearPowerModel {
+    /// Power consumed by the powered on resource (in W).
+    idle_power: f64,
+    /// Dynamic energy consumed for executing one Gflop (in J).
+    energy_per_flop: f64,
+}
+
+impl LinearPowerModel {
+    pub fn new(idle_power: f64, energy_per_flop: f64) -> Self {
+        Self {
+            idle_power,
+            energy_per_flop,
+        }
+    }
+}
+
+impl PowerModel for LinearPowerModel {
+    fn power(&self, speed: f64, busy_cores: u32) -> f64 {
+        self.idle_power + self.energy_per_flop * speed * busy_cores as f64
+    }
+}
+
+/// Power model of a resource whose cores operate at the reduced frequency.
+///
+/// The resource speed is scaled by the relative frequency, while the dynamic power is proportional to
+/// the cube of frequency, so that the dynamic energy per Gflop is scaled by the square of relative frequency.
+/// The idle power doesn't depend on the frequency.
+#[derive(Clone, Debug)]
+pub struct DvfsPowerModel {
+    idle_power: f64,
+    /// Dynamic energy consumed for executing one Gflop at the nominal frequency (in J).
+    energy_per_flop: f64,
+    /// Frequency relative to the nominal one, from (0, 1].
+    frequency: f64,
+}
+
+impl DvfsPowerModel {
+    pub fn new(idle_power: f64, energy_per_flop: f64, frequency: f64) -> Self {
+        assert!(
+            frequency > 0. && frequency <= 1.,
+            "Relative frequency must be in (0, 1]"
+        );
+        Self {
+            idle_power,
+            energy_per_flop,
+            frequency,
+        }
+    }
+}
+
+impl PowerModel for DvfsPowerModel {
+    fn power(&self, speed: f64, busy_cores: u32) -> f64 {
+        self.idle_power + self.energy_per_flop * self.frequency.powi(2) * speed * busy_cores as f64
+    }
+
+    fn speed(&self, nominal_speed: f64) -> f64 {
+        nominal_speed * self.frequency
+    }
+}
@@ -10,6 +10,8 @@ use simcore::component::Id;
 
 use dslab_compute::multicore::*;
 
+use crate::power::PowerModel;
+
 /// Represents a computing resource that can execute DAG tasks.
 ///
 /// Described by the number of CPU cores, their speed in flop/s and amount of memory.
@@ -29,6 +31,39 @@ pub struct Resource {
     /// Memory size in MB.
     pub memory: u64,
     pub memory_available: u64,
+    /// Price of using one core per unit of time.
+    pub price: f64,
+    /// Cost of executing a task on the resource per second, regardless of the number of used cores.
+    pub cost: f64,
+    /// Provisioning parameters of on-demand resource (None if the resource is always available).
+    pub provisioning: Option<Provisioning>,
+    /// Power consumption model (None if the energy consumption of the resource is not accounted).
+    pub power_model: Option<Rc<dyn PowerModel>>,
+}
+
+/// Parameters of on-demand resource, which is provisioned when the first task is scheduled on it
+/// and deprovisioned when DAG execution is completed.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default)]
+pub struct Provisioning {
+    /// Delay between the provisioning request and the moment the resource can execute tasks.
+    #[serde(default)]
+    pub startup_time: f64,
+    /// One-time cost of provisioning the resource.
+    #[serde(default)]
+    pub startup_cost: f64,
+    /// One-time cost of deprovisioning the resource.
+    #[serde(default)]
+    pub shutdown_cost: f64,
+}
+
+impl Provisioning {
+    pub fn new(startup_time: f64, startup_cost: f64, shutdown_cost: f64) -> Self {
+        Self {
+            startup_time,
+            startup_cost,
+            shutdown_cost,
+        }
+    }
 }
 
 /// Contains parameters of computing resource, can be used later to create a compute resource instance.
@@ -40,6 +75,15 @@ pub struct ResourceConfig {
     pub cores: u32,
     /// Memory size in MB.
     pub memory: u64,
+    /// Price of using one core per unit of time.
+    #[serde(default)]
+    pub price: f64,
+    /// Cost of executing a task on the resource per second, regardless of the number of used cores.
+    #[serde(default)]
+    pub cost: f64,
+    /// Provisioning parameters of on-demand resource.
+    #[serde(default)]
+    pub provisioning: Option<Provisioning>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -0,0 +1,67 @@
+//! Robustness of schedules to errors in task cost estimates.
+
+use rand::Rng;
+
+/// Distribution of relative errors of task costs (amounts of computations) used to perturb a DAG.
+#[derive(Clone, Copy, Debug)]
+pub enum CostPerturbation {
+    /// Task cost is multiplied by a factor uniformly distributed in `[1 - spread, 1 + spread]`.
+    Uniform { spread: f64 },
+    /// Task cost is multiplied by a normally distributed factor with mean 1 and given standard deviation.
+    /// Negative factors are replaced by zero.
+    Normal { std_dev: f64 },
+}
+
+impl CostPerturbation {
+    /// Samples a multiplier of task cost.
+    pub fn sample_factor<R: Rng>(&self, rng: &mut R) -> f64 {
+        match *self {
+            CostPerturbation::Uniform { spread } => 1. + spread * (2. * rng.gen::<f64>() - 1.),
+            CostPerturbation::Normal { std_dev } => {
+                // Box-Muller transform
+                let u1 = 1. - rng.gen::<f64>();
+                let u2 = rng.gen::<f64>();
+                let z = (-2. * u1.ln()).sqrt() * (2. * std::f64::consts::PI * u2).cos();
+                f64::max(0., 1. + std_dev * z)
+            }
+        }
+    }
+}
+
+/// Makespans of a schedule executed with perturbed task costs.
+#[derive(Clone, Debug)]
+pub struct RobustnessStats {
+    /// Makespan with the original task costs.
+    pub nominal_makespan: f64,
+    /// Makespans obtained in Monte Carlo trials.
+    pub makespans: Vec<f64>,
+}
+
+impl RobustnessStats {
+    /// Returns the mean makespan over the trials.
+    pub fn mean(&self) -> f64 {
+        self.makespans.iter().sum::<f64>() / self.makespans.len() as f64
+    }
+
+    /// Returns the variance of makespan over the trials.
+    pub fn variance(&self) -> f64 {
+        let mean = self.mean();
+        self.makespans.iter().map(|m| (m - mean).powi(2)).sum::<f64>() / self.makespans.len() as f64
+    }
+
+    /// Returns the standard deviation of makespan over the trials.
+    pub fn std_dev(&self) -> f64 {
+        self.variance().sqrt()
+    }
+
+    /// Returns the maximum makespan over the trials.
+    pub fn max(&self) -> f64 {
+        self.makespans.iter().cloned().fold(f64::NEG_INFINITY, f64::max)
+    }
+
+    /// Returns the fraction of trials in which the makespan exceeds the nominal one by more than `tolerance` fraction.
+    pub fn degradation_probability(&self, tolerance: f64) -> f64 {
+        let limit = self.nominal_makespan * (1. + tolerance);
+        self.makespans.iter().filter(|&&m| m > limit).count() as f64 / self.makespans.len() as f64
+    }
+}
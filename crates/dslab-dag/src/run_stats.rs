@@ -2,6 +2,7 @@ use std::collections::{HashMap, HashSet};
 
 use serde::{Deserialize, Serialize};
 
+use crate::resource::Resource;
 use crate::system::System;
 
 /// Contains metrics collected from a simulation run.
@@ -44,6 +45,35 @@ pub struct RunStats {
     pub cpu_utilization_active: f64,
     /// Average memory utilization for active resources only (analogous to cpu_utilization_active).
     pub memory_utilization_active: f64,
+    /// DAG deadline (if set).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deadline: Option<f64>,
+    /// DAG tardiness, i.e. max(0, completion time - deadline) (if DAG deadline is set).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dag_tardiness: Option<f64>,
+    /// Number of tasks with deadlines.
+    pub tasks_with_deadline: usize,
+    /// Number of tasks completed after their deadlines.
+    pub missed_task_deadlines: usize,
+    /// Total tardiness of tasks with deadlines.
+    pub total_task_tardiness: f64,
+    /// Total startup and shutdown cost of on-demand resources.
+    pub provisioning_cost: f64,
+    /// Total price of the core time used by tasks and cost of the task execution time on resources.
+    pub execution_cost: f64,
+    /// Total monetary cost of DAG execution (execution_cost + provisioning_cost).
+    pub total_cost: f64,
+    /// Number of failed task executions.
+    pub task_failures: usize,
+    /// Number of task re-executions caused by task failures or lost task outputs.
+    pub task_retries: usize,
+    /// Execution time of failed tasks and of completed tasks whose outputs were lost (in seconds),
+    /// this time is included in total_task_time.
+    pub wasted_task_time: f64,
+    /// Total energy consumed by resources with power models (in J).
+    pub energy: f64,
+    /// Average power consumption during DAG execution (energy / makespan, in W).
+    pub average_power: f64,
 
     #[serde(skip)]
     task_starts: HashMap<usize, (u32, u64, f64)>,
@@ -61,6 +91,20 @@ pub struct RunStats {
     resource_first_used: HashMap<usize, f64>,
     #[serde(skip)]
     resource_last_used: HashMap<usize, f64>,
+    #[serde(skip)]
+    task_tardiness: HashMap<usize, f64>,
+    #[serde(skip)]
+    resource_used_cores: HashMap<usize, Vec<(f64, u32)>>,
+    #[serde(skip)]
+    resource_core_time: HashMap<usize, f64>,
+    #[serde(skip)]
+    resource_task_time: HashMap<usize, f64>,
+    #[serde(skip)]
+    task_durations: HashMap<usize, f64>,
+    #[serde(skip)]
+    resource_power_on: HashMap<usize, f64>,
+    #[serde(skip)]
+    resource_power_off: HashMap<usize, f64>,
 }
 
 impl RunStats {
@@ -85,16 +129,154 @@ impl RunStats {
         self.used_resources.insert(resource);
         self.task_resource.insert(task, resource);
         self.resource_first_used.entry(resource).or_insert(time);
+        let used_cores = self.used_cores(resource);
+        self.set_used_cores(resource, used_cores + cores, time);
     }
 
     pub fn set_task_finish(&mut self, task: usize, time: f64) {
+        let duration = self.stop_task(task, time);
+        self.task_durations.insert(task, duration);
+    }
+
+    /// Records failure of the running task, its execution time is counted as wasted.
+    pub fn set_task_failed(&mut self, task: usize, time: f64) {
+        self.task_failures += 1;
+        self.wasted_task_time += self.stop_task(task, time);
+    }
+
+    /// Records the loss of outputs of completed task, its last execution time is counted as wasted.
+    pub fn set_task_outputs_lost(&mut self, task: usize) {
+        self.wasted_task_time += self.task_durations.remove(&task).unwrap_or(0.);
+    }
+
+    pub fn add_task_retry(&mut self) {
+        self.task_retries += 1;
+    }
+
+    /// Accounts the execution of the task and returns its duration.
+    fn stop_task(&mut self, task: usize, time: f64) -> f64 {
         let (cores, memory, start_time) = self.task_starts.remove(&task).unwrap();
         self.current_cores -= cores;
         self.current_memory -= memory;
         self.total_task_time += time - start_time;
         self.cpu_utilization += (time - start_time) * cores as f64;
         self.memory_utilization += (time - start_time) * memory as f64;
-        self.resource_last_used.insert(self.task_resource[&task], time);
+        let resource = self.task_resource[&task];
+        *self.resource_core_time.entry(resource).or_default() += (time - start_time) * cores as f64;
+        *self.resource_task_time.entry(resource).or_default() += time - start_time;
+        self.resource_last_used.insert(resource, time);
+        let used_cores = self.used_cores(resource);
+        self.set_used_cores(resource, used_cores - cores, time);
+        time - start_time
+    }
+
+    /// Returns the number of used cores of the resource over time as (time, cores) pairs,
+    /// each value holds until the next pair. Empty if no tasks were executed on the resource.
+    pub fn used_cores_series(&self, resource: usize) -> &[(f64, u32)] {
+        self.resource_used_cores
+            .get(&resource)
+            .map(|series| series.as_slice())
+            .unwrap_or(&[])
+    }
+
+    fn used_cores(&self, resource: usize) -> u32 {
+        self.used_cores_series(resource).last().map(|p| p.1).unwrap_or(0)
+    }
+
+    fn set_used_cores(&mut self, resource: usize, cores: u32, time: f64) {
+        let series = self
+            .resource_used_cores
+            .entry(resource)
+            .or_insert_with(|| vec![(0., 0)]);
+        // several tasks may start or finish at the same time
+        if series.last().unwrap().0 == time {
+            series.pop();
+        }
+        if series.last().map(|p| p.1) != Some(cores) {
+            series.push((time, cores));
+        }
+    }
+
+    /// Records task completion relative to its deadline.
+    ///
+    /// Tasks executed several times (in loops) are accounted by their last completion.
+    pub fn set_task_deadline_result(&mut self, task: usize, deadline: f64, time: f64) {
+        if let Some(prev) = self.task_tardiness.remove(&task) {
+            self.tasks_with_deadline -= 1;
+            if prev > 0. {
+                self.missed_task_deadlines -= 1;
+            }
+            self.total_task_tardiness -= prev;
+        }
+        let tardiness = (time - deadline).max(0.);
+        self.tasks_with_deadline += 1;
+        if tardiness > 0. {
+            self.missed_task_deadlines += 1;
+        }
+        self.total_task_tardiness += tardiness;
+        self.task_tardiness.insert(task, tardiness);
+    }
+
+    pub fn set_dag_deadline_result(&mut self, deadline: f64, time: f64) {
+        self.deadline = Some(deadline);
+        self.dag_tardiness = Some((time - deadline).max(0.));
+    }
+
+    /// Returns DAG tardiness, i.e. max(0, completion time - deadline), or None if DAG has no deadline.
+    pub fn tardiness(&self) -> Option<f64> {
+        self.dag_tardiness
+    }
+
+    /// Returns whether DAG was completed before its deadline, or None if DAG has no deadline.
+    pub fn deadline_met(&self) -> Option<bool> {
+        self.dag_tardiness.map(|t| t == 0.)
+    }
+
+    /// Returns tardiness of completed task, or None if the task has no deadline.
+    pub fn task_tardiness(&self, task: usize) -> Option<f64> {
+        self.task_tardiness.get(&task).cloned()
+    }
+
+    pub fn add_provisioning_cost(&mut self, cost: f64) {
+        self.provisioning_cost += cost;
+    }
+
+    /// Records the moment when the resource is powered on.
+    ///
+    /// Resources which are always available are considered powered on from the start of simulation by default.
+    pub fn set_resource_power_on(&mut self, resource: usize, time: f64) {
+        self.resource_power_on.insert(resource, time);
+        self.resource_power_off.remove(&resource);
+    }
+
+    pub fn set_resource_power_off(&mut self, resource: usize, time: f64) {
+        self.resource_power_off.insert(resource, time);
+    }
+
+    /// Returns the energy consumed by the resource until the given time, integrating its power
+    /// over the number of used cores.
+    fn resource_energy(&self, resource: usize, r: &Resource, time: f64) -> f64 {
+        let Some(power_model) = r.power_model.as_ref() else {
+            return 0.;
+        };
+        let start = match self.resource_power_on.get(&resource) {
+            Some(&start) => start,
+            None if r.provisioning.is_none() => 0.,
+            None => return 0.,
+        };
+        let end = self.resource_power_off.get(&resource).cloned().unwrap_or(time);
+        let series = self.used_cores_series(resource);
+        let mut energy = 0.;
+        let mut prev = (start, 0);
+        for &(t, cores) in series.iter().chain([(end, 0)].iter()) {
+            let from = prev.0.max(start);
+            let to = t.min(end);
+            if to > from {
+                energy += power_model.power(r.speed, prev.1) * (to - from);
+            }
+            prev = (t, cores);
+        }
+        energy
     }
 
     pub fn set_transfer_start(&mut self, data_item: usize, size: f64, time: f64) {
@@ -154,5 +336,23 @@ impl RunStats {
             self.memory_utilization = 1.;
         }
         self.used_resource_count = self.used_resources.len();
+        self.execution_cost = self
+            .resource_core_time
+            .iter()
+            .map(|(&i, core_time)| core_time * system.resources[i].price)
+            .chain(
+                self.resource_task_time
+                    .iter()
+                    .map(|(&i, task_time)| task_time * system.resources[i].cost),
+            )
+            .sum();
+        self.total_cost = self.execution_cost + self.provisioning_cost;
+        self.energy = system
+            .resources
+            .iter()
+            .enumerate()
+            .map(|(i, r)| self.resource_energy(i, r, time))
+            .sum();
+        self.average_power = if time > 0. { self.energy / time } else { 0. };
     }
 }
@@ -2,6 +2,7 @@
 
 use std::cell::RefCell;
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::ops::Range;
 use std::rc::Rc;
 use std::time::Instant;
 
@@ -20,10 +21,12 @@ use simcore::{log_debug, log_error, log_info};
 
 use crate::dag::DAG;
 use crate::data_item::{DataItemState, DataTransferMode};
+use crate::failure::{FailureModel, RetryPolicy};
 use crate::lower_bound::makespan_lower_bound;
 use crate::resource::Resource;
 use crate::run_stats::RunStats;
 use crate::scheduler::{Action, Scheduler, TimeSpan};
+use crate::schedulers::common::topsort;
 use crate::system::System;
 use crate::task::TaskState;
 use crate::trace_log::{Event as TraceEvent, Resource as TraceResource, TraceLog};
@@ -34,14 +37,35 @@ pub struct Config {
     pub data_transfer_mode: DataTransferMode,
 }
 
+/// Defines how the resources are shared between several DAGs executed together.
+///
+/// The policy orders the tasks of different DAGs scheduled at once (e.g. by a static scheduler) in the resource
+/// queues, the order of tasks within each DAG is preserved.
+#[derive(Clone, Debug)]
+pub enum DagSharingPolicy {
+    /// Tasks of earlier submitted DAGs go first.
+    Fifo,
+    /// Tasks of DAGs with higher priority go first, the priorities are given in the order of DAG submission.
+    Priority(Vec<i64>),
+    /// Tasks of different DAGs are interleaved, so that all DAGs progress evenly.
+    FairShare,
+}
+
 /// Represents a transfer of data item between resources.
 struct DataTransfer {
     data_id: usize,
     from: Id,
-    #[allow(dead_code)]
     to: Id,
 }
 
+/// Provisioning state of a resource.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ProvisionState {
+    Off,
+    Starting,
+    On,
+}
+
 #[derive(Clone, Debug)]
 struct QueuedTask {
     task_id: usize,
@@ -63,6 +87,8 @@ pub struct DAGRunner {
     computations: HashMap<u64, usize>,
     task_location: HashMap<usize, usize>,
     data_transfers: HashMap<usize, DataTransfer>,
+    // transfers of data items produced by the previous loop iteration
+    discarded_transfers: HashSet<usize>,
     data_location: HashMap<usize, Id>,
     outputs: HashSet<usize>,
     task_cores: HashMap<usize, Vec<u32>>,
@@ -77,8 +103,22 @@ pub struct DAGRunner {
     data_transfer_tasks: HashMap<Id, HashMap<usize, Vec<Id>>>,
     resource_data_items: HashMap<Id, BTreeSet<usize>>,
     available_cores: Vec<BTreeSet<u32>>,
+    provision_states: Vec<ProvisionState>,
+    // DAG index of each task when executing several DAGs, see DagSimulation::init_multiple
+    task_dags: HashMap<usize, usize>,
+    dag_sharing_policy: DagSharingPolicy,
+    dag_action_counts: Vec<usize>,
+    // number of not completed tasks of each DAG
+    dag_remaining_tasks: Vec<usize>,
+    dag_completion_times: Vec<Option<f64>>,
+    // DAGs submitted to the running execution which have not arrived yet
+    submitted_dags: HashMap<usize, DAG>,
+    next_submission_id: usize,
     trace_log_enabled: bool,
     run_stats: RunStats,
+    failure_model: Option<FailureModel>,
+    retry_policy: RetryPolicy,
+    aborted: bool,
     config: Config,
     ctx: SimulationContext,
 }
@@ -109,6 +149,7 @@ impl DAGRunner {
             .iter()
             .map(|resource| (0..resource.compute.borrow().cores_total()).collect())
             .collect();
+        let provision_states = resources.iter().map(Self::initial_provision_state).collect();
         Self {
             id: ctx.id(),
             dag,
@@ -118,6 +159,7 @@ impl DAGRunner {
             computations: HashMap::new(),
             task_location: HashMap::new(),
             data_transfers: HashMap::new(),
+            discarded_transfers: HashSet::new(),
             data_location: HashMap::new(),
             outputs: HashSet::new(),
             task_cores: HashMap::new(),
@@ -131,8 +173,19 @@ impl DAGRunner {
             data_transfer_tasks: HashMap::new(),
             resource_data_items: HashMap::new(),
             available_cores,
+            provision_states,
+            task_dags: HashMap::new(),
+            dag_sharing_policy: DagSharingPolicy::Fifo,
+            dag_action_counts: Vec::new(),
+            dag_remaining_tasks: Vec::new(),
+            dag_completion_times: Vec::new(),
+            submitted_dags: HashMap::new(),
+            next_submission_id: 0,
             trace_log_enabled: true,
             run_stats: RunStats::new(),
+            failure_model: None,
+            retry_policy: RetryPolicy::SameResource,
+            aborted: false,
             config,
             ctx,
         }
@@ -143,21 +196,58 @@ impl DAGRunner {
         self.trace_log_enabled = flag;
     }
 
+    /// Sets the DAG index of each task and the policy of resource sharing between the DAGs.
+    pub(crate) fn set_dag_sharing(&mut self, task_dags: Vec<usize>, dag_count: usize, policy: DagSharingPolicy) {
+        if let DagSharingPolicy::Priority(priorities) = &policy {
+            assert_eq!(priorities.len(), dag_count, "Priority must be set for each DAG");
+        }
+        self.dag_remaining_tasks = vec![0; dag_count];
+        for &dag in task_dags.iter() {
+            self.dag_remaining_tasks[dag] += 1;
+        }
+        self.task_dags = task_dags.into_iter().enumerate().collect();
+        self.dag_sharing_policy = policy;
+        self.dag_action_counts = vec![0; dag_count];
+        self.dag_completion_times = vec![None; dag_count];
+    }
+
+    /// Enables injection of failures according to the model, the failed tasks are executed again
+    /// according to the retry policy.
+    ///
+    /// Must be called before the start of DAG execution. The times of scripted failures must not be earlier
+    /// than the current time.
+    pub fn set_failure_model(&mut self, model: FailureModel, policy: RetryPolicy) {
+        match &model {
+            FailureModel::Random { probability } => {
+                assert!((0. ..1.).contains(probability), "Failure probability must be in [0, 1)");
+            }
+            FailureModel::Scripted(failures) => {
+                for &(time, _) in failures.iter() {
+                    assert!(
+                        time >= self.ctx.time(),
+                        "Failure time {} is earlier than the current time {}",
+                        time,
+                        self.ctx.time()
+                    );
+                }
+            }
+        }
+        self.failure_model = Some(model);
+        self.retry_policy = policy;
+    }
+
+    /// Returns the completion time of the DAG with given index when executing several DAGs.
+    pub fn dag_completion_time(&self, dag: usize) -> Option<f64> {
+        self.dag_completion_times[dag]
+    }
+
     /// Starts DAG execution.
     pub fn start(&mut self) {
         if !self.validate_input() {
             return;
         }
 
-        for (id, data_item) in self.dag.get_data_items().iter().enumerate() {
-            if data_item.state == DataItemState::Ready {
-                assert!(data_item.producer.is_none(), "Non-input data item has Ready state");
-                self.data_location.insert(id, self.id);
-                self.resource_data_items.entry(self.id).or_default().insert(id);
-            } else if data_item.consumers.is_empty() {
-                self.outputs.insert(id);
-            }
-        }
+        self.init_data_items(0..self.dag.get_data_items().len());
 
         log_info!(
             self.ctx,
@@ -167,6 +257,13 @@ impl DAGRunner {
             self.dag.get_data_items().len()
         );
         self.trace_config();
+        if let Some(FailureModel::Scripted(failures)) = &self.failure_model {
+            for &(time, resource) in failures.iter() {
+                // the execution may start later than the failure model was set
+                self.ctx
+                    .emit_self(ResourceFailure { resource }, (time - self.ctx.time()).max(0.));
+            }
+        }
         let time = Instant::now();
         let actions = self.scheduler.borrow_mut().start(
             &self.dag,
@@ -193,13 +290,195 @@ impl DAGRunner {
             log_info!(self.ctx, "expected makespan: {}", makespan);
             self.run_stats.set_expected_makespan(makespan);
         }
-        self.actions.extend(actions);
+        self.enqueue_actions(actions);
+        self.process_actions();
+    }
+
+    /// Adds a new resource to the running DAG execution.
+    ///
+    /// Dynamic schedulers can use the resource on the next scheduling decision,
+    /// static schedulers can use it after [rescheduling](DAGRunner::reschedule).
+    pub fn add_resource(&mut self, resource: Resource) {
+        let cores = resource.compute.borrow().cores_total();
+        if self.trace_log_enabled {
+            self.trace_log.resources.push(TraceResource {
+                name: resource.name.clone(),
+                speed: resource.compute.borrow().speed(),
+                cores: resource.cores_available,
+                memory: resource.memory_available,
+            });
+        }
+        log_info!(self.ctx, "added resource {}", resource.name);
+        self.resource_indexes.insert(resource.id, self.resources.len());
+        self.resource_queue.push((0..cores).map(|_| VecDeque::new()).collect());
+        self.available_cores.push((0..cores).collect());
+        self.provision_states.push(Self::initial_provision_state(&resource));
+        if resource.provisioning.is_none() {
+            self.run_stats
+                .set_resource_power_on(self.resources.len(), self.ctx.time());
+        }
+        self.resources.push(resource);
+        self.process_actions();
+    }
+
+    /// Submits a new DAG to the running execution, the DAG arrives after the given delay.
+    ///
+    /// On arrival the DAG is appended to the executed DAG, shifting the ids of its tasks and data items,
+    /// and the scheduler is notified via [Scheduler::on_new_tasks()]. The arrived DAGs share the resources
+    /// according to the DAG sharing policy (with priority 0 for [DagSharingPolicy::Priority]), their indices
+    /// follow the order of arrival. The execution is not completed until all submitted DAGs arrive and complete.
+    pub fn submit_dag(&mut self, dag: DAG, delay: f64) {
+        // the executed DAG becomes the first one, unless several DAGs are already executed
+        if self.dag_completion_times.is_empty() {
+            self.set_dag_sharing(vec![0; self.dag.get_tasks().len()], 1, DagSharingPolicy::Fifo);
+        }
+        let id = self.next_submission_id;
+        self.next_submission_id += 1;
+        self.submitted_dags.insert(id, dag);
+        self.ctx.emit_self(DagArrived { id }, delay);
+    }
+
+    fn on_dag_arrived(&mut self, id: usize) {
+        let dag = self.submitted_dags.remove(&id).unwrap();
+        let dag_index = self.dag_completion_times.len();
+        self.dag_action_counts.push(0);
+        self.dag_completion_times.push(None);
+        if let DagSharingPolicy::Priority(priorities) = &mut self.dag_sharing_policy {
+            priorities.push(0);
+        }
+
+        let data_offset = self.dag.get_data_items().len();
+        let task_offset = self.dag.append(&dag);
+        let new_tasks = (task_offset..self.dag.get_tasks().len()).collect::<Vec<_>>();
+        self.dag_remaining_tasks.push(new_tasks.len());
+        for &task_id in new_tasks.iter() {
+            self.task_dags.insert(task_id, dag_index);
+        }
+        self.init_data_items(data_offset..self.dag.get_data_items().len());
+        if self.trace_log_enabled {
+            self.trace_log.log_dag(&self.dag);
+        }
+        log_info!(
+            self.ctx,
+            "DAG {} arrived: {} tasks, {} data items",
+            dag_index,
+            new_tasks.len(),
+            dag.get_data_items().len()
+        );
+
+        let unscheduled = self.unschedule_pending_tasks();
+        log_info!(self.ctx, "rescheduling {} tasks", unscheduled);
+        let time = Instant::now();
+        let actions = self.scheduler.borrow_mut().on_new_tasks(
+            &new_tasks,
+            &self.dag,
+            System {
+                resources: &self.resources,
+                network: &self.network.borrow(),
+            },
+            self.config.clone(),
+            &self.ctx,
+        );
+        self.run_stats.add_scheduling_time(time.elapsed().as_secs_f64());
+        self.enqueue_replanned_actions(actions);
+    }
+
+    /// Registers the locations of DAG inputs and the DAG outputs among the given data items.
+    fn init_data_items(&mut self, data_items: Range<usize>) {
+        for id in data_items {
+            let data_item = self.dag.get_data_item(id);
+            if data_item.state == DataItemState::Ready {
+                assert!(data_item.producer.is_none(), "Non-input data item has Ready state");
+                if data_item.replicas.is_empty() {
+                    self.data_location.insert(id, self.id);
+                    self.resource_data_items.entry(self.id).or_default().insert(id);
+                }
+                for &replica in data_item.replicas.iter() {
+                    self.resource_data_items
+                        .entry(self.resources[replica].id)
+                        .or_default()
+                        .insert(id);
+                }
+            } else if data_item.consumers.is_empty() {
+                self.outputs.insert(id);
+            }
+        }
+    }
+
+    /// Re-plans the tasks which have not started yet.
+    ///
+    /// Scheduled tasks waiting in resource queues are returned to unscheduled state and the scheduler is invoked
+    /// as in the beginning of DAG execution, so it sees the current state of the DAG and resources.
+    /// Running and completed tasks are not affected, while the schedule actions for them are ignored.
+    /// The list schedulers plan the remaining tasks on all cores, taking into account that the cores of running tasks
+    /// are busy until their expected completion.
+    /// Pending data transfers to the resources of unscheduled tasks are cancelled, except the transfers
+    /// already in progress.
+    pub fn reschedule(&mut self) {
+        let unscheduled = self.unschedule_pending_tasks();
+        log_info!(self.ctx, "rescheduling {} tasks", unscheduled);
+
+        let time = Instant::now();
+        let actions = self.scheduler.borrow_mut().start(
+            &self.dag,
+            System {
+                resources: &self.resources,
+                network: &self.network.borrow(),
+            },
+            self.config.clone(),
+            &self.ctx,
+        );
+        self.run_stats.add_scheduling_time(time.elapsed().as_secs_f64());
+        self.enqueue_replanned_actions(actions);
+    }
+
+    /// Returns the tasks waiting in resource queues to unscheduled state and cancels pending data transfers
+    /// to their resources, returns the number of unscheduled tasks.
+    fn unschedule_pending_tasks(&mut self) -> usize {
+        for queues in self.resource_queue.iter_mut() {
+            for queue in queues.iter_mut() {
+                queue.clear();
+            }
+        }
+        let mut unscheduled = 0;
+        for task_id in 0..self.dag.get_tasks().len() {
+            if self.dag.unschedule_task(task_id) {
+                self.task_location.remove(&task_id);
+                unscheduled += 1;
+            }
+        }
+        for transfer_tasks in self.data_transfer_tasks.values_mut() {
+            for targets in transfer_tasks.values_mut() {
+                targets.retain(|&target| target == self.id);
+            }
+            transfer_tasks.retain(|_, targets| !targets.is_empty());
+        }
+        unscheduled
+    }
+
+    /// Enqueues the actions of re-planning, ignoring the actions for running and completed tasks.
+    fn enqueue_replanned_actions(&mut self, actions: Vec<Action>) {
+        let actions = actions
+            .into_iter()
+            .filter(|action| match action {
+                Action::ScheduleTask { task, .. } | Action::ScheduleTaskOnCores { task, .. } => {
+                    matches!(self.dag.get_task(*task).state, TaskState::Ready | TaskState::Pending)
+                }
+                Action::TransferData { .. } => true,
+            })
+            .collect();
+        self.enqueue_actions(actions);
         self.process_actions();
     }
 
     /// Returns true if the DAG execution is completed and false otherwise.
     pub fn is_completed(&self) -> bool {
-        self.dag.is_completed() && self.data_transfers.is_empty()
+        self.dag.is_completed() && self.data_transfers.is_empty() && self.submitted_dags.is_empty()
+    }
+
+    /// Returns true if the DAG execution is aborted due to a failure.
+    pub fn is_aborted(&self) -> bool {
+        self.aborted
     }
 
     /// Checks that all DAG tasks are completed.
@@ -231,6 +510,28 @@ impl DAGRunner {
         &self.run_stats
     }
 
+    /// Returns the busy fraction of cores of each resource over time as (time, fraction) pairs.
+    ///
+    /// Resources are identified by their indices, each value holds until the next pair.
+    pub fn resource_utilization_series(&self) -> HashMap<usize, Vec<(f64, f64)>> {
+        self.resources
+            .iter()
+            .enumerate()
+            .map(|(i, resource)| {
+                let cores = resource.compute.borrow().cores_total() as f64;
+                let series = match self.run_stats.used_cores_series(i) {
+                    [] => vec![(0., 0.)],
+                    series => series.iter().map(|&(time, used)| (time, used as f64 / cores)).collect(),
+                };
+                (i, series)
+            })
+            .collect()
+    }
+
+    pub(crate) fn resources(&self) -> &[Resource] {
+        &self.resources
+    }
+
     /// Returns makespan lower bound.
     pub fn makespan_lower_bound(&self) -> f64 {
         makespan_lower_bound(
@@ -243,6 +544,58 @@ impl DAGRunner {
         )
     }
 
+    fn initial_provision_state(resource: &Resource) -> ProvisionState {
+        if resource.provisioning.is_some() {
+            ProvisionState::Off
+        } else {
+            ProvisionState::On
+        }
+    }
+
+    /// Starts provisioning of on-demand resource if it is not provisioned yet.
+    fn provision_resource(&mut self, resource_idx: usize) {
+        if self.provision_states[resource_idx] != ProvisionState::Off {
+            return;
+        }
+        let provisioning = self.resources[resource_idx].provisioning.unwrap();
+        log_debug!(self.ctx, "provisioning resource {}", self.resources[resource_idx].name);
+        self.provision_states[resource_idx] = ProvisionState::Starting;
+        self.run_stats.add_provisioning_cost(provisioning.startup_cost);
+        self.run_stats.set_resource_power_on(resource_idx, self.ctx.time());
+        self.ctx.emit_self(
+            ResourceProvisioned { resource: resource_idx },
+            provisioning.startup_time,
+        );
+    }
+
+    fn on_resource_provisioned(&mut self, resource_idx: usize) {
+        log_debug!(
+            self.ctx,
+            "resource {} is provisioned",
+            self.resources[resource_idx].name
+        );
+        self.provision_states[resource_idx] = ProvisionState::On;
+        self.process_resource_queue(resource_idx);
+    }
+
+    /// Deprovisions all on-demand resources.
+    fn deprovision_resources(&mut self) {
+        for resource_idx in 0..self.resources.len() {
+            if let Some(provisioning) = self.resources[resource_idx].provisioning {
+                if self.provision_states[resource_idx] != ProvisionState::Off {
+                    log_debug!(
+                        self.ctx,
+                        "deprovisioning resource {}",
+                        self.resources[resource_idx].name
+                    );
+                    self.provision_states[resource_idx] = ProvisionState::Off;
+                    self.run_stats.add_provisioning_cost(provisioning.shutdown_cost);
+                    self.run_stats.set_resource_power_off(resource_idx, self.ctx.time());
+                }
+            }
+        }
+    }
+
     fn validate_input(&self) -> bool {
         if self.dag.get_tasks().iter().map(|task| task.min_cores).max()
             > self.resources.iter().map(|r| r.compute.borrow().cores_total()).max()
@@ -250,6 +603,26 @@ impl DAGRunner {
             log_error!(self.ctx, "some tasks require more cores than any resource can provide");
             return false;
         }
+        // tasks which are pairwise anti-affine need distinct resources
+        for (task_id, task) in self.dag.get_tasks().iter().enumerate() {
+            let mut clique = vec![task_id];
+            for &other in task.anti_affinity.iter() {
+                if clique
+                    .iter()
+                    .all(|t| self.dag.get_task(*t).anti_affinity.contains(&other))
+                {
+                    clique.push(other);
+                }
+            }
+            if clique.len() > self.resources.len() {
+                log_error!(
+                    self.ctx,
+                    "anti-affine tasks {:?} need more resources than available",
+                    clique
+                );
+                return false;
+            }
+        }
         true
     }
 
@@ -275,6 +648,7 @@ impl DAGRunner {
         need_cores: u32,
         allowed_cores: Vec<u32>,
         expected_span: Option<TimeSpan>,
+        to_front: bool,
     ) {
         let task_id = task;
         let task = self.dag.get_task(task_id);
@@ -288,6 +662,16 @@ impl DAGRunner {
             );
             return;
         }
+        if !self.dag.anti_affinity_allows(task_id, resource, |_| false) {
+            log_error!(
+                self.ctx,
+                "Wrong action, task {} can't run on resource {} because of anti-affinity with tasks {:?}",
+                task_id,
+                resource,
+                task.anti_affinity,
+            );
+            return;
+        }
         if need_cores > self.resources[resource].compute.borrow().cores_total() {
             log_error!(
                 self.ctx,
@@ -323,25 +707,25 @@ impl DAGRunner {
         }
         let data_items = self.dag.get_task(task_id).inputs.clone();
         self.task_location.insert(task_id, resource);
-        if self.config.data_transfer_mode == DataTransferMode::ViaMasterNode {
-            for &data_item_id in data_items.iter() {
-                self.add_data_transfer_task(data_item_id, self.id, self.resources[resource].id);
-            }
-        } else if self.config.data_transfer_mode == DataTransferMode::Direct {
-            for &data_item_id in data_items.iter() {
-                if let Some(location) = self.data_location.get(&data_item_id).cloned() {
-                    if location != self.resources[resource].id {
-                        self.add_data_transfer_task(data_item_id, location, self.resources[resource].id);
-                    }
+        self.dag.get_task_mut(task_id).resource = Some(resource);
+        for &data_item_id in data_items.iter() {
+            if let Some(source) = self.input_source(data_item_id, resource) {
+                if source != self.resources[resource].id {
+                    self.add_data_transfer_task(data_item_id, source, self.resources[resource].id);
                 }
             }
         }
         for core in allowed_cores.into_iter() {
-            self.resource_queue[resource][core as usize].push_back(QueuedTask {
+            let queued_task = QueuedTask {
                 task_id,
                 cores: need_cores,
                 action_id: self.action_id,
-            });
+            };
+            if to_front {
+                self.resource_queue[resource][core as usize].push_front(queued_task);
+            } else {
+                self.resource_queue[resource][core as usize].push_back(queued_task);
+            }
         }
         if let Some(time_span) = expected_span {
             log_debug!(
@@ -352,9 +736,43 @@ impl DAGRunner {
                 time_span.finish()
             );
         }
+        self.provision_resource(resource);
         self.process_resource_queue(resource);
     }
 
+    /// Adds scheduler actions to the queue, ordering them according to the DAG sharing policy.
+    fn enqueue_actions(&mut self, actions: Vec<Action>) {
+        if self.dag_action_counts.len() <= 1 {
+            self.actions.extend(actions);
+            return;
+        }
+        let mut keyed_actions = Vec::with_capacity(actions.len());
+        for action in actions {
+            let dag = match &action {
+                Action::ScheduleTask { task, .. } | Action::ScheduleTaskOnCores { task, .. } => {
+                    self.task_dags.get(task).cloned()
+                }
+                Action::TransferData { .. } => None,
+            };
+            // data transfers and auxiliary input/output tasks go first
+            let key = match dag {
+                Some(dag) => {
+                    self.dag_action_counts[dag] += 1;
+                    match &self.dag_sharing_policy {
+                        DagSharingPolicy::Fifo => (0, 0, dag),
+                        DagSharingPolicy::Priority(priorities) => (-priorities[dag], 0, dag),
+                        DagSharingPolicy::FairShare => (0, self.dag_action_counts[dag], dag),
+                    }
+                }
+                None => (i64::MIN, 0, 0),
+            };
+            keyed_actions.push((key, action));
+        }
+        // stable sort preserves the order of actions within each DAG
+        keyed_actions.sort_by_key(|(key, _)| *key);
+        self.actions.extend(keyed_actions.into_iter().map(|(_, action)| action));
+    }
+
     fn process_actions(&mut self) {
         for i in 0..self.resources.len() {
             self.process_resource_queue(i);
@@ -370,7 +788,7 @@ impl DAGRunner {
                 } => {
                     let allowed_cores =
                         (0..self.resources[resource].compute.borrow().cores_total()).collect::<Vec<_>>();
-                    self.process_schedule_action(task, resource, cores, allowed_cores, expected_span);
+                    self.process_schedule_action(task, resource, cores, allowed_cores, expected_span, false);
                 }
                 Action::ScheduleTaskOnCores {
                     task,
@@ -383,7 +801,7 @@ impl DAGRunner {
                         log_error!(self.ctx, "Wrong action, cores list {:?} contains same cores", cores);
                         return;
                     }
-                    self.process_schedule_action(task, resource, cores.len() as u32, cores, expected_span);
+                    self.process_schedule_action(task, resource, cores.len() as u32, cores, expected_span, false);
                 }
                 Action::TransferData { data_item, from, to } => {
                     self.add_data_transfer_task(data_item, from, to);
@@ -394,6 +812,9 @@ impl DAGRunner {
     }
 
     fn process_resource_queue(&mut self, resource_idx: usize) {
+        if self.provision_states[resource_idx] != ProvisionState::On {
+            return;
+        }
         while !self.resource_queue[resource_idx].is_empty() {
             let mut something_scheduled = false;
 
@@ -414,6 +835,9 @@ impl DAGRunner {
 
                 let queued_task = &self.resource_queue[resource_idx][core as usize][0];
                 let task = self.dag.get_task(queued_task.task_id);
+                if task.state != TaskState::Runnable {
+                    continue;
+                }
                 if task.memory > self.resources[resource_idx].memory_available {
                     continue;
                 }
@@ -533,6 +957,10 @@ impl DAGRunner {
     fn on_task_completed(&mut self, task_id: usize) {
         let task_name = self.dag.get_task(task_id).name.clone();
         self.run_stats.set_task_finish(task_id, self.ctx.time());
+        if let Some(deadline) = self.dag.get_task(task_id).deadline {
+            self.run_stats
+                .set_task_deadline_result(task_id, deadline, self.ctx.time());
+        }
         if self.trace_log_enabled {
             self.trace_log.log_event(
                 &self.ctx,
@@ -543,14 +971,12 @@ impl DAGRunner {
                 },
             );
         }
-        let location = *self.task_location.get(&task_id).unwrap();
-        let task_cores = self.task_cores.get(&task_id).unwrap();
-        self.resources[location].cores_available += task_cores.len() as u32;
-        for &core in task_cores.iter() {
-            self.available_cores[location].insert(core);
-        }
-        self.resources[location].memory_available += self.dag.get_task(task_id).memory;
+        let location = self.release_task_resources(task_id);
         self.dag.update_task_state(task_id, TaskState::Done);
+        self.dag.get_task_mut(task_id).finish_time = Some(self.ctx.time());
+        if let Some(&dag) = self.task_dags.get(&task_id) {
+            self.dag_remaining_tasks[dag] -= 1;
+        }
         let data_items = self.dag.get_task(task_id).outputs.clone();
 
         if self.config.data_transfer_mode != DataTransferMode::ViaMasterNode {
@@ -580,6 +1006,10 @@ impl DAGRunner {
 
             for &data_item_id in data_items.iter() {
                 for consumer in self.dag.get_data_item(data_item_id).consumers.clone().iter() {
+                    if matches!(self.dag.get_task(*consumer).state, TaskState::Running | TaskState::Done) {
+                        // the task is executed again after its outputs were lost
+                        continue;
+                    }
                     if let Some(consumer_location) = self.task_location.get(consumer).cloned() {
                         if location != consumer_location {
                             self.add_data_transfer_task(
@@ -604,9 +1034,23 @@ impl DAGRunner {
             }
         }
 
+        if let Some(loop_id) = self.dag.task_loop(task_id) {
+            if self.dag.is_loop_iteration_completed(loop_id) && self.dag.complete_loop_iteration(loop_id) {
+                self.restart_loop_body(loop_id);
+            }
+        }
+
+        // checked after the loop iteration is completed, since the next iteration resets the body tasks
+        if let Some(&dag) = self.task_dags.get(&task_id) {
+            if self.dag_remaining_tasks[dag] == 0 {
+                log_info!(self.ctx, "DAG {} is completed", dag);
+                self.dag_completion_times[dag] = Some(self.ctx.time());
+            }
+        }
+
         if !self.scheduler.borrow().is_static() {
             let time = Instant::now();
-            self.actions.extend(self.scheduler.borrow_mut().on_task_state_changed(
+            let actions = self.scheduler.borrow_mut().on_task_state_changed(
                 task_id,
                 TaskState::Done,
                 &self.dag,
@@ -615,14 +1059,65 @@ impl DAGRunner {
                     network: &self.network.borrow(),
                 },
                 &self.ctx,
-            ));
+            );
             self.run_stats.add_scheduling_time(time.elapsed().as_secs_f64());
+            self.enqueue_actions(actions);
         }
         self.process_actions();
 
         self.check_and_log_completed();
     }
 
+    /// Prepares the next iteration of the loop body.
+    ///
+    /// Data items produced by the previous iteration are forgotten. Dynamic schedulers get the reset body tasks
+    /// as ready tasks, while for static schedulers the placement of the previous iteration is reused.
+    fn restart_loop_body(&mut self, loop_id: usize) {
+        let body = self.dag.get_loop(loop_id).body.clone();
+        for &task_id in body.iter() {
+            self.count_reset_task(task_id);
+        }
+        log_debug!(
+            self.ctx,
+            "starting iteration {} of loop {}",
+            self.dag.get_loop(loop_id).completed_iterations() + 1,
+            loop_id
+        );
+        let body_outputs: HashSet<usize> = body
+            .iter()
+            .flat_map(|&task_id| self.dag.get_task(task_id).outputs.iter().cloned())
+            .collect();
+        for data_items in self.resource_data_items.values_mut() {
+            data_items.retain(|data_id| !body_outputs.contains(data_id));
+        }
+        for transfer_tasks in self.data_transfer_tasks.values_mut() {
+            transfer_tasks.retain(|data_id, _| !body_outputs.contains(data_id));
+        }
+        self.data_location.retain(|data_id, _| !body_outputs.contains(data_id));
+        for (&id, transfer) in self.data_transfers.iter() {
+            if body_outputs.contains(&transfer.data_id) {
+                self.discarded_transfers.insert(id);
+            }
+        }
+
+        if self.scheduler.borrow().is_static() {
+            let mut order = vec![0; self.dag.get_tasks().len()];
+            for (pos, task_id) in topsort(&self.dag).into_iter().enumerate() {
+                order[task_id] = pos;
+            }
+            let mut tasks = body.into_iter().collect::<Vec<_>>();
+            tasks.sort_by_key(|&task_id| order[task_id]);
+            // body tasks are put in front of other queued tasks, which may be waiting for the loop outputs
+            for &task_id in tasks.iter().rev() {
+                let resource = self.task_location[&task_id];
+                let cores = self.task_cores[&task_id].len() as u32;
+                let allowed_cores = (0..self.resources[resource].compute.borrow().cores_total()).collect::<Vec<_>>();
+                self.process_schedule_action(task_id, resource, cores, allowed_cores, None, true);
+                self.action_id += 1;
+            }
+        }
+    }
+
     fn start_task(&mut self, task_id: usize) {
         let task = self.dag.get_task(task_id);
         let location = *self.task_location.get(&task_id).unwrap();
@@ -636,6 +1131,21 @@ impl DAGRunner {
             self.id,
         );
         self.computations.insert(computation_id, task_id);
+        let task = self.dag.get_task_mut(task_id);
+        task.cores = self.task_cores[&task_id].clone();
+        task.start_time = Some(self.ctx.time());
+        let task = self.dag.get_task(task_id);
+        if let Some(FailureModel::Random { probability }) = self.failure_model {
+            if self.ctx.rand() < probability {
+                let exec_time = task.flops / self.resources[location].speed / task.cores_dependency.speedup(cores);
+                self.ctx.emit_self(
+                    TaskFailure {
+                        computation: computation_id,
+                    },
+                    self.ctx.rand() * exec_time,
+                );
+            }
+        }
 
         self.run_stats
             .set_task_start(task_id, location, cores, task.memory, self.ctx.time());
@@ -651,6 +1161,314 @@ impl DAGRunner {
         }
     }
 
+    /// Returns the cores and memory used by the task to the resource, returns the resource index.
+    fn release_task_resources(&mut self, task_id: usize) -> usize {
+        let location = *self.task_location.get(&task_id).unwrap();
+        let task_cores = self.task_cores.get(&task_id).unwrap();
+        self.resources[location].cores_available += task_cores.len() as u32;
+        for &core in task_cores.iter() {
+            self.available_cores[location].insert(core);
+        }
+        self.resources[location].memory_available += self.dag.get_task(task_id).memory;
+        location
+    }
+
+    fn on_task_failure(&mut self, computation: u64) {
+        let failed_task = self.fail_task(computation);
+        self.retry_tasks(vec![failed_task]);
+    }
+
+    fn on_resource_failure(&mut self, resource_idx: usize) {
+        if self.aborted || self.is_completed() {
+            return;
+        }
+        log_info!(self.ctx, "resource {} failed", self.resources[resource_idx].name);
+        let mut computations = self
+            .computations
+            .iter()
+            .filter(|(_, task_id)| self.task_location[task_id] == resource_idx)
+            .map(|(&computation, _)| computation)
+            .collect::<Vec<_>>();
+        computations.sort();
+        let mut tasks = computations
+            .into_iter()
+            .map(|computation| self.fail_task(computation))
+            .collect::<Vec<_>>();
+        tasks.extend(self.lose_resource_data(resource_idx));
+        self.retry_tasks(tasks);
+    }
+
+    /// Stops the failed task and returns it to the Ready state.
+    ///
+    /// Returns the task id and the index of resource where the task was running.
+    fn fail_task(&mut self, computation: u64) -> (usize, usize) {
+        let task_id = self.computations.remove(&computation).unwrap();
+        let location = self.release_task_resources(task_id);
+        self.resources[location]
+            .compute
+            .borrow_mut()
+            .cancel_computation(computation);
+        let task_name = self.dag.get_task(task_id).name.clone();
+        log_debug!(
+            self.ctx,
+            "task {} failed on resource {}",
+            task_name,
+            self.resources[location].name
+        );
+        self.run_stats.set_task_failed(task_id, self.ctx.time());
+        if self.trace_log_enabled {
+            self.trace_log.log_event(
+                &self.ctx,
+                TraceEvent::TaskFailed {
+                    time: self.ctx.time(),
+                    task_id,
+                    task_name,
+                },
+            );
+        }
+        self.task_location.remove(&task_id);
+        self.dag.reset_task(task_id);
+        (task_id, location)
+    }
+
+    /// Drops the data items stored on the failed resource and the data transfers from or to this resource.
+    ///
+    /// The completed tasks whose outputs are lost and still needed are returned to the Ready or Pending state.
+    /// Returns these tasks along with the indices of resources where they were executed.
+    fn lose_resource_data(&mut self, resource_idx: usize) -> Vec<(usize, usize)> {
+        let resource_id = self.resources[resource_idx].id;
+        let mut data_items = self.resource_data_items.remove(&resource_id).unwrap_or_default();
+        for (&id, transfer) in self.data_transfers.iter() {
+            if (transfer.from == resource_id || transfer.to == resource_id) && self.discarded_transfers.insert(id) {
+                data_items.insert(transfer.data_id);
+            }
+        }
+        for &data_id in data_items.iter() {
+            if self.dag.get_data_item(data_id).replicas.contains(&resource_idx) {
+                self.dag.remove_replica(data_id, resource_idx);
+                if self.dag.get_data_item(data_id).replicas.is_empty() {
+                    log_error!(self.ctx, "all replicas of data item {} are lost", data_id);
+                }
+            }
+            if self.data_location.get(&data_id) == Some(&resource_id) {
+                match self.find_data_copy(data_id) {
+                    Some(location) => self.data_location.insert(data_id, location),
+                    None => self.data_location.remove(&data_id),
+                };
+            }
+        }
+
+        // re-executed tasks need their inputs, which may be lost too
+        let mut lost_tasks = Vec::new();
+        loop {
+            let producers = data_items
+                .iter()
+                .filter(|&&data_id| self.is_data_lost(data_id))
+                .filter_map(|&data_id| self.dag.get_data_item(data_id).producer)
+                .filter(|&task_id| self.dag.get_task(task_id).state == TaskState::Done)
+                .collect::<BTreeSet<_>>();
+            if producers.is_empty() {
+                break;
+            }
+            for task_id in producers {
+                log_debug!(self.ctx, "outputs of task {} are lost", self.dag.get_task(task_id).name);
+                self.run_stats.set_task_outputs_lost(task_id);
+                let location = self.task_location.remove(&task_id).unwrap();
+                self.dag.reset_task(task_id);
+                self.count_reset_task(task_id);
+                lost_tasks.push((task_id, location));
+            }
+        }
+        lost_tasks
+    }
+
+    /// Counts the completed task which is reset to be executed again as remaining in its DAG.
+    fn count_reset_task(&mut self, task_id: usize) {
+        if let Some(&dag) = self.task_dags.get(&task_id) {
+            self.dag_remaining_tasks[dag] += 1;
+        }
+    }
+
+    /// Returns the location from which the task input is transferred to the resource,
+    /// or None if the input is not available yet or the transfers are ordered by the scheduler.
+    fn input_source(&self, data_item_id: usize, resource: usize) -> Option<Id> {
+        let data_item = self.dag.get_data_item(data_item_id);
+        match self.config.data_transfer_mode {
+            DataTransferMode::Manual => None,
+            _ if !data_item.replicas.is_empty() => data_item
+                .closest_replica(resource, &self.resources, &self.network.borrow())
+                .map(|(replica, _)| self.resources[replica].id),
+            DataTransferMode::ViaMasterNode => Some(self.id),
+            DataTransferMode::Direct => self.data_location.get(&data_item_id).cloned(),
+        }
+    }
+
+    /// Returns a location holding the copy of data item, preferring the runner.
+    fn find_data_copy(&self, data_id: usize) -> Option<Id> {
+        std::iter::once(self.id)
+            .chain(self.resources.iter().map(|resource| resource.id))
+            .find(|id| {
+                self.resource_data_items
+                    .get(id)
+                    .is_some_and(|data_items| data_items.contains(&data_id))
+            })
+    }
+
+    /// Checks whether the produced data item has no copies left, while it is needed by some consumer
+    /// which has not started yet or has to be uploaded as DAG output.
+    fn is_data_lost(&self, data_id: usize) -> bool {
+        let data_item = self.dag.get_data_item(data_id);
+        if data_item.state != DataItemState::Ready || data_item.producer.is_none() {
+            return false;
+        }
+        if self.find_data_copy(data_id).is_some()
+            || self
+                .data_transfers
+                .iter()
+                .any(|(id, transfer)| transfer.data_id == data_id && !self.discarded_transfers.contains(id))
+        {
+            return false;
+        }
+        self.outputs.contains(&data_id)
+            || data_item
+                .consumers
+                .iter()
+                .any(|&task_id| !matches!(self.dag.get_task(task_id).state, TaskState::Running | TaskState::Done))
+    }
+
+    /// Executes again the failed tasks and the tasks with lost outputs according to the retry policy.
+    fn retry_tasks(&mut self, mut tasks: Vec<(usize, usize)>) {
+        if tasks.is_empty() {
+            self.request_missing_inputs();
+            self.process_actions();
+            return;
+        }
+        match self.retry_policy {
+            RetryPolicy::SameResource => {
+                let mut order = vec![0; self.dag.get_tasks().len()];
+                for (pos, task_id) in topsort(&self.dag).into_iter().enumerate() {
+                    order[task_id] = pos;
+                }
+                tasks.sort_by_key(|&(task_id, _)| order[task_id]);
+                // retried tasks are put in front of other queued tasks, which may be waiting for their outputs
+                for &(task_id, resource) in tasks.iter().rev() {
+                    self.run_stats.add_task_retry();
+                    let state = match self.dag.get_task(task_id).state {
+                        TaskState::Ready => TaskState::Runnable,
+                        _ => TaskState::Scheduled,
+                    };
+                    self.dag.update_task_state(task_id, state);
+                    self.dag.get_task_mut(task_id).resource = Some(resource);
+                    self.task_location.insert(task_id, resource);
+                    let cores = self.task_cores[&task_id].len() as u32;
+                    for core in 0..self.resources[resource].compute.borrow().cores_total() {
+                        self.resource_queue[resource][core as usize].push_front(QueuedTask {
+                            task_id,
+                            cores,
+                            action_id: self.action_id,
+                        });
+                    }
+                    self.action_id += 1;
+                }
+                self.request_missing_inputs();
+                self.process_actions();
+            }
+            RetryPolicy::OtherResource => {
+                for &(task_id, resource) in tasks.iter() {
+                    self.run_stats.add_task_retry();
+                    if !self.exclude_resource(task_id, resource) {
+                        log_error!(
+                            self.ctx,
+                            "task {} can't be executed on resources other than {}",
+                            task_id,
+                            self.resources[resource].name
+                        );
+                        self.abort();
+                        return;
+                    }
+                }
+                self.reschedule();
+            }
+            RetryPolicy::Abort => {
+                self.abort();
+            }
+        }
+    }
+
+    /// Forbids the task to run on the resource, returns false if there are no other suitable resources.
+    fn exclude_resource(&mut self, task_id: usize, resource: usize) -> bool {
+        self.dag.get_task_mut(task_id).exclude_resource(resource);
+        let task = self.dag.get_task(task_id);
+        self.resources.iter().enumerate().any(|(i, resource)| {
+            task.is_allowed_on(i)
+                && resource.compute.borrow().cores_total() >= task.min_cores
+                && resource.compute.borrow().memory_total() >= task.memory
+        })
+    }
+
+    /// Requests the transfers of inputs which are missing on the resources of scheduled tasks,
+    /// e.g. after the inputs are lost due to a resource failure.
+    fn request_missing_inputs(&mut self) {
+        if self.config.data_transfer_mode == DataTransferMode::Manual {
+            return;
+        }
+        for task_id in 0..self.dag.get_tasks().len() {
+            if !matches!(
+                self.dag.get_task(task_id).state,
+                TaskState::Scheduled | TaskState::Runnable
+            ) {
+                continue;
+            }
+            let resource_idx = self.task_location[&task_id];
+            let target = self.resources[resource_idx].id;
+            for data_id in self.dag.get_task(task_id).inputs.clone() {
+                let requested = self
+                    .resource_data_items
+                    .get(&target)
+                    .is_some_and(|data_items| data_items.contains(&data_id))
+                    || self.data_transfers.iter().any(|(id, transfer)| {
+                        transfer.data_id == data_id && transfer.to == target && !self.discarded_transfers.contains(id)
+                    })
+                    || self
+                        .data_transfer_tasks
+                        .values()
+                        .any(|transfer_tasks| transfer_tasks.get(&data_id).is_some_and(|t| t.contains(&target)));
+                if requested {
+                    continue;
+                }
+                // in Direct mode lost data items are sent to the consumers when they are produced again
+                if self.config.data_transfer_mode == DataTransferMode::Direct
+                    && self.dag.get_data_item(data_id).state != DataItemState::Ready
+                {
+                    continue;
+                }
+                if let Some(source) = self.input_source(data_id, resource_idx) {
+                    if source != target {
+                        self.add_data_transfer_task(data_id, source, target);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Stops DAG execution after a failure which can't be handled according to the retry policy.
+    fn abort(&mut self) {
+        log_error!(self.ctx, "DAG execution is aborted due to failure");
+        self.aborted = true;
+        let mut computations = self.computations.keys().cloned().collect::<Vec<_>>();
+        computations.sort();
+        for computation in computations {
+            self.fail_task(computation);
+        }
+        self.actions.clear();
+        for queues in self.resource_queue.iter_mut() {
+            for queue in queues.iter_mut() {
+                queue.clear();
+            }
+        }
+        self.deprovision_resources();
+    }
+
     fn on_data_transfer_completed(&mut self, data_event_id: usize) {
         let data_transfer = self.data_transfers.remove(&data_event_id).unwrap();
         let data_id = data_transfer.data_id;
@@ -670,6 +1488,11 @@ impl DAGRunner {
             );
         }
 
+        if self.discarded_transfers.remove(&data_event_id) {
+            self.check_and_log_completed();
+            return;
+        }
+
         self.resource_data_items
             .entry(data_transfer.to)
             .or_default()
@@ -695,6 +1518,10 @@ impl DAGRunner {
 
     fn check_and_log_completed(&mut self) {
         if self.is_completed() {
+            if let Some(deadline) = self.dag.deadline() {
+                self.run_stats.set_dag_deadline_result(deadline, self.ctx.time());
+            }
+            self.deprovision_resources();
             self.run_stats.finalize(
                 self.ctx.time(),
                 System {
@@ -702,7 +1529,11 @@ impl DAGRunner {
                     network: &self.network.borrow(),
                 },
             );
-            log_info!(self.ctx, "finished DAG execution");
+            log_info!(
+                self.ctx,
+                "finished DAG execution, total cost: {:.3}",
+                self.run_stats.total_cost
+            );
         }
     }
 }
@@ -710,17 +1541,55 @@ impl DAGRunner {
 #[derive(Clone, Serialize)]
 pub struct Start {}
 
+#[derive(Clone, Serialize)]
+pub struct ResourceProvisioned {
+    resource: usize,
+}
+
+#[derive(Clone, Serialize)]
+pub struct TaskFailure {
+    computation: u64,
+}
+
+#[derive(Clone, Serialize)]
+pub struct DagArrived {
+    pub id: usize,
+}
+
+#[derive(Clone, Serialize)]
+pub struct ResourceFailure {
+    resource: usize,
+}
+
 impl EventHandler for DAGRunner {
     fn on(&mut self, event: Event) {
         cast!(match event.data {
             Start {} => {
                 self.start()
             }
+            ResourceProvisioned { resource } => {
+                self.on_resource_provisioned(resource);
+            }
+            TaskFailure { computation } => {
+                // the task may be already completed or failed with the resource
+                if self.computations.contains_key(&computation) {
+                    self.on_task_failure(computation);
+                }
+            }
+            ResourceFailure { resource } => {
+                self.on_resource_failure(resource);
+            }
+            DagArrived { id } => {
+                self.on_dag_arrived(id);
+            }
             CompStarted { .. } => {}
             CompFinished { id } => {
-                let task_id = self.computations.remove(&id).unwrap();
-                self.on_task_completed(task_id);
+                // the task may fail at the moment of its completion
+                if let Some(task_id) = self.computations.remove(&id) {
+                    self.on_task_completed(task_id);
+                }
             }
+            CompCancelled { .. } => {}
             DataTransferCompleted { dt } => {
                 self.on_data_transfer_completed(dt.id);
             }
@@ -11,15 +11,21 @@ use simcore::component::Id;
 use simcore::context::SimulationContext;
 
 use crate::dag::DAG;
+use crate::resource::ResourceConfig;
 use crate::runner::Config;
 use crate::system::System;
 use crate::task::TaskState;
 
+use crate::schedulers::budget::BudgetConstrainedScheduler;
 use crate::schedulers::dls::DlsScheduler;
 use crate::schedulers::dynamic_list::DynamicListScheduler;
 use crate::schedulers::heft::HeftScheduler;
+use crate::schedulers::local_search::LocalSearchScheduler;
 use crate::schedulers::lookahead::LookaheadScheduler;
+use crate::schedulers::pareto::ParetoScheduler;
+use crate::schedulers::partition::PartitionScheduler;
 use crate::schedulers::peft::PeftScheduler;
+use crate::schedulers::replay::ReplaySchedule;
 use crate::schedulers::simple_scheduler::SimpleScheduler;
 
 /// Represents an action ordered by the scheduler.
@@ -66,6 +72,38 @@ impl TimeSpan {
     }
 }
 
+/// Placement of a single task in a [`Schedule`].
+#[derive(Clone, Debug)]
+pub struct TaskAssignment {
+    pub task: usize,
+    /// Index of the resource.
+    pub resource: usize,
+    /// Indices of the used cores.
+    pub cores: Vec<u32>,
+    pub start: f64,
+    pub finish: f64,
+}
+
+/// Static schedule with its estimated makespan and cost.
+#[derive(Clone, Debug)]
+pub struct Schedule {
+    /// Task assignments ordered by start time.
+    pub assignments: Vec<TaskAssignment>,
+    pub makespan: f64,
+    /// Total price of the used core time.
+    pub cost: f64,
+}
+
+impl Schedule {
+    /// Checks whether this schedule is at least as good as the other one on both objectives and better on one of them.
+    pub fn dominates(&self, other: &Schedule) -> bool {
+        let eps = 1e-9;
+        self.makespan <= other.makespan + eps
+            && self.cost <= other.cost + eps
+            && (self.makespan < other.makespan - eps || self.cost < other.cost - eps)
+    }
+}
+
 /// Trait for implementing DAG scheduling algorithms.
 ///
 /// Includes callback methods which can return one or multiple actions corresponding to decisions
@@ -88,8 +126,42 @@ pub trait Scheduler {
         Vec::new()
     }
 
+    /// Called when new tasks are added to the running DAG execution, e.g. on arrival of a new DAG
+    /// (see [DAGRunner::submit_dag()](crate::runner::DAGRunner::submit_dag)).
+    ///
+    /// Before the call the tasks which have not started yet are returned to unscheduled state,
+    /// so the scheduler can re-plan them together with the new tasks. The actions for running
+    /// and completed tasks are ignored. By default the scheduler is started again on the current DAG.
+    fn on_new_tasks(
+        &mut self,
+        _tasks: &[usize],
+        dag: &DAG,
+        system: System,
+        config: Config,
+        ctx: &SimulationContext,
+    ) -> Vec<Action> {
+        self.start(dag, system, config, ctx)
+    }
+
     /// Should be true iff on_task_state_chaged always returns empty vector.
     fn is_static(&self) -> bool;
+
+    /// Builds a static schedule starting from the given one (e.g. obtained in a previous run).
+    ///
+    /// Useful for iterative tuning with local search schedulers, which improve the initial schedule instead of
+    /// building a new one from scratch. Returns `None` if the scheduler doesn't support warm start.
+    fn schedule_from(&mut self, _dag: &DAG, _resources: &[ResourceConfig], _initial: &Schedule) -> Option<Schedule> {
+        None
+    }
+
+    /// Builds a set of non-dominated static schedules trading off makespan vs cost,
+    /// ordered by increasing makespan (and decreasing cost).
+    ///
+    /// Allows to choose the operating point before DAG execution. Returns an empty vector
+    /// if the scheduler doesn't support multi-objective scheduling.
+    fn pareto_schedules(&self, _dag: &DAG, _resources: &[ResourceConfig]) -> Vec<Schedule> {
+        Vec::new()
+    }
 }
 
 pub type RcScheduler = Rc<RefCell<dyn Scheduler>>;
@@ -168,6 +240,13 @@ pub fn default_scheduler_resolver(params: &SchedulerParams) -> Option<RcSchedule
         "Lookahead" => Some(Rc::new(RefCell::new(LookaheadScheduler::from_params(params)))),
         "PEFT" => Some(Rc::new(RefCell::new(PeftScheduler::from_params(params)))),
         "DLS" => Some(Rc::new(RefCell::new(DlsScheduler::from_params(params)))),
+        "Pareto" => Some(Rc::new(RefCell::new(ParetoScheduler::from_params(params)))),
+        "Budget" => Some(Rc::new(RefCell::new(BudgetConstrainedScheduler::from_params(params)))),
+        "LocalSearch" => Some(Rc::new(RefCell::new(LocalSearchScheduler::from_params(params)))),
+        "Partition" => Some(Rc::new(RefCell::new(PartitionScheduler::from_params(params)))),
+        "Replay" => params
+            .get::<String, _>("file")
+            .map(|file| Rc::new(RefCell::new(ReplaySchedule::from_file(file))) as RcScheduler),
         _ => None,
     }
 }
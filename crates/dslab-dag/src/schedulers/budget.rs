@@ -0,0 +1,169 @@
+//! Budget-constrained scheduling.
+
+use simcore::context::SimulationContext;
+use simcore::{log_error, log_warn};
+
+use crate::dag::DAG;
+use crate::data_item::{DataTransferMode, DataTransferStrategy};
+use crate::resource::Resource;
+use crate::runner::Config;
+use crate::scheduler::{Action, Scheduler, SchedulerParams, TaskAssignment};
+use crate::schedulers::common::*;
+use crate::system::System;
+
+/// Budget-aware variant of HEFT which builds the fastest schedule whose cost fits into the given budget.
+///
+/// The cost of a task is its execution time multiplied by the resource cost per second
+/// (see [`Resource::cost`](crate::resource::Resource::cost)). The tasks are processed in HEFT order,
+/// and each task is assigned to the resource with the earliest finish time among the resources where the task
+/// cost doesn't exceed the task budget. The task budget is the remaining budget minus the minimal cost
+/// of the unscheduled tasks, so that the remaining tasks can always be executed on their cheapest resources.
+///
+/// If the budget is less than the minimal cost of executing the DAG, the budget is reported as infeasible
+/// (see [`is_feasible`](Self::is_feasible)) and each task is assigned to its cheapest resource.
+pub struct BudgetConstrainedScheduler {
+    budget: f64,
+    data_transfer_strategy: DataTransferStrategy,
+    feasible: bool,
+}
+
+impl BudgetConstrainedScheduler {
+    pub fn new(budget: f64) -> Self {
+        Self {
+            budget,
+            data_transfer_strategy: DataTransferStrategy::Eager,
+            feasible: true,
+        }
+    }
+
+    pub fn from_params(params: &SchedulerParams) -> Self {
+        Self {
+            budget: params.get("budget").unwrap_or(f64::INFINITY),
+            data_transfer_strategy: params
+                .get("data_transfer_strategy")
+                .unwrap_or(DataTransferStrategy::Eager),
+            feasible: true,
+        }
+    }
+
+    pub fn with_data_transfer_strategy(mut self, data_transfer_strategy: DataTransferStrategy) -> Self {
+        self.data_transfer_strategy = data_transfer_strategy;
+        self
+    }
+
+    /// Returns whether the budget covered the minimal cost of the tasks planned by the last scheduling.
+    pub fn is_feasible(&self) -> bool {
+        self.feasible
+    }
+
+    /// Returns the minimal cost of executing the DAG, i.e. the cost of executing each task on its cheapest resource.
+    pub fn min_cost(dag: &DAG, resources: &[Resource]) -> f64 {
+        (0..dag.get_tasks().len())
+            .map(|task_id| task_min_cost(task_id, dag, resources))
+            .sum()
+    }
+
+    fn schedule(&mut self, dag: &DAG, system: System, config: Config, ctx: &SimulationContext) -> Vec<Action> {
+        let resources = system.resources;
+        let network = system.network;
+
+        let avg_net_time = system.avg_net_time(ctx.id(), &config.data_transfer_mode);
+
+        let task_count = dag.get_tasks().len();
+
+        let task_ranks = calc_ranks(system.avg_flop_time(), avg_net_time, dag);
+        let mut task_ids = (0..task_count).collect::<Vec<_>>();
+        task_ids.sort_by(|&a, &b| task_ranks[b].total_cmp(&task_ranks[a]));
+
+        let mut schedule = PartialSchedule::new(dag, resources, ctx);
+        task_ids.retain(|&task_id| !schedule.started[task_id]);
+
+        let min_costs = (0..task_count)
+            .map(|task_id| task_min_cost(task_id, dag, resources))
+            .collect::<Vec<_>>();
+        let mut reserved_budget = task_ids.iter().map(|&task_id| min_costs[task_id]).sum::<f64>();
+        self.feasible = reserved_budget <= self.budget + 1e-9;
+        if !self.feasible {
+            log_error!(
+                ctx,
+                "budget {:.3} is infeasible, the minimal cost is {:.3}",
+                self.budget,
+                reserved_budget
+            );
+        }
+        let mut remaining_budget = self.budget;
+
+        plan_tasks(
+            &task_ids,
+            &mut schedule,
+            &self.data_transfer_strategy,
+            dag,
+            resources,
+            network,
+            &config,
+            ctx,
+            |task_id, candidates| {
+                reserved_budget -= min_costs[task_id];
+                let task_budget = remaining_budget - reserved_budget;
+                let cost = |c: &TaskAssignment| task_cost(task_id, c.resource, dag, resources);
+                // the fastest assignment within the task budget, otherwise the cheapest one
+                let best = candidates
+                    .iter()
+                    .filter(|c| cost(c) <= task_budget + 1e-9)
+                    .min_by(|a, b| a.finish.total_cmp(&b.finish).then(cost(a).total_cmp(&cost(b))))
+                    .or_else(|| {
+                        candidates
+                            .iter()
+                            .min_by(|a, b| cost(a).total_cmp(&cost(b)).then(a.finish.total_cmp(&b.finish)))
+                    })
+                    .unwrap()
+                    .clone();
+                remaining_budget -= cost(&best);
+                best
+            },
+        )
+    }
+}
+
+/// Returns the cost of executing the task on the resource.
+fn task_cost(task_id: usize, resource: usize, dag: &DAG, resources: &[Resource]) -> f64 {
+    let task = dag.get_task(task_id);
+    let exec_time = task.flops / resources[resource].speed / task.cores_dependency.speedup(task.min_cores);
+    exec_time * resources[resource].cost
+}
+
+/// Returns the minimal cost of the task among the resources which can execute it.
+fn task_min_cost(task_id: usize, dag: &DAG, resources: &[Resource]) -> f64 {
+    let task = dag.get_task(task_id);
+    (0..resources.len())
+        .filter(|&resource| {
+            task.is_allowed_on(resource)
+                && resources[resource].cores >= task.min_cores
+                && resources[resource].memory >= task.memory
+        })
+        .map(|resource| task_cost(task_id, resource, dag, resources))
+        .fold(f64::INFINITY, f64::min)
+}
+
+impl Scheduler for BudgetConstrainedScheduler {
+    fn start(&mut self, dag: &DAG, system: System, config: Config, ctx: &SimulationContext) -> Vec<Action> {
+        assert_ne!(
+            config.data_transfer_mode,
+            DataTransferMode::Manual,
+            "BudgetConstrainedScheduler doesn't support DataTransferMode::Manual"
+        );
+
+        if dag.get_tasks().iter().any(|task| task.min_cores != task.max_cores) {
+            log_warn!(
+                ctx,
+                "some tasks support different number of cores, but BudgetConstrainedScheduler will always use min_cores"
+            );
+        }
+
+        self.schedule(dag, system, config, ctx)
+    }
+
+    fn is_static(&self) -> bool {
+        true
+    }
+}
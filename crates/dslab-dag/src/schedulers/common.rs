@@ -7,9 +7,12 @@ use simcore::context::SimulationContext;
 use simcore::Id;
 
 use crate::dag::DAG;
-use crate::data_item::{DataTransferMode, DataTransferStrategy};
+use crate::data_item::{DataItem, DataTransferMode, DataTransferStrategy};
+use crate::resource::Resource;
 use crate::runner::Config;
+use crate::scheduler::{Action, TaskAssignment, TimeSpan};
 use crate::schedulers::treap::Treap;
+use crate::task::TaskState;
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct ScheduledTask {
@@ -45,18 +48,117 @@ impl PartialOrd for ScheduledTask {
 
 impl Eq for ScheduledTask {}
 
+/// Partial schedule built by the list scheduling algorithms.
+///
+/// Is initialized with the current state of DAG execution, so that only the remaining tasks are planned
+/// when the DAG is rescheduled. All cores of resources are planned, and each running task occupies its cores
+/// and memory from the current time until its expected completion. The completed and running tasks produce
+/// their outputs on their resources at their actual or expected completion times.
+pub struct PartialSchedule {
+    pub task_finish_times: Vec<f64>,
+    pub scheduled_tasks: Vec<Vec<BTreeSet<ScheduledTask>>>,
+    pub memory_usage: Vec<Treap>,
+    pub data_locations: HashMap<usize, Id>,
+    pub task_locations: HashMap<usize, Id>,
+    /// Whether the task is running or completed, such tasks are not planned again.
+    pub started: Vec<bool>,
+    /// Whether the resource is available without provisioning delay, i.e. it is not on-demand or has tasks placed on it.
+    pub provisioned: Vec<bool>,
+}
+
+impl PartialSchedule {
+    pub fn new(dag: &DAG, resources: &[Resource], ctx: &SimulationContext) -> Self {
+        let task_count = dag.get_tasks().len();
+        let mut schedule = Self {
+            task_finish_times: vec![0.; task_count],
+            scheduled_tasks: resources
+                .iter()
+                .map(|resource| (0..resource.cores).map(|_| BTreeSet::new()).collect())
+                .collect(),
+            memory_usage: (0..resources.len()).map(|_| Treap::new()).collect(),
+            data_locations: HashMap::new(),
+            task_locations: HashMap::new(),
+            started: vec![false; task_count],
+            provisioned: resources
+                .iter()
+                .map(|resource| resource.provisioning.is_none())
+                .collect(),
+        };
+        for (task_id, task) in dag.get_tasks().iter().enumerate() {
+            let Some(resource) = task.resource() else {
+                continue;
+            };
+            let finish_time = match task.state {
+                TaskState::Done => task.finish_time().unwrap(),
+                TaskState::Running => {
+                    let exec_time = task.flops
+                        / resources[resource].speed
+                        / task.cores_dependency.speedup(task.cores().len() as u32);
+                    let finish_time = (task.start_time().unwrap() + exec_time).max(ctx.time());
+                    for &core in task.cores().iter() {
+                        schedule.scheduled_tasks[resource][core as usize].insert(ScheduledTask::new(
+                            ctx.time(),
+                            finish_time,
+                            task_id,
+                        ));
+                    }
+                    schedule.memory_usage[resource].add(ctx.time(), finish_time, task.memory);
+                    finish_time
+                }
+                _ => continue,
+            };
+            schedule.started[task_id] = true;
+            schedule.provisioned[resource] = true;
+            schedule.task_finish_times[task_id] = finish_time;
+            for &output in task.outputs.iter() {
+                schedule.data_locations.insert(output, resources[resource].id);
+            }
+            schedule.task_locations.insert(task_id, resources[resource].id);
+        }
+        schedule
+    }
+
+    /// Adds the task planned on the given cores of resource.
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_task(
+        &mut self,
+        task_id: usize,
+        resource: usize,
+        start_time: f64,
+        finish_time: f64,
+        cores: &[u32],
+        dag: &DAG,
+        resources: &[Resource],
+    ) {
+        self.task_finish_times[task_id] = finish_time;
+        self.provisioned[resource] = true;
+        for &core in cores.iter() {
+            self.scheduled_tasks[resource][core as usize].insert(ScheduledTask::new(start_time, finish_time, task_id));
+        }
+        self.memory_usage[resource].add(start_time, finish_time, dag.get_task(task_id).memory);
+        for &output in dag.get_task(task_id).outputs.iter() {
+            self.data_locations.insert(output, resources[resource].id);
+        }
+        self.task_locations.insert(task_id, resources[resource].id);
+    }
+}
+
+/// Returns the time of transferring the replicated DAG input to the resource from its closest replica.
+fn replica_transfer_time(data_item: &DataItem, resource: usize, resources: &[Resource], network: &Network) -> f64 {
+    data_item
+        .closest_replica(resource, resources, network)
+        .map(|(_, time)| time)
+        .unwrap_or(0.)
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn evaluate_assignment(
     task_id: usize,
     resource: usize,
-    task_finish_times: &[f64],
-    scheduled_tasks: &[Vec<BTreeSet<ScheduledTask>>],
-    memory_usage: &[Treap],
-    data_location: &HashMap<usize, Id>,
-    task_location: &HashMap<usize, Id>,
+    schedule: &PartialSchedule,
     data_transfer_strategy: &DataTransferStrategy,
     dag: &DAG,
-    resources: &[crate::resource::Resource],
+    resources: &[Resource],
     network: &Network,
     config: &Config,
     ctx: &SimulationContext,
@@ -72,7 +174,15 @@ pub fn evaluate_assignment(
     if !dag.get_task(task_id).is_allowed_on(resource) {
         return None;
     }
+    if !dag.anti_affinity_allows(task_id, resource, |task| {
+        schedule.task_locations.get(&task) == Some(&resources[resource].id)
+    }) {
+        return None;
+    }
 
+    let task_finish_times = &schedule.task_finish_times;
+    let data_location = &schedule.data_locations;
+    let task_location = &schedule.task_locations;
     let data_transfer_mode = &config.data_transfer_mode;
 
     let start_time = match data_transfer_strategy {
@@ -96,8 +206,18 @@ pub fn evaluate_assignment(
                 // we ignore delays due to these parents, which makes start_time rather optimistic.
                 None => 0.,
             })
+            .chain(
+                // replicated DAG inputs are transferred from the closest replicas at the start of execution
+                dag.get_task(task_id)
+                    .inputs
+                    .iter()
+                    .map(|&id| dag.get_data_item(id))
+                    .filter(|&data_item| !data_item.replicas.is_empty())
+                    .map(|data_item| replica_transfer_time(data_item, resource, resources, network)),
+            )
             .max_by(|a, b| a.total_cmp(b))
-            .unwrap_or(0.),
+            .unwrap_or(0.)
+            .max(ctx.time()),
         DataTransferStrategy::Lazy => dag
             .get_task(task_id)
             .inputs
@@ -117,7 +237,13 @@ pub fn evaluate_assignment(
                 task_finish_times[task] + data_upload_time
             })
             .max_by(|a, b| a.total_cmp(b))
-            .unwrap_or(0.),
+            .unwrap_or(0.)
+            .max(ctx.time()),
+    };
+    // on-demand resource is provisioned when the first task is placed on it
+    let start_time = match resources[resource].provisioning {
+        Some(provisioning) if !schedule.provisioned[resource] => start_time.max(ctx.time() + provisioning.startup_time),
+        _ => start_time,
     };
 
     let download_time = match data_transfer_strategy {
@@ -127,6 +253,9 @@ pub fn evaluate_assignment(
             .inputs
             .iter()
             .map(|&f| match data_transfer_mode {
+                _ if !dag.get_data_item(f).replicas.is_empty() => {
+                    replica_transfer_time(dag.get_data_item(f), resource, resources, network)
+                }
                 DataTransferMode::ViaMasterNode => {
                     network.latency(ctx.id(), resources[resource].id)
                         + dag.get_data_item(f).size / network.bandwidth(ctx.id(), resources[resource].id)
@@ -150,13 +279,13 @@ pub fn evaluate_assignment(
         + download_time;
 
     let (start_time, cores) = find_earliest_slot(
-        &scheduled_tasks[resource],
+        &schedule.scheduled_tasks[resource],
         start_time,
         task_exec_time,
         need_cores,
         need_memory,
         resources[resource].compute.borrow().memory_total(),
-        &memory_usage[resource],
+        &schedule.memory_usage[resource],
     );
 
     assert!(cores.len() >= need_cores as usize);
@@ -166,6 +295,89 @@ pub fn evaluate_assignment(
     Some((start_time, start_time + task_exec_time, cores))
 }
 
+/// Plans the tasks one by one in the given order, as HEFT does, and returns the actions sorted by start time.
+///
+/// Each task is evaluated on all resources which can execute it, and `select` chooses one of these candidate
+/// assignments, which allows to restrict the used resources or to take the assignment cost into account.
+#[allow(clippy::too_many_arguments)]
+pub fn plan_tasks<F>(
+    task_ids: &[usize],
+    schedule: &mut PartialSchedule,
+    data_transfer_strategy: &DataTransferStrategy,
+    dag: &DAG,
+    resources: &[Resource],
+    network: &Network,
+    config: &Config,
+    ctx: &SimulationContext,
+    mut select: F,
+) -> Vec<Action>
+where
+    F: FnMut(usize, Vec<TaskAssignment>) -> TaskAssignment,
+{
+    let mut result: Vec<(f64, Action)> = Vec::new();
+
+    for &task_id in task_ids.iter() {
+        let candidates = (0..resources.len())
+            .filter_map(|resource| {
+                evaluate_assignment(
+                    task_id,
+                    resource,
+                    schedule,
+                    data_transfer_strategy,
+                    dag,
+                    resources,
+                    network,
+                    config,
+                    ctx,
+                )
+                .map(|(start, finish, cores)| TaskAssignment {
+                    task: task_id,
+                    resource,
+                    cores,
+                    start,
+                    finish,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        assert!(
+            !candidates.is_empty(),
+            "No suitable resource for task {}",
+            dag.get_task(task_id).name
+        );
+
+        let best = select(task_id, candidates);
+
+        schedule.add_task(
+            task_id,
+            best.resource,
+            best.start,
+            best.finish,
+            &best.cores,
+            dag,
+            resources,
+        );
+
+        result.push((
+            best.start,
+            Action::ScheduleTaskOnCores {
+                task: task_id,
+                resource: best.resource,
+                cores: best.cores,
+                expected_span: Some(TimeSpan::new(best.start, best.finish)),
+            },
+        ));
+    }
+
+    result.sort_by(|a, b| a.0.total_cmp(&b.0));
+    result.into_iter().map(|(_, b)| b).collect()
+}
+
+/// Returns the assignment with the earliest finish time, the first one is taken among the equal ones.
+pub fn earliest_finish_assignment(candidates: impl IntoIterator<Item = TaskAssignment>) -> Option<TaskAssignment> {
+    candidates.into_iter().min_by(|a, b| a.finish.total_cmp(&b.finish))
+}
+
 fn find_earliest_slot(
     scheduled_tasks: &[BTreeSet<ScheduledTask>],
     mut start_time: f64,
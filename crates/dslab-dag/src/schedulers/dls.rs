@@ -1,15 +1,11 @@
-use std::collections::{BTreeSet, HashMap};
-
 use simcore::context::SimulationContext;
 use simcore::log_warn;
-use simcore::Id;
 
 use crate::dag::DAG;
 use crate::data_item::{DataTransferMode, DataTransferStrategy};
 use crate::runner::Config;
 use crate::scheduler::{Action, Scheduler, SchedulerParams, TimeSpan};
 use crate::schedulers::common::*;
-use crate::schedulers::treap::Treap;
 use crate::system::System;
 
 pub struct DlsScheduler {
@@ -56,15 +52,9 @@ impl DlsScheduler {
         let mut task_ids = (0..task_count).collect::<Vec<_>>();
         task_ids.sort_by(|&a, &b| task_static_levels[b].total_cmp(&task_static_levels[a]));
 
-        let mut scheduled = vec![false; task_count];
-        let mut task_finish_times = vec![0.; task_count];
-        let mut scheduled_tasks: Vec<Vec<BTreeSet<ScheduledTask>>> = resources
-            .iter()
-            .map(|resource| (0..resource.cores_available).map(|_| BTreeSet::new()).collect())
-            .collect();
-        let mut memory_usage: Vec<Treap> = (0..resources.len()).map(|_| Treap::new()).collect();
-        let mut data_locations: HashMap<usize, Id> = HashMap::new();
-        let mut task_locations: HashMap<usize, Id> = HashMap::new();
+        let mut schedule = PartialSchedule::new(dag, resources, ctx);
+        let mut scheduled = schedule.started.clone();
+        task_ids.retain(|&task_id| !scheduled[task_id]);
 
         let mut result: Vec<(f64, Action)> = Vec::new();
 
@@ -86,11 +76,7 @@ impl DlsScheduler {
                     let res = evaluate_assignment(
                         task_id,
                         resource,
-                        &task_finish_times,
-                        &scheduled_tasks,
-                        &memory_usage,
-                        &data_locations,
-                        &task_locations,
+                        &schedule,
                         &self.data_transfer_strategy,
                         dag,
                         resources,
@@ -119,15 +105,7 @@ impl DlsScheduler {
             let (task_id, resource) = best_pair.unwrap();
 
             scheduled[task_id] = true;
-            task_finish_times[task_id] = best_finish;
-            for &core in best_cores.iter() {
-                scheduled_tasks[resource][core as usize].insert(ScheduledTask::new(best_start, best_finish, task_id));
-            }
-            memory_usage[resource].add(best_start, best_finish, dag.get_task(task_id).memory);
-            for &output in dag.get_task(task_id).outputs.iter() {
-                data_locations.insert(output, resources[resource].id);
-            }
-            task_locations.insert(task_id, resources[resource].id);
+            schedule.add_task(task_id, resource, best_start, best_finish, &best_cores, dag, resources);
 
             result.push((
                 best_start,
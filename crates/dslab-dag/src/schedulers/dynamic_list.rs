@@ -129,6 +129,7 @@ impl DynamicListScheduler {
             })
             .collect();
         let mut result: Vec<Action> = Vec::new();
+        let mut placed: HashMap<usize, usize> = HashMap::new();
 
         let avg_net_time = system.avg_net_time(ctx.id(), &DataTransferMode::Direct);
         let task_ranks = calc_ranks(system.avg_flop_time(), avg_net_time, dag);
@@ -219,6 +220,7 @@ impl DynamicListScheduler {
 
             let suitable_resources: Vec<usize> = (0..resources.len())
                 .filter(|&r| dag.get_task(task).is_allowed_on(r))
+                .filter(|&r| dag.anti_affinity_allows(task, r, |t| placed.get(&t) == Some(&r)))
                 .filter(|&r| {
                     resources[r].cores_available >= dag.get_task(task).min_cores
                         && resources[r].memory_available >= dag.get_task(task).memory
@@ -281,6 +283,7 @@ impl DynamicListScheduler {
 
             resources[best_resource].cores_available -= cores;
             resources[best_resource].memory_available -= dag.get_task(task).memory;
+            placed.insert(task, best_resource);
             result.push(Action::ScheduleTask {
                 task,
                 resource: best_resource,
@@ -328,6 +331,7 @@ impl DynamicListScheduler {
             })
             .collect();
         let mut result: Vec<Action> = Vec::new();
+        let mut placed: HashMap<usize, usize> = HashMap::new();
 
         let avg_net_time = system.avg_net_time(ctx.id(), &DataTransferMode::Direct);
         let task_ranks = calc_ranks(system.avg_flop_time(), avg_net_time, dag);
@@ -354,6 +358,7 @@ impl DynamicListScheduler {
                         .filter(|&t| {
                             resource.cores_available >= dag.get_task(*t).min_cores
                                 && resource.memory_available >= dag.get_task(*t).memory
+                                && dag.anti_affinity_allows(*t, resource.id, |o| placed.get(&o) == Some(&resource.id))
                         })
                         .sorted_by(|&a, &b| task_ranks[*b].total_cmp(&task_ranks[*a]))
                         .take(10)
@@ -368,6 +373,7 @@ impl DynamicListScheduler {
                         .filter(|&t| {
                             resource.cores_available >= dag.get_task(*t).min_cores
                                 && resource.memory_available >= dag.get_task(*t).memory
+                                && dag.anti_affinity_allows(*t, resource.id, |o| placed.get(&o) == Some(&resource.id))
                         })
                         .min_by(|&a, &b| match self.strategy.task_criterion {
                             TaskCriterion::RankPackMult => (task_ranks[*b] * dot_product(dag.get_task(*b), resource))
@@ -407,6 +413,7 @@ impl DynamicListScheduler {
 
                 resource.cores_available -= cores;
                 resource.memory_available -= task.memory;
+                placed.insert(task_id, resource.id);
                 result.push(Action::ScheduleTask {
                     task: task_id,
                     resource: resource.id,
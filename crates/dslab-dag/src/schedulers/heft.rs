@@ -1,15 +1,11 @@
-use std::collections::{BTreeSet, HashMap};
-
 use simcore::context::SimulationContext;
 use simcore::log_warn;
-use simcore::Id;
 
 use crate::dag::DAG;
 use crate::data_item::{DataTransferMode, DataTransferStrategy};
 use crate::runner::Config;
-use crate::scheduler::{Action, Scheduler, SchedulerParams, TimeSpan};
+use crate::scheduler::{Action, Scheduler, SchedulerParams};
 use crate::schedulers::common::*;
-use crate::schedulers::treap::Treap;
 use crate::system::System;
 
 pub struct HeftScheduler {
@@ -48,80 +44,20 @@ impl HeftScheduler {
         let mut task_ids = (0..task_count).collect::<Vec<_>>();
         task_ids.sort_by(|&a, &b| task_ranks[b].total_cmp(&task_ranks[a]));
 
-        let mut task_finish_times = vec![0.; task_count];
-        let mut scheduled_tasks: Vec<Vec<BTreeSet<ScheduledTask>>> = resources
-            .iter()
-            .map(|resource| (0..resource.cores_available).map(|_| BTreeSet::new()).collect())
-            .collect();
-        let mut memory_usage: Vec<Treap> = (0..resources.len()).map(|_| Treap::new()).collect();
-        let mut data_locations: HashMap<usize, Id> = HashMap::new();
-        let mut task_locations: HashMap<usize, Id> = HashMap::new();
-
-        let mut result: Vec<(f64, Action)> = Vec::new();
-
-        for task_id in task_ids.into_iter() {
-            let mut best_finish = -1.;
-            let mut best_start = -1.;
-            let mut best_resource = 0;
-            let mut best_cores: Vec<u32> = Vec::new();
-            for resource in 0..resources.len() {
-                let res = evaluate_assignment(
-                    task_id,
-                    resource,
-                    &task_finish_times,
-                    &scheduled_tasks,
-                    &memory_usage,
-                    &data_locations,
-                    &task_locations,
-                    &self.data_transfer_strategy,
-                    dag,
-                    resources,
-                    network,
-                    &config,
-                    ctx,
-                );
-                if res.is_none() {
-                    continue;
-                }
-                let (start_time, finish_time, cores) = res.unwrap();
-
-                if best_finish == -1. || best_finish > finish_time {
-                    best_start = start_time;
-                    best_finish = finish_time;
-                    best_resource = resource;
-                    best_cores = cores;
-                }
-            }
-
-            assert_ne!(best_finish, -1.);
-
-            task_finish_times[task_id] = best_finish;
-            for &core in best_cores.iter() {
-                scheduled_tasks[best_resource][core as usize].insert(ScheduledTask::new(
-                    best_start,
-                    best_finish,
-                    task_id,
-                ));
-            }
-            memory_usage[best_resource].add(best_start, best_finish, dag.get_task(task_id).memory);
-            for &output in dag.get_task(task_id).outputs.iter() {
-                data_locations.insert(output, resources[best_resource].id);
-            }
-            task_locations.insert(task_id, resources[best_resource].id);
-
-            result.push((
-                best_start,
-                Action::ScheduleTaskOnCores {
-                    task: task_id,
-                    resource: best_resource,
-                    cores: best_cores,
-                    expected_span: Some(TimeSpan::new(best_start, best_finish)),
-                },
-            ));
-        }
-
-        result.sort_by(|a, b| a.0.total_cmp(&b.0));
-        result.into_iter().map(|(_, b)| b).collect()
+        let mut schedule = PartialSchedule::new(dag, resources, ctx);
+        task_ids.retain(|&task_id| !schedule.started[task_id]);
+
+        plan_tasks(
+            &task_ids,
+            &mut schedule,
+            &self.data_transfer_strategy,
+            dag,
+            resources,
+            network,
+            &config,
+            ctx,
+            |_, candidates| earliest_finish_assignment(candidates).unwrap(),
+        )
     }
 }
 
@@ -143,6 +79,21 @@ impl Scheduler for HeftScheduler {
         self.schedule(dag, system, config, ctx)
     }
 
+    /// Re-ranks and plans the remaining tasks together with the new ones from the current time.
+    ///
+    /// The finish times and locations of completed and running tasks are taken from the current state
+    /// of execution, and the running tasks keep their cores busy until their expected completion.
+    fn on_new_tasks(
+        &mut self,
+        _tasks: &[usize],
+        dag: &DAG,
+        system: System,
+        config: Config,
+        ctx: &SimulationContext,
+    ) -> Vec<Action> {
+        self.schedule(dag, system, config, ctx)
+    }
+
     fn is_static(&self) -> bool {
         true
     }
@@ -0,0 +1,256 @@
+//! Local search scheduling which can be warm-started from a previous schedule.
+
+use simcore::context::SimulationContext;
+use simcore::log_warn;
+
+use crate::dag::DAG;
+use crate::data_item::DataTransferMode;
+use crate::resource::ResourceConfig;
+use crate::runner::Config;
+use crate::scheduler::{Action, Schedule, Scheduler, SchedulerParams, TaskAssignment, TimeSpan};
+use crate::schedulers::common::{calc_ranks, task_successors, topsort};
+use crate::system::System;
+
+/// Improves a static schedule by moving tasks between resources.
+///
+/// The schedule is defined by the task to resource mapping, the tasks are list-scheduled in HEFT order using
+/// `min_cores` cores. On each iteration the scheduler applies the move of a single task to another resource
+/// which improves the schedule the most, and stops when there is no improving move or after `max_iterations`
+/// iterations. Schedules are compared by makespan, and then by the sum of task finish times, which allows
+/// to escape plateaus where no single move reduces the makespan. Data transfer times are not taken into account.
+///
+/// By default the search starts from the mapping of each task to the first suitable resource which is not used
+/// by its anti-affine tasks, and the moves violating anti-affinity constraints are skipped. The search can be
+/// started from a prior schedule instead via [`Scheduler::schedule_from`] or [`Self::with_initial_schedule`].
+/// The tasks missing from the prior schedule or assigned to unsuitable resources get the default mapping.
+pub struct LocalSearchScheduler {
+    max_iterations: usize,
+    initial: Option<Schedule>,
+    iterations: usize,
+}
+
+impl LocalSearchScheduler {
+    pub fn new() -> Self {
+        Self {
+            max_iterations: 1000,
+            initial: None,
+            iterations: 0,
+        }
+    }
+
+    pub fn from_params(params: &SchedulerParams) -> Self {
+        Self {
+            max_iterations: params.get("max_iterations").unwrap_or(1000),
+            initial: None,
+            iterations: 0,
+        }
+    }
+
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Sets the schedule from which the search starts when the scheduler is used in the simulation.
+    pub fn with_initial_schedule(mut self, initial: Schedule) -> Self {
+        self.initial = Some(initial);
+        self
+    }
+
+    /// Returns the number of improving moves applied by the last search.
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    /// Runs the search from scratch.
+    pub fn schedule(&mut self, dag: &DAG, resources: &[ResourceConfig]) -> Schedule {
+        let mapping = Self::fill_mapping(dag, resources, vec![None; dag.get_tasks().len()]);
+        self.search(dag, resources, mapping)
+    }
+
+    /// Maps each unmapped task to the first suitable resource which is not used by its anti-affine tasks.
+    fn fill_mapping(dag: &DAG, resources: &[ResourceConfig], mut mapping: Vec<Option<usize>>) -> Vec<usize> {
+        for task_id in 0..dag.get_tasks().len() {
+            if mapping[task_id].is_some() {
+                continue;
+            }
+            let resource = *Self::allowed_resources(dag, resources, task_id)
+                .iter()
+                .find(|&&r| dag.anti_affinity_allows(task_id, r, |t| mapping[t] == Some(r)))
+                .unwrap_or_else(|| panic!("No suitable resource for task {}", dag.get_task(task_id).name));
+            mapping[task_id] = Some(resource);
+        }
+        mapping.into_iter().map(|resource| resource.unwrap()).collect()
+    }
+
+    fn allowed_resources(dag: &DAG, resources: &[ResourceConfig], task_id: usize) -> Vec<usize> {
+        let task = dag.get_task(task_id);
+        (0..resources.len())
+            .filter(|&r| {
+                task.is_allowed_on(r) && resources[r].cores >= task.min_cores && resources[r].memory >= task.memory
+            })
+            .collect()
+    }
+
+    fn search(&mut self, dag: &DAG, resources: &[ResourceConfig], mut mapping: Vec<usize>) -> Schedule {
+        let task_count = dag.get_tasks().len();
+        let avg_flop_time = resources.iter().map(|r| 1. / r.speed).sum::<f64>() / resources.len() as f64;
+        let ranks = calc_ranks(avg_flop_time, 0., dag);
+        let mut topo_pos = vec![0; task_count];
+        for (pos, task) in topsort(dag).into_iter().enumerate() {
+            topo_pos[task] = pos;
+        }
+        let mut order = (0..task_count).collect::<Vec<_>>();
+        order.sort_by(|&a, &b| ranks[b].total_cmp(&ranks[a]).then(topo_pos[a].cmp(&topo_pos[b])));
+        let allowed = (0..task_count)
+            .map(|task_id| Self::allowed_resources(dag, resources, task_id))
+            .collect::<Vec<_>>();
+
+        let mut best = Self::evaluate(dag, resources, &order, &mapping);
+        self.iterations = 0;
+        while self.iterations < self.max_iterations {
+            let mut best_move: Option<(usize, usize, Schedule)> = None;
+            for task_id in 0..task_count {
+                let current = mapping[task_id];
+                for &resource in allowed[task_id].iter().filter(|&&r| r != current) {
+                    if !dag.anti_affinity_allows(task_id, resource, |t| mapping[t] == resource) {
+                        continue;
+                    }
+                    mapping[task_id] = resource;
+                    let schedule = Self::evaluate(dag, resources, &order, &mapping);
+                    if Self::is_better(&schedule, best_move.as_ref().map(|m| &m.2).unwrap_or(&best)) {
+                        best_move = Some((task_id, resource, schedule));
+                    }
+                }
+                mapping[task_id] = current;
+            }
+            match best_move {
+                Some((task_id, resource, schedule)) => {
+                    mapping[task_id] = resource;
+                    best = schedule;
+                    self.iterations += 1;
+                }
+                None => break,
+            }
+        }
+        best
+    }
+
+    fn is_better(a: &Schedule, b: &Schedule) -> bool {
+        let eps = 1e-9;
+        let flowtime = |s: &Schedule| s.assignments.iter().map(|a| a.finish).sum::<f64>();
+        a.makespan < b.makespan - eps || (a.makespan < b.makespan + eps && flowtime(a) < flowtime(b) - eps)
+    }
+
+    fn evaluate(dag: &DAG, resources: &[ResourceConfig], order: &[usize], mapping: &[usize]) -> Schedule {
+        let mut ready_times = vec![0.; dag.get_tasks().len()];
+        let mut core_free_times: Vec<Vec<f64>> = resources.iter().map(|r| vec![0.; r.cores as usize]).collect();
+        let mut assignments = Vec::with_capacity(order.len());
+        let mut cost = 0.;
+
+        for &task_id in order {
+            let task = dag.get_task(task_id);
+            let resource = mapping[task_id];
+            let config = &resources[resource];
+            let free = &mut core_free_times[resource];
+            let mut cores = (0..free.len()).collect::<Vec<_>>();
+            cores.sort_by(|&a, &b| free[a].total_cmp(&free[b]));
+            cores.truncate(task.min_cores as usize);
+            let start = f64::max(ready_times[task_id], free[*cores.last().unwrap()]);
+            let exec_time = task.flops / config.speed / task.cores_dependency.speedup(task.min_cores);
+            let finish = start + exec_time;
+            for &core in cores.iter() {
+                free[core] = finish;
+            }
+            for (succ, _) in task_successors(task_id, dag) {
+                ready_times[succ] = f64::max(ready_times[succ], finish);
+            }
+            cost += exec_time * task.min_cores as f64 * config.price;
+            assignments.push(TaskAssignment {
+                task: task_id,
+                resource,
+                cores: cores.into_iter().map(|core| core as u32).collect(),
+                start,
+                finish,
+            });
+        }
+
+        assignments.sort_by(|a, b| a.start.total_cmp(&b.start));
+        let makespan = assignments.iter().map(|a| a.finish).fold(0., f64::max);
+        Schedule {
+            assignments,
+            makespan,
+            cost,
+        }
+    }
+}
+
+impl Scheduler for LocalSearchScheduler {
+    fn start(&mut self, dag: &DAG, system: System, config: Config, ctx: &SimulationContext) -> Vec<Action> {
+        assert_ne!(
+            config.data_transfer_mode,
+            DataTransferMode::Manual,
+            "LocalSearchScheduler doesn't support DataTransferMode::Manual"
+        );
+
+        if dag.get_tasks().iter().any(|task| task.min_cores != task.max_cores) {
+            log_warn!(
+                ctx,
+                "some tasks support different number of cores, but LocalSearchScheduler will always use min_cores"
+            );
+        }
+
+        let resources = system
+            .resources
+            .iter()
+            .map(|r| ResourceConfig {
+                name: r.name.clone(),
+                speed: r.speed,
+                cores: r.cores_available,
+                memory: r.memory_available,
+                price: r.price,
+                cost: r.cost,
+                provisioning: r.provisioning,
+            })
+            .collect::<Vec<_>>();
+        let schedule = match self.initial.take() {
+            Some(initial) => self.schedule_from(dag, &resources, &initial).unwrap(),
+            None => self.schedule(dag, &resources),
+        };
+        schedule
+            .assignments
+            .iter()
+            .map(|a| Action::ScheduleTaskOnCores {
+                task: a.task,
+                resource: a.resource,
+                cores: a.cores.clone(),
+                expected_span: Some(TimeSpan::new(a.start, a.finish)),
+            })
+            .collect()
+    }
+
+    fn is_static(&self) -> bool {
+        true
+    }
+
+    fn schedule_from(&mut self, dag: &DAG, resources: &[ResourceConfig], initial: &Schedule) -> Option<Schedule> {
+        let task_count = dag.get_tasks().len();
+        let mut mapping = vec![None; task_count];
+        for assignment in initial.assignments.iter() {
+            // assignments of unknown tasks or to unsuitable resources are ignored
+            if assignment.task < task_count
+                && Self::allowed_resources(dag, resources, assignment.task).contains(&assignment.resource)
+            {
+                mapping[assignment.task] = Some(assignment.resource);
+            }
+        }
+        let mapping = Self::fill_mapping(dag, resources, mapping);
+        Some(self.search(dag, resources, mapping))
+    }
+}
+
+impl Default for LocalSearchScheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,16 +1,13 @@
-use std::collections::{BTreeSet, HashMap};
 use std::str::FromStr;
 
 use simcore::context::SimulationContext;
 use simcore::log_warn;
-use simcore::Id;
 
 use crate::dag::DAG;
 use crate::data_item::{DataTransferMode, DataTransferStrategy};
 use crate::runner::Config;
 use crate::scheduler::{Action, Scheduler, SchedulerParams, TimeSpan};
 use crate::schedulers::common::*;
-use crate::schedulers::treap::Treap;
 use crate::system::System;
 
 pub enum DepthMode {
@@ -74,15 +71,9 @@ impl LookaheadScheduler {
         let mut task_ids = (0..task_count).collect::<Vec<_>>();
         task_ids.sort_by(|&a, &b| task_ranks[b].total_cmp(&task_ranks[a]));
 
-        let mut scheduled = vec![false; task_count];
-        let mut task_finish_times = vec![0.; task_count];
-        let mut scheduled_tasks: Vec<Vec<BTreeSet<ScheduledTask>>> = resources
-            .iter()
-            .map(|resource| (0..resource.cores_available).map(|_| BTreeSet::new()).collect())
-            .collect();
-        let mut memory_usage: Vec<Treap> = (0..resources.len()).map(|_| Treap::new()).collect();
-        let mut data_locations: HashMap<usize, Id> = HashMap::new();
-        let mut task_locations: HashMap<usize, Id> = HashMap::new();
+        let mut schedule = PartialSchedule::new(dag, resources, ctx);
+        let mut scheduled = schedule.started.clone();
+        task_ids.retain(|&task_id| !scheduled[task_id]);
 
         let mut result: Vec<(f64, Action)> = Vec::new();
 
@@ -96,11 +87,7 @@ impl LookaheadScheduler {
                 let res = evaluate_assignment(
                     task_id,
                     resource,
-                    &task_finish_times,
-                    &scheduled_tasks,
-                    &memory_usage,
-                    &data_locations,
-                    &task_locations,
+                    &schedule,
                     &self.data_transfer_strategy,
                     dag,
                     resources,
@@ -115,23 +102,11 @@ impl LookaheadScheduler {
                 let (start_time, finish_time, cores) = res.unwrap();
 
                 let mut to_undo: Vec<(usize, Vec<u32>, ScheduledTask)> = Vec::new();
-                let old_task_location = task_locations.clone();
-                let old_data_location = data_locations.clone();
-
-                for &core in cores.iter() {
-                    scheduled_tasks[resource][core as usize].insert(ScheduledTask::new(
-                        start_time,
-                        finish_time,
-                        task_id,
-                    ));
-                }
-                memory_usage[resource].add(start_time, finish_time, dag.get_task(task_id).memory);
-                task_finish_times[task_id] = finish_time;
+                let old_task_location = schedule.task_locations.clone();
+                let old_data_location = schedule.data_locations.clone();
+
+                schedule.add_task(task_id, resource, start_time, finish_time, &cores, dag, resources);
                 scheduled[task_id] = true;
-                for &output in dag.get_task(task_id).outputs.iter() {
-                    data_locations.insert(output, resources[resource].id);
-                }
-                task_locations.insert(task_id, resources[resource].id);
                 to_undo.push((
                     resource,
                     cores.clone(),
@@ -178,11 +153,7 @@ impl LookaheadScheduler {
                             let res = evaluate_assignment(
                                 task,
                                 resource,
-                                &task_finish_times,
-                                &scheduled_tasks,
-                                &memory_usage,
-                                &data_locations,
-                                &task_locations,
+                                &schedule,
                                 &self.data_transfer_strategy,
                                 dag,
                                 resources,
@@ -209,15 +180,7 @@ impl LookaheadScheduler {
                     };
 
                     scheduled[child] = true;
-                    task_finish_times[child] = finish;
-                    for &core in cores.iter() {
-                        scheduled_tasks[resource][core as usize].insert(ScheduledTask::new(start, finish, child));
-                    }
-                    memory_usage[resource].add(start, finish, dag.get_task(child).memory);
-                    for &output in dag.get_task(child).outputs.iter() {
-                        data_locations.insert(output, resources[resource].id);
-                    }
-                    task_locations.insert(child, resources[resource].id);
+                    schedule.add_task(child, resource, start, finish, &cores, dag, resources);
 
                     to_undo.push((resource, cores, ScheduledTask::new(start, finish, child)));
 
@@ -231,16 +194,16 @@ impl LookaheadScheduler {
                 for (resource, cores, scheduled_task) in to_undo.into_iter() {
                     scheduled[scheduled_task.task] = false;
                     for &core in cores.iter() {
-                        assert!(scheduled_tasks[resource][core as usize].remove(&scheduled_task));
+                        assert!(schedule.scheduled_tasks[resource][core as usize].remove(&scheduled_task));
                     }
-                    memory_usage[resource].remove(
+                    schedule.memory_usage[resource].remove(
                         scheduled_task.start_time,
                         scheduled_task.finish_time,
                         dag.get_task(scheduled_task.task).memory,
                     );
                 }
-                data_locations = old_data_location;
-                task_locations = old_task_location;
+                schedule.data_locations = old_data_location;
+                schedule.task_locations = old_task_location;
 
                 if best_makespan > makespan {
                     best_start = start_time;
@@ -254,19 +217,15 @@ impl LookaheadScheduler {
             assert_ne!(best_finish, -1.);
 
             scheduled[task_id] = true;
-            task_finish_times[task_id] = best_finish;
-            for &core in best_cores.iter() {
-                scheduled_tasks[best_resource][core as usize].insert(ScheduledTask::new(
-                    best_start,
-                    best_finish,
-                    task_id,
-                ));
-            }
-            for &output in dag.get_task(task_id).outputs.iter() {
-                data_locations.insert(output, resources[best_resource].id);
-            }
-            task_locations.insert(task_id, resources[best_resource].id);
-            memory_usage[best_resource].add(best_start, best_finish, dag.get_task(task_id).memory);
+            schedule.add_task(
+                task_id,
+                best_resource,
+                best_start,
+                best_finish,
+                &best_cores,
+                dag,
+                resources,
+            );
 
             result.push((
                 best_start,
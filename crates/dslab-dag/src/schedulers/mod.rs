@@ -1,11 +1,16 @@
 //! Scheduler implementations.
 
+pub mod budget;
 pub mod common;
 pub mod dls;
 pub mod dynamic_list;
 pub mod heft;
+pub mod local_search;
 pub mod lookahead;
+pub mod pareto;
+pub mod partition;
 pub mod peft;
+pub mod replay;
 pub mod simple_scheduler;
 pub mod simple_with_data;
 mod treap;
@@ -0,0 +1,220 @@
+//! Multi-objective scheduling trading off makespan vs cost.
+
+use simcore::context::SimulationContext;
+use simcore::log_warn;
+
+use crate::dag::DAG;
+use crate::data_item::DataTransferMode;
+use crate::resource::ResourceConfig;
+use crate::runner::Config;
+use crate::scheduler::{Action, Scheduler, SchedulerParams, TimeSpan};
+pub use crate::scheduler::{Schedule, TaskAssignment};
+use crate::schedulers::common::{calc_ranks, task_successors, topsort};
+use crate::system::System;
+
+/// Builds a set of Pareto-optimal schedules trading off makespan vs cost.
+///
+/// Each schedule is built by a list scheduling heuristic which processes tasks in HEFT order and assigns each task
+/// to the resource minimizing a weighted sum of the normalized finish time and cost of the task. The weight is
+/// varied from cost-only to makespan-only in `steps` steps, and the dominated schedules are filtered out.
+/// The resource price is charged per used core per unit of time. Data transfer times are not taken into account.
+///
+/// When used in the simulation, executes the schedule with minimal makespan whose cost fits into the `budget`
+/// (or the cheapest schedule if there is no such one).
+pub struct ParetoScheduler {
+    steps: usize,
+    budget: Option<f64>,
+}
+
+impl ParetoScheduler {
+    pub fn new() -> Self {
+        Self {
+            steps: 11,
+            budget: None,
+        }
+    }
+
+    pub fn from_params(params: &SchedulerParams) -> Self {
+        let scheduler = Self {
+            steps: 11,
+            budget: params.get("budget"),
+        };
+        scheduler.with_steps(params.get("steps").unwrap_or(11))
+    }
+
+    pub fn with_steps(mut self, steps: usize) -> Self {
+        assert!(steps >= 2, "ParetoScheduler needs at least two steps");
+        self.steps = steps;
+        self
+    }
+
+    pub fn with_budget(mut self, budget: f64) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    fn build_schedule(&self, dag: &DAG, resources: &[ResourceConfig], makespan_weight: f64) -> Schedule {
+        let task_count = dag.get_tasks().len();
+        let avg_flop_time = resources.iter().map(|r| 1. / r.speed).sum::<f64>() / resources.len() as f64;
+        let ranks = calc_ranks(avg_flop_time, 0., dag);
+        let mut topo_pos = vec![0; task_count];
+        for (pos, task) in topsort(dag).into_iter().enumerate() {
+            topo_pos[task] = pos;
+        }
+        let mut order = (0..task_count).collect::<Vec<_>>();
+        order.sort_by(|&a, &b| ranks[b].total_cmp(&ranks[a]).then(topo_pos[a].cmp(&topo_pos[b])));
+
+        let mut ready_times = vec![0.; task_count];
+        let mut core_free_times: Vec<Vec<f64>> = resources.iter().map(|r| vec![0.; r.cores as usize]).collect();
+        let mut assignments = Vec::with_capacity(task_count);
+        let mut mapping = vec![None; task_count];
+        let mut cost = 0.;
+
+        for task_id in order {
+            let task = dag.get_task(task_id);
+            // (resource, start, finish, cost)
+            let mut candidates: Vec<(usize, f64, f64, f64)> = Vec::new();
+            for (resource, config) in resources.iter().enumerate() {
+                if !task.is_allowed_on(resource) || config.cores < task.min_cores || config.memory < task.memory {
+                    continue;
+                }
+                if !dag.anti_affinity_allows(task_id, resource, |t| mapping[t] == Some(resource)) {
+                    continue;
+                }
+                let mut free = core_free_times[resource].clone();
+                free.sort_by(|a, b| a.total_cmp(b));
+                let start = f64::max(ready_times[task_id], free[task.min_cores as usize - 1]);
+                let exec_time = task.flops / config.speed / task.cores_dependency.speedup(task.min_cores);
+                let task_cost = exec_time * task.min_cores as f64 * config.price;
+                candidates.push((resource, start, start + exec_time, task_cost));
+            }
+            assert!(!candidates.is_empty(), "No suitable resource for task {}", task.name);
+
+            let normalize = |value: f64, min: f64, max: f64| {
+                if max - min > 1e-12 {
+                    (value - min) / (max - min)
+                } else {
+                    0.
+                }
+            };
+            let min_finish = candidates.iter().map(|c| c.2).fold(f64::INFINITY, f64::min);
+            let max_finish = candidates.iter().map(|c| c.2).fold(f64::NEG_INFINITY, f64::max);
+            let min_cost = candidates.iter().map(|c| c.3).fold(f64::INFINITY, f64::min);
+            let max_cost = candidates.iter().map(|c| c.3).fold(f64::NEG_INFINITY, f64::max);
+            let score = |c: &(usize, f64, f64, f64)| {
+                makespan_weight * normalize(c.2, min_finish, max_finish)
+                    + (1. - makespan_weight) * normalize(c.3, min_cost, max_cost)
+            };
+            let &(resource, start, finish, task_cost) = candidates
+                .iter()
+                .min_by(|a, b| {
+                    score(a)
+                        .total_cmp(&score(b))
+                        .then(a.2.total_cmp(&b.2))
+                        .then(a.3.total_cmp(&b.3))
+                })
+                .unwrap();
+
+            let free = &mut core_free_times[resource];
+            let mut cores = (0..free.len()).collect::<Vec<_>>();
+            cores.sort_by(|&a, &b| free[a].total_cmp(&free[b]));
+            cores.truncate(task.min_cores as usize);
+            for &core in cores.iter() {
+                free[core] = finish;
+            }
+            for (succ, _) in task_successors(task_id, dag) {
+                ready_times[succ] = f64::max(ready_times[succ], finish);
+            }
+            mapping[task_id] = Some(resource);
+            cost += task_cost;
+            assignments.push(TaskAssignment {
+                task: task_id,
+                resource,
+                cores: cores.into_iter().map(|core| core as u32).collect(),
+                start,
+                finish,
+            });
+        }
+
+        assignments.sort_by(|a, b| a.start.total_cmp(&b.start));
+        let makespan = assignments.iter().map(|a| a.finish).fold(0., f64::max);
+        Schedule {
+            assignments,
+            makespan,
+            cost,
+        }
+    }
+}
+
+impl Scheduler for ParetoScheduler {
+    fn start(&mut self, dag: &DAG, system: System, config: Config, ctx: &SimulationContext) -> Vec<Action> {
+        assert_ne!(
+            config.data_transfer_mode,
+            DataTransferMode::Manual,
+            "ParetoScheduler doesn't support DataTransferMode::Manual"
+        );
+
+        if dag.get_tasks().iter().any(|task| task.min_cores != task.max_cores) {
+            log_warn!(
+                ctx,
+                "some tasks support different number of cores, but ParetoScheduler will always use min_cores"
+            );
+        }
+
+        let resources = system
+            .resources
+            .iter()
+            .map(|r| ResourceConfig {
+                name: r.name.clone(),
+                speed: r.speed,
+                cores: r.cores_available,
+                memory: r.memory_available,
+                price: r.price,
+                cost: r.cost,
+                provisioning: r.provisioning,
+            })
+            .collect::<Vec<_>>();
+        let schedules = self.pareto_schedules(dag, &resources);
+        let schedule = match self.budget {
+            Some(budget) => schedules
+                .iter()
+                .find(|s| s.cost <= budget)
+                .unwrap_or_else(|| schedules.last().unwrap()),
+            None => schedules.first().unwrap(),
+        };
+        schedule
+            .assignments
+            .iter()
+            .map(|a| Action::ScheduleTaskOnCores {
+                task: a.task,
+                resource: a.resource,
+                cores: a.cores.clone(),
+                expected_span: Some(TimeSpan::new(a.start, a.finish)),
+            })
+            .collect()
+    }
+
+    fn is_static(&self) -> bool {
+        true
+    }
+
+    fn pareto_schedules(&self, dag: &DAG, resources: &[ResourceConfig]) -> Vec<Schedule> {
+        let mut schedules: Vec<Schedule> = (0..self.steps)
+            .map(|i| self.build_schedule(dag, resources, i as f64 / (self.steps - 1) as f64))
+            .collect();
+        schedules.sort_by(|a, b| a.makespan.total_cmp(&b.makespan).then(a.cost.total_cmp(&b.cost)));
+        let mut result: Vec<Schedule> = Vec::new();
+        for schedule in schedules {
+            if result.iter().all(|s| s.cost > schedule.cost + 1e-9) {
+                result.push(schedule);
+            }
+        }
+        result
+    }
+}
+
+impl Default for ParetoScheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,309 @@
+//! Locality-aware scheduling based on DAG partitioning.
+
+use std::collections::HashMap;
+
+use simcore::context::SimulationContext;
+use simcore::log_warn;
+
+use crate::dag::DAG;
+use crate::data_item::{DataTransferMode, DataTransferStrategy};
+use crate::runner::Config;
+use crate::scheduler::{Action, Scheduler, SchedulerParams};
+use crate::schedulers::common::*;
+use crate::system::System;
+
+/// Partitioning of DAG tasks into parts with balanced computational weight and minimal inter-part edge weight.
+///
+/// The weight of a task is its flops, the weight of an edge between two tasks is the total size of data items
+/// transferred between them. The target weight of each part is proportional to its capacity, and the weight
+/// of a part may exceed the target by at most `imbalance` fraction of it.
+///
+/// Parts are built by greedy graph growing: each part is grown from a seed task by adding the task most strongly
+/// connected to the part until the target weight is reached, the last part takes the remaining tasks.
+/// Then the partitioning is refined by moving single tasks to the parts they are most strongly connected to
+/// while this reduces the cut weight and keeps the parts balanced.
+#[derive(Clone, Debug)]
+pub struct GraphPartition {
+    parts: Vec<usize>,
+    part_count: usize,
+    cut_weight: f64,
+}
+
+impl GraphPartition {
+    pub fn new(dag: &DAG, capacities: &[f64], imbalance: f64) -> Self {
+        assert!(!capacities.is_empty(), "At least one part is required");
+        assert!(imbalance >= 0., "Imbalance must be non-negative");
+        let task_count = dag.get_tasks().len();
+        let part_count = capacities.len();
+
+        let mut weights = dag.get_tasks().iter().map(|t| t.flops).collect::<Vec<_>>();
+        if weights.iter().sum::<f64>() <= 0. {
+            weights.iter_mut().for_each(|w| *w = 1.);
+        }
+        let total_weight = weights.iter().sum::<f64>();
+        let total_capacity = capacities.iter().sum::<f64>();
+        let targets = capacities
+            .iter()
+            .map(|c| total_weight * c / total_capacity)
+            .collect::<Vec<_>>();
+        let limits = targets.iter().map(|t| t * (1. + imbalance)).collect::<Vec<_>>();
+
+        let mut edges: Vec<HashMap<usize, f64>> = vec![HashMap::new(); task_count];
+        for data_item in dag.get_data_items().iter() {
+            if let Some(producer) = data_item.producer {
+                for &consumer in data_item.consumers.iter() {
+                    *edges[producer].entry(consumer).or_default() += data_item.size;
+                    *edges[consumer].entry(producer).or_default() += data_item.size;
+                }
+            }
+        }
+
+        let mut topo_pos = vec![0; task_count];
+        for (pos, task) in topsort(dag).into_iter().enumerate() {
+            topo_pos[task] = pos;
+        }
+
+        // greedy graph growing
+        let mut parts = vec![part_count - 1; task_count];
+        let mut assigned = vec![false; task_count];
+        let mut part_weights = vec![0.; part_count];
+        for part in 0..part_count - 1 {
+            let mut connection = vec![0f64; task_count];
+            loop {
+                let next = (0..task_count).filter(|&t| !assigned[t]).min_by(|&a, &b| {
+                    connection[b]
+                        .total_cmp(&connection[a])
+                        .then(topo_pos[a].cmp(&topo_pos[b]))
+                });
+                let Some(task) = next else {
+                    break;
+                };
+                if part_weights[part] > 0. && part_weights[part] + weights[task] > limits[part] {
+                    break;
+                }
+                assigned[task] = true;
+                parts[task] = part;
+                part_weights[part] += weights[task];
+                for (&neighbour, &weight) in edges[task].iter() {
+                    connection[neighbour] += weight;
+                }
+                if part_weights[part] >= targets[part] {
+                    break;
+                }
+            }
+        }
+        for task in 0..task_count {
+            if !assigned[task] {
+                part_weights[part_count - 1] += weights[task];
+            }
+        }
+
+        // refinement by single task moves
+        let mut moved = true;
+        while moved {
+            moved = false;
+            for task in 0..task_count {
+                let current = parts[task];
+                let mut connection = vec![0.; part_count];
+                for (&neighbour, &weight) in edges[task].iter() {
+                    connection[parts[neighbour]] += weight;
+                }
+                let best = (0..part_count)
+                    .filter(|&p| p != current && part_weights[p] + weights[task] <= limits[p] + 1e-9)
+                    .max_by(|&a, &b| connection[a].total_cmp(&connection[b]).then(b.cmp(&a)));
+                if let Some(part) = best {
+                    if connection[part] > connection[current] + 1e-9 {
+                        parts[task] = part;
+                        part_weights[current] -= weights[task];
+                        part_weights[part] += weights[task];
+                        moved = true;
+                    }
+                }
+            }
+        }
+
+        let cut_weight = (0..task_count)
+            .flat_map(|task| {
+                edges[task]
+                    .iter()
+                    .map(move |(&neighbour, &weight)| (task, neighbour, weight))
+            })
+            .filter(|&(task, neighbour, _)| task < neighbour && parts[task] != parts[neighbour])
+            .map(|(_, _, weight)| weight)
+            .sum();
+
+        Self {
+            parts,
+            part_count,
+            cut_weight,
+        }
+    }
+
+    /// Returns the part of the task.
+    pub fn part(&self, task_id: usize) -> usize {
+        self.parts[task_id]
+    }
+
+    /// Returns the number of parts.
+    pub fn part_count(&self) -> usize {
+        self.part_count
+    }
+
+    /// Returns the tasks of the part.
+    pub fn part_tasks(&self, part: usize) -> Vec<usize> {
+        (0..self.parts.len()).filter(|&t| self.parts[t] == part).collect()
+    }
+
+    /// Returns the total weight of edges between tasks from different parts.
+    pub fn cut_weight(&self) -> f64 {
+        self.cut_weight
+    }
+}
+
+/// Schedules communication-heavy workflows with [`GraphPartition`] for data locality.
+///
+/// Resources are split into groups (by default each resource except the master one forms its own group),
+/// the DAG is partitioned into as many parts as there are groups with part capacities proportional
+/// to the total speed of group cores, and each part is assigned to the corresponding group.
+/// Then tasks are scheduled in HEFT order on the resource of their group which gives the earliest finish time
+/// (any resource is used if the task doesn't fit into its group).
+pub struct PartitionScheduler {
+    data_transfer_strategy: DataTransferStrategy,
+    resource_groups: Option<Vec<Vec<String>>>,
+    imbalance: f64,
+}
+
+impl PartitionScheduler {
+    pub fn new() -> Self {
+        Self {
+            data_transfer_strategy: DataTransferStrategy::Eager,
+            resource_groups: None,
+            imbalance: 0.1,
+        }
+    }
+
+    pub fn from_params(params: &SchedulerParams) -> Self {
+        Self {
+            data_transfer_strategy: params
+                .get("data_transfer_strategy")
+                .unwrap_or(DataTransferStrategy::Eager),
+            resource_groups: None,
+            imbalance: params.get("imbalance").unwrap_or(0.1),
+        }
+    }
+
+    pub fn with_data_transfer_strategy(mut self, data_transfer_strategy: DataTransferStrategy) -> Self {
+        self.data_transfer_strategy = data_transfer_strategy;
+        self
+    }
+
+    /// Sets resource groups as lists of resource names.
+    pub fn with_resource_groups(mut self, resource_groups: Vec<Vec<String>>) -> Self {
+        assert!(!resource_groups.is_empty(), "At least one resource group is required");
+        self.resource_groups = Some(resource_groups);
+        self
+    }
+
+    pub fn with_imbalance(mut self, imbalance: f64) -> Self {
+        self.imbalance = imbalance;
+        self
+    }
+
+    fn groups(&self, system: &System) -> Vec<Vec<usize>> {
+        let resources = system.resources;
+        match &self.resource_groups {
+            Some(groups) => groups
+                .iter()
+                .map(|names| {
+                    names
+                        .iter()
+                        .map(|name| {
+                            resources
+                                .iter()
+                                .position(|r| &r.name == name)
+                                .unwrap_or_else(|| panic!("Unknown resource {} in resource groups", name))
+                        })
+                        .collect()
+                })
+                .collect(),
+            None => (0..resources.len())
+                .filter(|&r| resources[r].name != "master")
+                .map(|r| vec![r])
+                .collect(),
+        }
+    }
+
+    fn schedule(&self, dag: &DAG, system: System, config: Config, ctx: &SimulationContext) -> Vec<Action> {
+        let resources = system.resources;
+        let network = system.network;
+
+        let groups = self.groups(&system);
+        let capacities = groups
+            .iter()
+            .map(|group| {
+                group
+                    .iter()
+                    .map(|&r| resources[r].speed * resources[r].cores as f64)
+                    .sum::<f64>()
+            })
+            .collect::<Vec<_>>();
+        let partition = GraphPartition::new(dag, &capacities, self.imbalance);
+
+        let avg_net_time = system.avg_net_time(ctx.id(), &config.data_transfer_mode);
+
+        let task_count = dag.get_tasks().len();
+
+        let task_ranks = calc_ranks(system.avg_flop_time(), avg_net_time, dag);
+        let mut task_ids = (0..task_count).collect::<Vec<_>>();
+        task_ids.sort_by(|&a, &b| task_ranks[b].total_cmp(&task_ranks[a]));
+
+        let mut schedule = PartialSchedule::new(dag, resources, ctx);
+        task_ids.retain(|&task_id| !schedule.started[task_id]);
+
+        plan_tasks(
+            &task_ids,
+            &mut schedule,
+            &self.data_transfer_strategy,
+            dag,
+            resources,
+            network,
+            &config,
+            ctx,
+            |task_id, candidates| {
+                let group = &groups[partition.part(task_id)];
+                earliest_finish_assignment(candidates.iter().filter(|c| group.contains(&c.resource)).cloned())
+                    .or_else(|| earliest_finish_assignment(candidates))
+                    .unwrap()
+            },
+        )
+    }
+}
+
+impl Scheduler for PartitionScheduler {
+    fn start(&mut self, dag: &DAG, system: System, config: Config, ctx: &SimulationContext) -> Vec<Action> {
+        assert_ne!(
+            config.data_transfer_mode,
+            DataTransferMode::Manual,
+            "PartitionScheduler doesn't support DataTransferMode::Manual"
+        );
+
+        if dag.get_tasks().iter().any(|task| task.min_cores != task.max_cores) {
+            log_warn!(
+                ctx,
+                "some tasks support different number of cores, but PartitionScheduler will always use min_cores"
+            );
+        }
+
+        self.schedule(dag, system, config, ctx)
+    }
+
+    fn is_static(&self) -> bool {
+        true
+    }
+}
+
+impl Default for PartitionScheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,204 @@
+//! Recording and replaying of scheduler decisions.
+
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use simcore::context::SimulationContext;
+
+use crate::dag::DAG;
+use crate::data_item::DataTransferMode;
+use crate::resource::ResourceConfig;
+use crate::runner::Config;
+use crate::scheduler::{Action, RcScheduler, Schedule, Scheduler};
+use crate::system::System;
+use crate::task::TaskState;
+
+/// Single decision to execute a task on a resource.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ScheduleDecision {
+    /// Time when the decision was made.
+    pub time: f64,
+    pub task: usize,
+    /// Name of the resource.
+    pub resource: String,
+    /// Number of used cores.
+    pub cores: u32,
+    /// Indices of the used cores if they were chosen by the scheduler.
+    pub core_ids: Option<Vec<u32>>,
+}
+
+/// Fixed schedule which replays recorded scheduler decisions.
+///
+/// The decisions can be recorded with [`ScheduleRecorder`] or produced by an external tool. All decisions are applied
+/// in the beginning of DAG execution in their order, so the tasks assigned to the same resource are executed
+/// in the order of the decisions. Resources are matched by name. Data transfers ordered by the original scheduler
+/// are not recorded, so DataTransferMode::Manual is not supported.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ReplaySchedule {
+    pub decisions: Vec<ScheduleDecision>,
+}
+
+impl ReplaySchedule {
+    pub fn new(decisions: Vec<ScheduleDecision>) -> Self {
+        Self { decisions }
+    }
+
+    /// Loads schedule from JSON file.
+    pub fn from_file<P: AsRef<Path>>(file: P) -> Self {
+        serde_json::from_str(
+            &std::fs::read_to_string(&file).unwrap_or_else(|_| panic!("Can't read file {}", file.as_ref().display())),
+        )
+        .unwrap_or_else(|e| panic!("Can't parse schedule from file {}: {e:?}", file.as_ref().display()))
+    }
+
+    /// Saves schedule to JSON file.
+    pub fn save_to_file(&self, filename: &str) -> Result<(), std::io::Error> {
+        File::create(filename)?.write_all(serde_json::to_string_pretty(self).unwrap().as_bytes())
+    }
+}
+
+impl Scheduler for ReplaySchedule {
+    fn start(&mut self, _dag: &DAG, system: System, config: Config, _ctx: &SimulationContext) -> Vec<Action> {
+        assert_ne!(
+            config.data_transfer_mode,
+            DataTransferMode::Manual,
+            "ReplaySchedule doesn't support DataTransferMode::Manual"
+        );
+        self.decisions
+            .iter()
+            .map(|decision| {
+                let resource = system
+                    .resources
+                    .iter()
+                    .position(|r| r.name == decision.resource)
+                    .unwrap_or_else(|| panic!("Unknown resource {} in replayed schedule", decision.resource));
+                match &decision.core_ids {
+                    Some(cores) => Action::ScheduleTaskOnCores {
+                        task: decision.task,
+                        resource,
+                        cores: cores.clone(),
+                        expected_span: None,
+                    },
+                    None => Action::ScheduleTask {
+                        task: decision.task,
+                        resource,
+                        cores: decision.cores,
+                        expected_span: None,
+                    },
+                }
+            })
+            .collect()
+    }
+
+    fn is_static(&self) -> bool {
+        true
+    }
+}
+
+/// Wraps a scheduler and records its decisions to execute tasks, which can be replayed later with [`ReplaySchedule`].
+///
+/// The assignments of schedules built with [`Scheduler::schedule_from()`] are recorded as the decisions made
+/// at time 0.
+pub struct ScheduleRecorder {
+    scheduler: RcScheduler,
+    decisions: Vec<ScheduleDecision>,
+}
+
+impl ScheduleRecorder {
+    pub fn new(scheduler: RcScheduler) -> Self {
+        Self {
+            scheduler,
+            decisions: Vec::new(),
+        }
+    }
+
+    /// Returns the recorded schedule.
+    pub fn schedule(&self) -> ReplaySchedule {
+        ReplaySchedule::new(self.decisions.clone())
+    }
+
+    fn record(&mut self, actions: &[Action], system: &System, ctx: &SimulationContext) {
+        for action in actions.iter() {
+            let (task, resource, cores, core_ids) = match action {
+                Action::ScheduleTask {
+                    task, resource, cores, ..
+                } => (*task, *resource, *cores, None),
+                Action::ScheduleTaskOnCores {
+                    task, resource, cores, ..
+                } => (*task, *resource, cores.len() as u32, Some(cores.clone())),
+                Action::TransferData { .. } => continue,
+            };
+            self.decisions.push(ScheduleDecision {
+                time: ctx.time(),
+                task,
+                resource: system.resources[resource].name.clone(),
+                cores,
+                core_ids,
+            });
+        }
+    }
+}
+
+impl Scheduler for ScheduleRecorder {
+    fn start(&mut self, dag: &DAG, system: System, config: Config, ctx: &SimulationContext) -> Vec<Action> {
+        let actions = self.scheduler.borrow_mut().start(dag, system, config, ctx);
+        self.record(&actions, &system, ctx);
+        actions
+    }
+
+    fn on_task_state_changed(
+        &mut self,
+        task: usize,
+        task_state: TaskState,
+        dag: &DAG,
+        system: System,
+        ctx: &SimulationContext,
+    ) -> Vec<Action> {
+        let actions = self
+            .scheduler
+            .borrow_mut()
+            .on_task_state_changed(task, task_state, dag, system, ctx);
+        self.record(&actions, &system, ctx);
+        actions
+    }
+
+    fn on_new_tasks(
+        &mut self,
+        tasks: &[usize],
+        dag: &DAG,
+        system: System,
+        config: Config,
+        ctx: &SimulationContext,
+    ) -> Vec<Action> {
+        let actions = self
+            .scheduler
+            .borrow_mut()
+            .on_new_tasks(tasks, dag, system, config, ctx);
+        self.record(&actions, &system, ctx);
+        actions
+    }
+
+    fn is_static(&self) -> bool {
+        self.scheduler.borrow().is_static()
+    }
+
+    fn schedule_from(&mut self, dag: &DAG, resources: &[ResourceConfig], initial: &Schedule) -> Option<Schedule> {
+        let schedule = self.scheduler.borrow_mut().schedule_from(dag, resources, initial)?;
+        self.decisions
+            .extend(schedule.assignments.iter().map(|assignment| ScheduleDecision {
+                time: 0.,
+                task: assignment.task,
+                resource: resources[assignment.resource].name.clone(),
+                cores: assignment.cores.len() as u32,
+                core_ids: Some(assignment.cores.clone()),
+            }));
+        Some(schedule)
+    }
+
+    fn pareto_schedules(&self, dag: &DAG, resources: &[ResourceConfig]) -> Vec<Schedule> {
+        self.scheduler.borrow().pareto_schedules(dag, resources)
+    }
+}
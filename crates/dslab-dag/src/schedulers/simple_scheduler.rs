@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use simcore::context::SimulationContext;
 
 use crate::dag::DAG;
@@ -29,11 +31,12 @@ impl SimpleScheduler {
             })
             .collect();
         let mut result: Vec<Action> = Vec::new();
+        let mut placed: HashMap<usize, usize> = HashMap::new();
         let ready_tasks = dag.get_ready_tasks().clone();
         for task_id in ready_tasks {
             let task = dag.get_task(task_id);
             for (i, resource) in resources.iter_mut().enumerate() {
-                if !task.is_allowed_on(i) {
+                if !task.is_allowed_on(i) || !dag.anti_affinity_allows(task_id, i, |t| placed.get(&t) == Some(&i)) {
                     continue;
                 }
                 if resource.cores_available < task.min_cores || resource.memory_available < task.memory {
@@ -42,6 +45,7 @@ impl SimpleScheduler {
                 let cores = resource.cores_available.min(task.max_cores);
                 resource.cores_available -= cores;
                 resource.memory_available -= task.memory;
+                placed.insert(task_id, i);
                 result.push(Action::ScheduleTask {
                     task: task_id,
                     resource: i,
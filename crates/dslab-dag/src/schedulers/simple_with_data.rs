@@ -36,11 +36,12 @@ impl SimpleDataScheduler {
             })
             .collect();
         let mut result: Vec<Action> = Vec::new();
+        let mut placed: HashMap<usize, usize> = HashMap::new();
         let ready_tasks = dag.get_ready_tasks().clone();
         for task_id in ready_tasks {
             let task = dag.get_task(task_id);
             for (i, resource) in resources.iter_mut().enumerate() {
-                if !task.is_allowed_on(i) {
+                if !task.is_allowed_on(i) || !dag.anti_affinity_allows(task_id, i, |t| placed.get(&t) == Some(&i)) {
                     continue;
                 }
                 if resource.cores_available < task.min_cores || resource.memory_available < task.memory {
@@ -49,6 +50,7 @@ impl SimpleDataScheduler {
                 let cores = resource.cores_available.min(task.max_cores);
                 resource.cores_available -= cores;
                 resource.memory_available -= task.memory;
+                placed.insert(task_id, i);
                 for &data_item in task.inputs.iter() {
                     if let Some(location) = self.data_location.get(&data_item) {
                         if *location != resource.id {
@@ -62,6 +62,15 @@ pub struct Task {
     pub outputs: Vec<usize>,
     pub(crate) ready_inputs: usize,
     pub resource_restriction: Option<ResourceRestriction>,
+    /// Soft deadline for task completion, which is used only to report tardiness.
+    pub deadline: Option<f64>,
+    /// Tasks which must not run on the same resource as this task.
+    pub anti_affinity: BTreeSet<usize>,
+    pub(crate) loop_id: Option<usize>,
+    pub(crate) resource: Option<usize>,
+    pub(crate) cores: Vec<u32>,
+    pub(crate) start_time: Option<f64>,
+    pub(crate) finish_time: Option<f64>,
 }
 
 impl Task {
@@ -86,6 +95,13 @@ impl Task {
             outputs: Vec::new(),
             ready_inputs: 0,
             resource_restriction: None,
+            deadline: None,
+            anti_affinity: BTreeSet::new(),
+            loop_id: None,
+            resource: None,
+            cores: Vec::new(),
+            start_time: None,
+            finish_time: None,
         }
     }
 
@@ -99,6 +115,39 @@ impl Task {
         self.outputs.push(data_item_id);
     }
 
+    /// Forbids execution of the task on the resource in addition to the existing restriction.
+    pub fn exclude_resource(&mut self, resource_id: usize) {
+        match &mut self.resource_restriction {
+            None => self.resource_restriction = Some(ResourceRestriction::Except(BTreeSet::from([resource_id]))),
+            Some(ResourceRestriction::Only(set)) => {
+                set.remove(&resource_id);
+            }
+            Some(ResourceRestriction::Except(set)) => {
+                set.insert(resource_id);
+            }
+        }
+    }
+
+    /// Returns the index of resource where the task is scheduled, running or completed.
+    pub fn resource(&self) -> Option<usize> {
+        self.resource
+    }
+
+    /// Returns the cores of resource used by the running or completed task.
+    pub fn cores(&self) -> &[u32] {
+        &self.cores
+    }
+
+    /// Returns the start time of the running or completed task.
+    pub fn start_time(&self) -> Option<f64> {
+        self.start_time
+    }
+
+    /// Returns the completion time of the completed task.
+    pub fn finish_time(&self) -> Option<f64> {
+        self.finish_time
+    }
+
     pub fn is_allowed_on(&self, resource_id: usize) -> bool {
         self.resource_restriction
             .as_ref()
@@ -32,6 +32,11 @@ pub enum Event {
         task_id: usize,
         task_name: String,
     },
+    TaskFailed {
+        time: f64,
+        task_id: usize,
+        task_name: String,
+    },
     StartUploading {
         time: f64,
         from: String,
@@ -55,6 +60,7 @@ impl Event {
             Event::TaskScheduled { time, .. }
             | Event::TaskStarted { time, .. }
             | Event::TaskCompleted { time, .. }
+            | Event::TaskFailed { time, .. }
             | Event::StartUploading { time, .. }
             | Event::FinishUploading { time, .. } => *time,
         }
@@ -72,6 +78,7 @@ impl Display for Event {
             } => write!(f, "scheduled task {task_name} to {location} on {cores} cores"),
             Event::TaskStarted { ref task_name, .. } => write!(f, "started task {task_name}"),
             Event::TaskCompleted { ref task_name, .. } => write!(f, "completed task {task_name}"),
+            Event::TaskFailed { ref task_name, .. } => write!(f, "failed task {task_name}"),
             Event::StartUploading {
                 ref data_name,
                 ref from,
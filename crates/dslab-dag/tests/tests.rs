@@ -1,24 +1,40 @@
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::rc::Rc;
 
 use rand::prelude::*;
 use rand_pcg::Pcg64;
 
 use dslab_compute::multicore::CoresDependency;
+use simcore::context::SimulationContext;
 use simcore::EPSILON;
 
+use dslab_dag::analysis::{critical_path, idle_intervals};
 use dslab_dag::dag::DAG;
 use dslab_dag::dag_simulation::DagSimulation;
 use dslab_dag::data_item::DataTransferMode;
+use dslab_dag::estimation::TaskCostEstimator;
+use dslab_dag::failure::{FailureModel, RetryPolicy};
+use dslab_dag::gantt::GanttChart;
 use dslab_dag::network::NetworkConfig;
-use dslab_dag::resource::ResourceConfig;
-use dslab_dag::runner::Config;
-use dslab_dag::scheduler::Scheduler;
+use dslab_dag::parsers::config::ParserConfig;
+use dslab_dag::power::{DvfsPowerModel, LinearPowerModel};
+use dslab_dag::resource::{Provisioning, ResourceConfig};
+use dslab_dag::robustness::CostPerturbation;
+use dslab_dag::runner::{Config, DagSharingPolicy};
+use dslab_dag::scheduler::{Action, Scheduler};
+use dslab_dag::schedulers::budget::BudgetConstrainedScheduler;
 use dslab_dag::schedulers::dls::DlsScheduler;
 use dslab_dag::schedulers::heft::HeftScheduler;
+use dslab_dag::schedulers::local_search::LocalSearchScheduler;
 use dslab_dag::schedulers::lookahead::LookaheadScheduler;
+use dslab_dag::schedulers::pareto::ParetoScheduler;
+use dslab_dag::schedulers::partition::{GraphPartition, PartitionScheduler};
 use dslab_dag::schedulers::peft::PeftScheduler;
+use dslab_dag::schedulers::replay::{ReplaySchedule, ScheduleDecision, ScheduleRecorder};
 use dslab_dag::schedulers::simple_scheduler::SimpleScheduler;
+use dslab_dag::system::System;
+use dslab_dag::trace_log::Event;
 
 const PRECISION: f64 = 1. / ((1 << 20) as f64);
 
@@ -98,10 +114,38 @@ fn gen_resources(rng: &mut Pcg64, num_resources: usize, infinite_memory: bool) -
             } else {
                 rng.gen_range(32..1024)
             },
+            price: 0.,
+            cost: 0.,
+            provisioning: None,
         })
         .collect()
 }
 
+/// Builds a DAG of four independent tasks with 100 flops whose outputs are consumed by a join task with 20 flops,
+/// and returns the DAG with the join task ID.
+fn fork_join_dag() -> (DAG, usize) {
+    let mut dag = DAG::new();
+    let join = dag.add_task("join", 20., 0, 1, 1, CoresDependency::Linear);
+    for i in 0..4 {
+        let task = dag.add_task(&format!("work{}", i), 100., 0, 1, 1, CoresDependency::Linear);
+        let data_id = dag.add_task_output(task, &format!("data{}", i), 1.);
+        dag.add_data_dependency(data_id, join);
+    }
+    (dag, join)
+}
+
+fn dual_core_resource(name: &str, speed: f64) -> ResourceConfig {
+    ResourceConfig {
+        name: name.to_string(),
+        speed,
+        cores: 2,
+        memory: 1024,
+        price: 0.,
+        cost: 0.,
+        provisioning: None,
+    }
+}
+
 fn gen_network(rng: &mut Pcg64) -> NetworkConfig {
     NetworkConfig::constant(rng.gen_range(0.0..1_000_000.0), rng.gen_range(0.0..1.0) * 1e6)
 }
@@ -394,3 +438,1263 @@ fn test_fork_join() {
     let result = sim.time();
     assert_float_eq(result, correct_result, EPSILON);
 }
+
+#[test]
+fn test_cost_estimation() {
+    let run = |prepare_flops: f64, process_flops: f64| {
+        let mut dag = DAG::new();
+        let prepare = dag.add_task("prepare", prepare_flops, 32, 1, 2, CoresDependency::Linear);
+        let process = dag.add_task("process", process_flops, 32, 1, 1, CoresDependency::Linear);
+        let data_id = dag.add_task_output(prepare, "data", 100.);
+        dag.add_data_dependency(data_id, process);
+
+        let mut sim = DagSimulation::new(
+            123,
+            Vec::new(),
+            NetworkConfig::constant(10., 0.),
+            Rc::new(RefCell::new(SimpleScheduler::new())),
+            Config {
+                data_transfer_mode: DataTransferMode::Direct,
+            },
+        );
+        sim.add_resource("0", 5., 2, 1024);
+        let runner = sim.init(dag);
+        sim.step_until_no_events();
+        assert!(runner.borrow().is_completed());
+        let trace = runner.borrow().trace_log().clone();
+        trace
+    };
+
+    let mut estimator = TaskCostEstimator::new();
+    estimator.add_trace(&run(20., 100.));
+    estimator.add_trace(&run(40., 200.));
+    assert_eq!(estimator.observation_count("prepare"), 2);
+    assert_float_eq(estimator.estimate("prepare").unwrap(), 30., EPSILON);
+    assert_float_eq(estimator.estimate("process").unwrap(), 150., EPSILON);
+    assert!(estimator.estimate("unknown").is_none());
+
+    let mut dag = DAG::new();
+    let prepare = dag.add_task("prepare", 1., 32, 1, 2, CoresDependency::Linear);
+    let process = dag.add_task("process", 1., 32, 1, 1, CoresDependency::Linear);
+    let unknown = dag.add_task("unknown", 1., 32, 1, 1, CoresDependency::Linear);
+    assert_eq!(estimator.apply(&mut dag), 2);
+    assert_float_eq(dag.get_task(prepare).flops, 30., EPSILON);
+    assert_float_eq(dag.get_task(process).flops, 150., EPSILON);
+    assert_eq!(dag.get_task(unknown).flops, 1.);
+
+    // tasks of the same type share the history
+    let mut estimator = TaskCostEstimator::new().with_task_type(|name| name.split('_').next().unwrap().to_string());
+    estimator.add_observation("stage_1", 10.);
+    estimator.add_observation("stage_2", 20.);
+    assert_float_eq(estimator.estimate("stage_3").unwrap(), 15., EPSILON);
+}
+
+#[test]
+fn test_deadline_tardiness() {
+    let mut dag = DAG::new();
+    for i in 0..4 {
+        dag.add_task(&i.to_string(), 10., 32, 1, 1, CoresDependency::Linear);
+    }
+    // four independent tasks take 2 seconds each on a single core, so the DAG completes at 8
+    dag.set_deadline(5.);
+    dag.set_task_deadline(0, 10.);
+    dag.set_task_deadline(3, 7.);
+
+    let mut sim = DagSimulation::new(
+        123,
+        Vec::new(),
+        NetworkConfig::constant(10., 0.),
+        Rc::new(RefCell::new(SimpleScheduler::new())),
+        Config {
+            data_transfer_mode: DataTransferMode::Direct,
+        },
+    );
+    sim.add_resource("0", 5., 1, 1024);
+    let runner = sim.init(dag);
+    sim.step_until_no_events();
+    assert!(runner.borrow().is_completed());
+    assert_float_eq(sim.time(), 8., EPSILON);
+
+    let runner = runner.borrow();
+    let stats = runner.run_stats();
+    assert_float_eq(stats.tardiness().unwrap(), 3., EPSILON);
+    assert_eq!(stats.deadline_met(), Some(false));
+    assert_eq!(stats.task_tardiness(0), Some(0.));
+    assert!(stats.task_tardiness(1).is_none());
+    assert_eq!(stats.tasks_with_deadline, 2);
+    assert_eq!(stats.missed_task_deadlines, 1);
+    assert_float_eq(stats.total_task_tardiness, 1., EPSILON);
+}
+
+#[test]
+fn test_pareto_schedules() {
+    let (dag, _) = fork_join_dag();
+    // fast resource is more expensive per flop than the slow one
+    let resources = vec![
+        ResourceConfig {
+            price: 8.,
+            ..dual_core_resource("fast", 10.)
+        },
+        ResourceConfig {
+            price: 1.,
+            ..dual_core_resource("slow", 2.)
+        },
+    ];
+    let scheduler = ParetoScheduler::new();
+    let schedules = scheduler.pareto_schedules(&dag, &resources);
+    assert!(schedules.len() >= 2);
+    for (i, a) in schedules.iter().enumerate() {
+        assert_eq!(a.assignments.len(), 5);
+        for b in schedules.iter().skip(i + 1) {
+            assert!(!a.dominates(b) && !b.dominates(a));
+        }
+    }
+    // the extreme points: everything on the fast resource vs everything on the slow one
+    let fastest = schedules.first().unwrap();
+    let cheapest = schedules.last().unwrap();
+    assert!(fastest.makespan < cheapest.makespan && fastest.cost > cheapest.cost);
+    assert_float_eq(cheapest.cost, 4. * 50. + 10., EPSILON);
+    assert_float_eq(cheapest.makespan, 2. * 50. + 10., EPSILON);
+    // single-objective schedulers don't provide a Pareto set
+    assert!(HeftScheduler::new().pareto_schedules(&dag, &resources).is_empty());
+
+    let run = |scheduler: ParetoScheduler| {
+        let mut sim = DagSimulation::new(
+            123,
+            resources.clone(),
+            NetworkConfig::constant(1e6, 0.),
+            Rc::new(RefCell::new(scheduler)),
+            Config {
+                data_transfer_mode: DataTransferMode::Direct,
+            },
+        );
+        let runner = sim.init(dag.clone());
+        sim.step_until_no_events();
+        assert!(runner.borrow().is_completed());
+        sim.time()
+    };
+    let budget = cheapest.cost + 1.;
+    assert!(run(ParetoScheduler::new()) < run(ParetoScheduler::new().with_budget(budget)));
+}
+
+#[test]
+fn test_budget_constrained_scheduler() {
+    let (dag, _) = fork_join_dag();
+    // fast resource is more expensive per flop than the slow one
+    let resources = vec![
+        ResourceConfig {
+            cost: 8.,
+            ..dual_core_resource("fast", 10.)
+        },
+        ResourceConfig {
+            cost: 1.,
+            ..dual_core_resource("slow", 2.)
+        },
+    ];
+
+    let run = |budget: f64| {
+        let scheduler = Rc::new(RefCell::new(BudgetConstrainedScheduler::new(budget)));
+        let mut sim = DagSimulation::new(
+            123,
+            resources.clone(),
+            NetworkConfig::constant(1e6, 0.),
+            scheduler.clone(),
+            Config {
+                data_transfer_mode: DataTransferMode::Direct,
+            },
+        );
+        let runner = sim.init(dag.clone());
+        sim.step_until_no_events();
+        assert!(runner.borrow().is_completed());
+        let total_cost = runner.borrow().run_stats().total_cost;
+        let feasible = scheduler.borrow().is_feasible();
+        (sim.time(), total_cost, feasible)
+    };
+
+    // everything on the slow resource
+    let min_cost = 4. * 50. + 10.;
+    let (unlimited_makespan, unlimited_cost, unlimited_feasible) = run(f64::INFINITY);
+    let (limited_makespan, limited_cost, limited_feasible) = run(min_cost + 100.);
+    let (tight_makespan, tight_cost, tight_feasible) = run(min_cost);
+    let (infeasible_makespan, infeasible_cost, infeasible_feasible) = run(min_cost / 2.);
+    assert!(unlimited_feasible && limited_feasible && tight_feasible);
+    assert!(!infeasible_feasible);
+    assert_float_eq(tight_cost, min_cost, EPSILON);
+    assert_float_eq(tight_makespan, infeasible_makespan, EPSILON);
+    assert!(unlimited_makespan < limited_makespan && limited_makespan < infeasible_makespan);
+    assert!(unlimited_cost > min_cost + 100.);
+    assert!(limited_cost <= min_cost + 100. + EPSILON);
+    assert_float_eq(infeasible_cost, min_cost, EPSILON);
+    assert_float_eq(infeasible_makespan, 2. * 50. + 10., EPSILON);
+}
+
+#[test]
+fn test_bounded_loop() {
+    let run = |scheduler: Rc<RefCell<dyn Scheduler>>, converged: Option<fn(u32) -> bool>| {
+        // prepare -> (a -> b) x 3 -> finish
+        let mut dag = DAG::new();
+        let prepare = dag.add_task("prepare", 10., 0, 1, 1, CoresDependency::Linear);
+        let a = dag.add_task("a", 10., 0, 1, 1, CoresDependency::Linear);
+        let b = dag.add_task("b", 20., 0, 1, 1, CoresDependency::Linear);
+        let finish = dag.add_task("finish", 10., 0, 1, 1, CoresDependency::Linear);
+        let data = dag.add_task_output(prepare, "data", 0.);
+        dag.add_data_dependency(data, a);
+        let partial = dag.add_task_output(a, "partial", 0.);
+        dag.add_data_dependency(partial, b);
+        let result = dag.add_task_output(b, "result", 0.);
+        dag.add_data_dependency(result, finish);
+        let body = dag.add_loop(&[a, b], 3);
+        if let Some(converged) = converged {
+            dag.set_loop_convergence(body, converged);
+        }
+
+        let mut sim = DagSimulation::new(
+            123,
+            Vec::new(),
+            NetworkConfig::constant(100., 0.),
+            scheduler,
+            Config {
+                data_transfer_mode: DataTransferMode::Direct,
+            },
+        );
+        sim.add_resource("0", 10., 1, 1024);
+        sim.add_resource("1", 10., 1, 1024);
+        let runner = sim.init(dag);
+        sim.step_until_no_events();
+        assert!(runner.borrow().is_completed());
+        let runner = runner.borrow();
+        let body_runs = runner
+            .trace_log()
+            .events
+            .iter()
+            .filter(|e| matches!(e, Event::TaskCompleted { task_name, .. } if task_name == "a" || task_name == "b"))
+            .count();
+        (body_runs, sim.time(), runner.run_stats().total_task_time)
+    };
+
+    let schedulers: Vec<Rc<RefCell<dyn Scheduler>>> = vec![
+        Rc::new(RefCell::new(SimpleScheduler::new())),
+        Rc::new(RefCell::new(HeftScheduler::new())),
+    ];
+    for scheduler in schedulers {
+        let (body_runs, makespan, total_task_time) = run(scheduler, None);
+        // body costs 1 + 2 seconds per iteration, prepare and finish take 1 second each
+        assert_eq!(body_runs, 6);
+        assert_float_eq(total_task_time, 2. + 3. * 3., EPSILON);
+        assert_float_eq(makespan, 2. + 3. * 3., EPSILON);
+    }
+
+    let (body_runs, makespan, _) = run(Rc::new(RefCell::new(HeftScheduler::new())), Some(|iter| iter >= 2));
+    assert_eq!(body_runs, 4);
+    assert_float_eq(makespan, 2. + 2. * 3., EPSILON);
+}
+
+#[test]
+fn test_reschedule_with_new_resource() {
+    let mut dag = DAG::new();
+    for i in 0..4 {
+        dag.add_task(&format!("task{}", i), 10., 0, 1, 1, CoresDependency::Linear);
+    }
+
+    let mut sim = DagSimulation::new(
+        123,
+        Vec::new(),
+        NetworkConfig::constant(100., 0.),
+        Rc::new(RefCell::new(HeftScheduler::new())),
+        Config {
+            data_transfer_mode: DataTransferMode::Direct,
+        },
+    );
+    sim.add_resource("0", 10., 1, 1024);
+    let runner = sim.init(dag);
+    // the first task is completed and the second one is running on the only resource
+    sim.step_for_duration(1.5);
+    sim.add_resource("1", 10., 1, 1024);
+    sim.reschedule_from(1.5);
+    sim.step_until_no_events();
+
+    let runner = runner.borrow();
+    assert!(runner.is_completed());
+    // one of the remaining tasks runs on the new resource, and the other one waits for the running task
+    assert_float_eq(sim.time(), 3., EPSILON);
+    let mut locations = Vec::new();
+    let mut finish_times = Vec::new();
+    for event in runner.trace_log().events.iter() {
+        match event {
+            Event::TaskScheduled { task_id, location, .. } => locations.push((*task_id, location.clone())),
+            Event::TaskCompleted { task_id, time, .. } => finish_times.push((*task_id, *time)),
+            _ => {}
+        }
+    }
+    locations.sort();
+    finish_times.sort_by_key(|(task_id, _)| *task_id);
+    let locations = locations.into_iter().map(|(_, location)| location).collect::<Vec<_>>();
+    assert_eq!(locations, vec!["0", "0", "1", "0"]);
+    for (task_id, expected) in [(0, 1.), (1, 2.), (2, 2.5), (3, 3.)] {
+        assert_eq!(finish_times[task_id].0, task_id);
+        assert_float_eq(finish_times[task_id].1, expected, EPSILON);
+    }
+}
+
+#[test]
+fn test_resource_failure() {
+    let run = |policy: RetryPolicy| {
+        // a -> b, each task takes 10 seconds
+        let mut dag = DAG::new();
+        let a = dag.add_task("a", 100., 0, 1, 1, CoresDependency::Linear);
+        let b = dag.add_task("b", 100., 0, 1, 1, CoresDependency::Linear);
+        let data_id = dag.add_task_output(a, "data", 1.);
+        dag.add_data_dependency(data_id, b);
+
+        let mut sim = DagSimulation::new(
+            123,
+            Vec::new(),
+            NetworkConfig::constant(1e6, 0.),
+            Rc::new(RefCell::new(HeftScheduler::new())),
+            Config {
+                data_transfer_mode: DataTransferMode::Direct,
+            },
+        );
+        sim.add_resource("0", 10., 1, 1024);
+        sim.add_resource("1", 10., 1, 1024);
+        // b is running on the resource which also stores its input
+        sim.set_failure_model(FailureModel::Scripted(vec![(15., 0)]), policy);
+        let runner = sim.init(dag);
+        sim.step_until_no_events();
+        let runner = runner.borrow();
+        let stats = runner.run_stats().clone();
+        (runner.is_completed(), runner.is_aborted(), sim.time(), stats)
+    };
+
+    // the input of b is lost, so a is executed again before b
+    let (completed, aborted, makespan, stats) = run(RetryPolicy::SameResource);
+    assert!(completed && !aborted);
+    assert_float_eq(makespan, 35., 1e-3);
+    assert_eq!(stats.task_failures, 1);
+    assert_eq!(stats.task_retries, 2);
+    assert_float_eq(stats.wasted_task_time, 15., EPSILON);
+
+    let (completed, aborted, makespan, stats) = run(RetryPolicy::OtherResource);
+    assert!(completed && !aborted);
+    assert_float_eq(makespan, 35., 1e-3);
+    assert_eq!(stats.task_retries, 2);
+
+    let (completed, aborted, makespan, stats) = run(RetryPolicy::Abort);
+    assert!(!completed && aborted);
+    assert_float_eq(makespan, 15., EPSILON);
+    assert_eq!(stats.task_retries, 0);
+}
+
+#[test]
+fn test_random_task_failures() {
+    let run = |probability: Option<f64>| {
+        let mut dag = DAG::new();
+        let join = dag.add_task("join", 10., 0, 1, 1, CoresDependency::Linear);
+        for i in 0..8 {
+            let task = dag.add_task(&format!("work{}", i), 100., 0, 1, 1, CoresDependency::Linear);
+            let data_id = dag.add_task_output(task, &format!("data{}", i), 1.);
+            dag.add_data_dependency(data_id, join);
+        }
+        let mut sim = DagSimulation::new(
+            123,
+            Vec::new(),
+            NetworkConfig::constant(100., 0.),
+            Rc::new(RefCell::new(SimpleScheduler::new())),
+            Config {
+                data_transfer_mode: DataTransferMode::ViaMasterNode,
+            },
+        );
+        sim.add_resource("0", 10., 2, 1024);
+        sim.add_resource("1", 10., 2, 1024);
+        if let Some(probability) = probability {
+            sim.set_failure_model(FailureModel::Random { probability }, RetryPolicy::SameResource);
+        }
+        let runner = sim.init(dag);
+        sim.step_until_no_events();
+        assert!(runner.borrow().is_completed());
+        let stats = runner.borrow().run_stats().clone();
+        (sim.time(), stats)
+    };
+
+    let (makespan, stats) = run(None);
+    assert_eq!(stats.task_failures, 0);
+    assert_eq!(stats.wasted_task_time, 0.);
+    let (failures_makespan, failures_stats) = run(Some(0.5));
+    assert!(failures_stats.task_failures > 0);
+    assert_eq!(failures_stats.task_retries, failures_stats.task_failures);
+    assert!(failures_stats.wasted_task_time > 0.);
+    assert_float_eq(
+        failures_stats.total_task_time - failures_stats.wasted_task_time,
+        stats.total_task_time,
+        1e-6,
+    );
+    assert!(failures_makespan > makespan);
+}
+
+/// Runs the second task on the on-demand resource only if it completes earlier than on the local resource.
+struct BurstScheduler {}
+
+impl Scheduler for BurstScheduler {
+    fn start(&mut self, dag: &DAG, system: System, _config: Config, _ctx: &SimulationContext) -> Vec<Action> {
+        let local = system.resources.iter().position(|r| r.name == "local").unwrap();
+        let cloud = system.resources.iter().position(|r| r.name == "cloud").unwrap();
+        let exec_time = |task: usize, resource: usize| dag.get_task(task).flops / system.resources[resource].speed;
+        let startup_time = system.resources[cloud].provisioning.unwrap().startup_time;
+        let burst = startup_time + exec_time(1, cloud) < exec_time(0, local) + exec_time(1, local);
+        let schedule = |task: usize, resource: usize| Action::ScheduleTask {
+            task,
+            resource,
+            cores: 1,
+            expected_span: None,
+        };
+        vec![schedule(0, local), schedule(1, if burst { cloud } else { local })]
+    }
+
+    fn is_static(&self) -> bool {
+        true
+    }
+}
+
+#[test]
+fn test_on_demand_resource() {
+    let run = |scheduler: Rc<RefCell<dyn Scheduler>>, startup_time: f64| {
+        let mut dag = DAG::new();
+        dag.add_task("task0", 100., 0, 1, 1, CoresDependency::Linear);
+        dag.add_task("task1", 100., 0, 1, 1, CoresDependency::Linear);
+        let mut sim = DagSimulation::new(
+            123,
+            Vec::new(),
+            NetworkConfig::constant(100., 0.),
+            scheduler,
+            Config {
+                data_transfer_mode: DataTransferMode::Direct,
+            },
+        );
+        sim.add_resource("local", 10., 1, 1024);
+        sim.add_on_demand_resource("cloud", 10., 1, 1024, 0., Provisioning::new(startup_time, 5., 1.));
+        let runner = sim.init(dag);
+        sim.step_until_no_events();
+        let runner = runner.borrow();
+        assert!(runner.is_completed());
+        (sim.time(), runner.run_stats().provisioning_cost)
+    };
+
+    // HEFT accounts for the startup delay and makes the same decisions
+    for burst_scheduler in [true, false] {
+        let scheduler = || -> Rc<RefCell<dyn Scheduler>> {
+            if burst_scheduler {
+                Rc::new(RefCell::new(BurstScheduler {}))
+            } else {
+                Rc::new(RefCell::new(HeftScheduler::new()))
+            }
+        };
+        // burst pays off: the second task runs in parallel after the startup delay
+        let (makespan, cost) = run(scheduler(), 4.);
+        assert_float_eq(makespan, 14., EPSILON);
+        assert_float_eq(cost, 6., EPSILON);
+        // startup delay outweighs the makespan reduction, the cloud resource is not provisioned
+        let (makespan, cost) = run(scheduler(), 12.);
+        assert_float_eq(makespan, 20., EPSILON);
+        assert_float_eq(cost, 0., EPSILON);
+    }
+}
+
+#[test]
+fn test_multiple_dags() {
+    let run = |policy: DagSharingPolicy| {
+        let mut dags = Vec::new();
+        for _ in 0..2 {
+            let mut dag = DAG::new();
+            let a = dag.add_task("a", 10., 0, 1, 1, CoresDependency::Linear);
+            let b = dag.add_task("b", 10., 0, 1, 1, CoresDependency::Linear);
+            let data = dag.add_task_output(a, "data", 1.);
+            dag.add_data_dependency(data, b);
+            dag.set_deadline(25.);
+            dags.push(dag);
+        }
+        let mut sim = DagSimulation::new(
+            123,
+            Vec::new(),
+            NetworkConfig::constant(100., 0.),
+            Rc::new(RefCell::new(HeftScheduler::new())),
+            Config {
+                data_transfer_mode: DataTransferMode::Direct,
+            },
+        );
+        sim.add_resource("0", 1., 1, 1024);
+        let runner = sim.init_multiple(dags, policy);
+        sim.step_until_no_events();
+        let runner = runner.borrow();
+        assert!(runner.is_completed());
+        assert_float_eq(sim.time(), 40., EPSILON);
+        let completion_times = (
+            runner.dag_completion_time(0).unwrap(),
+            runner.dag_completion_time(1).unwrap(),
+        );
+        // the deadline of each DAG is carried over to its exit task
+        let stats = runner.run_stats();
+        assert_eq!(stats.tasks_with_deadline, 2);
+        let missed = [completion_times.0, completion_times.1]
+            .iter()
+            .filter(|&&time| time > 25.)
+            .count();
+        assert_eq!(stats.missed_task_deadlines, missed);
+        completion_times
+    };
+
+    // both DAGs compete for the only core, so the order of their tasks defines the completion times
+    let (first, second) = run(DagSharingPolicy::Fifo);
+    assert_float_eq(first, 20., EPSILON);
+    assert_float_eq(second, 40., EPSILON);
+    let (first, second) = run(DagSharingPolicy::Priority(vec![1, 2]));
+    assert_float_eq(first, 40., EPSILON);
+    assert_float_eq(second, 20., EPSILON);
+    let (first, second) = run(DagSharingPolicy::FairShare);
+    assert_float_eq(first, 30., EPSILON);
+    assert_float_eq(second, 40., EPSILON);
+}
+
+#[test]
+fn test_data_dependent_output_size() {
+    let run = |reduce: bool| {
+        let mut dag = DAG::new();
+        let filter = dag.add_task("filter", 10., 32, 1, 1, CoresDependency::Linear);
+        let consumer = dag.add_task("consumer", 10., 32, 1, 1, CoresDependency::Linear);
+        let input = dag.add_data_item("input", 100.);
+        dag.add_data_dependency(input, filter);
+        let output = dag.add_task_output(filter, "output", 100.);
+        if reduce {
+            dag.set_output_size_function(output, |input_size| input_size / 2.);
+        }
+        dag.add_data_dependency(output, consumer);
+
+        let mut sim = DagSimulation::new(
+            123,
+            Vec::new(),
+            NetworkConfig::constant(10., 0.),
+            Rc::new(RefCell::new(SimpleScheduler::new())),
+            Config {
+                data_transfer_mode: DataTransferMode::ViaMasterNode,
+            },
+        );
+        sim.add_resource("0", 10., 1, 1024);
+        let runner = sim.init(dag);
+        sim.step_until_no_events();
+        assert!(runner.borrow().is_completed());
+        sim.time()
+    };
+    // each data item is transferred twice via the master node: 2 * 10 (input) + 1 + 2 * 10 (output) + 1
+    assert_float_eq(run(false), 42., EPSILON);
+    // the output is half of the input, so its transfers take half the time
+    assert_float_eq(run(true), 32., EPSILON);
+}
+
+#[test]
+fn test_local_search_warm_start() {
+    let (dag, join) = fork_join_dag();
+    let resources = vec![dual_core_resource("slow", 2.), dual_core_resource("fast", 10.)];
+
+    // from scratch the search starts with all tasks on the slow resource
+    let mut scheduler = LocalSearchScheduler::new();
+    let scratch = scheduler.schedule(&dag, &resources);
+    let scratch_iterations = scheduler.iterations();
+    assert!(scratch_iterations > 0);
+    assert!(scratch.makespan < 2. * 50. + 10.);
+
+    // a near-optimal initial schedule differs from the found one by a single task
+    let mut initial = scratch.clone();
+    let moved = initial.assignments.iter_mut().find(|a| a.task != join).unwrap();
+    moved.resource = 1 - moved.resource;
+    let warm = scheduler.schedule_from(&dag, &resources, &initial).unwrap();
+    assert!(scheduler.iterations() < scratch_iterations);
+    assert!(warm.makespan <= scratch.makespan + EPSILON);
+
+    // starting from a local optimum makes no moves
+    let same = scheduler.schedule_from(&dag, &resources, &scratch).unwrap();
+    assert_eq!(scheduler.iterations(), 0);
+    assert_float_eq(same.makespan, scratch.makespan, EPSILON);
+
+    // tasks missing from the initial schedule or assigned to unknown resources get the default mapping
+    let mut incomplete = scratch.clone();
+    incomplete.assignments.pop();
+    incomplete.assignments[0].resource = resources.len();
+    let repaired = scheduler.schedule_from(&dag, &resources, &incomplete).unwrap();
+    assert_eq!(repaired.assignments.len(), dag.get_tasks().len());
+    assert!(repaired.assignments.iter().all(|a| a.resource < resources.len()));
+    assert!(repaired.makespan < 2. * 50. + 10.);
+
+    let mut sim = DagSimulation::new(
+        123,
+        resources.clone(),
+        NetworkConfig::constant(1e6, 0.),
+        Rc::new(RefCell::new(LocalSearchScheduler::new().with_initial_schedule(initial))),
+        Config {
+            data_transfer_mode: DataTransferMode::Direct,
+        },
+    );
+    let runner = sim.init(dag);
+    sim.step_until_no_events();
+    assert!(runner.borrow().is_completed());
+    assert_float_eq(sim.time(), warm.makespan, 1e-3);
+}
+
+#[test]
+fn test_partition_keeps_clusters_together() {
+    // two diamond-shaped clusters with heavy edges connected by a single light edge,
+    // tasks of the clusters are interleaved
+    let mut dag = DAG::new();
+    let mut clusters = [Vec::new(), Vec::new()];
+    for i in 0..4 {
+        for (c, cluster) in clusters.iter_mut().enumerate() {
+            cluster.push(dag.add_task(&format!("c{}_t{}", c, i), 100., 0, 1, 1, CoresDependency::Linear));
+        }
+    }
+    for cluster in clusters.iter() {
+        let (source, sink) = (cluster[0], cluster[3]);
+        for &middle in cluster[1..3].iter() {
+            let data_id = dag.add_task_output(source, &format!("{}_{}", source, middle), 100.);
+            dag.add_data_dependency(data_id, middle);
+            let data_id = dag.add_task_output(middle, &format!("{}_{}", middle, sink), 100.);
+            dag.add_data_dependency(data_id, sink);
+        }
+    }
+    let data_id = dag.add_task_output(clusters[0][1], "bridge", 1.);
+    dag.add_data_dependency(data_id, clusters[1][2]);
+
+    let partition = GraphPartition::new(&dag, &[1., 1.], 0.1);
+    assert_eq!(partition.part_count(), 2);
+    assert_float_eq(partition.cut_weight(), 1., EPSILON);
+    for cluster in clusters.iter() {
+        assert!(cluster.iter().all(|&t| partition.part(t) == partition.part(cluster[0])));
+    }
+    assert_ne!(partition.part(clusters[0][0]), partition.part(clusters[1][0]));
+
+    let resources = (0..4)
+        .map(|i| ResourceConfig {
+            name: format!("r{}", i),
+            speed: 10.,
+            cores: 1,
+            memory: 1024,
+            price: 0.,
+            cost: 0.,
+            provisioning: None,
+        })
+        .collect::<Vec<_>>();
+    let groups = vec![
+        vec!["r0".to_string(), "r1".to_string()],
+        vec!["r2".to_string(), "r3".to_string()],
+    ];
+    let mut sim = DagSimulation::new(
+        123,
+        resources,
+        NetworkConfig::constant(10., 0.),
+        Rc::new(RefCell::new(
+            PartitionScheduler::new().with_resource_groups(groups.clone()),
+        )),
+        Config {
+            data_transfer_mode: DataTransferMode::Direct,
+        },
+    );
+    let runner = sim.init(dag);
+    runner.borrow_mut().enable_trace_log(true);
+    sim.step_until_no_events();
+    assert!(runner.borrow().is_completed());
+
+    let mut locations = vec![String::new(); 8];
+    for event in runner.borrow().trace_log().events.iter() {
+        if let Event::TaskScheduled { task_id, location, .. } = event {
+            locations[*task_id] = location.clone();
+        }
+    }
+    let group_of = |task_id: usize| groups.iter().position(|g| g.contains(&locations[task_id])).unwrap();
+    for cluster in clusters.iter() {
+        assert!(cluster.iter().all(|&t| group_of(t) == group_of(cluster[0])));
+    }
+    assert_ne!(group_of(clusters[0][0]), group_of(clusters[1][0]));
+}
+
+#[test]
+fn test_earliest_and_latest_finish() {
+    // a -> {b, c} -> d
+    let mut dag = DAG::new();
+    let a = dag.add_task("a", 10., 0, 1, 1, CoresDependency::Linear);
+    let b = dag.add_task("b", 20., 0, 1, 1, CoresDependency::Linear);
+    let c = dag.add_task("c", 20., 0, 1, 1, CoresDependency::Linear);
+    let d = dag.add_task("d", 10., 0, 1, 1, CoresDependency::Linear);
+    for (producer, consumer) in [(a, b), (a, c), (b, d), (c, d)] {
+        let data_id = dag.add_task_output(producer, &format!("{}_{}", producer, consumer), 1.);
+        dag.add_data_dependency(data_id, consumer);
+    }
+    let resource = |name: &str| ResourceConfig {
+        name: name.to_string(),
+        speed: 10.,
+        cores: 1,
+        memory: 1024,
+        price: 0.,
+        cost: 0.,
+        provisioning: None,
+    };
+
+    // on a single resource b and c are executed one after another
+    let single = vec![resource("r0")];
+    assert_float_eq(dag.earliest_finish(a, &single), 1., EPSILON);
+    assert_float_eq(dag.earliest_finish(d, &single), 6., EPSILON);
+    // on two resources b and c are executed in parallel
+    let double = vec![resource("r0"), resource("r1")];
+    assert_float_eq(dag.earliest_finish(b, &double), 3., EPSILON);
+    assert_float_eq(dag.earliest_finish(d, &double), 4., EPSILON);
+
+    assert_float_eq(dag.latest_finish(d, &double, 10.), 10., EPSILON);
+    assert_float_eq(dag.latest_finish(b, &double, 10.), 9., EPSILON);
+    assert_float_eq(dag.latest_finish(a, &double, 10.), 7., EPSILON);
+    dag.set_task_deadline(c, 5.);
+    assert_float_eq(dag.latest_finish(a, &double, 10.), 3., EPSILON);
+    for task in [a, b, c, d] {
+        assert!(dag.earliest_finish(task, &double) <= dag.latest_finish(task, &double, 10.) + EPSILON);
+    }
+}
+
+#[test]
+fn test_anti_affinity() {
+    // both tasks would be placed on the fast resource without the constraint
+    let mut dag = DAG::new();
+    let a = dag.add_task("a", 100., 0, 1, 1, CoresDependency::Linear);
+    let b = dag.add_task("b", 100., 0, 1, 1, CoresDependency::Linear);
+    dag.add_anti_affinity(&[a, b]);
+    assert!(dag.get_task(a).anti_affinity.contains(&b));
+    assert!(dag.get_task(b).anti_affinity.contains(&a));
+
+    let schedulers: Vec<Rc<RefCell<dyn Scheduler>>> = vec![
+        Rc::new(RefCell::new(SimpleScheduler::new())),
+        Rc::new(RefCell::new(HeftScheduler::new())),
+        Rc::new(RefCell::new(ParetoScheduler::new())),
+        Rc::new(RefCell::new(LocalSearchScheduler::new())),
+    ];
+    for scheduler in schedulers {
+        let resources = vec![
+            ResourceConfig {
+                name: "fast".to_string(),
+                speed: 100.,
+                cores: 2,
+                memory: 1024,
+                price: 0.,
+                cost: 0.,
+                provisioning: None,
+            },
+            ResourceConfig {
+                name: "slow".to_string(),
+                speed: 1.,
+                cores: 1,
+                memory: 1024,
+                price: 0.,
+                cost: 0.,
+                provisioning: None,
+            },
+        ];
+        let mut sim = DagSimulation::new(
+            123,
+            resources,
+            NetworkConfig::constant(10., 0.),
+            scheduler,
+            Config {
+                data_transfer_mode: DataTransferMode::Direct,
+            },
+        );
+        let runner = sim.init(dag.clone());
+        runner.borrow_mut().enable_trace_log(true);
+        sim.step_until_no_events();
+        assert!(runner.borrow().is_completed());
+
+        let mut locations = vec![String::new(); 2];
+        for event in runner.borrow().trace_log().events.iter() {
+            if let Event::TaskScheduled { task_id, location, .. } = event {
+                locations[*task_id] = location.clone();
+            }
+        }
+        assert_ne!(locations[a], locations[b]);
+    }
+}
+
+#[test]
+fn test_replay_heft_schedule() {
+    let mut rng = Pcg64::seed_from_u64(1);
+    let dag = gen_dag(&mut rng, 100, 300);
+    let resources = gen_resources(&mut rng, 5, true);
+    let network = gen_network(&mut rng);
+    let config = Config {
+        data_transfer_mode: DataTransferMode::Direct,
+    };
+
+    let recorder = Rc::new(RefCell::new(ScheduleRecorder::new(Rc::new(RefCell::new(
+        HeftScheduler::new(),
+    )))));
+    let mut sim = DagSimulation::new(
+        123,
+        resources.clone(),
+        network.clone(),
+        recorder.clone(),
+        config.clone(),
+    );
+    let runner = sim.init(dag.clone());
+    sim.step_until_no_events();
+    assert!(runner.borrow().is_completed());
+    let makespan = sim.time();
+
+    // the schedule also includes the tasks added by DagSimulation for DAG inputs and outputs
+    let schedule = recorder.borrow().schedule();
+    assert_eq!(schedule.decisions.len(), 102);
+    let path = std::env::temp_dir().join("dslab_dag_test_replay_heft_schedule.json");
+    schedule.save_to_file(path.to_str().unwrap()).unwrap();
+    let replay = ReplaySchedule::from_file(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    let mut sim = DagSimulation::new(123, resources, network, Rc::new(RefCell::new(replay)), config);
+    let runner = sim.init(dag);
+    sim.step_until_no_events();
+    assert!(runner.borrow().is_completed());
+    assert_float_eq(sim.time(), makespan, EPSILON);
+}
+
+#[test]
+fn test_resource_utilization_series() {
+    // b waits for its input which is transferred from the master resource, so r0 is idle after a completes
+    let mut dag = DAG::new();
+    dag.add_task("a", 10., 0, 1, 1, CoresDependency::Linear);
+    let b = dag.add_task("b", 10., 0, 1, 1, CoresDependency::Linear);
+    let data_id = dag.add_data_item("input", 20.);
+    dag.add_data_dependency(data_id, b);
+
+    let resources = vec![ResourceConfig {
+        name: "r0".to_string(),
+        speed: 10.,
+        cores: 2,
+        memory: 1024,
+        price: 0.,
+        cost: 0.,
+        provisioning: None,
+    }];
+    let mut sim = DagSimulation::new(
+        123,
+        resources,
+        NetworkConfig::constant(10., 0.),
+        Rc::new(RefCell::new(SimpleScheduler::new())),
+        Config {
+            data_transfer_mode: DataTransferMode::Direct,
+        },
+    );
+    let runner = sim.init(dag);
+    sim.step_until_no_events();
+    assert!(runner.borrow().is_completed());
+
+    assert_float_eq(sim.time(), 3., EPSILON);
+
+    let series = sim.resource_utilization_series();
+    let check = |actual: &[(f64, f64)], expected: &[(f64, f64)]| {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected.iter()) {
+            assert_float_eq(a.0, e.0, EPSILON);
+            assert_float_eq(a.1, e.1, EPSILON);
+        }
+    };
+    check(&series[&0], &[(0., 0.5), (1., 0.), (2., 0.5), (3., 0.)]);
+    // the input task on the master resource takes no time
+    check(&series[&1], &[(0., 0.)]);
+}
+
+#[test]
+fn test_robustness_analysis() {
+    // independent long and short tasks
+    let mut dag = DAG::new();
+    let long = dag.add_task("long", 100., 0, 1, 1, CoresDependency::Linear);
+    let short = dag.add_task("short", 50., 0, 1, 1, CoresDependency::Linear);
+
+    let resources = (0..2)
+        .map(|i| ResourceConfig {
+            name: format!("r{}", i),
+            speed: 10.,
+            cores: 1,
+            memory: 1024,
+            price: 0.,
+            cost: 0.,
+            provisioning: None,
+        })
+        .collect::<Vec<_>>();
+    let sim = DagSimulation::new(
+        123,
+        resources,
+        NetworkConfig::constant(10., 0.),
+        Rc::new(RefCell::new(SimpleScheduler::new())),
+        Config {
+            data_transfer_mode: DataTransferMode::Direct,
+        },
+    );
+    let decision = |task: usize, resource: &str| ScheduleDecision {
+        time: 0.,
+        task,
+        resource: resource.to_string(),
+        cores: 1,
+        core_ids: None,
+    };
+    // the short task runs in parallel with the long one and has slack, or runs after it
+    let slack = ReplaySchedule::new(vec![decision(long, "r0"), decision(short, "r1")]);
+    let tight = ReplaySchedule::new(vec![decision(long, "r0"), decision(short, "r0")]);
+
+    let perturbation = CostPerturbation::Uniform { spread: 0.5 };
+    let slack_stats = sim.robustness_analysis(&dag, &slack, perturbation, 100);
+    let tight_stats = sim.robustness_analysis(&dag, &tight, perturbation, 100);
+    assert_float_eq(slack_stats.nominal_makespan, 10., EPSILON);
+    assert_float_eq(tight_stats.nominal_makespan, 15., EPSILON);
+    assert_eq!(slack_stats.makespans.len(), 100);
+    assert!(slack_stats.variance() > 0.);
+    assert!(slack_stats.variance() < tight_stats.variance());
+    assert!(slack_stats.max() < tight_stats.max());
+
+    // trials are deterministic
+    let again = sim.robustness_analysis(&dag, &slack, perturbation, 100);
+    assert_eq!(again.makespans, slack_stats.makespans);
+}
+
+#[test]
+fn test_dax_parser() {
+    let dax = r#"<?xml version="1.0" encoding="UTF-8"?>
+<adag xmlns="http://pegasus.isi.edu/schema/DAX" version="2.1" name="test">
+  <job id="ID00000" namespace="test" name="split" version="1.0" runtime="10.0">
+    <uses file="input.txt" link="input" size="2000000"/>
+    <uses file="part.txt" link="output" size="1000000"/>
+  </job>
+  <job id="ID00001" namespace="test" name="process" version="1.0" runtime="20.0">
+    <uses file="part.txt" link="input" size="1000000"/>
+    <uses file="result1.txt" link="output" size="1000000"/>
+  </job>
+  <job id="ID00002" namespace="test" name="process" version="1.0" runtime="30.0">
+    <uses file="part.txt" link="input" size="1000000"/>
+  </job>
+  <child ref="ID00001">
+    <parent ref="ID00000"/>
+  </child>
+  <child ref="ID00002">
+    <parent ref="ID00000"/>
+    <parent ref="ID00001"/>
+  </child>
+</adag>
+"#;
+    let path = std::env::temp_dir().join("dslab_dag_test_dax_parser.xml");
+    std::fs::write(&path, dax).unwrap();
+    let dag = DAG::from_file(&path, &ParserConfig::with_reference_speed(10.));
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(dag.get_tasks().len(), 3);
+    assert_eq!(dag.get_task(0).name, "split_ID00000");
+    assert_float_eq(dag.get_task(2).flops, 300., EPSILON);
+    // the shared file is a single data item consumed by both jobs
+    let part = dag.get_task(0).outputs[0];
+    assert_eq!(dag.get_data_item(part).name, "part.txt");
+    assert_float_eq(dag.get_data_item(part).size, 1., EPSILON);
+    assert_eq!(dag.get_task(1).inputs, vec![part]);
+    // the dependency without shared files is added as a data item of zero size
+    assert_eq!(dag.get_task(2).inputs.len(), 2);
+    let control = dag.get_task(2).inputs[1];
+    assert_eq!(dag.get_data_item(control).producer, Some(1));
+    assert_eq!(dag.get_data_item(control).size, 0.);
+    assert_eq!(dag.get_data_items().len(), 4);
+}
+
+#[test]
+fn test_critical_path() {
+    // a -> c <- b and independent d, the output of a is large
+    let mut dag = DAG::new();
+    let a = dag.add_task("a", 100., 0, 1, 1, CoresDependency::Linear);
+    let b = dag.add_task("b", 50., 0, 1, 1, CoresDependency::Linear);
+    let c = dag.add_task("c", 10., 0, 1, 1, CoresDependency::Linear);
+    let d = dag.add_task("d", 20., 0, 1, 1, CoresDependency::Linear);
+    let data_a = dag.add_task_output(a, "data_a", 100.);
+    let data_b = dag.add_task_output(b, "data_b", 1.);
+    dag.add_data_dependency(data_a, c);
+    dag.add_data_dependency(data_b, c);
+
+    let decision = |task: usize, resource: &str| ScheduleDecision {
+        time: 0.,
+        task,
+        resource: resource.to_string(),
+        cores: 1,
+        core_ids: None,
+    };
+    let schedule = ReplaySchedule::new(vec![
+        decision(a, "0"),
+        decision(b, "1"),
+        decision(d, "1"),
+        decision(c, "1"),
+    ]);
+    let mut sim = DagSimulation::new(
+        123,
+        Vec::new(),
+        NetworkConfig::constant(10., 0.),
+        Rc::new(RefCell::new(schedule)),
+        Config {
+            data_transfer_mode: DataTransferMode::Direct,
+        },
+    );
+    sim.add_resource("0", 10., 1, 1024);
+    sim.add_resource("1", 10., 1, 1024);
+    let runner = sim.init(dag);
+    sim.step_until_no_events();
+    let runner = runner.borrow();
+    assert!(runner.is_completed());
+    // c waits for the transfer of data_a, which takes 10 seconds
+    assert_float_eq(sim.time(), 21., EPSILON);
+    assert_eq!(critical_path(runner.trace_log()), vec![a, c]);
+
+    let idle = idle_intervals(runner.trace_log());
+    assert_eq!(idle.len(), 3);
+    assert_eq!(idle[0], vec![(10., 21.)]);
+    // b and d run one after another, then the resource waits for data_a
+    assert_eq!(idle[1], vec![(7., 20.)]);
+    assert_eq!(idle[2], vec![(0., 21.)]);
+}
+
+#[test]
+fn test_replicated_inputs() {
+    // map-reduce over 4 large input chunks, the first half is stored on resource 0 and the second one on resource 1
+    let build_dag = || {
+        let mut dag = DAG::new();
+        let reduce = dag.add_task("reduce", 10., 0, 1, 1, CoresDependency::Linear);
+        let mut maps = Vec::new();
+        for i in 0..4 {
+            let chunk = dag.add_data_item(&format!("chunk{}", i), 100.);
+            dag.add_replica(chunk, i / 2);
+            let map = dag.add_task(&format!("map{}", i), 100., 0, 1, 1, CoresDependency::Linear);
+            dag.add_data_dependency(chunk, map);
+            let output = dag.add_task_output(map, &format!("output{}", i), 1.);
+            dag.add_data_dependency(output, reduce);
+            maps.push((map, chunk));
+        }
+        (dag, maps)
+    };
+    let run = |dag: DAG, scheduler: Rc<RefCell<dyn Scheduler>>| {
+        let mut sim = DagSimulation::new(
+            123,
+            Vec::new(),
+            NetworkConfig::constant(10., 0.),
+            scheduler,
+            Config {
+                data_transfer_mode: DataTransferMode::Direct,
+            },
+        );
+        sim.add_resource("0", 10., 2, 1024);
+        sim.add_resource("1", 10., 2, 1024);
+        let runner = sim.init(dag);
+        sim.step_until_no_events();
+        assert!(runner.borrow().is_completed());
+        let mut locations = HashMap::new();
+        let mut traffic = 0.;
+        for event in runner.borrow().trace_log().events.iter() {
+            match event {
+                Event::TaskScheduled { task_id, location, .. } => {
+                    locations.insert(*task_id, location.clone());
+                }
+                Event::StartUploading { data_item_id, .. } => {
+                    traffic += runner.borrow().trace_log().graph.data_items[*data_item_id].size;
+                }
+                _ => {}
+            }
+        }
+        (sim.time(), locations, traffic)
+    };
+
+    // HEFT places the map tasks near their inputs, only the map outputs are transferred
+    let (dag, maps) = build_dag();
+    let (makespan, locations, traffic) = run(dag, Rc::new(RefCell::new(HeftScheduler::new())));
+    for (i, &(map, _)) in maps.iter().enumerate() {
+        assert_eq!(locations[&map], (i / 2).to_string());
+    }
+    assert_float_eq(traffic, 2., EPSILON);
+    assert_float_eq(makespan, 11.1, EPSILON);
+
+    // pinned tasks are executed on the replicas by any scheduler
+    let (mut dag, maps) = build_dag();
+    for &(map, chunk) in maps.iter() {
+        dag.pin_to_replicas(map, chunk);
+    }
+    let (_, locations, traffic) = run(dag, Rc::new(RefCell::new(SimpleScheduler::new())));
+    for (i, &(map, _)) in maps.iter().enumerate() {
+        assert_eq!(locations[&map], (i / 2).to_string());
+    }
+    assert!(traffic < 4.);
+}
+
+#[test]
+fn test_energy_consumption() {
+    let run = |power_model: Box<dyn Fn(&mut DagSimulation)>| {
+        let mut dag = DAG::new();
+        dag.add_task("a", 100., 0, 1, 1, CoresDependency::Linear);
+        dag.add_task("b", 200., 0, 1, 1, CoresDependency::Linear);
+        let mut sim = DagSimulation::new(
+            123,
+            Vec::new(),
+            NetworkConfig::constant(10., 0.),
+            Rc::new(RefCell::new(SimpleScheduler::new())),
+            Config {
+                data_transfer_mode: DataTransferMode::Direct,
+            },
+        );
+        sim.add_resource("0", 10., 2, 1024);
+        power_model(&mut sim);
+        let runner = sim.init(dag);
+        sim.step_until_no_events();
+        let runner = runner.borrow();
+        assert!(runner.is_completed());
+        (sim.time(), runner.run_stats().clone())
+    };
+
+    // a and b run in parallel for 10 and 20 seconds: 50 W * 20 s idle + 2 J/Gflop * 300 Gflop dynamic
+    let (makespan, stats) = run(Box::new(|sim| sim.set_power_model("0", LinearPowerModel::new(50., 2.))));
+    assert_float_eq(makespan, 20., EPSILON);
+    assert_float_eq(stats.energy, 1600., EPSILON);
+    assert_float_eq(stats.average_power, 80., EPSILON);
+
+    // at half frequency the execution is twice longer, but the dynamic energy is reduced four times
+    let (makespan, stats) = run(Box::new(|sim| {
+        sim.set_power_model("0", DvfsPowerModel::new(50., 2., 0.5))
+    }));
+    assert_float_eq(makespan, 40., EPSILON);
+    assert_float_eq(stats.energy, 50. * 40. + 150., EPSILON);
+
+    // resources without power models are not accounted
+    let (_, stats) = run(Box::new(|_| {}));
+    assert_eq!(stats.energy, 0.);
+}
+
+#[test]
+fn test_gantt_chart() {
+    // a -> b with a transfer between resources and independent c
+    let mut dag = DAG::new();
+    let a = dag.add_task("a", 100., 0, 1, 1, CoresDependency::Linear);
+    let b = dag.add_task("b", 50., 0, 1, 1, CoresDependency::Linear);
+    let c = dag.add_task("c", 20., 0, 1, 1, CoresDependency::Linear);
+    let data_a = dag.add_task_output(a, "data_a", 100.);
+    dag.add_data_dependency(data_a, b);
+
+    let decision = |task: usize, resource: &str| ScheduleDecision {
+        time: 0.,
+        task,
+        resource: resource.to_string(),
+        cores: 1,
+        core_ids: None,
+    };
+    let schedule = ReplaySchedule::new(vec![decision(a, "0"), decision(c, "0"), decision(b, "1")]);
+    let mut sim = DagSimulation::new(
+        123,
+        Vec::new(),
+        NetworkConfig::constant(10., 0.),
+        Rc::new(RefCell::new(schedule)),
+        Config {
+            data_transfer_mode: DataTransferMode::Direct,
+        },
+    );
+    sim.add_resource("0", 10., 1, 1024);
+    sim.add_resource("1", 10., 1, 1024);
+    let runner = sim.init(dag);
+    sim.step_until_no_events();
+    let runner = runner.borrow();
+    assert!(runner.is_completed());
+
+    let chart = GanttChart::from_trace_log(runner.trace_log());
+    assert_float_eq(chart.makespan, 25., EPSILON);
+    assert_eq!(chart.resources.len(), 3);
+    let tasks = |i: usize| {
+        chart.resources[i]
+            .tasks
+            .iter()
+            .map(|task| (task.task_name.as_str(), task.start, task.finish))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(tasks(0), vec![("a", 0., 10.), ("c", 10., 12.)]);
+    // b waits for the transfer of data_a
+    assert_eq!(tasks(1), vec![("b", 20., 25.)]);
+    assert_eq!(chart.transfers.len(), 1);
+    assert_eq!(chart.transfers[0].data_name, "data_a");
+    assert_eq!(
+        (chart.transfers[0].from.as_str(), chart.transfers[0].to.as_str()),
+        ("0", "1")
+    );
+    assert_eq!((chart.transfers[0].start, chart.transfers[0].finish), (10., 20.));
+
+    let json_path = std::env::temp_dir().join("dslab_dag_test_gantt.json");
+    let svg_path = std::env::temp_dir().join("dslab_dag_test_gantt.svg");
+    runner.trace_log().save_gantt_json(json_path.to_str().unwrap()).unwrap();
+    runner.trace_log().save_gantt_svg(svg_path.to_str().unwrap()).unwrap();
+    let parsed: GanttChart = serde_json::from_str(&std::fs::read_to_string(&json_path).unwrap()).unwrap();
+    assert_eq!(parsed.resources[0].tasks.len(), 2);
+    let svg = std::fs::read_to_string(&svg_path).unwrap();
+    assert!(svg.starts_with("<svg"));
+    assert_eq!(svg.matches("<rect").count(), 4);
+    assert!(svg.contains(">b</text>"));
+}
+
+#[test]
+fn test_dag_arrival() {
+    let independent_tasks = |prefix: &str, count: usize, flops: f64| {
+        let mut dag = DAG::new();
+        for i in 0..count {
+            dag.add_task(&format!("{}{}", prefix, i), flops, 0, 1, 1, CoresDependency::Linear);
+        }
+        dag
+    };
+    let recorder = Rc::new(RefCell::new(ScheduleRecorder::new(Rc::new(RefCell::new(
+        HeftScheduler::new(),
+    )))));
+    let mut sim = DagSimulation::new(
+        123,
+        Vec::new(),
+        NetworkConfig::constant(10., 0.),
+        recorder.clone(),
+        Config {
+            data_transfer_mode: DataTransferMode::Direct,
+        },
+    );
+    sim.add_resource("0", 10., 1, 1024);
+    sim.add_resource("1", 10., 1, 1024);
+    let runner = sim.init(independent_tasks("a", 2, 100.));
+    // the second DAG arrives when all cores are busy, the third one after the first two are completed
+    sim.submit_dag_at(independent_tasks("b", 2, 50.), 5.);
+    sim.submit_dag_at(independent_tasks("c", 1, 50.), 20.);
+    sim.step_until_no_events();
+    let runner = runner.borrow();
+    assert!(runner.is_completed());
+    assert_float_eq(sim.time(), 25., EPSILON);
+    assert_eq!(runner.dag_completion_time(0), Some(10.));
+    assert_eq!(runner.dag_completion_time(1), Some(15.));
+    assert_eq!(runner.dag_completion_time(2), Some(25.));
+
+    let mut starts = HashMap::new();
+    let mut locations = HashMap::new();
+    for event in runner.trace_log().events.iter() {
+        match event {
+            Event::TaskStarted { time, task_name, .. } => {
+                // the running tasks are not restarted
+                assert!(starts.insert(task_name.clone(), *time).is_none());
+            }
+            Event::TaskScheduled {
+                task_name, location, ..
+            } => {
+                locations.insert(task_name.clone(), location.clone());
+            }
+            _ => {}
+        }
+    }
+    assert_eq!(starts.len(), 5);
+    assert_eq!(starts["b0"], 10.);
+    assert_eq!(starts["b1"], 10.);
+    assert_ne!(locations["b0"], locations["b1"]);
+    assert_eq!(starts["c0"], 20.);
+
+    // only the new and not started tasks are planned on arrival of each DAG
+    let decisions = recorder
+        .borrow()
+        .schedule()
+        .decisions
+        .iter()
+        .map(|decision| (decision.time, decision.task))
+        .collect::<Vec<_>>();
+    assert_eq!(decisions, vec![(0., 0), (0., 1), (5., 2), (5., 3), (20., 4)]);
+}
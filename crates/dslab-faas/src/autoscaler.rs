@@ -0,0 +1,59 @@
+//! Concurrency-based autoscaling of warm containers.
+//!
+//! Unlike keepalive policies, which decide the lifetime of each container after it becomes idle, the autoscaler
+//! periodically observes the number of concurrent invocations of each application and adjusts the number of its
+//! containers to track the target concurrency per container. New containers are deployed idle (warm) in advance,
+//! excess idle containers are terminated. Note that the keepalive policy still applies to all containers,
+//! so the autoscaler is usually combined with a long keepalive window.
+
+/// Target tracking autoscaler.
+#[derive(Clone)]
+pub struct Autoscaler {
+    target_concurrency: f64,
+    min_containers: usize,
+    max_containers: usize,
+    interval: f64,
+}
+
+impl Autoscaler {
+    /// Creates new Autoscaler.
+    /// `target_concurrency` is the desired number of concurrent invocations per container, the number of containers
+    /// of each application is kept in `[min_containers, max_containers]` range and is updated every `interval`.
+    pub fn new(target_concurrency: f64, min_containers: usize, max_containers: usize, interval: f64) -> Self {
+        assert!(target_concurrency > 0., "Target concurrency must be positive");
+        assert!(interval > 0., "Autoscaling interval must be positive");
+        assert!(min_containers <= max_containers, "Invalid scaling bounds");
+        Self {
+            target_concurrency,
+            min_containers,
+            max_containers,
+            interval,
+        }
+    }
+
+    /// Returns the desired number of concurrent invocations per container.
+    pub fn target_concurrency(&self) -> f64 {
+        self.target_concurrency
+    }
+
+    /// Returns the minimum number of containers of each application.
+    pub fn min_containers(&self) -> usize {
+        self.min_containers
+    }
+
+    /// Returns the maximum number of containers of each application.
+    pub fn max_containers(&self) -> usize {
+        self.max_containers
+    }
+
+    /// Returns the time between autoscaling decisions.
+    pub fn interval(&self) -> f64 {
+        self.interval
+    }
+
+    /// Returns the desired number of containers given the observed number of concurrent invocations.
+    pub fn desired_containers(&self, concurrency: usize) -> usize {
+        let desired = (concurrency as f64 / self.target_concurrency - 1e-9).ceil().max(0.) as usize;
+        desired.clamp(self.min_containers, self.max_containers)
+    }
+}
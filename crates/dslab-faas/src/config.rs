@@ -8,9 +8,12 @@ use std::path::Path;
 
 use serde::{Deserialize, Serialize};
 
+use crate::autoscaler::Autoscaler;
 use crate::coldstart::{default_coldstart_policy_resolver, ColdStartPolicy, FixedTimeColdStartPolicy};
 use crate::cpu::{default_cpu_policy_resolver, ContendedCpuPolicy, CpuPolicy};
 use crate::deployer::{default_idle_deployer_resolver, BasicDeployer, IdleDeployer};
+use crate::gateway::ApiGateway;
+use crate::hedging::HedgingPolicy;
 use crate::invoker::{default_invoker_resolver, FIFOInvoker, Invoker};
 use crate::parallel::{ParallelConfig, ParallelHostConfig};
 use crate::scheduler::{default_scheduler_resolver, BasicScheduler, Scheduler};
@@ -23,6 +26,8 @@ pub struct HostConfig {
     pub resources: Vec<(String, u64)>,
     /// Host CPU cores.
     pub cores: u32,
+    /// Host labels.
+    pub labels: Vec<String>,
 }
 
 impl From<ParallelHostConfig> for HostConfig {
@@ -31,6 +36,7 @@ impl From<ParallelHostConfig> for HostConfig {
             invoker: value.invoker,
             resources: value.resources,
             cores: value.cores,
+            labels: value.labels,
         }
     }
 }
@@ -41,6 +47,7 @@ impl Default for HostConfig {
             invoker: Box::new(FIFOInvoker::new()),
             resources: Vec::new(),
             cores: 1,
+            labels: Vec::new(),
         }
     }
 }
@@ -53,7 +60,11 @@ impl From<ParallelConfig> for Config {
             cpu_policy: value.cpu_policy,
             idle_deployer: value.idle_deployer,
             scheduler: value.scheduler,
+            api_gateway: value.api_gateway,
+            autoscaler: value.autoscaler,
+            hedging: value.hedging,
             hosts: hosts.drain(..).map(HostConfig::from).collect(),
+            runtimes: value.runtimes,
         }
     }
 }
@@ -71,6 +82,17 @@ fn default_one() -> u32 {
     1
 }
 
+/// Describes a runtime shared by a group of applications, see [`crate::container::SharedRuntime`].
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RuntimeConfig {
+    /// Runtime name.
+    pub name: String,
+    /// Time needed to initialize the runtime.
+    pub init_time: f64,
+    /// Ids of applications sharing the runtime, i.e. their indices in the order of addition.
+    pub apps: Vec<usize>,
+}
+
 /// Similar to [`HostConfig`], but is YAML-serializable.
 #[derive(Clone, Serialize, Deserialize)]
 pub struct RawHostConfig {
@@ -84,6 +106,9 @@ pub struct RawHostConfig {
     /// Number of such hosts in the system.
     #[serde(default = "default_one")]
     pub count: u32,
+    /// Host labels.
+    #[serde(default)]
+    pub labels: Vec<String>,
 }
 
 /// YAML-serializable config
@@ -104,6 +129,9 @@ pub struct RawConfig {
     /// Raw host data.
     #[serde(default)]
     pub hosts: Vec<RawHostConfig>,
+    /// Runtimes shared by groups of applications.
+    #[serde(default)]
+    pub runtimes: Vec<RuntimeConfig>,
 }
 
 /// Parses map with options from string.
@@ -154,8 +182,16 @@ pub struct Config {
     pub idle_deployer: Box<dyn IdleDeployer>,
     /// [`crate::scheduler::Scheduler`] implementation.
     pub scheduler: Box<dyn Scheduler>,
+    /// Optional [`crate::gateway::ApiGateway`] that throttles incoming requests.
+    pub api_gateway: Option<ApiGateway>,
+    /// Optional [`crate::autoscaler::Autoscaler`] that scales warm containers based on observed concurrency.
+    pub autoscaler: Option<Autoscaler>,
+    /// Optional [`crate::hedging::HedgingPolicy`] that duplicates slow invocations to reduce tail latency.
+    pub hedging: Option<HedgingPolicy>,
     /// Host data.
     pub hosts: Vec<HostConfig>,
+    /// Runtimes shared by groups of applications, they override the runtimes specified in the trace.
+    pub runtimes: Vec<RuntimeConfig>,
 }
 
 impl Default for Config {
@@ -165,7 +201,11 @@ impl Default for Config {
             cpu_policy: Box::<ContendedCpuPolicy>::default(),
             idle_deployer: Box::new(BasicDeployer {}),
             scheduler: Box::new(BasicScheduler {}),
+            api_gateway: None,
+            autoscaler: None,
+            hedging: None,
             hosts: Vec::new(),
+            runtimes: Vec::new(),
         }
     }
 }
@@ -205,6 +245,7 @@ impl Config {
         if !raw.scheduler.is_empty() {
             me.scheduler = scheduler_resolver(&raw.scheduler);
         }
+        me.runtimes = raw.runtimes;
         for host in raw.hosts {
             let mut resources = Vec::with_capacity(host.resources.len());
             for item in host.resources {
@@ -220,6 +261,7 @@ impl Config {
                     invoker,
                     resources: resources.clone(),
                     cores: host.cores,
+                    labels: host.labels.clone(),
                 };
                 me.hosts.push(curr);
             }
@@ -1,6 +1,7 @@
 //! Container model and host-level container manager.
 
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::rc::Rc;
 
 use simcore::context::SimulationContext;
@@ -24,6 +25,74 @@ pub enum ContainerStatus {
     Terminated,
 }
 
+/// Snapshot restore parameters of an application (similar to AWS Lambda SnapStart).
+///
+/// The first container of the application is deployed as usual and then spends `creation_time` creating a snapshot.
+/// Once the snapshot is created, new containers of the application on any host are restored from it in
+/// `restore_time` instead of being deployed from scratch.
+#[derive(Clone, Copy, Debug)]
+pub struct Snapshot {
+    /// Time needed to create the snapshot after the container is deployed.
+    pub creation_time: f64,
+    /// Time needed to restore a container from the snapshot.
+    pub restore_time: f64,
+}
+
+impl Snapshot {
+    /// Creates new Snapshot.
+    pub fn new(creation_time: f64, restore_time: f64) -> Self {
+        Self {
+            creation_time,
+            restore_time,
+        }
+    }
+}
+
+/// Language runtime or another expensive initialization step (e.g. a common layer) shared by several applications.
+///
+/// The runtime initialization takes `init_time` out of the container deployment time. Once the runtime is up on a host,
+/// i.e. the host has a running or idle container of some application with the same runtime, new containers
+/// of such applications on this host skip the initialization.
+#[derive(Clone, Debug)]
+pub struct SharedRuntime {
+    /// Runtime name, applications with the same runtime name share it.
+    pub name: String,
+    /// Time needed to initialize the runtime.
+    pub init_time: f64,
+}
+
+impl SharedRuntime {
+    /// Creates new SharedRuntime.
+    pub fn new(name: &str, init_time: f64) -> Self {
+        Self {
+            name: name.to_string(),
+            init_time,
+        }
+    }
+}
+
+/// Stores the times when application snapshots become available, shared by all hosts.
+#[derive(Default)]
+pub struct SnapshotStore {
+    ready_times: HashMap<usize, f64>,
+}
+
+impl SnapshotStore {
+    /// Checks whether the snapshot of given app is available at given time.
+    pub fn is_ready(&self, app_id: usize, time: f64) -> bool {
+        self.ready_times.get(&app_id).is_some_and(|&t| t <= time)
+    }
+
+    /// Returns the time when the snapshot of given app becomes available if its creation has started.
+    pub fn ready_time(&self, app_id: usize) -> Option<f64> {
+        self.ready_times.get(&app_id).copied()
+    }
+
+    fn start_creation(&mut self, app_id: usize, ready_time: f64) {
+        self.ready_times.insert(app_id, ready_time);
+    }
+}
+
 /// Container model.
 pub struct Container {
     /// Container status.
@@ -48,6 +117,12 @@ pub struct Container {
     pub end_event: Option<EventId>,
     /// CPU share of the container.
     pub cpu_share: f64,
+    /// Indices of GPU devices allocated for the container.
+    pub gpus: Vec<usize>,
+    /// Whether the container is restored from the application snapshot.
+    pub restored_from_snapshot: bool,
+    /// Name of the shared runtime of the container application, if any.
+    pub runtime: Option<String>,
 }
 
 impl Container {
@@ -81,12 +156,18 @@ pub struct ContainerManager {
     container_counter: Counter,
     /// A set of invocations that will start on each non-running container that is being deployed.
     reservations: FxIndexMap<usize, Vec<usize>>,
+    snapshots: Rc<RefCell<SnapshotStore>>,
     ctx: Rc<RefCell<SimulationContext>>,
 }
 
 impl ContainerManager {
     /// Creates new ContainerManager on a specified host.
-    pub fn new(host_id: usize, resources: ResourceProvider, ctx: Rc<RefCell<SimulationContext>>) -> Self {
+    pub fn new(
+        host_id: usize,
+        resources: ResourceProvider,
+        snapshots: Rc<RefCell<SnapshotStore>>,
+        ctx: Rc<RefCell<SimulationContext>>,
+    ) -> Self {
         Self {
             active_invocations: 0,
             host_id,
@@ -96,6 +177,7 @@ impl ContainerManager {
             full_containers_by_app: Default::default(),
             container_counter: Counter::default(),
             reservations: FxIndexMap::default(),
+            snapshots,
             ctx,
         }
     }
@@ -105,6 +187,11 @@ impl ContainerManager {
         self.resources.can_allocate(resources)
     }
 
+    /// Checks whether the host has enough GPU devices for given resources regardless of the current allocations.
+    pub fn has_gpu_capacity(&self, resources: &ResourceConsumer) -> bool {
+        self.resources.has_gpu_capacity(resources)
+    }
+
     /// Returns total amount of a resource.
     pub fn get_total_resource(&self, id: usize) -> u64 {
         self.resources.get_resource(id).unwrap().get_available()
@@ -156,11 +243,37 @@ impl ContainerManager {
         PossibleContainerIterator::new(None, &self.containers, &self.reservations, limit, allow_deploying)
     }
 
+    /// Returns the number of existing containers of given app.
+    pub fn count_app_containers(&self, app_id: usize) -> usize {
+        self.free_containers_by_app.get(app_id).map(|s| s.len()).unwrap_or(0)
+            + self.full_containers_by_app.get(app_id).map(|s| s.len()).unwrap_or(0)
+    }
+
+    /// Returns the number of containers of given app that are not terminated.
+    pub fn count_live_app_containers(&self, app_id: usize) -> usize {
+        self.containers
+            .values()
+            .filter(|c| c.app_id == app_id && c.status != ContainerStatus::Terminated)
+            .count()
+    }
+
+    /// Returns the number of invocations of given app that are running or waiting for a deploying container.
+    pub fn count_app_invocations(&self, app_id: usize) -> usize {
+        self.containers
+            .values()
+            .filter(|c| c.app_id == app_id)
+            .map(|c| c.invocations.len() + self.count_reservations(c.id))
+            .sum()
+    }
+
     /// Tries to deploy a new container for given app.
     pub fn try_deploy(&mut self, app: &Application, time: f64) -> Option<(usize, f64)> {
+        if app.get_placement().anti_affinity && self.count_app_containers(app.id) > 0 {
+            return None;
+        }
         if self.resources.can_allocate(app.get_resources()) {
             let id = self.deploy_container(app, time);
-            return Some((id, app.get_deployment_time()));
+            return Some((id, self.containers.get(&id).unwrap().deployment_time));
         }
         None
     }
@@ -186,6 +299,13 @@ impl ContainerManager {
         self.free_containers_by_app.get_mut(container.app_id).remove(&id);
         self.full_containers_by_app.get_mut(container.app_id).remove(&id);
         self.resources.release(&container.resources);
+        self.resources.release_gpus(&container.resources, &container.gpus);
+    }
+
+    /// Returns the factor by which invocations on the container are slowed down due to GPU sharing.
+    pub fn gpu_slowdown(&self, id: usize) -> f64 {
+        let container = self.containers.get(&id).unwrap();
+        self.resources.gpus().slowdown(&container.gpus)
     }
 
     /// Moves a container to free list if it was full.
@@ -205,14 +325,48 @@ impl ContainerManager {
         self.full_containers_by_app.get_mut(app_id).insert(id);
     }
 
+    /// Checks whether the host has a running or idle container with given shared runtime.
+    pub fn is_runtime_up(&self, runtime: &str) -> bool {
+        self.containers.values().any(|c| {
+            (c.status == ContainerStatus::Running || c.status == ContainerStatus::Idle)
+                && c.runtime.as_deref() == Some(runtime)
+        })
+    }
+
+    /// Returns the deployment time of a new container of given app and whether it is restored from snapshot.
+    /// The first deployment of an app with snapshot support also starts the snapshot creation.
+    /// The runtime initialization is skipped if the app runtime is already up on the host.
+    fn deployment_delay(&self, app: &Application, time: f64) -> (f64, bool) {
+        let mut deployment_time = app.get_deployment_time();
+        if let Some(runtime) = app.get_runtime() {
+            if self.is_runtime_up(&runtime.name) {
+                deployment_time = f64::max(0., deployment_time - runtime.init_time);
+            }
+        }
+        if let Some(snapshot) = app.get_snapshot() {
+            let mut snapshots = self.snapshots.borrow_mut();
+            if snapshots.is_ready(app.id, time) {
+                return (snapshot.restore_time, true);
+            }
+            if snapshots.ready_time(app.id).is_none() {
+                let delay = deployment_time + snapshot.creation_time;
+                snapshots.start_creation(app.id, time + delay);
+                return (delay, false);
+            }
+        }
+        (deployment_time, false)
+    }
+
     /// Deploys a new container for given application.
     fn deploy_container(&mut self, app: &Application, time: f64) -> usize {
         let cont_id = self.container_counter.increment();
+        let gpus = self.resources.allocate_gpus(app.get_resources());
+        let (delay, restored_from_snapshot) = self.deployment_delay(app, time);
         let container = Container {
             status: ContainerStatus::Deploying,
             id: cont_id,
             host_id: self.host_id,
-            deployment_time: app.get_deployment_time(),
+            deployment_time: delay,
             app_id: app.id,
             invocations: Default::default(),
             resources: app.get_resources().clone(),
@@ -220,13 +374,16 @@ impl ContainerManager {
             end_event: None,
             last_change: time,
             cpu_share: app.get_cpu_share(),
+            gpus,
+            restored_from_snapshot,
+            runtime: app.get_runtime().map(|r| r.name.clone()),
         };
         self.resources.allocate(&container.resources);
         self.containers.insert(cont_id, container);
         self.free_containers_by_app.get_mut(app.id).insert(cont_id);
         self.ctx
             .borrow_mut()
-            .emit_self(ContainerStartEvent { id: cont_id }, app.get_deployment_time());
+            .emit_self(ContainerStartEvent { id: cont_id }, delay);
         cont_id
     }
 }
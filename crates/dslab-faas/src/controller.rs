@@ -2,16 +2,24 @@
 
 use std::boxed::Box;
 use std::cell::RefCell;
+use std::collections::VecDeque;
 use std::rc::Rc;
 
 use simcore::cast;
+use simcore::context::SimulationContext;
 use simcore::event::Event;
 use simcore::handler::EventHandler;
 
+use crate::autoscaler::Autoscaler;
 use crate::deployer::IdleDeployer;
-use crate::event::{IdleDeployEvent, InvocationStartEvent, SimulationEndEvent};
-use crate::function::FunctionRegistry;
+use crate::event::{
+    AutoscaleEvent, GatewayDequeueEvent, HedgeEvent, IdleDeployEvent, InvocationStartEvent, SimulationEndEvent,
+};
+use crate::function::{Application, FunctionRegistry};
+use crate::gateway::{ApiGateway, GatewayDecision};
+use crate::hedging::HedgingPolicy;
 use crate::host::Host;
+use crate::invocation::{InvocationRegistry, InvocationStatus};
 use crate::invoker::InvokerDecision;
 use crate::scheduler::Scheduler;
 use crate::stats::Stats;
@@ -19,26 +27,50 @@ use crate::stats::Stats;
 /// Responsible for handling incoming invocation requests and deploying prewarmed containers.
 pub struct Controller {
     function_registry: Rc<RefCell<FunctionRegistry>>,
+    invocation_registry: Rc<RefCell<InvocationRegistry>>,
     hosts: Vec<Rc<RefCell<Host>>>,
+    partitioned: Vec<bool>,
+    deferred_requests: VecDeque<(usize, usize)>,
     idle_deployer: Box<dyn IdleDeployer>,
     scheduler: Box<dyn Scheduler>,
+    api_gateway: Option<ApiGateway>,
+    gateway_dequeue_pending: bool,
+    autoscaler: Option<Autoscaler>,
+    autoscale_pending: bool,
+    hedging: Option<HedgingPolicy>,
     stats: Rc<RefCell<Stats>>,
+    ctx: SimulationContext,
 }
 
 impl Controller {
     /// Creates new controller.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         function_registry: Rc<RefCell<FunctionRegistry>>,
+        invocation_registry: Rc<RefCell<InvocationRegistry>>,
         idle_deployer: Box<dyn IdleDeployer>,
         scheduler: Box<dyn Scheduler>,
+        api_gateway: Option<ApiGateway>,
+        autoscaler: Option<Autoscaler>,
+        hedging: Option<HedgingPolicy>,
         stats: Rc<RefCell<Stats>>,
+        ctx: SimulationContext,
     ) -> Self {
         Self {
             function_registry,
+            invocation_registry,
             hosts: Vec::new(),
+            partitioned: Vec::new(),
+            deferred_requests: VecDeque::new(),
             idle_deployer,
             scheduler,
+            api_gateway,
+            gateway_dequeue_pending: false,
+            autoscaler,
+            autoscale_pending: false,
+            hedging,
             stats,
+            ctx,
         }
     }
 
@@ -48,23 +80,282 @@ impl Controller {
             // do not deploy container when the expected invocation has already happened
             return;
         }
-        let reg = self.function_registry.borrow();
-        let app = reg.get_app(app_id).unwrap();
-        if let Some(host) = self.idle_deployer.deploy(app, &self.hosts) {
-            self.hosts[host].borrow_mut().try_deploy(app, time);
+        let fr = self.function_registry.clone();
+        let reg = fr.borrow();
+        self.deploy_idle_container(reg.get_app(app_id).unwrap(), time);
+    }
+
+    /// Deploys a new idle container of the application, returns false if there is no suitable host.
+    fn deploy_idle_container(&mut self, app: &Application, time: f64) -> bool {
+        let host = if let Some(candidates) = self.placement_candidates(app) {
+            if candidates.is_empty() {
+                return false;
+            }
+            let subset: Vec<_> = candidates.iter().map(|&i| self.hosts[i].clone()).collect();
+            self.idle_deployer.deploy(app, &subset).map(|i| candidates[i])
+        } else {
+            self.idle_deployer.deploy(app, &self.hosts)
+        };
+        if let Some(host) = host {
+            self.hosts[host].borrow_mut().try_deploy(app, time).is_some()
+        } else {
+            false
+        }
+    }
+
+    fn schedule_autoscale(&mut self) {
+        if let Some(autoscaler) = self.autoscaler.as_ref() {
+            if !self.autoscale_pending {
+                self.ctx.emit_self(AutoscaleEvent {}, autoscaler.interval());
+                self.autoscale_pending = true;
+            }
         }
     }
 
-    fn invoke(&mut self, id: usize, func_id: usize, time: f64) -> InvokerDecision {
+    fn on_autoscale(&mut self, time: f64) {
+        self.autoscale_pending = false;
+        let autoscaler = self.autoscaler.clone().unwrap();
+        let fr = self.function_registry.clone();
+        let reg = fr.borrow();
+        // keep ticking only while some application has load or hasn't reached the desired size yet,
+        // so that the simulation can run out of events
+        let mut active = false;
+        for app in reg.get_apps() {
+            let mut concurrency = 0;
+            let mut containers = 0;
+            for host in self.reachable_hosts() {
+                let host = host.borrow();
+                concurrency += host.count_app_invocations(app.id);
+                containers += host.count_live_app_containers(app.id);
+            }
+            let desired = autoscaler.desired_containers(concurrency);
+            let mut pending = desired != containers;
+            if desired > containers {
+                for _ in containers..desired {
+                    if !self.deploy_idle_container(app, time) {
+                        // no room for new containers, retry only if the load changes
+                        pending = false;
+                        break;
+                    }
+                }
+            } else if desired < containers {
+                let mut excess = containers - desired;
+                for host in self.reachable_hosts() {
+                    if excess == 0 {
+                        break;
+                    }
+                    excess -= host.borrow_mut().terminate_idle_containers(app.id, excess);
+                }
+            }
+            active |= concurrency > 0 || pending;
+        }
+        if active {
+            self.schedule_autoscale();
+        }
+    }
+
+    fn reachable_hosts(&self) -> impl Iterator<Item = &Rc<RefCell<Host>>> {
+        self.hosts
+            .iter()
+            .zip(self.partitioned.iter())
+            .filter(|(_, &partitioned)| !partitioned)
+            .map(|(host, _)| host)
+    }
+
+    /// Checks whether the host satisfies the application placement constraints and GPU requirements.
+    fn satisfies_placement(&self, host_id: usize, app: &Application) -> bool {
+        let host = self.hosts[host_id].borrow();
+        host.has_labels(&app.get_placement().required_labels) && host.has_gpu_capacity(app.get_resources())
+    }
+
+    /// Returns indices of reachable hosts satisfying the application placement constraints and GPU requirements,
+    /// or None if the application has no such constraints and all hosts are reachable.
+    /// The returned list is empty if there are no suitable hosts or all of them are partitioned from the controller.
+    fn placement_candidates(&self, app: &Application) -> Option<Vec<usize>> {
+        let placement = app.get_placement();
+        let needs_gpu = app.get_resources().gpu().is_some();
+        let has_partitions = self.partitioned.iter().any(|&p| p);
+        if placement.required_labels.is_empty() && !placement.anti_affinity && !needs_gpu && !has_partitions {
+            return None;
+        }
+        let mut candidates: Vec<usize> = (0..self.hosts.len())
+            .filter(|&i| !self.partitioned[i] && self.satisfies_placement(i, app))
+            .collect();
+        if placement.anti_affinity {
+            // prefer hosts that can run the invocation without creating a second container of the application,
+            // otherwise the invocation is queued on one of the hosts
+            let spread: Vec<usize> = candidates
+                .iter()
+                .copied()
+                .filter(|&i| {
+                    let host = self.hosts[i].borrow();
+                    host.can_invoke(app, true) || host.count_app_containers(app.id) == 0
+                })
+                .collect();
+            if !spread.is_empty() {
+                candidates = spread;
+            }
+        }
+        Some(candidates)
+    }
+
+    fn invoke(&mut self, id: usize, func_id: usize, time: f64) -> Option<InvokerDecision> {
         let reg = self.function_registry.borrow();
         let app = reg.get_app_by_function(func_id).unwrap();
-        let host = self.scheduler.select_host(app, &self.hosts);
-        self.hosts[host].borrow_mut().invoke(id, time)
+        let host = if let Some(candidates) = self.placement_candidates(app) {
+            if candidates.is_empty() {
+                if !(0..self.hosts.len()).any(|i| self.satisfies_placement(i, app)) {
+                    self.invocation_registry.borrow_mut()[id].status = InvocationStatus::Rejected;
+                    self.stats.borrow_mut().on_rejected_invocation(app.id, func_id);
+                    return None;
+                }
+                // all suitable hosts are unreachable, hold the request until some of them reconnects
+                self.deferred_requests.push_back((id, func_id));
+                return None;
+            }
+            let subset: Vec<_> = candidates.iter().map(|&i| self.hosts[i].clone()).collect();
+            candidates[self.scheduler.select_host(app, &subset)]
+        } else {
+            self.scheduler.select_host(app, &self.hosts)
+        };
+        if let Some(hedging) = self.hedging.as_ref() {
+            self.ctx.emit_self(HedgeEvent { id }, hedging.delay());
+        }
+        Some(self.hosts[host].borrow_mut().invoke(id, time))
+    }
+
+    /// Dispatches a duplicate of the invocation to another host if the invocation hasn't finished yet.
+    fn on_hedge(&mut self, id: usize, time: f64) {
+        let mut ir = self.invocation_registry.borrow_mut();
+        let invocation = ir[id];
+        // the invocation is already completed in some way or hedged
+        if matches!(
+            invocation.status,
+            InvocationStatus::Finished
+                | InvocationStatus::TimedOut
+                | InvocationStatus::Throttled
+                | InvocationStatus::Rejected
+        ) || invocation.duplicate.is_some()
+        {
+            return;
+        }
+        let reg = self.function_registry.borrow();
+        let app = reg.get_app(invocation.app_id).unwrap();
+        let mut candidates = self
+            .placement_candidates(app)
+            .unwrap_or_else(|| (0..self.hosts.len()).collect());
+        candidates.retain(|&i| Some(i) != invocation.host_id);
+        if candidates.is_empty() {
+            return;
+        }
+        let subset: Vec<_> = candidates.iter().map(|&i| self.hosts[i].clone()).collect();
+        let host = candidates[self.scheduler.select_host(app, &subset)];
+        // the duplicate inherits the arrival time, so that its response time is measured from the original request
+        let duplicate = ir.add_invocation(
+            invocation.app_id,
+            invocation.func_id,
+            invocation.duration,
+            invocation.arrival_time,
+        );
+        ir[duplicate].duplicate_of = Some(id);
+        ir[id].duplicate = Some(duplicate);
+        drop(ir);
+        drop(reg);
+        self.hosts[host].borrow_mut().invoke(duplicate, time);
+    }
+
+    fn on_request(&mut self, id: usize, func_id: usize, time: f64) {
+        self.schedule_autoscale();
+        if let Some(gateway) = self.api_gateway.as_mut() {
+            match gateway.on_request(id, func_id, time) {
+                GatewayDecision::Pass => {
+                    self.invoke(id, func_id, time);
+                }
+                GatewayDecision::Queued => {
+                    if !self.gateway_dequeue_pending {
+                        let delay = gateway.next_token_delay();
+                        self.ctx.emit_self(GatewayDequeueEvent {}, delay);
+                        self.gateway_dequeue_pending = true;
+                    }
+                }
+                GatewayDecision::Throttled => {
+                    let mut ir = self.invocation_registry.borrow_mut();
+                    let invocation = &mut ir[id];
+                    invocation.status = InvocationStatus::Throttled;
+                    self.stats
+                        .borrow_mut()
+                        .on_throttled_invocation(invocation.app_id, invocation.func_id);
+                }
+            }
+        } else {
+            self.invoke(id, func_id, time);
+        }
+    }
+
+    fn on_gateway_dequeue(&mut self, time: f64) {
+        self.gateway_dequeue_pending = false;
+        let gateway = self.api_gateway.as_mut().unwrap();
+        let passed = gateway.dequeue(time);
+        if gateway.queue_len() > 0 {
+            let delay = gateway.next_token_delay();
+            self.ctx.emit_self(GatewayDequeueEvent {}, delay);
+            self.gateway_dequeue_pending = true;
+        }
+        for (id, func_id) in passed {
+            self.invoke(id, func_id, time);
+        }
+    }
+
+    /// Returns the API gateway if it is enabled.
+    pub fn api_gateway(&self) -> Option<&ApiGateway> {
+        self.api_gateway.as_ref()
+    }
+
+    /// Returns the autoscaler if it is enabled.
+    pub fn autoscaler(&self) -> Option<&Autoscaler> {
+        self.autoscaler.as_ref()
+    }
+
+    /// Returns the number of containers of given application that are not terminated.
+    pub fn count_app_containers(&self, app_id: usize) -> usize {
+        self.hosts
+            .iter()
+            .map(|host| host.borrow().count_live_app_containers(app_id))
+            .sum()
     }
 
     /// Registers a new host in the controller.
     pub fn add_host(&mut self, host: Rc<RefCell<Host>>) {
         self.hosts.push(host);
+        self.partitioned.push(false);
+    }
+
+    /// Partitions the host from the controller, modeling a control plane failure.
+    /// New invocations and containers are not placed on the partitioned host, while the invocations
+    /// that are already running there are not affected.
+    pub fn partition_host(&mut self, host_id: usize) {
+        self.partitioned[host_id] = true;
+    }
+
+    /// Restores connectivity between the controller and the host.
+    /// Requests that couldn't be dispatched while all suitable hosts were unreachable are retried.
+    pub fn heal_host(&mut self, host_id: usize) {
+        self.partitioned[host_id] = false;
+        let time = self.ctx.time();
+        let deferred: Vec<_> = self.deferred_requests.drain(..).collect();
+        for (id, func_id) in deferred {
+            self.invoke(id, func_id, time);
+        }
+    }
+
+    /// Checks whether the host is reachable from the controller.
+    pub fn is_host_reachable(&self, host_id: usize) -> bool {
+        !self.partitioned[host_id]
+    }
+
+    /// Returns the number of requests waiting for a reachable host.
+    pub fn deferred_requests(&self) -> usize {
+        self.deferred_requests.len()
     }
 
     fn update_end_metrics(&mut self, time: f64) {
@@ -77,6 +368,15 @@ impl Controller {
 impl EventHandler for Controller {
     fn on(&mut self, event: Event) {
         cast!(match event.data {
+            AutoscaleEvent {} => {
+                self.on_autoscale(event.time);
+            }
+            GatewayDequeueEvent {} => {
+                self.on_gateway_dequeue(event.time);
+            }
+            HedgeEvent { id } => {
+                self.on_hedge(id, event.time);
+            }
             IdleDeployEvent {
                 id,
                 expected_invocation,
@@ -84,7 +384,7 @@ impl EventHandler for Controller {
                 self.idle_deploy(id, expected_invocation, event.time);
             }
             InvocationStartEvent { id, func_id } => {
-                self.invoke(id, func_id, event.time);
+                self.on_request(id, func_id, event.time);
             }
             SimulationEndEvent {} => {
                 self.update_end_metrics(event.time);
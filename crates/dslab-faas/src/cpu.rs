@@ -14,7 +14,7 @@ use simcore::event::EventId;
 
 use crate::container::Container;
 use crate::event::InvocationEndEvent;
-use crate::invocation::Invocation;
+use crate::invocation::{Invocation, InvocationStatus};
 
 #[derive(Clone)]
 struct WorkItem {
@@ -161,11 +161,20 @@ impl CpuPolicy for IsolatedCpuPolicy {
 
     fn on_invocation_end(
         &mut self,
-        _invocation: &mut Invocation,
+        invocation: &mut Invocation,
         container: &mut Container,
         _time: f64,
         ctx: &mut SimulationContext,
     ) {
+        // an invocation may be terminated by timeout while its container waits for free cores
+        if let Some(invs) = self.invocation_map.get_mut(&container.id) {
+            invs.retain(|inv| inv.0 != invocation.id);
+            if invs.is_empty() {
+                self.invocation_map.remove(&container.id);
+                self.queue.retain(|item| item.0 != container.id);
+            }
+            return;
+        }
         if container.invocations.is_empty() {
             self.load -= container.cpu_share;
             while let Some(item) = self.queue.pop_front() {
@@ -301,7 +310,11 @@ impl CpuPolicy for ContendedCpuPolicy {
         time: f64,
         ctx: &mut SimulationContext,
     ) {
-        self.end_event = None;
+        if invocation.status != InvocationStatus::TimedOut {
+            // the end event of this invocation is being processed, otherwise it's still pending
+            // and is cancelled on rescheduling
+            self.end_event = None;
+        }
         self.shift_time(time);
         self.remove_invocation(invocation.id);
         if !container.invocations.is_empty() {
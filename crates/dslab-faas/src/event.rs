@@ -1,6 +1,10 @@
 //! Simulation events.
 use serde::Serialize;
 
+/// The autoscaler must update the number of containers.
+#[derive(Clone, Serialize)]
+pub struct AutoscaleEvent {}
+
 /// An idle container must be destroyed.
 #[derive(Clone, Serialize)]
 pub struct ContainerEndEvent {
@@ -15,6 +19,17 @@ pub struct ContainerStartEvent {
     pub id: usize,
 }
 
+/// Some of the requests queued at the API gateway may pass through it.
+#[derive(Clone, Serialize)]
+pub struct GatewayDequeueEvent {}
+
+/// A duplicate of an invocation must be dispatched if the invocation hasn't finished yet.
+#[derive(Clone, Serialize)]
+pub struct HedgeEvent {
+    /// Invocation id.
+    pub id: usize,
+}
+
 /// A new prewarmed container is deployed.
 #[derive(Clone, Serialize)]
 pub struct IdleDeployEvent {
@@ -31,6 +46,13 @@ pub struct InvocationEndEvent {
     pub id: usize,
 }
 
+/// A running invocation exceeds the function timeout.
+#[derive(Clone, Serialize)]
+pub struct InvocationTimeoutEvent {
+    /// Invocation id.
+    pub id: usize,
+}
+
 /// A new invocation starts executing.
 #[derive(Clone, Serialize)]
 pub struct InvocationStartEvent {
@@ -0,0 +1,306 @@
+//! Functions for parsing OpenWhisk and Knative activation logs into a workload trace.
+//!
+//! The logs are read as CSV tables (e.g. exported from OpenWhisk activation records or Knative request logs),
+//! the columns are located by their header names, see [`ActivationLogColumns`].
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+use std::str::FromStr;
+
+use chrono::{DateTime, NaiveDateTime};
+use csv::{ReaderBuilder, StringRecord};
+
+use crate::extra::azure_trace_2019::{ApplicationRecord, AzureTrace, FunctionRecord};
+use crate::trace::RequestData;
+
+/// Format of timestamps in the log.
+#[derive(Clone, Debug)]
+pub enum TimestampFormat {
+    /// Unix time in seconds.
+    UnixSeconds,
+    /// Unix time in milliseconds (used by OpenWhisk).
+    UnixMillis,
+    /// RFC 3339 date and time with timezone, e.g. `2023-05-01T12:00:00.5+03:00` (used by Knative).
+    Rfc3339,
+    /// Date and time in a custom [chrono format](chrono::format::strftime) without timezone,
+    /// which is interpreted as local time with the given offset from UTC in seconds.
+    Custom {
+        /// Format string.
+        format: String,
+        /// Offset of the local time from UTC in seconds.
+        utc_offset: i64,
+    },
+}
+
+/// Unit of durations in the log.
+///
+/// Durations with a unit suffix (`s`, `ms`, `us`), such as Go-formatted latencies in Knative logs,
+/// are parsed according to the suffix.
+#[derive(Clone, Copy, Debug)]
+pub enum DurationUnit {
+    /// Seconds.
+    Seconds,
+    /// Milliseconds.
+    Millis,
+}
+
+/// Names of the log columns used to build the trace.
+#[derive(Clone, Debug)]
+pub struct ActivationLogColumns {
+    /// Function name.
+    pub function: String,
+    /// Application name. If not set, each function forms its own application.
+    pub app: Option<String>,
+    /// Activation start (or request arrival) time.
+    pub start: String,
+    /// Activation end time.
+    pub end: Option<String>,
+    /// Activation duration.
+    pub duration: Option<String>,
+    /// Memory limit of the function container.
+    pub memory: Option<String>,
+}
+
+/// Struct with activation log settings.
+pub struct ActivationLogConfig {
+    /// Mapping of the log columns.
+    pub columns: ActivationLogColumns,
+    /// Format of the start and end times.
+    pub timestamp_format: TimestampFormat,
+    /// Unit of the durations.
+    pub duration_unit: DurationUnit,
+    /// Field delimiter.
+    pub delimiter: u8,
+    /// This option sets concurrency level for all apps in the trace.
+    pub concurrency_level: usize,
+    /// This option sets name for the memory resource.
+    pub memory_name: String,
+    /// Memory of apps without memory information in the log.
+    pub default_memory: u64,
+    /// Cold start latency, currently it's the same for all apps.
+    pub cold_start_latency: f64,
+    /// Duration of activations if neither the duration nor the end time is known and the function has no
+    /// other activations with known duration (otherwise the mean duration of the function is used).
+    pub default_duration: f64,
+}
+
+impl ActivationLogConfig {
+    /// Returns the settings for OpenWhisk activation records with fields `namespace`, `name`, `start`, `end`,
+    /// `duration` and `memory`, where the times and durations are in milliseconds.
+    pub fn openwhisk() -> Self {
+        Self {
+            columns: ActivationLogColumns {
+                function: "name".to_string(),
+                app: Some("namespace".to_string()),
+                start: "start".to_string(),
+                end: Some("end".to_string()),
+                duration: Some("duration".to_string()),
+                memory: Some("memory".to_string()),
+            },
+            timestamp_format: TimestampFormat::UnixMillis,
+            duration_unit: DurationUnit::Millis,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the settings for Knative request logs with fields `service`, `revision`, `timestamp` (RFC 3339)
+    /// and `latency` (in seconds, possibly with unit suffix).
+    pub fn knative() -> Self {
+        Self {
+            columns: ActivationLogColumns {
+                function: "revision".to_string(),
+                app: Some("service".to_string()),
+                start: "timestamp".to_string(),
+                end: None,
+                duration: Some("latency".to_string()),
+                memory: None,
+            },
+            timestamp_format: TimestampFormat::Rfc3339,
+            duration_unit: DurationUnit::Seconds,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for ActivationLogConfig {
+    fn default() -> Self {
+        Self {
+            columns: ActivationLogColumns {
+                function: "function".to_string(),
+                app: None,
+                start: "start".to_string(),
+                end: Some("end".to_string()),
+                duration: Some("duration".to_string()),
+                memory: None,
+            },
+            timestamp_format: TimestampFormat::UnixSeconds,
+            duration_unit: DurationUnit::Seconds,
+            delimiter: b',',
+            concurrency_level: 1,
+            memory_name: "mem".to_string(),
+            default_memory: 256,
+            cold_start_latency: 1.,
+            default_duration: 1.,
+        }
+    }
+}
+
+/// Parses timestamp into seconds since Unix epoch.
+pub fn parse_timestamp(value: &str, format: &TimestampFormat) -> Result<f64, String> {
+    let value = value.trim();
+    match format {
+        TimestampFormat::UnixSeconds => f64::from_str(value).map_err(|e| e.to_string()),
+        TimestampFormat::UnixMillis => f64::from_str(value).map(|t| t / 1000.).map_err(|e| e.to_string()),
+        TimestampFormat::Rfc3339 => DateTime::parse_from_rfc3339(value)
+            .map(|t| t.timestamp_micros() as f64 / 1e6)
+            .map_err(|e| e.to_string()),
+        TimestampFormat::Custom { format, utc_offset } => NaiveDateTime::parse_from_str(value, format)
+            .map(|t| t.and_utc().timestamp_micros() as f64 / 1e6 - *utc_offset as f64)
+            .map_err(|e| e.to_string()),
+    }
+}
+
+/// Parses duration into seconds.
+pub fn parse_duration(value: &str, unit: DurationUnit) -> Result<f64, String> {
+    let value = value.trim();
+    let (number, scale) = if let Some(number) = value.strip_suffix("ms") {
+        (number, 1e-3)
+    } else if let Some(number) = value.strip_suffix("us") {
+        (number, 1e-6)
+    } else if let Some(number) = value.strip_suffix('s') {
+        (number, 1.)
+    } else {
+        match unit {
+            DurationUnit::Seconds => (value, 1.),
+            DurationUnit::Millis => (value, 1e-3),
+        }
+    };
+    f64::from_str(number).map(|d| d * scale).map_err(|e| e.to_string())
+}
+
+/// This function parses activation log file and generates experiment.
+pub fn process_activation_log(path: &Path, config: ActivationLogConfig) -> AzureTrace {
+    let file = std::fs::File::open(path).unwrap_or_else(|e| panic!("Can't open {}: {}", path.display(), e));
+    parse_activation_log(file, config)
+}
+
+/// This function parses activation log from a reader and generates experiment.
+///
+/// Request times are shifted so that the first request arrives at time zero.
+/// Rows with empty function name or start time are skipped.
+pub fn parse_activation_log<R: Read>(reader: R, config: ActivationLogConfig) -> AzureTrace {
+    let mut reader = ReaderBuilder::new()
+        .delimiter(config.delimiter)
+        .flexible(true)
+        .from_reader(reader);
+    let headers = reader.headers().unwrap().clone();
+    let column = |name: &str| {
+        headers
+            .iter()
+            .position(|h| h.trim() == name)
+            .unwrap_or_else(|| panic!("Column {} is not found in the activation log", name))
+    };
+    let columns = &config.columns;
+    let function_col = column(&columns.function);
+    let app_col = columns.app.as_deref().map(column);
+    let start_col = column(&columns.start);
+    let end_col = columns.end.as_deref().map(column);
+    let duration_col = columns.duration.as_deref().map(column);
+    let memory_col = columns.memory.as_deref().map(column);
+    let field = |record: &StringRecord, col: Option<usize>| {
+        col.and_then(|col| record.get(col))
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+
+    let mut func_data = HashMap::<String, usize>::new();
+    let mut app_data = HashMap::<String, usize>::new();
+    let mut func_records = Vec::<FunctionRecord>::new();
+    let mut app_records = Vec::<ApplicationRecord>::new();
+    // (function id, start, duration if known)
+    let mut activations = Vec::<(usize, f64, Option<f64>)>::new();
+    for rec in reader.records() {
+        let record = rec.unwrap();
+        let (Some(func), Some(start)) = (field(&record, Some(function_col)), field(&record, Some(start_col))) else {
+            continue;
+        };
+        let start = parse_timestamp(&start, &config.timestamp_format)
+            .unwrap_or_else(|e| panic!("Can't parse start time {}: {}", start, e));
+        let duration = field(&record, duration_col)
+            .map(|d| {
+                parse_duration(&d, config.duration_unit).unwrap_or_else(|e| panic!("Can't parse duration {}: {}", d, e))
+            })
+            .or_else(|| {
+                field(&record, end_col).map(|end| {
+                    parse_timestamp(&end, &config.timestamp_format)
+                        .unwrap_or_else(|e| panic!("Can't parse end time {}: {}", end, e))
+                        - start
+                })
+            });
+        let memory = field(&record, memory_col)
+            .map(|m| u64::from_str(&m).unwrap_or_else(|e| panic!("Can't parse memory {}: {}", m, e)));
+
+        let app = field(&record, app_col).unwrap_or_else(|| func.clone());
+        let app_id = *app_data.entry(app).or_insert_with(|| {
+            app_records.push(ApplicationRecord {
+                mem: 0,
+                cold_start: config.cold_start_latency,
+            });
+            app_records.len() - 1
+        });
+        if let Some(memory) = memory {
+            app_records[app_id].mem = app_records[app_id].mem.max(memory);
+        }
+        let func_id = *func_data.entry(format!("{}/{}", app_id, func)).or_insert_with(|| {
+            func_records.push(FunctionRecord { app_id });
+            func_records.len() - 1
+        });
+        activations.push((func_id, start, duration));
+    }
+    for app in app_records.iter_mut() {
+        if app.mem == 0 {
+            app.mem = config.default_memory;
+        }
+    }
+
+    let mut known_durations = vec![(0., 0usize); func_records.len()];
+    for &(func_id, _, duration) in activations.iter() {
+        if let Some(duration) = duration {
+            known_durations[func_id].0 += duration;
+            known_durations[func_id].1 += 1;
+        }
+    }
+    let time_start = activations.iter().map(|a| a.1).fold(f64::INFINITY, f64::min);
+    let mut invocations = activations
+        .into_iter()
+        .map(|(func_id, start, duration)| {
+            let duration = duration.unwrap_or_else(|| {
+                let (sum, count) = known_durations[func_id];
+                if count > 0 {
+                    sum / count as f64
+                } else {
+                    config.default_duration
+                }
+            });
+            RequestData {
+                id: func_id,
+                duration: f64::max(0.001, duration),
+                time: start - time_start,
+            }
+        })
+        .collect::<Vec<_>>();
+    invocations.sort_by(|a, b| a.time.total_cmp(&b.time));
+
+    let mut time_range = 0.0;
+    for req in invocations.iter() {
+        time_range = f64::max(time_range, req.time + req.duration);
+    }
+    AzureTrace {
+        concurrency_level: config.concurrency_level,
+        memory_name: config.memory_name,
+        sim_end: Some(time_range),
+        trace_records: invocations,
+        function_records: func_records,
+        app_records,
+    }
+}
@@ -0,0 +1,136 @@
+//! Coldstart policy that predicts the next invocation of a function from the exponentially weighted moving average of its interarrival times.
+use std::collections::{HashMap, HashSet};
+
+use crate::coldstart::{ColdStartPolicy, KeepaliveDecision};
+use crate::container::Container;
+use crate::function::Application;
+use crate::invocation::Invocation;
+
+struct FunctionData {
+    pub last_arrival: f64,
+    /// EWMA of interarrival times, known after the second arrival.
+    pub mean: Option<f64>,
+    /// Exponentially weighted variance of interarrival times.
+    pub variance: f64,
+}
+
+/// Sets the keepalive window of a container to a multiple of the predicted interarrival time of its application,
+/// shrinking the window for bursty traffic.
+///
+/// For each function the policy maintains the exponentially weighted moving average (EWMA) of interarrival times
+/// and their exponentially weighted variance. The keepalive window for a function is
+/// `multiplier * mean / (1 + cv)`, where `cv` is the coefficient of variation of interarrival times,
+/// so that bursts of invocations followed by long gaps don't keep the containers idle for the whole gaps.
+/// The window is limited by `max_keepalive`, which is also used until at least two invocations of the function
+/// are known. A container is kept for the maximum window among the functions of its application.
+pub struct AdaptiveExponentialPolicy {
+    smoothing: f64,
+    multiplier: f64,
+    max_keepalive: f64,
+    functions: HashMap<usize, FunctionData>,
+    app_functions: HashMap<usize, HashSet<usize>>,
+}
+
+impl AdaptiveExponentialPolicy {
+    /// Creates new AdaptiveExponentialPolicy, `smoothing` is the weight of the latest interarrival time in EWMA.
+    pub fn new(smoothing: f64, multiplier: f64, max_keepalive: f64) -> Self {
+        assert!(smoothing > 0. && smoothing <= 1., "Smoothing factor must be in (0, 1]");
+        Self {
+            smoothing,
+            multiplier,
+            max_keepalive,
+            functions: HashMap::new(),
+            app_functions: HashMap::new(),
+        }
+    }
+
+    /// Creates policy from a map of strings containing policy parameters.
+    pub fn from_options_map(options: &HashMap<String, String>) -> Self {
+        let smoothing = options.get("smoothing").unwrap().parse::<f64>().unwrap();
+        let multiplier = options
+            .get("multiplier")
+            .map(|x| x.parse::<f64>().unwrap())
+            .unwrap_or(2.0);
+        let max_keepalive = options.get("max_keepalive").unwrap().parse::<f64>().unwrap();
+        Self::new(smoothing, multiplier, max_keepalive)
+    }
+
+    /// Returns the predicted interarrival time of the function, or None if it is not known yet.
+    pub fn predicted_interarrival(&self, func_id: usize) -> Option<f64> {
+        self.functions.get(&func_id).and_then(|data| data.mean)
+    }
+
+    /// Returns the keepalive window for the function.
+    pub fn function_window(&self, func_id: usize) -> f64 {
+        match self.functions.get(&func_id) {
+            Some(FunctionData {
+                mean: Some(mean),
+                variance,
+                ..
+            }) => {
+                let cv = if *mean > 0. { variance.sqrt() / mean } else { 0. };
+                f64::min(self.max_keepalive, self.multiplier * mean / (1. + cv))
+            }
+            _ => self.max_keepalive,
+        }
+    }
+}
+
+impl ColdStartPolicy for AdaptiveExponentialPolicy {
+    fn keepalive_decision(&mut self, container: &Container) -> KeepaliveDecision {
+        let window = match self.app_functions.get(&container.app_id) {
+            Some(functions) => functions
+                .iter()
+                .map(|&func_id| self.function_window(func_id))
+                .fold(0., f64::max),
+            None => self.max_keepalive,
+        };
+        KeepaliveDecision::NewWindow(window)
+    }
+
+    fn prewarm_window(&mut self, _app: &Application) -> f64 {
+        0.0
+    }
+
+    fn update(&mut self, invocation: &Invocation, app: &Application) {
+        self.app_functions.entry(app.id).or_default().insert(invocation.func_id);
+        let arrival = invocation.arrival_time;
+        let data = match self.functions.get_mut(&invocation.func_id) {
+            Some(data) => data,
+            None => {
+                self.functions.insert(
+                    invocation.func_id,
+                    FunctionData {
+                        last_arrival: arrival,
+                        mean: None,
+                        variance: 0.,
+                    },
+                );
+                return;
+            }
+        };
+        // invocations are reported on completion, so the concurrent ones may come out of arrival order
+        if arrival <= data.last_arrival {
+            return;
+        }
+        let interarrival = arrival - data.last_arrival;
+        data.last_arrival = arrival;
+        match data.mean {
+            Some(mean) => {
+                let diff = interarrival - mean;
+                data.mean = Some(mean + self.smoothing * diff);
+                data.variance = (1. - self.smoothing) * (data.variance + self.smoothing * diff * diff);
+            }
+            None => {
+                data.mean = Some(interarrival);
+            }
+        }
+    }
+
+    fn to_string(&self) -> String {
+        format!(
+            "AdaptiveExponentialPolicy[smoothing={:.2},multiplier={:.2},max_keepalive={:.2}]",
+            self.smoothing, self.multiplier, self.max_keepalive
+        )
+    }
+}
@@ -0,0 +1,138 @@
+//! Coldstart policy that keeps warm the containers of applications with the most expensive cold starts under a global memory budget.
+use std::collections::HashMap;
+
+use crate::coldstart::{ColdStartPolicy, KeepaliveDecision};
+use crate::container::Container;
+use crate::function::Application;
+use crate::invocation::Invocation;
+
+struct ApplicationData {
+    pub cold_start: f64,
+    pub invocations: usize,
+    pub first_arrival: f64,
+    /// Memory of the application container, known after its first keepalive decision.
+    pub memory: Option<u64>,
+}
+
+struct WarmContainer {
+    pub app_id: usize,
+    pub memory: u64,
+    pub until: f64,
+}
+
+/// Keeps idle containers warm for a fixed keepalive window while their total memory fits into a global budget
+/// shared by all hosts, preferring applications with the highest cold start cost.
+///
+/// The cold start cost of an application is its container deployment time multiplied by its invocation rate.
+/// An idle container is kept warm only if its memory fits into the budget together with the memory of the containers
+/// kept warm already and the memory reserved for the more expensive applications that currently have no warm containers
+/// (the size of one container per application). Otherwise the container is terminated right away.
+/// The invocation rate of an application is estimated over the time since its first invocation,
+/// but not less than the keepalive window.
+pub struct CostAwareColdStartPolicy {
+    keepalive_window: f64,
+    memory_budget: u64,
+    memory_resource: usize,
+    apps: HashMap<usize, ApplicationData>,
+    warm: HashMap<(usize, usize), WarmContainer>,
+}
+
+impl CostAwareColdStartPolicy {
+    /// Creates new CostAwareColdStartPolicy, `memory_resource` is the id of the resource treated as memory.
+    pub fn new(keepalive_window: f64, memory_budget: u64, memory_resource: usize) -> Self {
+        Self {
+            keepalive_window,
+            memory_budget,
+            memory_resource,
+            apps: HashMap::new(),
+            warm: HashMap::new(),
+        }
+    }
+
+    /// Creates policy from a map of strings containing policy parameters.
+    pub fn from_options_map(options: &HashMap<String, String>) -> Self {
+        let keepalive = options.get("keepalive").unwrap().parse::<f64>().unwrap();
+        let budget = options.get("budget").unwrap().parse::<u64>().unwrap();
+        let memory_resource = options
+            .get("memory_resource")
+            .map(|x| x.parse::<usize>().unwrap())
+            .unwrap_or(0);
+        Self::new(keepalive, budget, memory_resource)
+    }
+
+    /// Returns the total memory of containers which are currently kept warm by the policy.
+    pub fn warm_memory(&self) -> u64 {
+        self.warm.values().map(|c| c.memory).sum()
+    }
+
+    fn cost(&self, app_id: usize, time: f64) -> f64 {
+        match self.apps.get(&app_id) {
+            Some(data) => {
+                let rate = data.invocations as f64 / f64::max(time - data.first_arrival, self.keepalive_window);
+                data.cold_start * rate
+            }
+            None => 0.,
+        }
+    }
+}
+
+impl ColdStartPolicy for CostAwareColdStartPolicy {
+    fn keepalive_decision(&mut self, container: &Container) -> KeepaliveDecision {
+        // last_change should be equal to current time
+        let time = container.last_change;
+        self.warm.retain(|_, c| c.until > time);
+        self.warm.remove(&(container.host_id, container.id));
+        let memory = container
+            .resources
+            .iter()
+            .find(|(id, _)| *id == self.memory_resource)
+            .map(|(_, req)| req.quantity)
+            .unwrap_or(0);
+        if let Some(data) = self.apps.get_mut(&container.app_id) {
+            data.memory = Some(memory);
+        }
+
+        let cost = self.cost(container.app_id, time);
+        let reserved: u64 = self
+            .apps
+            .iter()
+            .filter(|(&app_id, _)| app_id != container.app_id && self.cost(app_id, time) > cost)
+            .filter(|(&app_id, _)| self.warm.values().all(|c| c.app_id != app_id))
+            .map(|(_, data)| data.memory.unwrap_or(0))
+            .sum();
+        if self.warm_memory() + reserved + memory > self.memory_budget {
+            return KeepaliveDecision::TerminateNow;
+        }
+        self.warm.insert(
+            (container.host_id, container.id),
+            WarmContainer {
+                app_id: container.app_id,
+                memory,
+                until: time + self.keepalive_window,
+            },
+        );
+        KeepaliveDecision::NewWindow(self.keepalive_window)
+    }
+
+    fn prewarm_window(&mut self, _app: &Application) -> f64 {
+        0.0
+    }
+
+    fn update(&mut self, invocation: &Invocation, app: &Application) {
+        let data = self.apps.entry(app.id).or_insert_with(|| ApplicationData {
+            cold_start: app.get_deployment_time(),
+            invocations: 0,
+            first_arrival: invocation.arrival_time,
+            memory: None,
+        });
+        data.invocations += 1;
+        data.first_arrival = f64::min(data.first_arrival, invocation.arrival_time);
+    }
+
+    fn to_string(&self) -> String {
+        format!(
+            "CostAwareColdStartPolicy[keepalive={:.2},budget={},memory_resource={}]",
+            self.keepalive_window, self.memory_budget, self.memory_resource
+        )
+    }
+}
@@ -1,7 +1,10 @@
 //! Various implementations of DSLab FaaS traits, including trace formats, schedulers and coldstart policies.
+pub mod activation_log;
+pub mod adaptive_exponential;
 pub(crate) mod arima_extra;
 pub mod azure_trace_2019;
 pub mod azure_trace_2021;
+pub mod cost_aware;
 pub mod hermod;
 pub mod hybrid_histogram;
 pub mod opendc_trace;
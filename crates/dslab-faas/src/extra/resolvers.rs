@@ -1,6 +1,8 @@
 //! Additional resolvers for YAML configs.
 use crate::coldstart::{default_coldstart_policy_resolver, ColdStartPolicy};
 use crate::config::parse_options;
+use crate::extra::adaptive_exponential::AdaptiveExponentialPolicy;
+use crate::extra::cost_aware::CostAwareColdStartPolicy;
 use crate::extra::hermod::HermodScheduler;
 use crate::extra::hybrid_histogram::HybridHistogramPolicy;
 use crate::scheduler::{default_scheduler_resolver, Scheduler};
@@ -11,6 +13,14 @@ pub fn extra_coldstart_policy_resolver(s: &str) -> Box<dyn ColdStartPolicy> {
         let opts = parse_options(&s[22..s.len() - 1]);
         return Box::new(HybridHistogramPolicy::from_options_map(&opts));
     }
+    if s.len() >= 26 && &s[0..25] == "CostAwareColdStartPolicy[" && s.ends_with(']') {
+        let opts = parse_options(&s[25..s.len() - 1]);
+        return Box::new(CostAwareColdStartPolicy::from_options_map(&opts));
+    }
+    if s.len() >= 27 && &s[0..26] == "AdaptiveExponentialPolicy[" && s.ends_with(']') {
+        let opts = parse_options(&s[26..s.len() - 1]);
+        return Box::new(AdaptiveExponentialPolicy::from_options_map(&opts));
+    }
     default_coldstart_policy_resolver(s)
 }
 
@@ -1,6 +1,18 @@
 //! Function and application models.
+use std::collections::HashMap;
+
+use crate::container::{SharedRuntime, Snapshot};
 use crate::resource::ResourceConsumer;
 
+/// Constraints on the placement of application containers.
+#[derive(Clone, Default)]
+pub struct PlacementConstraints {
+    /// Labels that a host must have to run containers of the application.
+    pub required_labels: Vec<String>,
+    /// If true, at most one container of the application can exist on each host.
+    pub anti_affinity: bool,
+}
+
 /// An application shares a common container image.
 /// Functions from the same application can be executed on the same container (limited by `concurrent_invocations` field).
 pub struct Application {
@@ -10,6 +22,9 @@ pub struct Application {
     container_deployment_time: f64,
     container_cpu_share: f64,
     container_resources: ResourceConsumer,
+    placement: PlacementConstraints,
+    snapshot: Option<Snapshot>,
+    runtime: Option<SharedRuntime>,
 }
 
 impl Application {
@@ -26,9 +41,34 @@ impl Application {
             container_deployment_time,
             container_cpu_share,
             container_resources,
+            placement: Default::default(),
+            snapshot: None,
+            runtime: None,
         }
     }
 
+    /// Sets placement constraints for containers of this application.
+    pub fn with_placement(mut self, placement: PlacementConstraints) -> Self {
+        self.placement = placement;
+        self
+    }
+
+    /// Enables restoring containers of this application from a snapshot.
+    pub fn with_snapshot(mut self, snapshot: Snapshot) -> Self {
+        self.snapshot = Some(snapshot);
+        self
+    }
+
+    /// Sets the runtime shared by containers of this application with other applications.
+    pub fn with_runtime(mut self, runtime: SharedRuntime) -> Self {
+        assert!(
+            runtime.init_time >= 0. && runtime.init_time <= self.container_deployment_time,
+            "Runtime initialization time must be between 0 and the container deployment time"
+        );
+        self.runtime = Some(runtime);
+        self
+    }
+
     /// Returns maximum possible number of invocations that can be run simultaneously on one container of this application.
     pub fn get_concurrent_invocations(&self) -> usize {
         self.concurrent_invocations
@@ -48,18 +88,78 @@ impl Application {
     pub fn get_resources(&self) -> &ResourceConsumer {
         &self.container_resources
     }
+
+    /// Returns placement constraints for containers of this application.
+    pub fn get_placement(&self) -> &PlacementConstraints {
+        &self.placement
+    }
+
+    /// Returns snapshot restore parameters of this application, if snapshots are enabled.
+    pub fn get_snapshot(&self) -> Option<&Snapshot> {
+        self.snapshot.as_ref()
+    }
+
+    /// Returns the shared runtime of this application, if any.
+    pub fn get_runtime(&self) -> Option<&SharedRuntime> {
+        self.runtime.as_ref()
+    }
 }
 
 /// A single function of an application.
 pub struct Function {
     /// Application id.
     pub app_id: usize,
+    timeout: Option<f64>,
 }
 
 impl Function {
     /// Creates new function.
     pub fn new(app_id: usize) -> Self {
-        Self { app_id }
+        Self { app_id, timeout: None }
+    }
+
+    /// Sets the maximum execution time of the function invocations.
+    /// Invocations running longer are terminated along with their container.
+    pub fn set_timeout(&mut self, timeout: f64) {
+        assert!(timeout > 0., "Function timeout must be positive");
+        self.timeout = Some(timeout);
+    }
+
+    /// Returns the maximum execution time of the function invocations, if it is limited.
+    pub fn get_timeout(&self) -> Option<f64> {
+        self.timeout
+    }
+}
+
+/// A version of a function that receives some share of the function invocations.
+///
+/// Each version is implemented by a separate [`Function`], possibly of another application,
+/// so it can have different cold start behavior, and its invocations are accounted separately in the stats.
+#[derive(Clone, Copy, Debug)]
+pub struct FunctionVersion {
+    /// Id of the function implementing the version.
+    pub func_id: usize,
+    /// Relative share of invocations routed to the version.
+    pub weight: f64,
+    /// Multiplier applied to the duration of invocations routed to the version.
+    pub duration_factor: f64,
+}
+
+impl FunctionVersion {
+    /// Creates new version with unchanged invocation durations.
+    pub fn new(func_id: usize, weight: f64) -> Self {
+        Self {
+            func_id,
+            weight,
+            duration_factor: 1.,
+        }
+    }
+
+    /// Sets the multiplier of invocation durations.
+    pub fn with_duration_factor(mut self, duration_factor: f64) -> Self {
+        assert!(duration_factor > 0., "Duration factor must be positive");
+        self.duration_factor = duration_factor;
+        self
     }
 }
 
@@ -68,6 +168,7 @@ impl Function {
 pub struct FunctionRegistry {
     apps: Vec<Application>,
     functions: Vec<Function>,
+    traffic_splits: HashMap<usize, Vec<FunctionVersion>>,
 }
 
 impl FunctionRegistry {
@@ -80,6 +181,11 @@ impl FunctionRegistry {
         }
     }
 
+    /// Returns a mutable reference to a [`Function`] by its `id`.
+    pub fn get_function_mut(&mut self, id: usize) -> Option<&mut Function> {
+        self.functions.get_mut(id)
+    }
+
     /// Returns a reference to an [`Application`] by its `id`.
     pub fn get_app(&self, id: usize) -> Option<&Application> {
         if id < self.apps.len() {
@@ -89,6 +195,11 @@ impl FunctionRegistry {
         }
     }
 
+    /// Returns all registered applications.
+    pub fn get_apps(&self) -> &[Application] {
+        &self.apps
+    }
+
     /// Returns a reference to an [`Application`] that owns a [`Function`] specified by `id`.
     pub fn get_app_by_function(&self, id: usize) -> Option<&Application> {
         if let Some(func) = self.get_function(id) {
@@ -111,6 +222,42 @@ impl FunctionRegistry {
         self.add_function(Function::new(app_id))
     }
 
+    /// Sets the versions among which the invocations of the function specified by `id` are split.
+    /// The function itself may be one of the versions.
+    pub fn set_traffic_split(&mut self, id: usize, versions: Vec<FunctionVersion>) {
+        assert!(id < self.functions.len(), "Non-existing function id {}", id);
+        assert!(
+            versions.iter().all(|v| v.func_id < self.functions.len()),
+            "Non-existing function among the versions"
+        );
+        assert!(
+            versions.iter().all(|v| v.weight >= 0.) && versions.iter().any(|v| v.weight > 0.),
+            "Version weights must be non-negative and not all zero"
+        );
+        self.traffic_splits.insert(id, versions);
+    }
+
+    /// Returns the versions of the function specified by `id` if its traffic is split.
+    pub fn get_traffic_split(&self, id: usize) -> Option<&[FunctionVersion]> {
+        self.traffic_splits.get(&id).map(|v| v.as_slice())
+    }
+
+    /// Chooses the version of the function specified by `id` for an invocation using `sample` function
+    /// which returns uniform random value from [0, 1). Returns `None` if the traffic of the function is not split,
+    /// in this case `sample` is not called.
+    pub fn choose_version(&self, id: usize, sample: impl FnOnce() -> f64) -> Option<FunctionVersion> {
+        let versions = self.traffic_splits.get(&id)?;
+        let total_weight = versions.iter().map(|v| v.weight).sum::<f64>();
+        let mut point = sample() * total_weight;
+        for version in versions.iter() {
+            if point < version.weight {
+                return Some(*version);
+            }
+            point -= version.weight;
+        }
+        versions.iter().rev().find(|v| v.weight > 0.).copied()
+    }
+
     /// Adds a new [`Application`] and returns its `id`. Note: the application is created without any functions, they must be added separately.
     pub fn add_app(&mut self, mut a: Application) -> usize {
         let id = self.apps.len();
@@ -0,0 +1,113 @@
+//! API gateway that rate-limits incoming invocation requests before they reach the hosts.
+//!
+//! The gateway models platform-level throttling with a token bucket: each request consumes one token,
+//! tokens are refilled at a constant rate up to the bucket size. Requests that arrive when the bucket is empty
+//! wait in a bounded FIFO queue, requests that don't fit in the queue are throttled.
+use std::collections::VecDeque;
+
+/// Gateway's decision on a new invocation request.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GatewayDecision {
+    /// The request passes through the gateway immediately.
+    Pass,
+    /// The request waits in the gateway queue until a token becomes available.
+    Queued,
+    /// The request is rejected because the bucket is empty and the queue is full.
+    Throttled,
+}
+
+/// Token bucket API gateway.
+#[derive(Clone)]
+pub struct ApiGateway {
+    bucket_size: f64,
+    refill_rate: f64,
+    queue_capacity: usize,
+    tokens: f64,
+    last_refill: f64,
+    queue: VecDeque<(usize, usize)>,
+}
+
+impl ApiGateway {
+    /// Creates new ApiGateway with a full bucket.
+    /// `refill_rate` is the number of tokens added per time unit, `queue_capacity` is the maximum number of
+    /// requests waiting for a token (zero means that requests are throttled as soon as the bucket is empty).
+    pub fn new(bucket_size: u64, refill_rate: f64, queue_capacity: usize) -> Self {
+        assert!(refill_rate > 0., "Refill rate must be positive");
+        Self {
+            bucket_size: bucket_size as f64,
+            refill_rate,
+            queue_capacity,
+            tokens: bucket_size as f64,
+            last_refill: 0.,
+            queue: VecDeque::new(),
+        }
+    }
+
+    /// Returns bucket size.
+    pub fn bucket_size(&self) -> u64 {
+        self.bucket_size as u64
+    }
+
+    /// Returns the number of tokens added per time unit.
+    pub fn refill_rate(&self) -> f64 {
+        self.refill_rate
+    }
+
+    /// Returns the maximum number of queued requests.
+    pub fn queue_capacity(&self) -> usize {
+        self.queue_capacity
+    }
+
+    /// Returns the number of requests waiting in the gateway queue.
+    pub fn queue_len(&self) -> usize {
+        self.queue.len()
+    }
+
+    fn refill(&mut self, time: f64) {
+        if time > self.last_refill {
+            self.tokens = f64::min(
+                self.bucket_size,
+                self.tokens + (time - self.last_refill) * self.refill_rate,
+            );
+            self.last_refill = time;
+        }
+    }
+
+    fn try_take_token(&mut self) -> bool {
+        if self.tokens + 1e-9 >= 1. {
+            self.tokens = f64::max(0., self.tokens - 1.);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Processes a new invocation request.
+    pub fn on_request(&mut self, id: usize, func_id: usize, time: f64) -> GatewayDecision {
+        self.refill(time);
+        // preserve FIFO order: new requests can't overtake the queued ones
+        if self.queue.is_empty() && self.try_take_token() {
+            return GatewayDecision::Pass;
+        }
+        if self.queue.len() < self.queue_capacity {
+            self.queue.push_back((id, func_id));
+            return GatewayDecision::Queued;
+        }
+        GatewayDecision::Throttled
+    }
+
+    /// Returns (invocation id, function id) pairs of queued requests that can pass through the gateway now.
+    pub fn dequeue(&mut self, time: f64) -> Vec<(usize, usize)> {
+        self.refill(time);
+        let mut result = Vec::new();
+        while !self.queue.is_empty() && self.try_take_token() {
+            result.push(self.queue.pop_front().unwrap());
+        }
+        result
+    }
+
+    /// Returns the time until the next token becomes available.
+    pub fn next_token_delay(&self) -> f64 {
+        f64::max(0., (1. - self.tokens) / self.refill_rate)
+    }
+}
@@ -0,0 +1,154 @@
+//! GPU model.
+//!
+//! Unlike other resources, GPUs are allocated to containers as particular devices, each device has its own memory.
+//! A container either uses its devices exclusively or shares them with other containers (similar to NVIDIA MPS).
+//! Shared devices are time-sliced: when an invocation starts, its duration is multiplied by
+//! `1 + sharing_slowdown * (tenants - 1)`, where `tenants` is the number of containers sharing the device.
+
+/// GPU sharing mode of a container.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GpuSharing {
+    /// Devices are not shared with any other container.
+    Exclusive,
+    /// Devices may be shared with other containers as long as there is enough GPU memory.
+    Shared,
+}
+
+/// Requirement for allocating GPU devices.
+#[derive(Clone, Debug)]
+pub struct GpuRequirement {
+    /// Number of devices.
+    pub count: usize,
+    /// GPU memory required on each device.
+    pub memory: u64,
+    /// Sharing mode.
+    pub sharing: GpuSharing,
+}
+
+impl GpuRequirement {
+    /// Creates new GpuRequirement.
+    pub fn new(count: usize, memory: u64, sharing: GpuSharing) -> Self {
+        Self { count, memory, sharing }
+    }
+}
+
+/// A single GPU device.
+#[derive(Clone)]
+pub struct Gpu {
+    memory: u64,
+    used_memory: u64,
+    tenants: usize,
+    exclusive: bool,
+}
+
+impl Gpu {
+    /// Creates new GPU with given amount of memory.
+    pub fn new(memory: u64) -> Self {
+        Self {
+            memory,
+            used_memory: 0,
+            tenants: 0,
+            exclusive: false,
+        }
+    }
+
+    /// Returns the total amount of GPU memory.
+    pub fn memory(&self) -> u64 {
+        self.memory
+    }
+
+    /// Returns the amount of allocated GPU memory.
+    pub fn used_memory(&self) -> u64 {
+        self.used_memory
+    }
+
+    /// Returns the number of containers using the device.
+    pub fn tenants(&self) -> usize {
+        self.tenants
+    }
+
+    fn fits(&self, req: &GpuRequirement) -> bool {
+        if self.exclusive || self.used_memory + req.memory > self.memory {
+            return false;
+        }
+        req.sharing == GpuSharing::Shared || self.tenants == 0
+    }
+}
+
+/// GPU devices of a host.
+#[derive(Clone)]
+pub struct GpuPool {
+    gpus: Vec<Gpu>,
+    sharing_slowdown: f64,
+}
+
+impl GpuPool {
+    /// Creates new GpuPool. By default, each additional tenant of a shared device slows the invocations down
+    /// by the duration of the invocation itself.
+    pub fn new(gpus: Vec<Gpu>) -> Self {
+        Self {
+            gpus,
+            sharing_slowdown: 1.,
+        }
+    }
+
+    /// Sets the relative slowdown caused by each additional tenant of a shared device.
+    pub fn with_sharing_slowdown(mut self, sharing_slowdown: f64) -> Self {
+        assert!(sharing_slowdown >= 0., "Sharing slowdown must be non-negative");
+        self.sharing_slowdown = sharing_slowdown;
+        self
+    }
+
+    /// Returns the GPU devices.
+    pub fn gpus(&self) -> &[Gpu] {
+        &self.gpus
+    }
+
+    /// Checks whether it is possible to allocate the required devices.
+    pub fn can_allocate(&self, req: &GpuRequirement) -> bool {
+        self.gpus.iter().filter(|gpu| gpu.fits(req)).count() >= req.count
+    }
+
+    /// Checks whether the pool has enough devices for the requirement when none of them is allocated.
+    pub fn has_capacity(&self, req: &GpuRequirement) -> bool {
+        self.gpus.iter().filter(|gpu| gpu.memory >= req.memory).count() >= req.count
+    }
+
+    /// Allocates the required devices preferring the least loaded ones, returns their indices.
+    pub fn allocate(&mut self, req: &GpuRequirement) -> Vec<usize> {
+        let mut candidates: Vec<usize> = (0..self.gpus.len()).filter(|&i| self.gpus[i].fits(req)).collect();
+        assert!(candidates.len() >= req.count, "Not enough GPUs");
+        candidates.sort_by_key(|&i| self.gpus[i].tenants);
+        candidates.truncate(req.count);
+        for &i in candidates.iter() {
+            let gpu = &mut self.gpus[i];
+            gpu.used_memory += req.memory;
+            gpu.tenants += 1;
+            gpu.exclusive = req.sharing == GpuSharing::Exclusive;
+        }
+        candidates
+    }
+
+    /// Releases previously allocated devices.
+    pub fn release(&mut self, req: &GpuRequirement, gpus: &[usize]) {
+        for &i in gpus {
+            let gpu = &mut self.gpus[i];
+            gpu.used_memory -= req.memory;
+            gpu.tenants -= 1;
+            gpu.exclusive = false;
+        }
+    }
+
+    /// Returns the factor by which invocations running on given devices are slowed down due to sharing.
+    pub fn slowdown(&self, gpus: &[usize]) -> f64 {
+        gpus.iter()
+            .map(|&i| 1. + self.sharing_slowdown * (self.gpus[i].tenants.max(1) - 1) as f64)
+            .fold(1., f64::max)
+    }
+}
+
+impl Default for GpuPool {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
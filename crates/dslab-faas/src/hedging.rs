@@ -0,0 +1,25 @@
+//! Request hedging.
+//!
+//! If an invocation hasn't finished within the hedging delay after it was dispatched to a host, the controller
+//! dispatches a duplicate invocation to another host, and the response of the copy that finishes first is used.
+//! This reduces tail latency caused by slow or overloaded hosts at the cost of the extra work. The copy that loses
+//! the race is not cancelled and runs to completion, its execution time is reported as the hedging cost.
+
+/// Hedging policy with a fixed delay.
+#[derive(Clone)]
+pub struct HedgingPolicy {
+    delay: f64,
+}
+
+impl HedgingPolicy {
+    /// Creates new HedgingPolicy which dispatches a duplicate if the invocation hasn't finished within `delay`.
+    pub fn new(delay: f64) -> Self {
+        assert!(delay > 0., "Hedging delay must be positive");
+        Self { delay }
+    }
+
+    /// Returns the time after which a duplicate invocation is dispatched.
+    pub fn delay(&self) -> f64 {
+        self.delay
+    }
+}
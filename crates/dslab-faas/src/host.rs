@@ -6,19 +6,22 @@
 //! - [Invoker] -- a component that routes invocation requests to appropriate containers and creates
 //!   new containers if needed.
 use std::cell::RefCell;
+use std::collections::{BTreeSet, HashMap};
 use std::rc::Rc;
 
 use simcore::cast;
 use simcore::context::SimulationContext;
-use simcore::event::Event;
+use simcore::event::{Event, EventId};
 use simcore::handler::EventHandler;
 
 use crate::coldstart::{ColdStartPolicy, KeepaliveDecision};
-use crate::container::{ContainerManager, ContainerStatus};
+use crate::container::{ContainerManager, ContainerStatus, SnapshotStore};
 use crate::cpu::{Cpu, CpuPolicy};
-use crate::event::{ContainerEndEvent, ContainerStartEvent, IdleDeployEvent, InvocationEndEvent};
+use crate::event::{
+    ContainerEndEvent, ContainerStartEvent, IdleDeployEvent, InvocationEndEvent, InvocationTimeoutEvent,
+};
 use crate::function::{Application, FunctionRegistry};
-use crate::invocation::{InvocationRegistry, InvocationStatus};
+use crate::invocation::{Invocation, InvocationRegistry, InvocationStatus};
 use crate::invoker::{Invoker, InvokerDecision};
 use crate::resource::{ResourceConsumer, ResourceProvider};
 use crate::simulation::HandlerId;
@@ -30,11 +33,13 @@ pub struct Host {
     invoker: Box<dyn Invoker>,
     container_manager: ContainerManager,
     cpu: Cpu,
+    labels: BTreeSet<String>,
     function_registry: Rc<RefCell<FunctionRegistry>>,
     invocation_registry: Rc<RefCell<InvocationRegistry>>,
     coldstart: Rc<RefCell<dyn ColdStartPolicy>>,
     controller_id: HandlerId,
     stats: Rc<RefCell<Stats>>,
+    timeout_events: HashMap<usize, EventId>,
     ctx: Rc<RefCell<SimulationContext>>,
 }
 
@@ -47,24 +52,28 @@ impl Host {
         cpu_policy: Box<dyn CpuPolicy>,
         resources: ResourceProvider,
         invoker: Box<dyn Invoker>,
+        labels: Vec<String>,
         function_registry: Rc<RefCell<FunctionRegistry>>,
         invocation_registry: Rc<RefCell<InvocationRegistry>>,
         coldstart: Rc<RefCell<dyn ColdStartPolicy>>,
         controller_id: HandlerId,
         stats: Rc<RefCell<Stats>>,
+        snapshots: Rc<RefCell<SnapshotStore>>,
         ctx: SimulationContext,
     ) -> Self {
         let ctx = Rc::new(RefCell::new(ctx));
         Self {
             id,
             invoker,
-            container_manager: ContainerManager::new(id, resources, ctx.clone()),
+            container_manager: ContainerManager::new(id, resources, snapshots, ctx.clone()),
             cpu: Cpu::new(cores, cpu_policy, ctx.clone()),
+            labels: labels.into_iter().collect(),
             function_registry,
             invocation_registry,
             coldstart,
             controller_id,
             stats,
+            timeout_events: HashMap::new(),
             ctx,
         }
     }
@@ -74,6 +83,11 @@ impl Host {
         self.container_manager.can_allocate(resources)
     }
 
+    /// Checks whether the host has enough GPU devices for given resources regardless of the current allocations.
+    pub fn has_gpu_capacity(&self, resources: &ResourceConsumer) -> bool {
+        self.container_manager.has_gpu_capacity(resources)
+    }
+
     /// Checks whether the host can invoke a function of the given [`crate::function::Application`] on existing container and optionally on deploying.
     pub fn can_invoke(&self, app: &Application, allow_deploying: bool) -> bool {
         self.container_manager
@@ -82,6 +96,50 @@ impl Host {
             .is_some()
     }
 
+    /// Returns host labels.
+    pub fn labels(&self) -> &BTreeSet<String> {
+        &self.labels
+    }
+
+    /// Checks whether the host has all given labels.
+    pub fn has_labels(&self, labels: &[String]) -> bool {
+        labels.iter().all(|label| self.labels.contains(label))
+    }
+
+    /// Returns the number of existing containers of given application on this host.
+    pub fn count_app_containers(&self, app_id: usize) -> usize {
+        self.container_manager.count_app_containers(app_id)
+    }
+
+    /// Returns the number of containers of given app that are not terminated.
+    pub fn count_live_app_containers(&self, app_id: usize) -> usize {
+        self.container_manager.count_live_app_containers(app_id)
+    }
+
+    /// Returns the number of invocations of given app that are running or waiting for a deploying container.
+    pub fn count_app_invocations(&self, app_id: usize) -> usize {
+        self.container_manager.count_app_invocations(app_id)
+    }
+
+    /// Terminates up to `limit` idle containers of given app. Returns the number of terminated containers.
+    pub fn terminate_idle_containers(&mut self, app_id: usize, limit: usize) -> usize {
+        let ids: Vec<usize> = self
+            .container_manager
+            .get_containers()
+            .values()
+            .filter(|c| c.app_id == app_id && c.status == ContainerStatus::Idle)
+            .map(|c| c.id)
+            .take(limit)
+            .collect();
+        for &id in ids.iter() {
+            if let Some(event_id) = self.container_manager.get_container(id).unwrap().end_event {
+                self.ctx.borrow_mut().cancel_event(event_id);
+            }
+            self.new_container_end_event(id, 0.0);
+        }
+        ids.len()
+    }
+
     /// Returns the amount of active invocations on this host.
     pub fn active_invocation_count(&self) -> usize {
         self.container_manager.active_invocation_count()
@@ -131,7 +189,11 @@ impl Host {
             time,
         );
         let mut stats = self.stats.borrow_mut();
-        stats.on_new_invocation(invocation.app_id, invocation.func_id);
+        if invocation.duplicate_of.is_some() {
+            stats.on_hedged_invocation(invocation.app_id, invocation.func_id);
+        } else {
+            stats.on_new_invocation(invocation.app_id, invocation.func_id);
+        }
         match status {
             InvokerDecision::Warm(container_id) => {
                 drop(stats);
@@ -152,6 +214,14 @@ impl Host {
                 invocation.status = InvocationStatus::WaitingForContainer;
                 invocation.container_id = Some(container_id);
                 stats.on_cold_start(invocation.app_id, invocation.func_id, delay);
+                if self
+                    .container_manager
+                    .get_container(container_id)
+                    .unwrap()
+                    .restored_from_snapshot
+                {
+                    stats.on_snapshot_restore(invocation.app_id, invocation.func_id);
+                }
                 drop(stats);
                 self.container_manager.reserve_container(container_id, id);
                 if self.container_manager.count_reservations(container_id) == concurrency_limit {
@@ -160,6 +230,7 @@ impl Host {
             }
             _ => {
                 invocation.status = InvocationStatus::Queued;
+                stats.update_queue_length(self.id, time, self.invoker.queue_len());
             }
         }
         status
@@ -194,12 +265,40 @@ impl Host {
         container.last_change = time;
         container.status = ContainerStatus::Running;
         container.start_invocation(id);
-        let mut ir = self.invocation_registry.borrow_mut();
+        let ir = self.invocation_registry.clone();
+        let mut ir = ir.borrow_mut();
         let invocation = &mut ir[id];
         invocation.start_time = Some(time);
         invocation.status = InvocationStatus::Running;
         invocation.container_id = Some(cont_id);
-        self.cpu.on_new_invocation(invocation, container, time);
+        self.run_invocation(invocation, cont_id, time);
+    }
+
+    /// Passes a started invocation to the CPU model, taking the slowdown due to GPU sharing into account.
+    fn run_invocation(&mut self, invocation: &mut Invocation, cont_id: usize, time: f64) {
+        let timeout = self
+            .function_registry
+            .borrow()
+            .get_function(invocation.func_id)
+            .unwrap()
+            .get_timeout();
+        if let Some(timeout) = timeout {
+            let event_id = self
+                .ctx
+                .borrow_mut()
+                .emit_self(InvocationTimeoutEvent { id: invocation.id }, timeout);
+            self.timeout_events.insert(invocation.id, event_id);
+        }
+        let slowdown = self.container_manager.gpu_slowdown(cont_id);
+        let container = self.container_manager.get_container_mut(cont_id).unwrap();
+        if slowdown > 1. {
+            // the original duration is kept in the registry, so that the slowdown is reflected in the stats
+            let mut slowed = *invocation;
+            slowed.duration *= slowdown;
+            self.cpu.on_new_invocation(&mut slowed, container, time);
+        } else {
+            self.cpu.on_new_invocation(invocation, container, time);
+        }
     }
 
     fn on_container_start(&mut self, id: usize, time: f64) {
@@ -239,10 +338,21 @@ impl Host {
     }
 
     fn on_invocation_end(&mut self, id: usize, time: f64) {
+        if self.invocation_registry.borrow()[id].status == InvocationStatus::TimedOut {
+            // the end event was emitted by the CPU model before the invocation timed out
+            return;
+        }
+        if let Some(event_id) = self.timeout_events.remove(&id) {
+            self.ctx.borrow_mut().cancel_event(event_id);
+        }
         let ir = self.invocation_registry.clone();
         let fr = self.function_registry.clone();
         let mut invocation_registry = ir.borrow_mut();
         let function_registry = fr.borrow();
+        // the response of a hedged invocation is given by the copy that finishes first
+        let lost_hedge = invocation_registry[id]
+            .hedge_peer()
+            .is_some_and(|peer| invocation_registry[peer].status == InvocationStatus::Finished);
         let invocation = &mut invocation_registry[id];
         invocation.finish_time = Some(time);
         invocation.status = InvocationStatus::Finished;
@@ -256,7 +366,11 @@ impl Host {
         self.container_manager.try_move_container_to_free(cont_id);
         let container = self.container_manager.get_container_mut(cont_id).unwrap();
         container.end_invocation(id, time);
-        self.stats.borrow_mut().update_invocation_stats(invocation);
+        if lost_hedge {
+            self.stats.borrow_mut().on_lost_hedge(invocation);
+        } else {
+            self.stats.borrow_mut().update_invocation_stats(invocation);
+        }
         self.cpu.on_invocation_end(invocation, container, time);
         let app = function_registry.get_app(app_id).unwrap();
         if container.status == ContainerStatus::Idle {
@@ -299,6 +413,35 @@ impl Host {
         }
     }
 
+    fn on_invocation_timeout(&mut self, id: usize, time: f64) {
+        self.timeout_events.remove(&id);
+        let ir = self.invocation_registry.clone();
+        let mut invocation_registry = ir.borrow_mut();
+        let invocation = &mut invocation_registry[id];
+        invocation.finish_time = Some(time);
+        invocation.status = InvocationStatus::TimedOut;
+        let cont_id = invocation.container_id.unwrap();
+        self.coldstart.borrow_mut().update(
+            invocation,
+            self.function_registry.borrow().get_app(invocation.app_id).unwrap(),
+        );
+        self.container_manager.dec_active_invocations();
+        self.container_manager.try_move_container_to_free(cont_id);
+        let container = self.container_manager.get_container_mut(cont_id).unwrap();
+        container.end_invocation(id, time);
+        self.stats
+            .borrow_mut()
+            .on_timeout(invocation.app_id, invocation.func_id);
+        self.cpu.on_invocation_end(invocation, container, time);
+        // the container is killed along with the timed out invocation unless it runs other invocations
+        if container.status == ContainerStatus::Idle {
+            if let Some(event_id) = container.end_event {
+                self.ctx.borrow_mut().cancel_event(event_id);
+            }
+            self.new_container_end_event(cont_id, 0.0);
+        }
+    }
+
     fn new_container_end_event(&mut self, container_id: usize, delay: f64) {
         let event_id = self
             .ctx
@@ -321,15 +464,21 @@ impl Host {
         if reqs.is_empty() {
             return;
         }
+        self.stats
+            .borrow_mut()
+            .update_queue_length(self.id, time, self.invoker.queue_len());
+        let ir = self.invocation_registry.clone();
         for req in reqs.drain(..) {
-            let mut ir = self.invocation_registry.borrow_mut();
+            let mut ir = ir.borrow_mut();
             let invocation = &mut ir[req.id];
+            self.stats
+                .borrow_mut()
+                .update_wait_time(self.id, time, time - invocation.arrival_time);
             invocation.container_id = Some(req.container_id);
             if req.delay.is_none() {
                 invocation.status = InvocationStatus::Running;
                 invocation.start_time = Some(time);
-                let container = self.container_manager.get_container_mut(req.container_id).unwrap();
-                self.cpu.on_new_invocation(invocation, container, time);
+                self.run_invocation(invocation, req.container_id, time);
             } else {
                 invocation.status = InvocationStatus::WaitingForContainer;
             }
@@ -352,6 +501,10 @@ impl EventHandler for Host {
                 self.on_invocation_end(id, event.time);
                 self.dequeue_requests(event.time);
             }
+            InvocationTimeoutEvent { id } => {
+                self.on_invocation_timeout(id, event.time);
+                self.dequeue_requests(event.time);
+            }
         });
     }
 }
@@ -14,6 +14,12 @@ pub enum InvocationStatus {
     Running,
     /// Invocation is finished.
     Finished,
+    /// Invocation request was throttled by the API gateway.
+    Throttled,
+    /// Invocation request was rejected since no host satisfies the placement constraints of its application.
+    Rejected,
+    /// Invocation was terminated after running longer than the function timeout.
+    TimedOut,
 }
 
 /// Function invocation.
@@ -39,6 +45,10 @@ pub struct Invocation {
     pub start_time: Option<f64>,
     /// Execution finish time.
     pub finish_time: Option<f64>,
+    /// Id of the hedged duplicate of this invocation, if it was dispatched.
+    pub duplicate: Option<usize>,
+    /// Id of the original invocation if this invocation is a hedged duplicate.
+    pub duplicate_of: Option<usize>,
 }
 
 impl Invocation {
@@ -52,6 +62,11 @@ impl Invocation {
         self.finish_time.unwrap() - self.arrival_time
     }
 
+    /// Returns the id of the other copy of a hedged invocation.
+    pub fn hedge_peer(&self) -> Option<usize> {
+        self.duplicate.or(self.duplicate_of)
+    }
+
     /// Returns invocation wait time (start - arrival).
     pub fn wait_time(&self) -> f64 {
         self.start_time.unwrap() - self.arrival_time
@@ -79,6 +94,8 @@ impl InvocationRegistry {
             container_id: None,
             start_time: None,
             finish_time: None,
+            duplicate: None,
+            duplicate_of: None,
         };
         self.invocations.push(invocation);
         id
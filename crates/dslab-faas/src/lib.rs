@@ -1,6 +1,7 @@
 #![warn(missing_docs)]
 #![doc = include_str!("../readme.md")]
 
+pub mod autoscaler;
 pub mod coldstart;
 pub mod config;
 pub mod container;
@@ -10,6 +11,9 @@ pub mod deployer;
 pub mod event;
 pub mod extra;
 pub mod function;
+pub mod gateway;
+pub mod gpu;
+pub mod hedging;
 pub mod host;
 pub mod invocation;
 pub mod invoker;
@@ -12,10 +12,13 @@ use threadpool::ThreadPool;
 
 use simcore::simulation::Simulation;
 
+use crate::autoscaler::Autoscaler;
 use crate::coldstart::{ColdStartPolicy, FixedTimeColdStartPolicy};
-use crate::config::{Config, ConfigParamResolvers, RawConfig};
+use crate::config::{Config, ConfigParamResolvers, RawConfig, RuntimeConfig};
 use crate::cpu::{ContendedCpuPolicy, CpuPolicy};
 use crate::deployer::{BasicDeployer, IdleDeployer};
+use crate::gateway::ApiGateway;
+use crate::hedging::HedgingPolicy;
 use crate::invoker::{FIFOInvoker, Invoker};
 use crate::scheduler::{BasicScheduler, Scheduler};
 use crate::simulation::ServerlessSimulation;
@@ -30,6 +33,8 @@ pub struct ParallelHostConfig {
     pub resources: Vec<(String, u64)>,
     /// Host CPU cores.
     pub cores: u32,
+    /// Host labels.
+    pub labels: Vec<String>,
 }
 
 impl Default for ParallelHostConfig {
@@ -38,6 +43,7 @@ impl Default for ParallelHostConfig {
             invoker: Box::new(FIFOInvoker::new()),
             resources: Vec::new(),
             cores: 1,
+            labels: Vec::new(),
         }
     }
 }
@@ -52,8 +58,16 @@ pub struct ParallelConfig {
     pub idle_deployer: Box<dyn IdleDeployer + Send>,
     /// [`crate::scheduler::Scheduler`] implementation.
     pub scheduler: Box<dyn Scheduler + Send>,
+    /// Optional [`crate::gateway::ApiGateway`] that throttles incoming requests.
+    pub api_gateway: Option<ApiGateway>,
+    /// Optional [`crate::autoscaler::Autoscaler`] that scales warm containers based on observed concurrency.
+    pub autoscaler: Option<Autoscaler>,
+    /// Optional [`crate::hedging::HedgingPolicy`] that duplicates slow invocations to reduce tail latency.
+    pub hedging: Option<HedgingPolicy>,
     /// Host configuration data.
     pub hosts: Vec<ParallelHostConfig>,
+    /// Runtimes shared by groups of applications.
+    pub runtimes: Vec<RuntimeConfig>,
 }
 
 impl Default for ParallelConfig {
@@ -63,7 +77,11 @@ impl Default for ParallelConfig {
             cpu_policy: Box::<ContendedCpuPolicy>::default(),
             idle_deployer: Box::new(BasicDeployer {}),
             scheduler: Box::new(BasicScheduler {}),
+            api_gateway: None,
+            autoscaler: None,
+            hedging: None,
             hosts: Vec::new(),
+            runtimes: Vec::new(),
         }
     }
 }
@@ -1,8 +1,10 @@
 //! Resource model.
 //!
 //! The simulator considers all resources to be renewable and all resources except CPU to be strictly isolated among containers.
+//! GPUs are an exception, see [`crate::gpu`].
 use std::collections::HashMap;
 
+use crate::gpu::{GpuPool, GpuRequirement};
 use crate::util::{VecMap, VecMapIterator};
 
 /// Transforms resource name to resource id in current simulation.
@@ -91,6 +93,7 @@ impl ResourceRequirement {
 #[derive(Clone, Default)]
 pub struct ResourceProvider {
     resources: VecMap<Resource>,
+    gpus: GpuPool,
 }
 
 impl ResourceProvider {
@@ -100,18 +103,38 @@ impl ResourceProvider {
         for r in resources.drain(..) {
             map.insert(r.id, r);
         }
-        Self { resources: map }
+        Self {
+            resources: map,
+            gpus: Default::default(),
+        }
     }
 
     /// Creates new ResourceProvider without resources.
     pub fn new_empty() -> Self {
         Self {
             resources: Default::default(),
+            gpus: Default::default(),
         }
     }
 
+    /// Adds GPU devices to the provider.
+    pub fn with_gpus(mut self, gpus: GpuPool) -> Self {
+        self.gpus = gpus;
+        self
+    }
+
+    /// Returns GPU devices of the provider.
+    pub fn gpus(&self) -> &GpuPool {
+        &self.gpus
+    }
+
     /// Checks whether it is possible to allocate resources required by the consumer.
     pub fn can_allocate(&self, consumer: &ResourceConsumer) -> bool {
+        if let Some(req) = consumer.gpu() {
+            if !self.gpus.can_allocate(req) {
+                return false;
+            }
+        }
         for (id, req) in consumer.iter() {
             if let Some(resource) = self.resources.get(id) {
                 if !resource.can_allocate(req) {
@@ -138,6 +161,23 @@ impl ResourceProvider {
         }
     }
 
+    /// Checks whether the provider has enough GPU devices for the consumer regardless of the current allocations.
+    pub fn has_gpu_capacity(&self, consumer: &ResourceConsumer) -> bool {
+        consumer.gpu().is_none_or(|req| self.gpus.has_capacity(req))
+    }
+
+    /// Allocates GPU devices required by the consumer, returns their indices.
+    pub fn allocate_gpus(&mut self, consumer: &ResourceConsumer) -> Vec<usize> {
+        consumer.gpu().map(|req| self.gpus.allocate(req)).unwrap_or_default()
+    }
+
+    /// Releases GPU devices allocated by the consumer.
+    pub fn release_gpus(&mut self, consumer: &ResourceConsumer, gpus: &[usize]) {
+        if let Some(req) = consumer.gpu() {
+            self.gpus.release(req, gpus);
+        }
+    }
+
     /// Returns reference to a resource specified by `id` if it exists.
     pub fn get_resource(&self, id: usize) -> Option<&Resource> {
         self.resources.get(id)
@@ -148,6 +188,7 @@ impl ResourceProvider {
 #[derive(Clone, Default)]
 pub struct ResourceConsumer {
     resources: VecMap<ResourceRequirement>,
+    gpu: Option<GpuRequirement>,
 }
 
 impl ResourceConsumer {
@@ -157,16 +198,31 @@ impl ResourceConsumer {
         for r in resources.drain(..) {
             map.insert(r.id, r);
         }
-        Self { resources: map }
+        Self {
+            resources: map,
+            gpu: None,
+        }
     }
 
     /// Creates new ResourceConsumer without resources.
     pub fn new_empty() -> Self {
         Self {
             resources: Default::default(),
+            gpu: None,
         }
     }
 
+    /// Adds a requirement for GPU devices.
+    pub fn with_gpu(mut self, gpu: GpuRequirement) -> Self {
+        self.gpu = Some(gpu);
+        self
+    }
+
+    /// Returns the requirement for GPU devices if any.
+    pub fn gpu(&self) -> Option<&GpuRequirement> {
+        self.gpu.as_ref()
+    }
+
     /// Iterates over resource requirements of the consumer.
     pub fn iter(&self) -> VecMapIterator<ResourceRequirement> {
         self.resources.iter()
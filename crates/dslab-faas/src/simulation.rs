@@ -1,6 +1,7 @@
 //! FaaS simulation.
 use std::boxed::Box;
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::ops::Range;
 use std::rc::Rc;
 
@@ -9,10 +10,11 @@ use simcore::simulation::Simulation;
 
 use crate::coldstart::ColdStartPolicy;
 use crate::config::Config;
+use crate::container::{SharedRuntime, SnapshotStore};
 use crate::controller::Controller;
 use crate::cpu::CpuPolicy;
 use crate::event::{InvocationStartEvent, SimulationEndEvent};
-use crate::function::{Application, Function, FunctionRegistry};
+use crate::function::{Application, Function, FunctionRegistry, FunctionVersion};
 use crate::host::Host;
 use crate::invocation::{Invocation, InvocationRegistry};
 use crate::invoker::{FIFOInvoker, Invoker};
@@ -29,12 +31,14 @@ pub struct ServerlessSimulation {
     controller: Rc<RefCell<Controller>>,
     controller_id: HandlerId,
     cpu_policy: Box<dyn CpuPolicy>,
+    app_runtimes: HashMap<usize, SharedRuntime>,
     function_registry: Rc<RefCell<FunctionRegistry>>,
     host_ctr: Counter,
     invocation_registry: Rc<RefCell<InvocationRegistry>>,
     ctx: SimulationContext,
     resource_name_resolver: ResourceNameResolver,
     sim: Simulation,
+    snapshots: Rc<RefCell<SnapshotStore>>,
     stats: Rc<RefCell<Stats>>,
 }
 
@@ -45,11 +49,17 @@ impl ServerlessSimulation {
         let ctx = sim.create_context("entry point");
         let function_registry: Rc<RefCell<FunctionRegistry>> = Rc::new(RefCell::new(Default::default()));
         let invocation_registry: Rc<RefCell<InvocationRegistry>> = Rc::new(RefCell::new(Default::default()));
+        let controller_ctx = sim.create_context("controller");
         let controller = Rc::new(RefCell::new(Controller::new(
             function_registry.clone(),
+            invocation_registry.clone(),
             config.idle_deployer,
             config.scheduler,
+            config.api_gateway,
+            config.autoscaler,
+            config.hedging,
             stats.clone(),
+            controller_ctx,
         )));
         let controller_id = sim.add_handler("controller", controller.clone());
         let mut this_sim = Self {
@@ -57,12 +67,14 @@ impl ServerlessSimulation {
             controller,
             controller_id,
             cpu_policy: config.cpu_policy,
+            app_runtimes: HashMap::new(),
             function_registry,
             host_ctr: Default::default(),
             invocation_registry,
             ctx,
             resource_name_resolver: Default::default(),
             sim,
+            snapshots: Default::default(),
             stats,
         };
         for host in config.hosts {
@@ -71,11 +83,37 @@ impl ServerlessSimulation {
                 .iter()
                 .map(|x| this_sim.create_resource(&x.0, x.1))
                 .collect();
-            this_sim.add_host(Some(host.invoker), ResourceProvider::new(resources), host.cores);
+            this_sim.add_host_with_labels(
+                Some(host.invoker),
+                ResourceProvider::new(resources),
+                host.cores,
+                host.labels,
+            );
+        }
+        for runtime in config.runtimes {
+            for app_id in runtime.apps {
+                assert!(
+                    this_sim
+                        .app_runtimes
+                        .insert(app_id, SharedRuntime::new(&runtime.name, runtime.init_time))
+                        .is_none(),
+                    "Application {} is assigned to several runtimes",
+                    app_id
+                );
+            }
         }
         this_sim
     }
 
+    /// Sets the runtime assigned to the next added application in the config.
+    fn with_configured_runtime(&self, app: Application) -> Application {
+        let app_id = self.function_registry.borrow().get_apps().len();
+        match self.app_runtimes.get(&app_id) {
+            Some(runtime) => app.with_runtime(runtime.clone()),
+            None => app,
+        }
+    }
+
     /// Resolves resource name into `id` if it exists.
     pub fn try_resolve_resource_name(&self, name: &str) -> Option<usize> {
         self.resource_name_resolver.try_resolve(name)
@@ -101,6 +139,21 @@ impl ServerlessSimulation {
         self.invocation_registry.borrow()[id].to_vec()
     }
 
+    /// Returns the number of containers of given application that are not terminated.
+    pub fn count_app_containers(&self, app_id: usize) -> usize {
+        self.controller.borrow().count_app_containers(app_id)
+    }
+
+    /// Partitions the host from the controller, so that no new invocations or containers are placed on it.
+    pub fn partition_host(&mut self, host_id: usize) {
+        self.controller.borrow_mut().partition_host(host_id);
+    }
+
+    /// Restores connectivity between the host and the controller.
+    pub fn heal_host(&mut self, host_id: usize) {
+        self.controller.borrow_mut().heal_host(host_id);
+    }
+
     /// Returns simulation metrics.
     pub fn stats(&self) -> Stats {
         self.stats.borrow().clone()
@@ -118,6 +171,17 @@ impl ServerlessSimulation {
 
     /// Adds a new [`crate::host::Host`].
     pub fn add_host(&mut self, invoker: Option<Box<dyn Invoker>>, resources: ResourceProvider, cores: u32) {
+        self.add_host_with_labels(invoker, resources, cores, Vec::new());
+    }
+
+    /// Adds a new [`crate::host::Host`] with given labels, which are matched against application placement constraints.
+    pub fn add_host_with_labels(
+        &mut self,
+        invoker: Option<Box<dyn Invoker>>,
+        resources: ResourceProvider,
+        cores: u32,
+        labels: Vec<String>,
+    ) {
         let id = self.host_ctr.increment();
         let real_invoker = invoker.unwrap_or_else(|| Box::new(FIFOInvoker::new()));
         let ctx = self.sim.create_context(format!("host_{}", id));
@@ -127,11 +191,13 @@ impl ServerlessSimulation {
             self.cpu_policy.init(cores),
             resources,
             real_invoker,
+            labels,
             self.function_registry.clone(),
             self.invocation_registry.clone(),
             self.coldstart.clone(),
             self.controller_id,
             self.stats.clone(),
+            self.snapshots.clone(),
             ctx,
         )));
         self.sim.add_handler(format!("host_{}", id), host.clone());
@@ -143,13 +209,33 @@ impl ServerlessSimulation {
         self.function_registry.borrow_mut().add_function(f)
     }
 
+    /// Sets the timeout of a [`crate::function::Function`].
+    pub fn set_function_timeout(&mut self, id: usize, timeout: f64) {
+        self.function_registry
+            .borrow_mut()
+            .get_function_mut(id)
+            .unwrap()
+            .set_timeout(timeout);
+    }
+
+    /// Splits the invocations of a [`crate::function::Function`] among its versions (e.g. for canary releases).
+    ///
+    /// Each invocation request sent to the function is routed to a version chosen randomly according to the version
+    /// weights, the routing is deterministic for a fixed simulation seed. Requests sent directly to the versions
+    /// are not routed.
+    pub fn set_traffic_split(&mut self, id: usize, versions: Vec<FunctionVersion>) {
+        self.function_registry.borrow_mut().set_traffic_split(id, versions);
+    }
+
     /// Adds a new [`crate::function::Application`] with a single function.
     pub fn add_app_with_single_function(&mut self, app: Application) -> usize {
+        let app = self.with_configured_runtime(app);
         self.function_registry.borrow_mut().add_app_with_single_function(app)
     }
 
     /// Adds a new [`crate::function::Application`].
     pub fn add_app(&mut self, app: Application) -> usize {
+        let app = self.with_configured_runtime(app);
         self.function_registry.borrow_mut().add_app(app)
     }
 
@@ -163,12 +249,16 @@ impl ServerlessSimulation {
                     .map(|x| self.create_resource_requirement(&x.0, x.1))
                     .collect(),
             );
-            self.add_app(Application::new(
+            let mut application = Application::new(
                 app.concurrent_invocations,
                 app.container_deployment_time,
                 app.container_cpu_share,
                 res,
-            ));
+            );
+            if let Some(runtime) = app.runtime {
+                application = application.with_runtime(runtime);
+            }
+            self.add_app(application);
         }
         for func in trace.function_iter() {
             self.add_function(Function::new(func));
@@ -197,7 +287,11 @@ impl ServerlessSimulation {
         let first_idx = ir.len();
         if let Some(item) = iter.peek() {
             if self.ctx.can_emit_ordered(item.time - self.sim.time()) {
-                for req in iter {
+                for mut req in iter {
+                    if let Some(version) = fr.choose_version(req.id, || self.ctx.rand()) {
+                        req.id = version.func_id;
+                        req.duration *= version.duration_factor;
+                    }
                     let app_id = fr
                         .get_function(req.id)
                         .ok_or(format!(
@@ -214,7 +308,11 @@ impl ServerlessSimulation {
                     );
                 }
             } else {
-                for req in iter {
+                for mut req in iter {
+                    if let Some(version) = fr.choose_version(req.id, || self.ctx.rand()) {
+                        req.id = version.func_id;
+                        req.duration *= version.duration_factor;
+                    }
                     let app_id = fr
                         .get_function(req.id)
                         .ok_or(format!(
@@ -237,6 +335,10 @@ impl ServerlessSimulation {
 
     /// Sends a single invocation request.
     pub fn send_invocation_request(&mut self, id: usize, duration: f64, time: f64) -> usize {
+        let (id, duration) = match self.function_registry.borrow().choose_version(id, || self.ctx.rand()) {
+            Some(version) => (version.func_id, duration * version.duration_factor),
+            None => (id, duration),
+        };
         let app_id = self
             .function_registry
             .borrow()
@@ -137,6 +137,44 @@ impl Serialize for SampleMetric {
     }
 }
 
+/// Time series of a metric, consists of (time, value) points ordered by time.
+#[derive(Clone, Default, Serialize)]
+pub struct TimeSeries {
+    points: Vec<(f64, f64)>,
+}
+
+impl TimeSeries {
+    /// Adds a new point to the series.
+    pub fn add(&mut self, time: f64, value: f64) {
+        self.points.push((time, value));
+    }
+
+    /// Returns the number of points in this series.
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Checks whether the series is empty.
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Returns the value of the last point.
+    pub fn last_value(&self) -> Option<f64> {
+        self.points.last().map(|p| p.1)
+    }
+
+    /// Returns maximum value in this series.
+    pub fn max_value(&self) -> Option<f64> {
+        self.points.iter().map(|p| p.1).reduce(f64::max)
+    }
+
+    /// Returns the slice with the points of this series.
+    pub fn points(&self) -> &[(f64, f64)] {
+        &self.points
+    }
+}
+
 /// Metrics related to invocations and execution speed.
 #[derive(Clone, Default, Serialize)]
 pub struct InvocationStats {
@@ -144,6 +182,16 @@ pub struct InvocationStats {
     pub invocations: u64,
     /// Number of cold starts.
     pub cold_starts: u64,
+    /// Number of cold starts served by containers restored from a snapshot.
+    pub snapshot_restores: u64,
+    /// Number of invocation requests throttled by the API gateway.
+    pub throttled_invocations: u64,
+    /// Number of invocation requests rejected since no host satisfies the placement constraints.
+    pub rejected_invocations: u64,
+    /// Number of duplicate invocations dispatched by request hedging (not counted in `invocations`).
+    pub hedged_invocations: u64,
+    /// Number of invocations terminated after exceeding the function timeout.
+    pub timeouts: u64,
     /// This metric counts latency of cold starts only, warm starts are not counted as zero.
     pub cold_start_latency: SampleMetric,
     /// Measures queueing time of requests stuck in the invoker queue (other requests are not counted at all).
@@ -156,6 +204,8 @@ pub struct InvocationStats {
     pub abs_total_slowdown: SampleMetric,
     /// Relative total execution slowdown (includes queueing and cold starts).
     pub rel_total_slowdown: SampleMetric,
+    /// Execution time of hedged invocation copies whose response wasn't used.
+    pub hedging_cost: SampleMetric,
 }
 
 impl InvocationStats {
@@ -165,11 +215,41 @@ impl InvocationStats {
         self.cold_starts += 1;
     }
 
+    /// Updates metrics on a cold start served by a container restored from a snapshot.
+    pub fn on_snapshot_restore(&mut self) {
+        self.snapshot_restores += 1;
+    }
+
     /// Updates metrics on a new invocation.
     pub fn on_new_invocation(&mut self) {
         self.invocations += 1;
     }
 
+    /// Updates metrics on a throttled invocation request.
+    pub fn on_throttled_invocation(&mut self) {
+        self.throttled_invocations += 1;
+    }
+
+    /// Updates metrics on a rejected invocation request.
+    pub fn on_rejected_invocation(&mut self) {
+        self.rejected_invocations += 1;
+    }
+
+    /// Updates metrics on a dispatched duplicate invocation.
+    pub fn on_hedged_invocation(&mut self) {
+        self.hedged_invocations += 1;
+    }
+
+    /// Updates metrics on the end of an invocation copy that lost the hedging race.
+    pub fn on_lost_hedge(&mut self, execution_time: f64) {
+        self.hedging_cost.add(execution_time);
+    }
+
+    /// Updates metrics on an invocation that exceeded the function timeout.
+    pub fn on_timeout(&mut self) {
+        self.timeouts += 1;
+    }
+
     /// Updates metrics on invocation end.
     pub fn update(&mut self, invocation: &Invocation) {
         let len = invocation.execution_time();
@@ -203,11 +283,41 @@ impl GlobalStats {
         self.invocation_stats.on_cold_start(delay);
     }
 
+    /// Updates metrics on a cold start served by a container restored from a snapshot.
+    pub fn on_snapshot_restore(&mut self) {
+        self.invocation_stats.on_snapshot_restore();
+    }
+
     /// Updates metrics on a new invocation.
     pub fn on_new_invocation(&mut self) {
         self.invocation_stats.on_new_invocation();
     }
 
+    /// Updates metrics on a throttled invocation request.
+    pub fn on_throttled_invocation(&mut self) {
+        self.invocation_stats.on_throttled_invocation();
+    }
+
+    /// Updates metrics on a rejected invocation request.
+    pub fn on_rejected_invocation(&mut self) {
+        self.invocation_stats.on_rejected_invocation();
+    }
+
+    /// Updates metrics on a dispatched duplicate invocation.
+    pub fn on_hedged_invocation(&mut self) {
+        self.invocation_stats.on_hedged_invocation();
+    }
+
+    /// Updates metrics on the end of an invocation copy that lost the hedging race.
+    pub fn on_lost_hedge(&mut self, execution_time: f64) {
+        self.invocation_stats.on_lost_hedge(execution_time);
+    }
+
+    /// Updates metrics on an invocation that exceeded the function timeout.
+    pub fn on_timeout(&mut self) {
+        self.invocation_stats.on_timeout();
+    }
+
     /// Updates metrics on invocation end.
     pub fn update_invocation_stats(&mut self, invocation: &Invocation) {
         self.invocation_stats.update(invocation);
@@ -259,6 +369,14 @@ pub struct Stats {
     pub func_stats: DefaultVecMap<InvocationStats>,
     /// Global metrics.
     pub global_stats: GlobalStats,
+    /// Total length of invoker queues, sampled when invocations are enqueued or dequeued.
+    pub queue_length: TimeSeries,
+    /// Host-wise length of invoker queue, sampled when invocations are enqueued or dequeued.
+    pub host_queue_length: DefaultVecMap<TimeSeries>,
+    /// Waiting time of queued invocations (since their arrival), sampled when invocations are dequeued.
+    pub wait_time: TimeSeries,
+    /// Host-wise waiting time of queued invocations, sampled when invocations are dequeued.
+    pub host_wait_time: DefaultVecMap<TimeSeries>,
 }
 
 impl Stats {
@@ -269,6 +387,13 @@ impl Stats {
         self.func_stats.get_mut(func_id).on_cold_start(delay);
     }
 
+    /// Updates metrics on a cold start served by a container restored from a snapshot.
+    pub fn on_snapshot_restore(&mut self, app_id: usize, func_id: usize) {
+        self.global_stats.on_snapshot_restore();
+        self.app_stats.get_mut(app_id).on_snapshot_restore();
+        self.func_stats.get_mut(func_id).on_snapshot_restore();
+    }
+
     /// Updates metrics on a new invocation.
     pub fn on_new_invocation(&mut self, app_id: usize, func_id: usize) {
         self.global_stats.on_new_invocation();
@@ -276,6 +401,44 @@ impl Stats {
         self.func_stats.get_mut(func_id).on_new_invocation();
     }
 
+    /// Updates metrics on a throttled invocation request.
+    pub fn on_throttled_invocation(&mut self, app_id: usize, func_id: usize) {
+        self.global_stats.on_throttled_invocation();
+        self.app_stats.get_mut(app_id).on_throttled_invocation();
+        self.func_stats.get_mut(func_id).on_throttled_invocation();
+    }
+
+    /// Updates metrics on a rejected invocation request.
+    pub fn on_rejected_invocation(&mut self, app_id: usize, func_id: usize) {
+        self.global_stats.on_rejected_invocation();
+        self.app_stats.get_mut(app_id).on_rejected_invocation();
+        self.func_stats.get_mut(func_id).on_rejected_invocation();
+    }
+
+    /// Updates metrics on a dispatched duplicate invocation.
+    pub fn on_hedged_invocation(&mut self, app_id: usize, func_id: usize) {
+        self.global_stats.on_hedged_invocation();
+        self.app_stats.get_mut(app_id).on_hedged_invocation();
+        self.func_stats.get_mut(func_id).on_hedged_invocation();
+    }
+
+    /// Updates metrics on the end of an invocation copy that lost the hedging race.
+    pub fn on_lost_hedge(&mut self, invocation: &Invocation) {
+        let execution_time = invocation.execution_time();
+        self.global_stats.on_lost_hedge(execution_time);
+        self.app_stats.get_mut(invocation.app_id).on_lost_hedge(execution_time);
+        self.func_stats
+            .get_mut(invocation.func_id)
+            .on_lost_hedge(execution_time);
+    }
+
+    /// Updates metrics on an invocation that exceeded the function timeout.
+    pub fn on_timeout(&mut self, app_id: usize, func_id: usize) {
+        self.global_stats.on_timeout();
+        self.app_stats.get_mut(app_id).on_timeout();
+        self.func_stats.get_mut(func_id).on_timeout();
+    }
+
     /// Updates metrics on invocation end.
     pub fn update_invocation_stats(&mut self, invocation: &Invocation) {
         self.global_stats.update_invocation_stats(invocation);
@@ -294,4 +457,43 @@ impl Stats {
     pub fn update_wasted_resources(&mut self, time: f64, resource: &ResourceConsumer) {
         self.global_stats.update_wasted_resources(time, resource);
     }
+
+    /// Updates queue length series on a change of host invoker queue.
+    pub fn update_queue_length(&mut self, host_id: usize, time: f64, queue_len: usize) {
+        let series = self.host_queue_length.get_mut(host_id);
+        let prev = series.last_value().unwrap_or(0.);
+        let curr = queue_len as f64;
+        if prev == curr {
+            return;
+        }
+        series.add(time, curr);
+        let total = self.queue_length.last_value().unwrap_or(0.) + curr - prev;
+        self.queue_length.add(time, total);
+    }
+
+    /// Updates wait time series on a dequeued invocation.
+    pub fn update_wait_time(&mut self, host_id: usize, time: f64, wait_time: f64) {
+        self.wait_time.add(time, wait_time);
+        self.host_wait_time.get_mut(host_id).add(time, wait_time);
+    }
+
+    /// Returns the series of total invoker queue length.
+    pub fn queue_length_series(&self) -> &TimeSeries {
+        &self.queue_length
+    }
+
+    /// Returns the series of invoker queue length on given host.
+    pub fn host_queue_length_series(&self, host_id: usize) -> Option<&TimeSeries> {
+        self.host_queue_length.get(host_id)
+    }
+
+    /// Returns the series of waiting times of queued invocations.
+    pub fn wait_time_series(&self) -> &TimeSeries {
+        &self.wait_time
+    }
+
+    /// Returns the series of waiting times of queued invocations on given host.
+    pub fn host_wait_time_series(&self, host_id: usize) -> Option<&TimeSeries> {
+        self.host_wait_time.get(host_id)
+    }
 }
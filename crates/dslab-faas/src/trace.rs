@@ -1,6 +1,8 @@
 //! Workload trace trait.
 use std::cmp::Ordering;
 
+use crate::container::SharedRuntime;
+
 /// Raw application data.
 #[derive(Default, Clone)]
 pub struct ApplicationData {
@@ -12,6 +14,8 @@ pub struct ApplicationData {
     pub container_cpu_share: f64,
     /// Host resources required by containers of this application.
     pub container_resources: Vec<(String, u64)>,
+    /// Runtime shared by containers of this application with other applications.
+    pub runtime: Option<SharedRuntime>,
 }
 
 impl ApplicationData {
@@ -27,6 +31,7 @@ impl ApplicationData {
             container_deployment_time,
             container_cpu_share,
             container_resources,
+            runtime: None,
         }
     }
 }
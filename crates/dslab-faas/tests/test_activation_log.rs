@@ -0,0 +1,74 @@
+mod common;
+use common::assert_float_eq;
+
+use dslab_faas::config::Config;
+use dslab_faas::extra::activation_log::{parse_activation_log, ActivationLogConfig};
+use dslab_faas::simulation::ServerlessSimulation;
+use dslab_faas::trace::Trace;
+use simcore::simulation::Simulation;
+
+#[test]
+fn test_openwhisk_log() {
+    let log = "\
+namespace,name,start,end,duration,memory
+guest,hello,1700000000000,1700000000250,250,256
+guest,hello,1700000001500,1700000002000,,256
+guest,resize,1700000000500,,,512
+guest,resize,1700000003000,1700000003800,800,512
+other,hello,1700000002000,,,
+";
+    let config = ActivationLogConfig {
+        default_duration: 2.,
+        ..ActivationLogConfig::openwhisk()
+    };
+    let trace = parse_activation_log(log.as_bytes(), config);
+    // functions with the same name from different namespaces are different
+    assert_eq!(trace.function_iter().collect::<Vec<_>>(), vec![0, 0, 1]);
+    let apps = trace.app_iter().collect::<Vec<_>>();
+    assert_eq!(apps.len(), 2);
+    assert_eq!(apps[0].container_resources, vec![("mem".to_string(), 512)]);
+    assert_eq!(apps[1].container_resources, vec![("mem".to_string(), 256)]);
+
+    let mut sim = ServerlessSimulation::new(Simulation::new(1), Config::default());
+    let range = sim.load_trace(&trace);
+    let invocations = sim.get_invocations(range);
+    // (function, arrival time, duration)
+    let expected = [
+        (0, 0., 0.25),
+        // missing duration and end time are replaced with the mean duration of the function
+        (1, 0.5, 0.8),
+        // missing duration is computed from the end time
+        (0, 1.5, 0.5),
+        // the function has no known durations at all
+        (2, 2., 2.),
+        (1, 3., 0.8),
+    ];
+    assert_eq!(invocations.len(), expected.len());
+    for (invocation, &(func_id, time, duration)) in invocations.iter().zip(expected.iter()) {
+        assert_eq!(invocation.func_id, func_id);
+        assert_float_eq(invocation.arrival_time, time, 1e-9);
+        assert_float_eq(invocation.duration, duration, 1e-9);
+    }
+    assert_float_eq(trace.simulation_end().unwrap(), 4., 1e-9);
+}
+
+#[test]
+fn test_knative_log() {
+    let log = "\
+service,revision,timestamp,latency
+shop,shop-00001,2023-05-01T12:00:01.5+01:00,0.25s
+shop,shop-00001,2023-05-01T11:00:00Z,120ms
+shop,shop-00002,2023-05-01T11:00:03.000Z,2
+";
+    let trace = parse_activation_log(log.as_bytes(), ActivationLogConfig::knative());
+    assert_eq!(trace.function_iter().collect::<Vec<_>>(), vec![0, 0]);
+    // timestamps with different timezones are ordered by the actual time
+    let requests = trace.request_iter().collect::<Vec<_>>();
+    let expected = [(0, 0., 0.12), (0, 1.5, 0.25), (1, 3., 2.)];
+    assert_eq!(requests.len(), expected.len());
+    for (request, &(func_id, time, duration)) in requests.iter().zip(expected.iter()) {
+        assert_eq!(request.id, func_id);
+        assert_float_eq(request.time, time, 1e-9);
+        assert_float_eq(request.duration, duration, 1e-9);
+    }
+}
@@ -0,0 +1,50 @@
+mod common;
+use common::assert_float_eq;
+
+use dslab_faas::autoscaler::Autoscaler;
+use dslab_faas::coldstart::FixedTimeColdStartPolicy;
+use dslab_faas::config::Config;
+use dslab_faas::cpu::IgnoredCpuPolicy;
+use dslab_faas::function::Application;
+use dslab_faas::resource::{ResourceConsumer, ResourceProvider};
+use dslab_faas::simulation::ServerlessSimulation;
+use simcore::simulation::Simulation;
+
+#[test]
+fn test_autoscaler_follows_concurrency() {
+    let config = Config {
+        // long keepalive, so that the autoscaler is the only reason for terminating containers
+        coldstart_policy: Box::new(FixedTimeColdStartPolicy::new(100., 0., false)),
+        cpu_policy: Box::new(IgnoredCpuPolicy::default()),
+        autoscaler: Some(Autoscaler::new(0.5, 0, 10, 1.)),
+        ..Default::default()
+    };
+    let mut sim = ServerlessSimulation::new(Simulation::new(1), config);
+    let host_mem = sim.create_resource("mem", 100);
+    sim.add_host(None, ResourceProvider::new(vec![host_mem]), 100);
+    let fn_mem = sim.create_resource_requirement("mem", 1);
+    let f = sim.add_app_with_single_function(Application::new(1, 1., 1., ResourceConsumer::new(vec![fn_mem])));
+    let app = 0;
+    let first = sim.send_invocation_request(f, 20., 0.);
+    let second = sim.send_invocation_request(f, 20., 5.);
+    let third = sim.send_invocation_request(f, 20., 5.);
+    sim.step_for_duration(4.);
+    // one invocation with target concurrency 0.5 per container results in one spare warm container
+    assert_eq!(sim.count_app_containers(app), 2);
+    sim.step_for_duration(6.);
+    // concurrency grows to 3, the autoscaler tracks it with 6 containers
+    assert_eq!(sim.count_app_containers(app), 6);
+    sim.step_for_duration(20.);
+    // all invocations have finished, the idle containers are terminated long before the keepalive expires
+    assert_eq!(sim.count_app_containers(app), 0);
+    sim.step_until_no_events();
+    // the spare container is used by one of the later invocations, only the other one is a cold start
+    assert_float_eq(sim.get_invocation(first).start_time.unwrap(), 1., 1e-9);
+    let later_starts = [
+        sim.get_invocation(second).start_time.unwrap(),
+        sim.get_invocation(third).start_time.unwrap(),
+    ];
+    assert!(later_starts.contains(&5.) && later_starts.contains(&6.));
+    assert_eq!(sim.invocation_stats().cold_starts, 2);
+    assert!(sim.global_stats().wasted_resource_time[0].sum() < 100.);
+}
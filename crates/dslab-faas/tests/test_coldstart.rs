@@ -0,0 +1,81 @@
+use dslab_faas::coldstart::{ColdStartPolicy, FixedTimeColdStartPolicy};
+use dslab_faas::config::Config;
+use dslab_faas::extra::adaptive_exponential::AdaptiveExponentialPolicy;
+use dslab_faas::extra::cost_aware::CostAwareColdStartPolicy;
+use dslab_faas::function::Application;
+use dslab_faas::resource::{ResourceConsumer, ResourceProvider};
+use dslab_faas::simulation::ServerlessSimulation;
+use simcore::simulation::Simulation;
+
+#[test]
+fn test_cost_aware_coldstart_policy() {
+    // the memory budget fits only one idle container
+    let config = Config {
+        coldstart_policy: Box::new(CostAwareColdStartPolicy::new(20.0, 1, 0)),
+        ..Default::default()
+    };
+    let mut sim = ServerlessSimulation::new(Simulation::new(1), config);
+    {
+        let mem = sim.create_resource("mem", 10);
+        sim.add_host(None, ResourceProvider::new(vec![mem]), 2);
+    }
+    let mem1 = sim.create_resource_requirement("mem", 1);
+    let cheap = sim.add_app_with_single_function(Application::new(1, 0.5, 1., ResourceConsumer::new(vec![mem1])));
+    let mem2 = sim.create_resource_requirement("mem", 1);
+    let expensive = sim.add_app_with_single_function(Application::new(1, 5., 1., ResourceConsumer::new(vec![mem2])));
+    for i in 0..5 {
+        sim.send_invocation_request(cheap, 1.0, 10.0 * i as f64);
+        sim.send_invocation_request(expensive, 1.0, 10.0 * i as f64);
+    }
+    sim.step_until_no_events();
+    let stats = sim.stats();
+    assert_eq!(stats.global_stats.invocation_stats.invocations, 10);
+    // in the first round the cheap function takes the budget since nothing is known about the expensive one,
+    // afterwards the budget is reserved for the expensive function
+    assert_eq!(stats.func_stats[expensive].cold_starts, 2);
+    assert_eq!(stats.func_stats[cheap].cold_starts, 4);
+}
+
+fn run_with_policy(policy: Box<dyn ColdStartPolicy>, arrivals: &[f64]) -> (u64, f64) {
+    let config = Config {
+        coldstart_policy: policy,
+        ..Default::default()
+    };
+    let mut sim = ServerlessSimulation::new(Simulation::new(1), config);
+    {
+        let mem = sim.create_resource("mem", 10);
+        sim.add_host(None, ResourceProvider::new(vec![mem]), 2);
+    }
+    let mem = sim.create_resource_requirement("mem", 1);
+    let f = sim.add_app_with_single_function(Application::new(1, 1., 1., ResourceConsumer::new(vec![mem])));
+    for &time in arrivals.iter() {
+        sim.send_invocation_request(f, 0.5, time);
+    }
+    sim.step_until_no_events();
+    let stats = sim.stats();
+    assert_eq!(stats.global_stats.invocation_stats.invocations, arrivals.len() as u64);
+    (
+        stats.func_stats[f].cold_starts,
+        stats.global_stats.wasted_resource_time[0].sum(),
+    )
+}
+
+#[test]
+fn test_adaptive_exponential_policy() {
+    // periodic invocations: the window adapts to the period and only the first invocation is a cold start
+    let periodic = (0..10).map(|i| 10.0 * i as f64).collect::<Vec<_>>();
+    let fixed = run_with_policy(Box::new(FixedTimeColdStartPolicy::new(5.0, 0.0, true)), &periodic);
+    let adaptive = run_with_policy(Box::new(AdaptiveExponentialPolicy::new(0.5, 2.0, 120.0)), &periodic);
+    assert_eq!(fixed.0, 10);
+    assert_eq!(adaptive.0, 1);
+
+    // bursts of invocations with long gaps: the window shrinks, so that the containers are not kept idle
+    // for the whole gaps, while the cold starts are the same as with the long fixed window
+    let bursty = (0..5)
+        .flat_map(|burst| (0..5).map(move |i| 300.0 * burst as f64 + i as f64))
+        .collect::<Vec<_>>();
+    let fixed = run_with_policy(Box::new(FixedTimeColdStartPolicy::new(120.0, 0.0, true)), &bursty);
+    let adaptive = run_with_policy(Box::new(AdaptiveExponentialPolicy::new(0.5, 2.0, 120.0)), &bursty);
+    assert_eq!(adaptive.0, fixed.0);
+    assert!(adaptive.1 < fixed.1 / 10.);
+}
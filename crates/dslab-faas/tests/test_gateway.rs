@@ -0,0 +1,48 @@
+mod common;
+use common::assert_float_eq;
+
+use dslab_faas::config::Config;
+use dslab_faas::cpu::IgnoredCpuPolicy;
+use dslab_faas::function::Application;
+use dslab_faas::gateway::ApiGateway;
+use dslab_faas::invocation::InvocationStatus;
+use dslab_faas::resource::{ResourceConsumer, ResourceProvider};
+use dslab_faas::simulation::ServerlessSimulation;
+use simcore::simulation::Simulation;
+
+#[test]
+fn test_gateway_throttling() {
+    let config = Config {
+        cpu_policy: Box::new(IgnoredCpuPolicy::default()),
+        api_gateway: Some(ApiGateway::new(2, 0.5, 2)),
+        ..Default::default()
+    };
+    let mut sim = ServerlessSimulation::new(Simulation::new(1), config);
+    let host_mem = sim.create_resource("mem", 100);
+    sim.add_host(None, ResourceProvider::new(vec![host_mem]), 100);
+    let fn_mem = sim.create_resource_requirement("mem", 1);
+    let f = sim.add_app_with_single_function(Application::new(1, 0., 1., ResourceConsumer::new(vec![fn_mem])));
+    // the bucket holds 2 tokens and the queue holds 2 requests, so 6 simultaneous requests overflow it by 2
+    let mut ids = Vec::new();
+    for _ in 0..6 {
+        ids.push(sim.send_invocation_request(f, 1.0, 0.0));
+    }
+    // this request arrives when the queue is empty again and one token has been refilled
+    ids.push(sim.send_invocation_request(f, 1.0, 6.0));
+    sim.step_until_no_events();
+    let expected_start = [Some(0.), Some(0.), Some(2.), Some(4.), None, None, Some(6.)];
+    for (id, start) in ids.iter().zip(expected_start.iter()) {
+        let invocation = sim.get_invocation(*id);
+        if let Some(t) = start {
+            assert_eq!(invocation.status, InvocationStatus::Finished);
+            assert_float_eq(invocation.start_time.unwrap(), *t, 1e-9);
+        } else {
+            assert_eq!(invocation.status, InvocationStatus::Throttled);
+            assert!(invocation.start_time.is_none());
+        }
+    }
+    let stats = sim.invocation_stats();
+    assert_eq!(stats.invocations, 5);
+    assert_eq!(stats.throttled_invocations, 2);
+    assert_eq!(sim.stats().func_stats[f].throttled_invocations, 2);
+}
@@ -0,0 +1,68 @@
+mod common;
+use common::assert_float_eq;
+
+use dslab_faas::config::Config;
+use dslab_faas::cpu::IgnoredCpuPolicy;
+use dslab_faas::function::Application;
+use dslab_faas::gpu::{Gpu, GpuPool, GpuRequirement, GpuSharing};
+use dslab_faas::invocation::InvocationStatus;
+use dslab_faas::resource::{ResourceConsumer, ResourceProvider};
+use dslab_faas::simulation::ServerlessSimulation;
+use simcore::simulation::Simulation;
+
+fn run(sharing: GpuSharing) -> Vec<(usize, f64, f64)> {
+    let config = Config {
+        cpu_policy: Box::new(IgnoredCpuPolicy::default()),
+        ..Default::default()
+    };
+    let mut sim = ServerlessSimulation::new(Simulation::new(1), config);
+    // host 0 has no GPUs, host 1 has a single GPU with room for two containers
+    let host_mem = sim.create_resource("mem", 100);
+    sim.add_host(None, ResourceProvider::new(vec![host_mem]), 100);
+    let host_mem = sim.create_resource("mem", 100);
+    sim.add_host(
+        None,
+        ResourceProvider::new(vec![host_mem]).with_gpus(GpuPool::new(vec![Gpu::new(16)]).with_sharing_slowdown(0.5)),
+        100,
+    );
+    let fn_mem = sim.create_resource_requirement("mem", 1);
+    let resources = ResourceConsumer::new(vec![fn_mem]).with_gpu(GpuRequirement::new(1, 8, sharing));
+    let f = sim.add_app_with_single_function(Application::new(1, 0., 1., resources));
+    let ids: Vec<_> = (0..2).map(|_| sim.send_invocation_request(f, 1., 0.)).collect();
+    sim.step_until_no_events();
+    ids.into_iter()
+        .map(|id| {
+            let invocation = sim.get_invocation(id);
+            assert_eq!(invocation.status, InvocationStatus::Finished);
+            (
+                invocation.host_id.unwrap(),
+                invocation.start_time.unwrap(),
+                invocation.finish_time.unwrap(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_exclusive_gpu() {
+    let invocations = run(GpuSharing::Exclusive);
+    // both invocations run on the GPU host one after another, since the device can't be shared
+    for &(host, _, _) in invocations.iter() {
+        assert_eq!(host, 1);
+    }
+    assert_float_eq(invocations[0].1, 0., 1e-9);
+    assert_float_eq(invocations[0].2, 1., 1e-9);
+    assert!(invocations[1].1 >= 1. - 1e-9);
+    assert_float_eq(invocations[1].2 - invocations[1].1, 1., 1e-9);
+}
+
+#[test]
+fn test_shared_gpu() {
+    let invocations = run(GpuSharing::Shared);
+    // both containers share the device and run concurrently, but slower
+    for &(host, start, finish) in invocations.iter() {
+        assert_eq!(host, 1);
+        assert_float_eq(start, 0., 1e-9);
+        assert_float_eq(finish, 1.5, 1e-9);
+    }
+}
@@ -0,0 +1,91 @@
+mod common;
+use common::assert_float_eq;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use dslab_faas::coldstart::FixedTimeColdStartPolicy;
+use dslab_faas::config::Config;
+use dslab_faas::cpu::IgnoredCpuPolicy;
+use dslab_faas::function::Application;
+use dslab_faas::hedging::HedgingPolicy;
+use dslab_faas::host::Host;
+use dslab_faas::invocation::InvocationStatus;
+use dslab_faas::resource::{ResourceConsumer, ResourceProvider};
+use dslab_faas::scheduler::Scheduler;
+use dslab_faas::simulation::ServerlessSimulation;
+use simcore::simulation::Simulation;
+
+/// Always selects the first available host.
+struct FirstHostScheduler {}
+
+impl Scheduler for FirstHostScheduler {
+    fn select_host(&mut self, _app: &Application, _hosts: &[Rc<RefCell<Host>>]) -> usize {
+        0
+    }
+}
+
+fn run(hedging: Option<HedgingPolicy>, timeout: Option<f64>) -> ServerlessSimulation {
+    let config = Config {
+        coldstart_policy: Box::new(FixedTimeColdStartPolicy::new(100., 0., false)),
+        cpu_policy: Box::new(IgnoredCpuPolicy::default()),
+        scheduler: Box::new(FirstHostScheduler {}),
+        hedging,
+        ..Default::default()
+    };
+    let mut sim = ServerlessSimulation::new(Simulation::new(1), config);
+    // the first host can run only one container, so it becomes slow under load
+    for mem in [1, 2] {
+        let host_mem = sim.create_resource("mem", mem);
+        sim.add_host(None, ResourceProvider::new(vec![host_mem]), 1);
+    }
+    let fn_mem = sim.create_resource_requirement("mem", 1);
+    let f = sim.add_app_with_single_function(Application::new(1, 0.5, 1., ResourceConsumer::new(vec![fn_mem])));
+    if let Some(timeout) = timeout {
+        sim.set_function_timeout(f, timeout);
+    }
+    sim.send_invocation_request(f, 10., 0.);
+    sim.send_invocation_request(f, 1., 1.);
+    sim.step_until_no_events();
+    sim
+}
+
+#[test]
+fn test_hedging_reduces_latency() {
+    // without hedging, the second invocation waits in the queue of the first host until the first invocation ends
+    let sim = run(None, None);
+    assert_float_eq(sim.get_invocation(1).response_time(), 10.5, 1e-9);
+    assert_eq!(sim.invocation_stats().hedged_invocations, 0);
+
+    let sim = run(Some(HedgingPolicy::new(2.)), None);
+    // the duplicate of the first invocation is dispatched at 2 and loses the race
+    let first = sim.get_invocation(0);
+    assert_eq!(first.duplicate, Some(2));
+    assert_float_eq(first.finish_time.unwrap(), 10.5, 1e-9);
+    assert_float_eq(sim.get_invocation(2).finish_time.unwrap(), 12.5, 1e-9);
+    // the duplicate of the second invocation is dispatched at 3 to the second host and returns first
+    let duplicate = sim.get_invocation(3);
+    assert_eq!(duplicate.duplicate_of, Some(1));
+    assert_eq!(duplicate.host_id, Some(1));
+    assert_float_eq(duplicate.response_time(), 3.5, 1e-9);
+    assert!(duplicate.finish_time.unwrap() < sim.get_invocation(1).finish_time.unwrap());
+
+    let stats = sim.invocation_stats();
+    assert_eq!(stats.invocations, 2);
+    assert_eq!(stats.hedged_invocations, 2);
+    // the losing copies of both invocations are the extra cost of hedging
+    assert_eq!(stats.hedging_cost.len(), 2);
+    assert_float_eq(stats.hedging_cost.sum(), 11., 1e-9);
+}
+
+#[test]
+fn test_no_hedging_after_timeout() {
+    // the first invocation times out before the hedging delay, so it is not duplicated
+    let sim = run(Some(HedgingPolicy::new(2.)), Some(1.2));
+    let first = sim.get_invocation(0);
+    assert_eq!(first.status, InvocationStatus::TimedOut);
+    assert_eq!(first.duplicate, None);
+    // only the second invocation, which is still running at its hedging time, is duplicated
+    assert!(sim.get_invocation(1).duplicate.is_some());
+    assert_eq!(sim.invocation_stats().hedged_invocations, 1);
+}
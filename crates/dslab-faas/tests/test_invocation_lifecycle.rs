@@ -1,8 +1,9 @@
 use std::boxed::Box;
 
 use dslab_faas::coldstart::FixedTimeColdStartPolicy;
-use dslab_faas::config::Config;
-use dslab_faas::cpu::IgnoredCpuPolicy;
+use dslab_faas::config::{Config, ConfigParamResolvers, RawConfig};
+use dslab_faas::container::{SharedRuntime, Snapshot};
+use dslab_faas::cpu::{ContendedCpuPolicy, IgnoredCpuPolicy};
 use dslab_faas::function::Application;
 use dslab_faas::invocation::InvocationStatus;
 use dslab_faas::resource::{ResourceConsumer, ResourceProvider};
@@ -41,3 +42,169 @@ fn test_invocation_lifecycle() {
     assert_eq!(sim.get_invocation(0).status, InvocationStatus::Finished);
     assert_eq!(sim.get_invocation(1).status, InvocationStatus::Finished);
 }
+
+#[test]
+fn test_snapshot_restore() {
+    let config = Config {
+        coldstart_policy: Box::new(FixedTimeColdStartPolicy::new(0.0, 0.0, false)),
+        cpu_policy: Box::new(IgnoredCpuPolicy::default()),
+        ..Default::default()
+    };
+    let mut sim = ServerlessSimulation::new(Simulation::new(1), config);
+    let host_mem = sim.create_resource("mem", 1);
+    sim.add_host(None, ResourceProvider::new(vec![host_mem]), 1);
+    let fn_mem = sim.create_resource_requirement("mem", 1);
+    let f = sim.add_app_with_single_function(
+        Application::new(1, 2., 1., ResourceConsumer::new(vec![fn_mem])).with_snapshot(Snapshot::new(3., 0.5)),
+    );
+    sim.send_invocation_request(f, 1.0, 0.0);
+    sim.send_invocation_request(f, 1.0, 10.0);
+    sim.step_until_no_events();
+    // the first cold start deploys the container and creates the snapshot
+    let first = sim.get_invocation(0);
+    assert_eq!(first.start_time, Some(5.0));
+    // later cold starts restore from the snapshot, which is faster than deploying a new container
+    let second = sim.get_invocation(1);
+    assert_eq!(second.status, InvocationStatus::Finished);
+    assert_eq!(second.start_time, Some(10.5));
+    let stats = sim.invocation_stats();
+    assert_eq!(stats.cold_starts, 2);
+    assert_eq!(stats.snapshot_restores, 1);
+}
+
+#[test]
+fn test_shared_runtime() {
+    let config = Config {
+        coldstart_policy: Box::new(FixedTimeColdStartPolicy::new(0.0, 0.0, false)),
+        cpu_policy: Box::new(IgnoredCpuPolicy::default()),
+        ..Default::default()
+    };
+    let mut sim = ServerlessSimulation::new(Simulation::new(1), config);
+    let host_mem = sim.create_resource("mem", 3);
+    sim.add_host(None, ResourceProvider::new(vec![host_mem]), 1);
+    let mut add_app = |runtime: SharedRuntime| {
+        let fn_mem = sim.create_resource_requirement("mem", 1);
+        sim.add_app_with_single_function(
+            Application::new(1, 2., 1., ResourceConsumer::new(vec![fn_mem])).with_runtime(runtime),
+        )
+    };
+    let f1 = add_app(SharedRuntime::new("python", 1.5));
+    let f2 = add_app(SharedRuntime::new("python", 1.5));
+    let f3 = add_app(SharedRuntime::new("java", 1.5));
+    sim.send_invocation_request(f1, 5.0, 0.0);
+    sim.send_invocation_request(f2, 1.0, 3.0);
+    sim.send_invocation_request(f3, 1.0, 3.0);
+    sim.send_invocation_request(f2, 1.0, 20.0);
+    sim.step_until_no_events();
+    // the first container initializes the runtime
+    assert_eq!(sim.get_invocation(0).start_time, Some(2.0));
+    // the runtime is already up on the host, so only the function itself is deployed
+    assert_eq!(sim.get_invocation(1).start_time, Some(3.5));
+    // another runtime is not shared
+    assert_eq!(sim.get_invocation(2).start_time, Some(5.0));
+    // all containers of the runtime are terminated by then
+    assert_eq!(sim.get_invocation(3).start_time, Some(22.0));
+    assert_eq!(sim.invocation_stats().cold_starts, 4);
+}
+
+#[test]
+fn test_shared_runtime_from_config() {
+    let raw: RawConfig = serde_yaml::from_str(
+        r#"
+hosts:
+  - resources:
+      - name: mem
+        quantity: 3
+    cores: 1
+runtimes:
+  - name: python
+    init_time: 1.5
+    apps: [0, 1]
+"#,
+    )
+    .unwrap();
+    let mut config = Config::from_raw(raw, ConfigParamResolvers::default());
+    config.cpu_policy = Box::new(IgnoredCpuPolicy::default());
+    let mut sim = ServerlessSimulation::new(Simulation::new(1), config);
+    let mut add_app = || {
+        let fn_mem = sim.create_resource_requirement("mem", 1);
+        sim.add_app_with_single_function(Application::new(1, 2., 1., ResourceConsumer::new(vec![fn_mem])))
+    };
+    let f1 = add_app();
+    let f2 = add_app();
+    let f3 = add_app();
+    sim.send_invocation_request(f1, 5.0, 0.0);
+    sim.send_invocation_request(f2, 1.0, 3.0);
+    sim.send_invocation_request(f3, 1.0, 3.0);
+    sim.step_until_no_events();
+    assert_eq!(sim.get_invocation(0).start_time, Some(2.0));
+    // the second application shares the runtime with the first one
+    assert_eq!(sim.get_invocation(1).start_time, Some(3.5));
+    // the third application has no runtime in the config
+    assert_eq!(sim.get_invocation(2).start_time, Some(5.0));
+}
+
+#[test]
+fn test_invocation_timeout() {
+    let config = Config {
+        coldstart_policy: Box::new(FixedTimeColdStartPolicy::new(10.0, 0.0, false)),
+        cpu_policy: Box::new(IgnoredCpuPolicy::default()),
+        ..Default::default()
+    };
+    let mut sim = ServerlessSimulation::new(Simulation::new(1), config);
+    let host_mem = sim.create_resource("mem", 1);
+    sim.add_host(None, ResourceProvider::new(vec![host_mem]), 1);
+    let fn_mem = sim.create_resource_requirement("mem", 1);
+    let f = sim.add_app_with_single_function(Application::new(1, 1., 1., ResourceConsumer::new(vec![fn_mem])));
+    sim.set_function_timeout(f, 2.0);
+    sim.send_invocation_request(f, 5.0, 0.0);
+    sim.send_invocation_request(f, 1.0, 0.5);
+    sim.step_for_duration(3.5);
+    // the first invocation fails at the timeout and its container is killed
+    let first = sim.get_invocation(0);
+    assert_eq!(first.status, InvocationStatus::TimedOut);
+    assert_eq!(first.start_time, Some(1.0));
+    assert_eq!(first.finish_time, Some(3.0));
+    // the reclaimed resources are used to deploy a container for the queued invocation
+    assert_eq!(sim.get_invocation(1).status, InvocationStatus::WaitingForContainer);
+    assert_eq!(sim.count_app_containers(0), 1);
+    sim.step_until_no_events();
+    let second = sim.get_invocation(1);
+    assert_eq!(second.status, InvocationStatus::Finished);
+    assert_eq!(second.start_time, Some(4.0));
+    assert_eq!(second.finish_time, Some(5.0));
+    assert_eq!(sim.get_invocation(0).status, InvocationStatus::TimedOut);
+    let stats = sim.invocation_stats();
+    assert_eq!(stats.invocations, 2);
+    assert_eq!(stats.timeouts, 1);
+    assert_eq!(stats.cold_starts, 2);
+}
+
+#[test]
+fn test_invocation_timeout_contended() {
+    let config = Config {
+        coldstart_policy: Box::new(FixedTimeColdStartPolicy::new(10.0, 0.0, false)),
+        cpu_policy: Box::<ContendedCpuPolicy>::default(),
+        ..Default::default()
+    };
+    let mut sim = ServerlessSimulation::new(Simulation::new(1), config);
+    let host_mem = sim.create_resource("mem", 1);
+    sim.add_host(None, ResourceProvider::new(vec![host_mem]), 1);
+    let fn_mem = sim.create_resource_requirement("mem", 1);
+    let f = sim.add_app_with_single_function(Application::new(2, 0., 1., ResourceConsumer::new(vec![fn_mem])));
+    sim.set_function_timeout(f, 4.0);
+    sim.send_invocation_request(f, 10.0, 0.0);
+    sim.send_invocation_request(f, 1.8, 2.0);
+    sim.step_until_no_events();
+    // the invocations share the core from time 2 until the first one times out at time 4,
+    // then the second one completes its remaining work alone
+    let first = sim.get_invocation(0);
+    assert_eq!(first.status, InvocationStatus::TimedOut);
+    assert_eq!(first.finish_time, Some(4.0));
+    let second = sim.get_invocation(1);
+    assert_eq!(second.status, InvocationStatus::Finished);
+    assert!((second.finish_time.unwrap() - 4.8).abs() < 1e-9);
+    let stats = sim.invocation_stats();
+    assert_eq!(stats.invocations, 2);
+    assert_eq!(stats.timeouts, 1);
+}
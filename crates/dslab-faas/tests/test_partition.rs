@@ -0,0 +1,71 @@
+mod common;
+use common::assert_float_eq;
+
+use dslab_faas::coldstart::FixedTimeColdStartPolicy;
+use dslab_faas::config::Config;
+use dslab_faas::cpu::IgnoredCpuPolicy;
+use dslab_faas::function::Application;
+use dslab_faas::invocation::InvocationStatus;
+use dslab_faas::resource::{ResourceConsumer, ResourceProvider};
+use dslab_faas::simulation::ServerlessSimulation;
+use simcore::simulation::Simulation;
+
+fn make_sim() -> (ServerlessSimulation, usize) {
+    let config = Config {
+        coldstart_policy: Box::new(FixedTimeColdStartPolicy::new(100., 0., false)),
+        cpu_policy: Box::new(IgnoredCpuPolicy::default()),
+        ..Default::default()
+    };
+    let mut sim = ServerlessSimulation::new(Simulation::new(1), config);
+    for _ in 0..2 {
+        let host_mem = sim.create_resource("mem", 10);
+        sim.add_host(None, ResourceProvider::new(vec![host_mem]), 10);
+    }
+    let fn_mem = sim.create_resource_requirement("mem", 1);
+    let f = sim.add_app_with_single_function(Application::new(1, 1., 1., ResourceConsumer::new(vec![fn_mem])));
+    (sim, f)
+}
+
+#[test]
+fn test_partitioned_host_is_avoided() {
+    let (mut sim, f) = make_sim();
+    let warmup = sim.send_invocation_request(f, 1., 0.);
+    sim.step_for_duration(5.);
+    assert_eq!(sim.get_invocation(warmup).host_id, Some(0));
+    // host 0 keeps a warm container, but the controller can't reach it anymore
+    sim.partition_host(0);
+    let first = sim.send_invocation_request(f, 1., 10.);
+    let second = sim.send_invocation_request(f, 1., 20.);
+    sim.step_for_duration(30.);
+    for id in [first, second] {
+        let invocation = sim.get_invocation(id);
+        assert_eq!(invocation.status, InvocationStatus::Finished);
+        assert_eq!(invocation.host_id, Some(1));
+    }
+    // the first invocation on host 1 needs a cold start, the second one reuses its container
+    assert_float_eq(sim.get_invocation(first).start_time.unwrap(), 11., 1e-9);
+    assert_float_eq(sim.get_invocation(second).start_time.unwrap(), 20., 1e-9);
+    assert_eq!(sim.invocation_stats().cold_starts, 2);
+    // after the partition heals, the warm container on host 0 is used again
+    sim.heal_host(0);
+    let third = sim.send_invocation_request(f, 1., 40.);
+    sim.step_for_duration(10.);
+    assert_eq!(sim.get_invocation(third).host_id, Some(0));
+    assert_eq!(sim.invocation_stats().cold_starts, 2);
+}
+
+#[test]
+fn test_requests_wait_for_reachable_host() {
+    let (mut sim, f) = make_sim();
+    sim.partition_host(0);
+    sim.partition_host(1);
+    let id = sim.send_invocation_request(f, 1., 0.);
+    sim.step_for_duration(5.);
+    assert_eq!(sim.get_invocation(id).host_id, None);
+    sim.heal_host(1);
+    sim.step_until_no_events();
+    let invocation = sim.get_invocation(id);
+    assert_eq!(invocation.status, InvocationStatus::Finished);
+    assert_eq!(invocation.host_id, Some(1));
+    assert_float_eq(invocation.start_time.unwrap(), 6., 1e-9);
+}
@@ -0,0 +1,106 @@
+mod common;
+use common::assert_float_eq;
+
+use dslab_faas::config::Config;
+use dslab_faas::cpu::IgnoredCpuPolicy;
+use dslab_faas::function::{Application, PlacementConstraints};
+use dslab_faas::invocation::InvocationStatus;
+use dslab_faas::resource::{ResourceConsumer, ResourceProvider};
+use dslab_faas::simulation::ServerlessSimulation;
+use simcore::simulation::Simulation;
+
+#[test]
+fn test_required_labels() {
+    let config = Config {
+        cpu_policy: Box::new(IgnoredCpuPolicy::default()),
+        ..Default::default()
+    };
+    let mut sim = ServerlessSimulation::new(Simulation::new(1), config);
+    for (mem, labels) in [
+        (10, vec![]),
+        (1, vec!["gpu".to_string()]),
+        (10, vec!["ssd".to_string()]),
+    ] {
+        let host_mem = sim.create_resource("mem", mem);
+        sim.add_host_with_labels(None, ResourceProvider::new(vec![host_mem]), 10, labels);
+    }
+    let fn_mem = sim.create_resource_requirement("mem", 1);
+    let placement = PlacementConstraints {
+        required_labels: vec!["gpu".to_string()],
+        ..Default::default()
+    };
+    let f = sim.add_app_with_single_function(
+        Application::new(1, 0.5, 1., ResourceConsumer::new(vec![fn_mem])).with_placement(placement),
+    );
+    // the labeled host fits a single container, so the second request waits for the first one
+    let first = sim.send_invocation_request(f, 1.0, 0.0);
+    let second = sim.send_invocation_request(f, 1.0, 0.0);
+    sim.step_until_no_events();
+    let first = sim.get_invocation(first);
+    let second = sim.get_invocation(second);
+    assert_eq!(first.host_id, Some(1));
+    assert_eq!(second.host_id, Some(1));
+    assert_eq!(first.status, InvocationStatus::Finished);
+    assert_eq!(second.status, InvocationStatus::Finished);
+    assert_float_eq(first.start_time.unwrap(), 0.5, 1e-9);
+    assert!(second.start_time.unwrap() >= first.finish_time.unwrap() - 1e-9);
+    assert_eq!(sim.invocation_stats().queueing_time.len(), 1);
+}
+
+#[test]
+fn test_unsatisfiable_placement() {
+    let config = Config {
+        cpu_policy: Box::new(IgnoredCpuPolicy::default()),
+        ..Default::default()
+    };
+    let mut sim = ServerlessSimulation::new(Simulation::new(1), config);
+    let host_mem = sim.create_resource("mem", 10);
+    sim.add_host_with_labels(None, ResourceProvider::new(vec![host_mem]), 10, vec!["ssd".to_string()]);
+    let fn_mem = sim.create_resource_requirement("mem", 1);
+    let placement = PlacementConstraints {
+        required_labels: vec!["gpu".to_string()],
+        ..Default::default()
+    };
+    let f = sim.add_app_with_single_function(
+        Application::new(1, 0.5, 1., ResourceConsumer::new(vec![fn_mem])).with_placement(placement),
+    );
+    let id = sim.send_invocation_request(f, 1.0, 0.0);
+    sim.step_until_no_events();
+    let invocation = sim.get_invocation(id);
+    assert_eq!(invocation.status, InvocationStatus::Rejected);
+    assert_eq!(invocation.host_id, None);
+    assert_eq!(sim.invocation_stats().rejected_invocations, 1);
+    assert_eq!(sim.invocation_stats().invocations, 0);
+}
+
+#[test]
+fn test_anti_affinity() {
+    let config = Config {
+        cpu_policy: Box::new(IgnoredCpuPolicy::default()),
+        ..Default::default()
+    };
+    let mut sim = ServerlessSimulation::new(Simulation::new(1), config);
+    for _ in 0..2 {
+        let host_mem = sim.create_resource("mem", 10);
+        sim.add_host(None, ResourceProvider::new(vec![host_mem]), 10);
+    }
+    let fn_mem = sim.create_resource_requirement("mem", 1);
+    let placement = PlacementConstraints {
+        anti_affinity: true,
+        ..Default::default()
+    };
+    let f = sim.add_app_with_single_function(
+        Application::new(1, 0., 1., ResourceConsumer::new(vec![fn_mem])).with_placement(placement),
+    );
+    let ids: Vec<_> = (0..3).map(|_| sim.send_invocation_request(f, 1.0, 0.0)).collect();
+    sim.step_until_no_events();
+    let invocations: Vec<_> = ids.iter().map(|id| sim.get_invocation(*id)).collect();
+    // one container per host: the first two invocations are spread, the third one waits
+    assert_ne!(invocations[0].host_id, invocations[1].host_id);
+    assert_float_eq(invocations[0].start_time.unwrap(), 0., 1e-9);
+    assert_float_eq(invocations[1].start_time.unwrap(), 0., 1e-9);
+    assert!(invocations[2].start_time.unwrap() >= 1. - 1e-9);
+    for invocation in invocations.iter() {
+        assert_eq!(invocation.status, InvocationStatus::Finished);
+    }
+}
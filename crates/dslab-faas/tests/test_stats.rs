@@ -1,8 +1,15 @@
 mod common;
 use common::assert_float_eq;
 
+use dslab_faas::coldstart::FixedTimeColdStartPolicy;
+use dslab_faas::config::Config;
+use dslab_faas::cpu::IgnoredCpuPolicy;
+use dslab_faas::function::Application;
 use dslab_faas::invocation::{Invocation, InvocationStatus};
+use dslab_faas::resource::{ResourceConsumer, ResourceProvider};
+use dslab_faas::simulation::ServerlessSimulation;
 use dslab_faas::stats::InvocationStats;
+use simcore::simulation::Simulation;
 
 #[test]
 fn test_invocation_stats() {
@@ -18,6 +25,8 @@ fn test_invocation_stats() {
         container_id: Some(0),
         start_time: Some(0.5),
         finish_time: Some(2.0),
+        duplicate: None,
+        duplicate_of: None,
     };
     let inv2 = Invocation {
         id: 1,
@@ -30,6 +39,8 @@ fn test_invocation_stats() {
         container_id: Some(1),
         start_time: Some(0.5),
         finish_time: Some(2.0),
+        duplicate: None,
+        duplicate_of: None,
     };
     let inv3 = Invocation {
         id: 2,
@@ -42,6 +53,8 @@ fn test_invocation_stats() {
         container_id: Some(0),
         start_time: Some(2.0),
         finish_time: Some(3.0),
+        duplicate: None,
+        duplicate_of: None,
     };
     stats.update(&inv1);
     stats.update(&inv2);
@@ -51,3 +64,28 @@ fn test_invocation_stats() {
     assert_float_eq(stats.abs_exec_slowdown.mean(), 0.8 / 3.0, 1e-9);
     assert_float_eq(stats.rel_exec_slowdown.mean(), (0.5 + 0.3 / 1.2) / 3.0, 1e-9);
 }
+
+#[test]
+fn test_queue_length_series() {
+    let config = Config {
+        coldstart_policy: Box::new(FixedTimeColdStartPolicy::new(100., 0., false)),
+        cpu_policy: Box::new(IgnoredCpuPolicy::default()),
+        ..Default::default()
+    };
+    let mut sim = ServerlessSimulation::new(Simulation::new(1), config);
+    let host_mem = sim.create_resource("mem", 1);
+    sim.add_host(None, ResourceProvider::new(vec![host_mem]), 1);
+    let fn_mem = sim.create_resource_requirement("mem", 1);
+    let f = sim.add_app_with_single_function(Application::new(1, 1., 1., ResourceConsumer::new(vec![fn_mem])));
+    // the first invocation waits for the only container, the other two are queued until it becomes free
+    for _ in 0..3 {
+        sim.send_invocation_request(f, 1., 0.);
+    }
+    sim.step_until_no_events();
+    let stats = sim.stats();
+    let expected = [(0., 1.), (0., 2.), (2., 1.), (3., 0.)];
+    assert_eq!(stats.queue_length_series().points(), &expected);
+    assert_eq!(stats.host_queue_length_series(0).unwrap().points(), &expected);
+    assert_eq!(stats.queue_length_series().max_value(), Some(2.));
+    assert_eq!(stats.wait_time_series().points(), &[(2., 2.), (3., 3.)]);
+}
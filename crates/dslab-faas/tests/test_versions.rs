@@ -0,0 +1,60 @@
+mod common;
+use common::assert_float_eq;
+
+use dslab_faas::coldstart::FixedTimeColdStartPolicy;
+use dslab_faas::config::Config;
+use dslab_faas::function::{Application, FunctionVersion};
+use dslab_faas::resource::{ResourceConsumer, ResourceProvider};
+use dslab_faas::simulation::ServerlessSimulation;
+use simcore::simulation::Simulation;
+
+fn run_canary(seed: u64) -> ServerlessSimulation {
+    let config = Config {
+        coldstart_policy: Box::new(FixedTimeColdStartPolicy::new(f64::MAX / 10.0, 0.0, true)),
+        ..Default::default()
+    };
+    let mut sim = ServerlessSimulation::new(Simulation::new(seed), config);
+    {
+        let mem = sim.create_resource("mem", 4);
+        sim.add_host(None, ResourceProvider::new(vec![mem]), 4);
+    }
+    let mem1 = sim.create_resource_requirement("mem", 1);
+    let stable = sim.add_app_with_single_function(Application::new(1, 1., 1., ResourceConsumer::new(vec![mem1])));
+    let mem2 = sim.create_resource_requirement("mem", 1);
+    let canary = sim.add_app_with_single_function(Application::new(1, 3., 1., ResourceConsumer::new(vec![mem2])));
+    sim.set_traffic_split(
+        stable,
+        vec![
+            FunctionVersion::new(stable, 0.9),
+            FunctionVersion::new(canary, 0.1).with_duration_factor(2.),
+        ],
+    );
+    for i in 0..2000 {
+        sim.send_invocation_request(stable, 0.1, i as f64);
+    }
+    sim.step_until_no_events();
+    sim
+}
+
+#[test]
+fn test_canary_routing() {
+    let sim = run_canary(1);
+    let stats = sim.stats();
+    let stable_stats = &stats.func_stats[0];
+    let canary_stats = &stats.func_stats[1];
+    assert_eq!(stable_stats.invocations + canary_stats.invocations, 2000);
+    let canary_share = canary_stats.invocations as f64 / 2000.;
+    assert!((canary_share - 0.1).abs() < 0.02, "canary share {}", canary_share);
+
+    // each version has its own container with its own cold start and execution time
+    assert_float_eq(stable_stats.cold_start_latency.max().unwrap(), 1., 1e-9);
+    assert_float_eq(canary_stats.cold_start_latency.max().unwrap(), 3., 1e-9);
+    for inv in sim.get_invocations(0..2000) {
+        let expected = if inv.func_id == 1 { 0.2 } else { 0.1 };
+        assert_float_eq(inv.duration, expected, 1e-9);
+    }
+
+    // routing is deterministic for a fixed seed
+    let other = run_canary(1);
+    assert_eq!(other.stats().func_stats[1].invocations, canary_stats.invocations);
+}
@@ -0,0 +1,30 @@
+//! Admission control of VM allocation requests.
+
+use crate::core::common::Allocation;
+use crate::core::monitoring::Monitoring;
+use crate::core::resource_pool::ResourcePoolState;
+
+/// Decision made by admission controller regarding the allocation request.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AdmissionDecision {
+    /// Pass the request to VM placement.
+    Admit,
+    /// Reject the request with given reason, the requested VMs get [`VmStatus::Rejected`](crate::core::vm::VmStatus) status.
+    Reject(String),
+    /// Retry admission of the request after given delay.
+    Defer(f64),
+}
+
+/// Trait for implementation of admission control policies.
+///
+/// Admission controller is invoked by the scheduler before VM placement, it can reject or defer the requests
+/// to preserve capacity headroom, enforce tenant fairness, etc. The controller gets the allocations of all VMs
+/// from the request and the scheduler's view of the resource pool state.
+pub trait AdmissionController {
+    fn admit(
+        &mut self,
+        allocations: &[Allocation],
+        pool_state: &ResourcePoolState,
+        monitoring: &Monitoring,
+    ) -> AdmissionDecision;
+}
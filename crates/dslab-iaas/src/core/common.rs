@@ -8,6 +8,7 @@ pub struct Allocation {
     pub id: u32,
     pub cpu_usage: u32,
     pub memory_usage: u64,
+    pub bandwidth_usage: u64,
 }
 
 /// Describes a result of checking the allocation feasibility.
@@ -15,6 +16,7 @@ pub struct Allocation {
 pub enum AllocationVerdict {
     NotEnoughCPU,
     NotEnoughMemory,
+    NotEnoughBandwidth,
     Success,
     HostNotFound,
 }
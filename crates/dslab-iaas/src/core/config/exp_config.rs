@@ -20,10 +20,13 @@ struct RawExperimentConfig {
     pub vm_start_duration: Option<NumericValues<f64>>,
     pub vm_stop_duration: Option<NumericValues<f64>>,
     pub allow_vm_overcommit: Option<bool>,
+    pub allow_bandwidth_overcommit: Option<bool>,
     pub network_throughput: Option<NumericValues<u64>>,
     pub simulation_length: Option<NumericValues<f64>>,
     pub step_duration: Option<NumericValues<f64>>,
     pub vm_allocation_timeout: Option<NumericValues<f64>>,
+    pub host_wake_up_duration: Option<NumericValues<f64>>,
+    pub host_sleep_power: Option<NumericValues<f64>>,
     pub trace: Option<GenericValues<VmDatasetConfig>>,
     pub hosts: Option<Vec<HostConfig>>,
     pub schedulers: Option<Vec<RawSchedulerConfig>>,
@@ -53,10 +56,13 @@ struct ConfigState {
     pub vm_start_duration: Rc<RefCell<GenericDynVar<f64>>>,
     pub vm_stop_duration: Rc<RefCell<GenericDynVar<f64>>>,
     pub allow_vm_overcommit: bool,
+    pub allow_bandwidth_overcommit: bool,
     pub network_throughput: Rc<RefCell<GenericDynVar<u64>>>,
     pub simulation_length: Rc<RefCell<GenericDynVar<f64>>>,
     pub step_duration: Rc<RefCell<GenericDynVar<f64>>>,
     pub vm_allocation_timeout: Rc<RefCell<GenericDynVar<f64>>>,
+    pub host_wake_up_duration: Rc<RefCell<GenericDynVar<f64>>>,
+    pub host_sleep_power: Rc<RefCell<GenericDynVar<f64>>>,
     pub trace: Option<Rc<RefCell<GenericDynVar<VmDatasetConfig>>>>,
     pub hosts: Vec<HostConfig>,
     pub schedulers: Vec<SchedulerConfigState>,
@@ -166,6 +172,24 @@ impl ExperimentConfig {
             dyn_vars.push(vm_allocation_timeout.clone());
         }
 
+        let host_wake_up_duration = rc!(refcell!(GenericDynVar::from_numeric(
+            "host_wake_up_duration",
+            current_state_raw
+                .host_wake_up_duration
+                .unwrap_or(NumericValues::Value(10.))
+        )));
+        if host_wake_up_duration.borrow().has_multiple_values() {
+            dyn_vars.push(host_wake_up_duration.clone());
+        }
+
+        let host_sleep_power = rc!(refcell!(GenericDynVar::from_numeric(
+            "host_sleep_power",
+            current_state_raw.host_sleep_power.unwrap_or(NumericValues::Value(0.))
+        )));
+        if host_sleep_power.borrow().has_multiple_values() {
+            dyn_vars.push(host_sleep_power.clone());
+        }
+
         let trace: Option<Rc<RefCell<GenericDynVar<VmDatasetConfig>>>> = current_state_raw
             .trace
             .map(|raw_trace| rc!(refcell!(GenericDynVar::new("trace", raw_trace))));
@@ -203,10 +227,13 @@ impl ExperimentConfig {
             vm_start_duration,
             vm_stop_duration,
             allow_vm_overcommit: current_state_raw.allow_vm_overcommit.unwrap_or(false),
+            allow_bandwidth_overcommit: current_state_raw.allow_bandwidth_overcommit.unwrap_or(false),
             network_throughput,
             simulation_length,
             step_duration,
             vm_allocation_timeout,
+            host_wake_up_duration,
+            host_sleep_power,
             trace,
             hosts: current_state_raw.hosts.unwrap_or_default(),
             schedulers,
@@ -247,10 +274,13 @@ impl ExperimentConfig {
             vm_start_duration: self.current_state.vm_start_duration.borrow().value(),
             vm_stop_duration: self.current_state.vm_stop_duration.borrow().value(),
             allow_vm_overcommit: self.current_state.allow_vm_overcommit,
+            allow_bandwidth_overcommit: self.current_state.allow_bandwidth_overcommit,
             network_throughput: self.current_state.network_throughput.borrow().value(),
             simulation_length: self.current_state.simulation_length.borrow().value(),
             step_duration: self.current_state.step_duration.borrow().value(),
             vm_allocation_timeout: self.current_state.vm_allocation_timeout.borrow().value(),
+            host_wake_up_duration: self.current_state.host_wake_up_duration.borrow().value(),
+            host_sleep_power: self.current_state.host_sleep_power.borrow().value(),
             trace,
             hosts: self.current_state.hosts.clone(),
             schedulers,
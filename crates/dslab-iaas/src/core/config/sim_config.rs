@@ -13,10 +13,13 @@ struct RawSimulationConfig {
     pub vm_start_duration: Option<f64>,
     pub vm_stop_duration: Option<f64>,
     pub allow_vm_overcommit: Option<bool>,
+    pub allow_bandwidth_overcommit: Option<bool>,
     pub network_throughput: Option<u64>,
     pub simulation_length: Option<f64>,
     pub step_duration: Option<f64>,
     pub vm_allocation_timeout: Option<f64>,
+    pub host_wake_up_duration: Option<f64>,
+    pub host_sleep_power: Option<f64>,
     pub trace: Option<VmDatasetConfig>,
     pub hosts: Option<Vec<HostConfig>>,
     pub schedulers: Option<Vec<SchedulerConfig>>,
@@ -51,6 +54,9 @@ pub struct HostConfig {
     pub cpus: u32,
     /// Host memory capacity in GB.
     pub memory: u64,
+    /// Host network bandwidth capacity, not limited by default.
+    #[serde(default)]
+    pub bandwidth: Option<u64>,
     /// Number of such hosts.
     pub count: Option<u32>,
 }
@@ -86,6 +92,9 @@ pub struct SimulationConfig {
     pub vm_stop_duration: f64,
     /// Whether to schedule VMs based on real resource utilization instead of allocated resources.
     pub allow_vm_overcommit: bool,
+    /// Whether to allow the total network bandwidth of VMs to exceed the host bandwidth capacity,
+    /// in which case the VMs contend for the host bandwidth.
+    pub allow_bandwidth_overcommit: bool,
     /// Network throughput in GB/s.
     /// Currently used to compute VM migration duration.
     pub network_throughput: u64,
@@ -95,6 +104,10 @@ pub struct SimulationConfig {
     pub step_duration: f64,
     /// Timeout in seconds after which unallocated VM becomes failed.
     pub vm_allocation_timeout: f64,
+    /// Time in seconds needed to wake up a sleeping host before it can accept VMs.
+    pub host_wake_up_duration: f64,
+    /// Power consumption of a sleeping host.
+    pub host_sleep_power: f64,
     /// Used VM trace dataset.
     pub trace: Option<VmDatasetConfig>,
     /// Configurations of physical hosts.
@@ -119,10 +132,13 @@ impl SimulationConfig {
             vm_start_duration: raw.vm_start_duration.unwrap_or(1.),
             vm_stop_duration: raw.vm_stop_duration.unwrap_or(0.5),
             allow_vm_overcommit: raw.allow_vm_overcommit.unwrap_or(false),
+            allow_bandwidth_overcommit: raw.allow_bandwidth_overcommit.unwrap_or(false),
             network_throughput: raw.network_throughput.unwrap_or(1),
             simulation_length: raw.simulation_length.unwrap_or(0.),
             step_duration: raw.step_duration.unwrap_or(500.),
             vm_allocation_timeout: raw.vm_allocation_timeout.unwrap_or(50.),
+            host_wake_up_duration: raw.host_wake_up_duration.unwrap_or(10.),
+            host_sleep_power: raw.host_sleep_power.unwrap_or(0.),
             trace: raw.trace,
             hosts: raw.hosts.unwrap_or_default(),
             schedulers: raw.schedulers.unwrap_or_default(),
@@ -63,8 +63,9 @@ pub mod allocation {
 pub mod vm {
     use serde::Serialize;
 
+    /// VM boot is completed and the VM starts running (emitted by host after the VM start duration).
     #[derive(Clone, Serialize)]
-    pub struct VMStarted {
+    pub struct VmBooted {
         pub vm_id: u32,
     }
 
@@ -74,6 +75,21 @@ pub mod vm {
     }
 }
 
+// HOST POWER EVENTS ///////////////////////////////////////////////////////////////////////////////
+
+pub mod power {
+    use serde::Serialize;
+
+    #[derive(Clone, Serialize)]
+    pub struct HostSleepRequest {}
+
+    #[derive(Clone, Serialize)]
+    pub struct HostWakeUpRequest {}
+
+    #[derive(Clone, Serialize)]
+    pub struct HostWokenUp {}
+}
+
 // MONITORING EVENTS ///////////////////////////////////////////////////////////////////////////////
 
 pub mod monitoring {
@@ -87,6 +103,9 @@ pub mod monitoring {
         pub recently_added_vms: Vec<u32>,
         pub recently_removed_vms: Vec<u32>,
     }
+
+    #[derive(Clone, Serialize)]
+    pub struct RecordFragmentation {}
 }
 
 pub mod vm_api {
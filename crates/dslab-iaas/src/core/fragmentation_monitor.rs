@@ -0,0 +1,83 @@
+//! Component recording resource fragmentation over time.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use simcore::cast;
+use simcore::context::SimulationContext;
+use simcore::event::Event;
+use simcore::handler::EventHandler;
+
+use crate::core::events::monitoring::RecordFragmentation;
+use crate::core::host_manager::HostManager;
+
+/// Returns the resource fragmentation of the hosts, i.e. the share of free resources located on active hosts
+/// (the hosts with at least one allocated VM). Free resources on active hosts are scattered and may be unusable
+/// for large VMs, unlike the resources of idle hosts. The value is averaged over CPU and memory.
+pub fn fragmentation<'a>(hosts: impl Iterator<Item = &'a Rc<RefCell<HostManager>>>) -> f64 {
+    let mut cpu_free = 0.;
+    let mut cpu_free_active = 0.;
+    let mut memory_free = 0.;
+    let mut memory_free_active = 0.;
+    for host in hosts {
+        let host = host.borrow();
+        let host_cpu_free = host.cpu_total() as f64 - host.cpu_allocated();
+        let host_memory_free = host.memory_total() as f64 - host.memory_allocated();
+        cpu_free += host_cpu_free;
+        memory_free += host_memory_free;
+        if host.cpu_allocated() > 0. || host.memory_allocated() > 0. {
+            cpu_free_active += host_cpu_free;
+            memory_free_active += host_memory_free;
+        }
+    }
+    let ratio = |active: f64, total: f64| if total > 0. { active / total } else { 0. };
+    (ratio(cpu_free_active, cpu_free) + ratio(memory_free_active, memory_free)) / 2.
+}
+
+/// This component periodically records the resource fragmentation of hosts (see [`fragmentation()`])
+/// starting from the time it is created.
+pub struct FragmentationMonitor {
+    period: f64,
+    hosts: Vec<Rc<RefCell<HostManager>>>,
+    samples: Vec<(f64, f64)>,
+    ctx: SimulationContext,
+}
+
+impl FragmentationMonitor {
+    /// Creates component recording fragmentation with the specified period.
+    pub fn new(period: f64, ctx: SimulationContext) -> Self {
+        assert!(period > 0., "Fragmentation recording period must be positive");
+        ctx.emit_self_now(RecordFragmentation {});
+        Self {
+            period,
+            hosts: Vec::new(),
+            samples: Vec::new(),
+            ctx,
+        }
+    }
+
+    /// Adds host to the monitored ones.
+    pub fn add_host(&mut self, host: Rc<RefCell<HostManager>>) {
+        self.hosts.push(host);
+    }
+
+    /// Returns the recorded fragmentation values as (time, value) pairs.
+    pub fn samples(&self) -> &[(f64, f64)] {
+        &self.samples
+    }
+
+    fn record(&mut self) {
+        self.samples.push((self.ctx.time(), fragmentation(self.hosts.iter())));
+        self.ctx.emit_self(RecordFragmentation {}, self.period);
+    }
+}
+
+impl EventHandler for FragmentationMonitor {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            RecordFragmentation {} => {
+                self.record();
+            }
+        })
+    }
+}
@@ -21,19 +21,45 @@ use crate::core::events::allocation::{
     AllocationFailed, AllocationReleaseRequest, AllocationReleased, MigrationRequest, VmCreateRequest,
 };
 use crate::core::events::monitoring::HostStateUpdate;
-use crate::core::events::vm::{VMDeleted, VMStarted};
+use crate::core::events::power::{HostSleepRequest, HostWakeUpRequest, HostWokenUp};
+use crate::core::events::vm::{VMDeleted, VmBooted};
 use crate::core::events::vm_api::VmStatusChanged;
 use crate::core::logger::Logger;
 use crate::core::slav_metric::HostSLAVMetric;
 use crate::core::vm::{VirtualMachine, VmStatus};
 use crate::core::vm_api::VmAPI;
 
+/// Host power state.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub enum HostPowerState {
+    /// Host is powered on and runs some VMs.
+    Active,
+    /// Host is powered on but has no VMs.
+    Idle,
+    /// Host is sleeping and consumes the reduced power, it should be woken up before accepting VMs.
+    Sleep,
+    /// Host is waking up, the incoming VMs are started once the wake-up is completed.
+    WakingUp,
+}
+
+impl std::fmt::Display for HostPowerState {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            HostPowerState::Active => write!(f, "active"),
+            HostPowerState::Idle => write!(f, "idle"),
+            HostPowerState::Sleep => write!(f, "sleep"),
+            HostPowerState::WakingUp => write!(f, "waking up"),
+        }
+    }
+}
+
 /// Represents a single physical machine or host for short, which possesses a certain amount of resources and performs
 /// execution of VMs assigned to it by a scheduler. It models the main VM lifecycle stages such as creation, deletion
 /// and migration, and reports the VM status changes to VM API component. Host manager periodically computes its
 /// current load, as the sum of loads produced by currently running VMs, and reports it to the monitoring component.
 /// Host manager also records the total energy consumption of the host computed using the power model
-/// defined as a function of CPU load.
+/// defined as a function of CPU load. An idle host can be put to sleep to save energy, in this case it is woken up
+/// upon arrival of a new VM which is started after the wake-up delay.
 pub struct HostManager {
     pub id: u32,
     pub rack_id: Option<u32>,
@@ -50,11 +76,17 @@ pub struct HostManager {
     cpu_overcommit: u32,
     memory_overcommit: u64,
 
+    bandwidth_total: u64,
+    bandwidth_allocated: u64,
+
     vms: HashSet<u32>,
     recently_added_vms: Vec<u32>,
     recently_removed_vms: Vec<u32>,
     recent_vm_status_changes: HashMap<u32, VmStatus>,
     energy_meter: EnergyMeter,
+    power_state: HostPowerState,
+    pending_vm_creations: Vec<u32>,
+    pending_migrations: Vec<(u32, u32)>,
 
     monitoring_id: u32,
     placement_store_id: u32,
@@ -98,11 +130,16 @@ impl HostManager {
             memory_available: memory_total,
             cpu_overcommit: 0,
             memory_overcommit: 0,
+            bandwidth_total: u64::MAX,
+            bandwidth_allocated: 0,
             vms: HashSet::new(),
             recently_added_vms: Vec::new(),
             recently_removed_vms: Vec::new(),
             recent_vm_status_changes: HashMap::new(),
             energy_meter: EnergyMeter::new(),
+            power_state: HostPowerState::Idle,
+            pending_vm_creations: Vec::new(),
+            pending_migrations: Vec::new(),
             monitoring_id,
             placement_store_id,
             vm_api,
@@ -118,6 +155,11 @@ impl HostManager {
     /// Checks if incoming VM can be allocated on this host.
     fn can_allocate(&self, vm_id: u32) -> AllocationVerdict {
         let vm = self.vm_api.borrow().get_vm(vm_id).borrow().clone();
+        if !self.sim_config.allow_bandwidth_overcommit
+            && self.bandwidth_total < self.bandwidth_allocated + vm.bandwidth_usage
+        {
+            return AllocationVerdict::NotEnoughBandwidth;
+        }
         if self.allow_vm_overcommit {
             return AllocationVerdict::Success;
         }
@@ -149,8 +191,10 @@ impl HostManager {
             self.memory_available -= vm.memory_usage;
             self.memory_allocated += vm.memory_usage;
         }
+        self.bandwidth_allocated += vm.bandwidth_usage;
         self.recently_added_vms.push(vm.id);
         self.vms.insert(vm.id);
+        self.power_state = HostPowerState::Active;
         let cpu_load = self.cpu_load(time);
         let power = self.current_power(cpu_load);
         self.energy_meter.update(time, power);
@@ -175,8 +219,12 @@ impl HostManager {
             self.memory_allocated -= vm.memory_usage - self.memory_overcommit;
             self.memory_overcommit = 0;
         }
+        self.bandwidth_allocated -= vm.bandwidth_usage;
         self.vms.remove(&vm.id);
         self.recently_removed_vms.push(vm.id);
+        if self.vms.is_empty() {
+            self.power_state = HostPowerState::Idle;
+        }
         let cpu_load = self.cpu_load(time);
         let power = self.current_power(cpu_load);
         self.energy_meter.update(time, power);
@@ -203,6 +251,33 @@ impl HostManager {
         self.memory_allocated as f64
     }
 
+    /// Sets the host network bandwidth capacity, by default the bandwidth is not limited.
+    pub fn set_bandwidth(&mut self, bandwidth: u64) {
+        self.bandwidth_total = bandwidth;
+    }
+
+    /// Returns the host network bandwidth capacity.
+    pub fn bandwidth_total(&self) -> u64 {
+        self.bandwidth_total
+    }
+
+    /// Returns the total network bandwidth reserved by VMs, which may exceed the capacity if bandwidth overcommit is
+    /// allowed.
+    pub fn bandwidth_allocated(&self) -> u64 {
+        self.bandwidth_allocated
+    }
+
+    /// Returns the fraction of the reserved bandwidth that each VM actually gets.
+    /// It is less than 1 if the host bandwidth is overcommitted and VMs contend for it, which slows down the migration
+    /// of VMs to this host.
+    pub fn bandwidth_share(&self) -> f64 {
+        if self.bandwidth_allocated <= self.bandwidth_total {
+            1.
+        } else {
+            self.bandwidth_total as f64 / self.bandwidth_allocated as f64
+        }
+    }
+
     /// Returns the current CPU load (used/total) by summing the resource consumption of all active VMs on this host.
     pub fn cpu_load(&self, time: f64) -> f64 {
         let mut cpu_used = 0.;
@@ -223,8 +298,16 @@ impl HostManager {
         memory_used / self.memory_total as f64
     }
 
+    /// Returns the current power state.
+    pub fn power_state(&self) -> HostPowerState {
+        self.power_state
+    }
+
     /// Returns the current power consumption.
     pub fn current_power(&self, cpu_load: f64) -> f64 {
+        if self.power_state == HostPowerState::Sleep {
+            return self.sim_config.host_sleep_power;
+        }
         // CPU utilization is capped by 100%
         let cpu_util = cpu_load.min(1.);
         self.power_model.get_power(HostState::cpu_util(cpu_util))
@@ -245,8 +328,71 @@ impl HostManager {
         self.slav_metric.value()
     }
 
+    /// Returns true if the host is sleeping or waking up, i.e. cannot start VMs right now.
+    fn is_asleep(&self) -> bool {
+        self.power_state == HostPowerState::Sleep || self.power_state == HostPowerState::WakingUp
+    }
+
+    /// Puts idle host to sleep, requests for other hosts are ignored.
+    fn on_sleep_request(&mut self) {
+        if self.power_state != HostPowerState::Idle {
+            self.logger.borrow_mut().log_trace(
+                &self.ctx,
+                format!("host {} is {}, do not put it to sleep", self.name, self.power_state),
+            );
+            return;
+        }
+        let time = self.ctx.time();
+        self.power_state = HostPowerState::Sleep;
+        self.energy_meter.update(time, self.current_power(0.));
+        self.logger
+            .borrow_mut()
+            .log_debug(&self.ctx, format!("host {} is put to sleep", self.name));
+    }
+
+    /// Starts waking up the sleeping host.
+    fn wake_up(&mut self) {
+        if self.power_state != HostPowerState::Sleep {
+            return;
+        }
+        let time = self.ctx.time();
+        self.power_state = HostPowerState::WakingUp;
+        self.energy_meter.update(time, self.current_power(0.));
+        self.logger
+            .borrow_mut()
+            .log_debug(&self.ctx, format!("host {} is waking up", self.name));
+        self.ctx
+            .emit_self(HostWokenUp {}, self.sim_config.host_wake_up_duration);
+    }
+
+    /// Invoked upon host wake-up completion, processes the requests received while the host was asleep.
+    fn on_woken_up(&mut self) {
+        if self.power_state != HostPowerState::WakingUp {
+            return;
+        }
+        self.power_state = HostPowerState::Idle;
+        self.logger
+            .borrow_mut()
+            .log_debug(&self.ctx, format!("host {} is woken up", self.name));
+        for vm_id in mem::take(&mut self.pending_vm_creations) {
+            self.on_allocation_request(vm_id);
+        }
+        for (source_host, vm_id) in mem::take(&mut self.pending_migrations) {
+            self.on_migration_request(source_host, vm_id);
+        }
+    }
+
     /// Processes allocation request, allocates resources to start new VM.
     fn on_allocation_request(&mut self, vm_id: u32) -> bool {
+        if self.is_asleep() {
+            self.logger.borrow_mut().log_debug(
+                &self.ctx,
+                format!("host {} is asleep, vm {} waits for wake-up", self.name, vm_id),
+            );
+            self.pending_vm_creations.push(vm_id);
+            self.wake_up();
+            return true;
+        }
         if self.can_allocate(vm_id) == AllocationVerdict::Success {
             let vm = self.vm_api.borrow().get_vm(vm_id);
             let start_duration = vm.borrow().start_duration();
@@ -255,7 +401,7 @@ impl HostManager {
             self.logger
                 .borrow_mut()
                 .log_debug(&self.ctx, format!("vm {} allocated on host {}", vm_id, self.name));
-            self.ctx.emit_self(VMStarted { vm_id }, start_duration);
+            self.ctx.emit_self(VmBooted { vm_id }, start_duration);
             true
         } else {
             self.logger.borrow_mut().log_debug(
@@ -276,12 +422,20 @@ impl HostManager {
 
     /// Processes migration request (as migration target), allocates resources to start new VM, updates VM status.
     fn on_migration_request(&mut self, source_host: u32, vm_id: u32) {
+        if self.is_asleep() {
+            self.pending_migrations.push((source_host, vm_id));
+            self.wake_up();
+            return;
+        }
         if self.can_allocate(vm_id) == AllocationVerdict::Success {
             let vm = self.vm_api.borrow().get_vm(vm_id);
-            let migration_duration = (vm.borrow().memory_usage as f64) / (self.sim_config.network_throughput as f64);
+            let memory_usage = vm.borrow().memory_usage;
             let start_duration = vm.borrow().start_duration();
 
             self.allocate(self.ctx.time(), vm);
+            // the migrated VM gets only its share of the host bandwidth if it is contended
+            let migration_duration =
+                (memory_usage as f64) / (self.sim_config.network_throughput as f64 * self.bandwidth_share());
             self.logger.borrow_mut().log_debug(
                 &self.ctx,
                 format!("vm {} allocated on host {}, start migration", vm_id, self.name),
@@ -289,7 +443,7 @@ impl HostManager {
             self.recent_vm_status_changes.insert(vm_id, VmStatus::Migrating);
 
             self.ctx
-                .emit_self(VMStarted { vm_id }, migration_duration + start_duration);
+                .emit_self(VmBooted { vm_id }, migration_duration + start_duration);
             self.ctx.emit(
                 AllocationReleaseRequest {
                     vm_id,
@@ -329,11 +483,11 @@ impl HostManager {
         }
     }
 
-    /// Invoked upon VM startup, updates VM status and schedules VM release event according to its lifetime.
-    fn on_vm_started(&mut self, vm_id: u32) {
+    /// Invoked upon VM boot completion, updates VM status and schedules VM release event according to its lifetime.
+    fn on_vm_booted(&mut self, vm_id: u32) {
         self.logger
             .borrow_mut()
-            .log_debug(&self.ctx, format!("vm {} started and running", vm_id));
+            .log_debug(&self.ctx, format!("vm {} booted and running", vm_id));
         let vm = self.vm_api.borrow().get_vm(vm_id);
         let start_time = vm.borrow().start_time();
 
@@ -420,8 +574,8 @@ impl EventHandler for HostManager {
             AllocationReleaseRequest { vm_id, is_migrating } => {
                 self.on_allocation_release_request(vm_id, is_migrating);
             }
-            VMStarted { vm_id } => {
-                self.on_vm_started(vm_id);
+            VmBooted { vm_id } => {
+                self.on_vm_booted(vm_id);
             }
             VMDeleted { vm_id } => {
                 self.on_vm_deleted(vm_id);
@@ -429,6 +583,15 @@ impl EventHandler for HostManager {
             SendHostState {} => {
                 self.send_host_state();
             }
+            HostSleepRequest {} => {
+                self.on_sleep_request();
+            }
+            HostWakeUpRequest {} => {
+                self.wake_up();
+            }
+            HostWokenUp {} => {
+                self.on_woken_up();
+            }
         })
     }
 }
@@ -1,7 +1,9 @@
+pub mod admission_controller;
 pub mod common;
 pub mod config;
 pub mod energy_meter;
 pub mod events;
+pub mod fragmentation_monitor;
 pub mod host_manager;
 pub mod load_model;
 pub mod logger;
@@ -74,6 +74,11 @@ impl PlacementStore {
             .add_host(id, cpu_total, memory_total, cpu_total, memory_total, rack_id);
     }
 
+    /// Sets network bandwidth capacity of the host.
+    pub fn set_host_bandwidth(&mut self, id: u32, bandwidth: u64) {
+        self.pool_state.set_host_bandwidth(id, bandwidth);
+    }
+
     /// Registers scheduler so that PS can notify it about allocation events.
     pub fn add_scheduler(&mut self, id: u32) {
         self.schedulers.insert(id);
@@ -100,7 +105,13 @@ impl PlacementStore {
         let mut can_be_committed = true;
         let mut pool_state_copy = self.pool_state.clone();
         for (alloc, &host_id) in allocations.iter().zip(host_ids.iter()) {
-            if self.pool_state.can_allocate(alloc, host_id, self.allow_vm_overcommit) == AllocationVerdict::Success {
+            if self.pool_state.can_allocate(
+                alloc,
+                host_id,
+                self.allow_vm_overcommit,
+                self.sim_config.allow_bandwidth_overcommit,
+            ) == AllocationVerdict::Success
+            {
                 pool_state_copy.allocate(alloc, host_id);
             } else {
                 self.logger.borrow_mut().log_debug(
@@ -19,6 +19,11 @@ pub struct HostInfo {
     pub cpu_overcommit: u32,
     pub memory_overcommit: u64,
 
+    /// Network bandwidth capacity, `u64::MAX` if the bandwidth is not limited.
+    pub bandwidth_total: u64,
+    pub bandwidth_available: u64,
+    pub bandwidth_overcommit: u64,
+
     pub allocations: BTreeMap<u32, Allocation>,
 
     pub rack_id: Option<u32>,
@@ -42,6 +47,9 @@ impl HostInfo {
             memory_available,
             cpu_overcommit: 0,
             memory_overcommit: 0,
+            bandwidth_total: u64::MAX,
+            bandwidth_available: u64::MAX,
+            bandwidth_overcommit: 0,
             allocations: BTreeMap::new(),
             rack_id,
         }
@@ -75,6 +83,15 @@ impl ResourcePoolState {
         );
     }
 
+    /// Sets network bandwidth capacity of the host, by default the bandwidth is not limited.
+    pub fn set_host_bandwidth(&mut self, host_id: u32, bandwidth: u64) {
+        let host = self.hosts.get_mut(&host_id).unwrap();
+        let allocated: u64 = host.allocations.values().map(|alloc| alloc.bandwidth_usage).sum();
+        host.bandwidth_total = bandwidth;
+        host.bandwidth_available = bandwidth.saturating_sub(allocated);
+        host.bandwidth_overcommit = allocated.saturating_sub(bandwidth);
+    }
+
     /// Returns IDs of all hosts.
     pub fn get_host_ids(&self) -> Vec<u32> {
         self.hosts.keys().cloned().collect()
@@ -96,7 +113,15 @@ impl ResourcePoolState {
     }
 
     /// Checks if the specified allocation is currently possible on the specified host.
-    pub fn can_allocate(&self, alloc: &Allocation, host_id: u32, allow_cpu_overcommit: bool) -> AllocationVerdict {
+    ///
+    /// CPU and network bandwidth can be overcommitted if the corresponding flag is set, memory cannot be overcommitted.
+    pub fn can_allocate(
+        &self,
+        alloc: &Allocation,
+        host_id: u32,
+        allow_cpu_overcommit: bool,
+        allow_bandwidth_overcommit: bool,
+    ) -> AllocationVerdict {
         if let Some(host) = self.hosts.get(&host_id) {
             if host.cpu_available < alloc.cpu_usage && !allow_cpu_overcommit {
                 return AllocationVerdict::NotEnoughCPU;
@@ -104,6 +129,9 @@ impl ResourcePoolState {
             if host.memory_available < alloc.memory_usage {
                 return AllocationVerdict::NotEnoughMemory;
             }
+            if host.bandwidth_available < alloc.bandwidth_usage && !allow_bandwidth_overcommit {
+                return AllocationVerdict::NotEnoughBandwidth;
+            }
         } else {
             return AllocationVerdict::HostNotFound;
         }
@@ -131,6 +159,13 @@ impl ResourcePoolState {
                 host.memory_available -= alloc.memory_usage;
             }
 
+            if host.bandwidth_available < alloc.bandwidth_usage {
+                host.bandwidth_overcommit += alloc.bandwidth_usage - host.bandwidth_available;
+                host.bandwidth_available = 0;
+            } else {
+                host.bandwidth_available -= alloc.bandwidth_usage;
+            }
+
             host.allocations.insert(alloc.id, alloc.clone());
         }
     }
@@ -152,6 +187,13 @@ impl ResourcePoolState {
                 host.memory_overcommit = 0;
             }
 
+            if host.bandwidth_overcommit >= alloc.bandwidth_usage {
+                host.bandwidth_overcommit -= alloc.bandwidth_usage;
+            } else {
+                host.bandwidth_available += alloc.bandwidth_usage - host.bandwidth_overcommit;
+                host.bandwidth_overcommit = 0;
+            }
+
             host.allocations.remove(&alloc.id);
         }
     }
@@ -195,4 +237,40 @@ impl ResourcePoolState {
     pub fn get_memory_load(&self, host_id: u32) -> f64 {
         1. - self.hosts[&host_id].memory_available as f64 / self.hosts[&host_id].memory_total as f64
     }
+
+    /// Returns the network bandwidth capacity of the specified host.
+    pub fn get_total_bandwidth(&self, host_id: u32) -> u64 {
+        self.hosts[&host_id].bandwidth_total
+    }
+
+    /// Returns the amount of available network bandwidth on the specified host.
+    pub fn get_available_bandwidth(&self, host_id: u32) -> u64 {
+        self.hosts[&host_id].bandwidth_available
+    }
+
+    /// Returns the bandwidth allocation rate (ratio of allocated to total bandwidth) of the specified host.
+    /// The rate exceeds 1 if the host bandwidth is overcommitted.
+    pub fn get_bandwidth_load(&self, host_id: u32) -> f64 {
+        let host = &self.hosts[&host_id];
+        let allocated = host.bandwidth_total - host.bandwidth_available + host.bandwidth_overcommit;
+        allocated as f64 / host.bandwidth_total as f64
+    }
+
+    /// Returns the bandwidth allocation rate of the rack, i.e. the ratio of bandwidth allocated on the rack hosts
+    /// to their total bandwidth, or 0 if the rack has no hosts with limited bandwidth.
+    pub fn get_rack_bandwidth_load(&self, rack_id: u32) -> f64 {
+        let mut allocated = 0.;
+        let mut total = 0.;
+        for host in self.hosts.values() {
+            if host.rack_id == Some(rack_id) && host.bandwidth_total != u64::MAX {
+                allocated += (host.bandwidth_total - host.bandwidth_available + host.bandwidth_overcommit) as f64;
+                total += host.bandwidth_total as f64;
+            }
+        }
+        if total > 0. {
+            allocated / total
+        } else {
+            0.
+        }
+    }
 }
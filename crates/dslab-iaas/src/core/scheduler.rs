@@ -8,6 +8,7 @@ use simcore::context::SimulationContext;
 use simcore::event::Event;
 use simcore::handler::EventHandler;
 
+use crate::core::admission_controller::{AdmissionController, AdmissionDecision};
 use crate::core::common::Allocation;
 use crate::core::config::sim_config::SimulationConfig;
 use crate::core::events::allocation::{
@@ -40,6 +41,7 @@ pub struct Scheduler {
     vm_api: Rc<RefCell<VmAPI>>,
     placement_store_id: u32,
     vm_placement_algorithm: VMPlacementAlgorithm,
+    admission_controller: Option<Box<dyn AdmissionController>>,
     ctx: SimulationContext,
     logger: Rc<RefCell<Box<dyn Logger>>>,
     sim_config: Rc<SimulationConfig>,
@@ -65,6 +67,7 @@ impl Scheduler {
             vm_api,
             placement_store_id,
             vm_placement_algorithm,
+            admission_controller: None,
             ctx,
             logger,
             sim_config,
@@ -77,6 +80,29 @@ impl Scheduler {
             .add_host(id, cpu_total, memory_total, cpu_total, memory_total, rack_id);
     }
 
+    /// Sets network bandwidth capacity of the host in local resource pool state.
+    pub fn set_host_bandwidth(&mut self, id: u32, bandwidth: u64) {
+        self.pool_state.set_host_bandwidth(id, bandwidth);
+    }
+
+    /// Sets admission controller which is invoked for each allocation request before VM placement.
+    pub fn set_admission_controller(&mut self, admission_controller: Box<dyn AdmissionController>) {
+        self.admission_controller = Some(admission_controller);
+    }
+
+    fn notify_vm_status(&self, vm_ids: Vec<u32>, status: VmStatus) {
+        for vm_id in vm_ids {
+            self.ctx.emit(
+                VmStatusChanged {
+                    vm_id,
+                    status: status.clone(),
+                },
+                self.vm_api.borrow().get_id(),
+                self.sim_config.message_delay,
+            );
+        }
+    }
+
     /// Computes the placements (hosts) for a set of allocations using the configured placement algorithm.
     ///
     /// Returns None is it is not possible to satisfy all allocations.
@@ -117,16 +143,7 @@ impl Scheduler {
         // check if request is timed out
         let start_time = self.vm_api.borrow().get_vm(vm_ids[0]).borrow().allocation_start_time;
         if self.ctx.time() > start_time + self.sim_config.vm_allocation_timeout {
-            for vm_id in vm_ids {
-                self.ctx.emit(
-                    VmStatusChanged {
-                        vm_id,
-                        status: VmStatus::FailedToAllocate,
-                    },
-                    self.vm_api.borrow().get_id(),
-                    self.sim_config.message_delay,
-                );
-            }
+            self.notify_vm_status(vm_ids, VmStatus::FailedToAllocate);
             return;
         }
 
@@ -134,6 +151,26 @@ impl Scheduler {
             .iter()
             .map(|vm_id| self.vm_api.borrow().get_vm_allocation(*vm_id))
             .collect();
+        // check the request with admission controller
+        if let Some(admission_controller) = self.admission_controller.as_mut() {
+            match admission_controller.admit(&allocations, &self.pool_state, &self.monitoring.borrow()) {
+                AdmissionDecision::Admit => {}
+                AdmissionDecision::Reject(reason) => {
+                    self.logger
+                        .borrow_mut()
+                        .log_debug(&self.ctx, format!("rejected {} vms: {}", vm_ids.len(), reason));
+                    self.notify_vm_status(vm_ids, VmStatus::Rejected);
+                    return;
+                }
+                AdmissionDecision::Defer(delay) => {
+                    self.logger
+                        .borrow_mut()
+                        .log_debug(&self.ctx, format!("deferred {} vms for {}", vm_ids.len(), delay));
+                    self.ctx.emit_self(AllocationRequest { vm_ids }, delay);
+                    return;
+                }
+            }
+        }
         // try to find placements using the placement algorithm
         if let Some(placements) = self.compute_placements(&allocations) {
             for (host, alloc) in placements.iter().zip(allocations.iter()) {
@@ -17,6 +17,7 @@ pub enum VmStatus {
     Finished,
     Migrating,
     FailedToAllocate,
+    Rejected,
 }
 
 impl Display for VmStatus {
@@ -27,6 +28,7 @@ impl Display for VmStatus {
             VmStatus::Finished => write!(f, "finished"),
             VmStatus::Migrating => write!(f, "migrating"),
             VmStatus::FailedToAllocate => write!(f, "failed_to_allocate"),
+            VmStatus::Rejected => write!(f, "rejected"),
         }
     }
 }
@@ -38,6 +40,10 @@ pub struct ResourceConsumer {
     pub memory_usage: u64,
     pub cpu_load_model: Box<dyn LoadModel>,
     pub memory_load_model: Box<dyn LoadModel>,
+    /// Network bandwidth reserved by VM on its host.
+    pub bandwidth_usage: u64,
+    /// VM start (boot) duration, e.g. depending on the VM image. If not set, the value from simulation config is used.
+    pub start_duration: Option<f64>,
 }
 
 impl ResourceConsumer {
@@ -53,6 +59,8 @@ impl ResourceConsumer {
             memory_usage,
             cpu_load_model,
             memory_load_model,
+            bandwidth_usage: 0,
+            start_duration: None,
         }
     }
 
@@ -63,6 +71,8 @@ impl ResourceConsumer {
             memory_usage,
             cpu_load_model: Box::new(ConstantLoadModel::new(1.0)),
             memory_load_model: Box::new(ConstantLoadModel::new(1.0)),
+            bandwidth_usage: 0,
+            start_duration: None,
         }
     }
 
@@ -73,8 +83,22 @@ impl ResourceConsumer {
             memory_usage,
             cpu_load_model: Box::new(ConstantLoadModel::new(cpu_load)),
             memory_load_model: Box::new(ConstantLoadModel::new(memory_load)),
+            bandwidth_usage: 0,
+            start_duration: None,
         }
     }
+
+    /// Sets network bandwidth reserved by VM on its host.
+    pub fn with_bandwidth(mut self, bandwidth_usage: u64) -> Self {
+        self.bandwidth_usage = bandwidth_usage;
+        self
+    }
+
+    /// Sets VM start (boot) duration overriding the value from simulation config.
+    pub fn with_start_duration(mut self, start_duration: f64) -> Self {
+        self.start_duration = Some(start_duration);
+        self
+    }
 }
 
 /// Represents virtual machine (VM).
@@ -87,9 +111,11 @@ pub struct VirtualMachine {
     pub id: u32,
     pub cpu_usage: u32,
     pub memory_usage: u64,
+    pub bandwidth_usage: u64,
     pub allocation_start_time: f64,
     lifetime: f64,
     start_time: f64,
+    start_duration: Option<f64>,
     cpu_load_model: Box<dyn LoadModel>,
     memory_load_model: Box<dyn LoadModel>,
     sim_config: Rc<SimulationConfig>,
@@ -119,9 +145,11 @@ impl VirtualMachine {
             id,
             cpu_usage: resource_consumer.cpu_usage,
             memory_usage: resource_consumer.memory_usage,
+            bandwidth_usage: resource_consumer.bandwidth_usage,
             allocation_start_time,
             lifetime,
             start_time: -1.,
+            start_duration: resource_consumer.start_duration,
             cpu_load_model: resource_consumer.cpu_load_model,
             memory_load_model: resource_consumer.memory_load_model,
             sim_config,
@@ -138,9 +166,12 @@ impl VirtualMachine {
         self.start_time
     }
 
-    /// Returns VM start duration (the value is taken from the simulation config).
+    /// Returns VM start duration (if not set for this VM, the value is taken from the simulation config).
+    ///
+    /// During the start the VM resources are allocated on the host, but the VM is not running yet.
+    /// When the start is completed, the host emits [`VmBooted`](crate::core::events::vm::VmBooted) event.
     pub fn start_duration(&self) -> f64 {
-        self.sim_config.vm_start_duration
+        self.start_duration.unwrap_or(self.sim_config.vm_start_duration)
     }
 
     /// Returns VM stop duration (the value is taken from the simulation config).
@@ -82,6 +82,7 @@ impl VmAPI {
             id: vm_id,
             cpu_usage: self.vms.get(&vm_id).unwrap().borrow().cpu_usage,
             memory_usage: self.vms.get(&vm_id).unwrap().borrow().memory_usage,
+            bandwidth_usage: self.vms.get(&vm_id).unwrap().borrow().bandwidth_usage,
         }
     }
 
@@ -10,6 +10,7 @@ use crate::core::vm_placement_algorithms::cosine_similarity::CosineSimilarity;
 use crate::core::vm_placement_algorithms::delta_perp_distance::DeltaPerpDistance;
 use crate::core::vm_placement_algorithms::dot_product::DotProduct;
 use crate::core::vm_placement_algorithms::first_fit::FirstFit;
+use crate::core::vm_placement_algorithms::min_fragmentation::MinFragmentation;
 use crate::core::vm_placement_algorithms::norm_diff::L2NormDiff;
 use crate::core::vm_placement_algorithms::rack_anti_affinity::RackAntiAffinity;
 use crate::core::vm_placement_algorithms::weighted_dot_product::WeightedDotProduct;
@@ -63,6 +64,7 @@ pub fn placement_algorithm_resolver(config_str: String) -> VMPlacementAlgorithm
         "FirstFit" => VMPlacementAlgorithm::single(FirstFit::new()),
         "BestFit" => VMPlacementAlgorithm::single(BestFit::new()),
         "WorstFit" => VMPlacementAlgorithm::single(WorstFit::new()),
+        "MinFragmentation" => VMPlacementAlgorithm::single(MinFragmentation::new()),
         "BestFitThreshold" => VMPlacementAlgorithm::single(BestFitThreshold::from_string(&options.unwrap())),
         "CosineSimilarity" => VMPlacementAlgorithm::single(CosineSimilarity::new()),
         "DotProduct" => VMPlacementAlgorithm::single(DotProduct::new()),
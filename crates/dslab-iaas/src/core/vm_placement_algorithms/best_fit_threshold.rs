@@ -36,7 +36,7 @@ impl SingleVMPlacementAlgorithm for BestFitThreshold {
         let mut result: Option<u32> = None;
         let mut best_cpu_load: f64 = 0.;
         for host in monitoring.get_hosts_list() {
-            if pool_state.can_allocate(alloc, *host, true) == AllocationVerdict::Success {
+            if pool_state.can_allocate(alloc, *host, true, false) == AllocationVerdict::Success {
                 let state = monitoring.get_host_state(*host);
                 let cpu_used = state.cpu_load * state.cpu_total as f64;
                 let cpu_load_new = (cpu_used + alloc.cpu_usage as f64) / state.cpu_total as f64;
@@ -23,7 +23,7 @@ impl SingleVMPlacementAlgorithm for CosineSimilarity {
         let mut max_cosine: f64 = f64::MIN;
 
         for host in pool_state.get_host_ids() {
-            if pool_state.can_allocate(alloc, host, false) == AllocationVerdict::Success {
+            if pool_state.can_allocate(alloc, host, false, false) == AllocationVerdict::Success {
                 let capacity_cpu = pool_state.get_total_cpu(host) as f64;
                 let capacity_mem = pool_state.get_total_memory(host) as f64;
                 let capacity_norm = (capacity_cpu.powi(2) + capacity_mem.powi(2)).sqrt();
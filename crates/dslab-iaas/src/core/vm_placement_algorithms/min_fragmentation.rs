@@ -0,0 +1,42 @@
+//! Min Fragmentation algorithm.
+
+use crate::core::common::Allocation;
+use crate::core::common::AllocationVerdict;
+use crate::core::monitoring::Monitoring;
+use crate::core::resource_pool::ResourcePoolState;
+use crate::core::vm_placement_algorithm::SingleVMPlacementAlgorithm;
+
+/// Uses the suitable host with minimal resulting fragmentation, i.e. performs best fit on the tightest dimension.
+/// For each host the remaining CPU and memory after VM placement are normalized to the host's capacity,
+/// and the host with the minimal remaining amount of the scarcest resource is selected.
+/// Ties are broken by the total remaining amount of both resources.
+#[derive(Default)]
+pub struct MinFragmentation;
+
+impl MinFragmentation {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl SingleVMPlacementAlgorithm for MinFragmentation {
+    fn select_host(&self, alloc: &Allocation, pool_state: &ResourcePoolState, _monitoring: &Monitoring) -> Option<u32> {
+        let mut result: Option<u32> = None;
+        let mut best_score = (f64::MAX, f64::MAX);
+
+        for host in pool_state.get_host_ids() {
+            if pool_state.can_allocate(alloc, host, false, false) == AllocationVerdict::Success {
+                let cpu_left = (pool_state.get_available_cpu(host) - alloc.cpu_usage) as f64
+                    / pool_state.get_total_cpu(host) as f64;
+                let memory_left = (pool_state.get_available_memory(host) - alloc.memory_usage) as f64
+                    / pool_state.get_total_memory(host) as f64;
+                let score = (cpu_left.min(memory_left), cpu_left + memory_left);
+                if score < best_score {
+                    best_score = score;
+                    result = Some(host);
+                }
+            }
+        }
+        result
+    }
+}
@@ -4,6 +4,7 @@ pub mod cosine_similarity;
 pub mod delta_perp_distance;
 pub mod dot_product;
 pub mod first_fit;
+pub mod min_fragmentation;
 pub mod norm_diff;
 pub mod rack_anti_affinity;
 pub mod weighted_dot_product;
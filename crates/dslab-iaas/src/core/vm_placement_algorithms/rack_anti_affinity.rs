@@ -1,6 +1,6 @@
 //! Rack anti-affinity algorithm.
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use crate::core::common::{Allocation, AllocationVerdict};
 use crate::core::monitoring::Monitoring;
@@ -8,7 +8,7 @@ use crate::core::resource_pool::ResourcePoolState;
 use crate::core::vm_placement_algorithm::MultiVMPlacementAlgorithm;
 
 /// Multi VM placement algorithm which places each VM from request on a separate rack.
-/// First Fit is used as a basic algorithm for host selection.
+/// Racks with lower network bandwidth load are preferred, First Fit is used for host selection within the same load.
 #[derive(Default)]
 pub struct RackAntiAffinity;
 
@@ -29,16 +29,28 @@ impl MultiVMPlacementAlgorithm for RackAntiAffinity {
         let mut pool = pool_state.clone();
         let mut used_racks = HashSet::new();
         for alloc in allocations {
-            if let Some(host) = pool.get_hosts().find(|&host| {
-                !used_racks.contains(
-                    &host
-                        .rack_id
-                        .expect("Rack is not set for host, cannot execute rack-aware placement algorithm"),
-                ) && pool_state.can_allocate(alloc, host.id, false) == AllocationVerdict::Success
-            }) {
-                used_racks.insert(host.rack_id.unwrap());
-                result.push(host.id);
-                pool.allocate(alloc, host.id);
+            let mut rack_loads = HashMap::new();
+            let candidate = pool
+                .get_hosts()
+                .filter(|&host| {
+                    !used_racks.contains(
+                        &host
+                            .rack_id
+                            .expect("Rack is not set for host, cannot execute rack-aware placement algorithm"),
+                    ) && pool.can_allocate(alloc, host.id, false, false) == AllocationVerdict::Success
+                })
+                .map(|host| {
+                    let rack_id = host.rack_id.unwrap();
+                    let load = *rack_loads
+                        .entry(rack_id)
+                        .or_insert_with(|| pool.get_rack_bandwidth_load(rack_id));
+                    (host.id, rack_id, load)
+                })
+                .min_by(|a, b| a.2.total_cmp(&b.2));
+            if let Some((host_id, rack_id, _)) = candidate {
+                used_racks.insert(rack_id);
+                result.push(host_id);
+                pool.allocate(alloc, host_id);
             } else {
                 return None;
             }
@@ -33,7 +33,7 @@ impl SingleVMPlacementAlgorithm for WeightedDotProduct {
         memory_weight /= pool_state.get_host_count() as f64;
 
         for host in pool_state.get_host_ids() {
-            if pool_state.can_allocate(alloc, host, false) == AllocationVerdict::Success {
+            if pool_state.can_allocate(alloc, host, false, false) == AllocationVerdict::Success {
                 let cpu_product = (pool_state.get_available_cpu(host) * alloc.cpu_usage) as f64;
                 let memory_product = (pool_state.get_available_memory(host) * alloc.memory_usage) as f64;
                 let product = cpu_weight * cpu_product / (pool_state.get_total_cpu(host)).pow(2) as f64
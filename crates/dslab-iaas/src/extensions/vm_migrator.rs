@@ -8,6 +8,7 @@ use serde::Serialize;
 
 use crate::core::config::sim_config::SimulationConfig;
 use crate::core::events::allocation::MigrationRequest;
+use crate::core::events::power::HostSleepRequest;
 use crate::core::monitoring::Monitoring;
 use crate::core::vm::VmStatus;
 use crate::core::vm_api::VmAPI;
@@ -26,10 +27,12 @@ pub struct PerformMigrations {}
 /// It periodically checks the state of resource pool and tries to find the overloaded and underloaded hosts.
 /// If there are any, it selects some VMs from these hosts and migrates them to other hosts in order to turn off the
 /// underloaded hosts and return the overloaded hosts to the normal state.
+/// Optionally, the hosts left without VMs are put to sleep to save energy.
 pub struct VmMigrator {
     interval: f64,
     overload_threshold: f64,
     underload_threshold: f64,
+    sleep_idle_hosts: bool,
     monitoring: Option<Rc<RefCell<Monitoring>>>,
     vm_api: Option<Rc<RefCell<VmAPI>>>,
     sim_config: Option<Rc<SimulationConfig>>,
@@ -53,6 +56,11 @@ impl VmMigrator {
         self.sim_config = Some(sim_config);
     }
 
+    /// Enables or disables putting the hosts without VMs to sleep.
+    pub fn set_sleep_idle_hosts(&mut self, sleep_idle_hosts: bool) {
+        self.sleep_idle_hosts = sleep_idle_hosts;
+    }
+
     /// Periodic process, which finds hosts and performs VM migrations.
     fn perform_migrations(&mut self) {
         if self.monitoring.is_none() {
@@ -241,6 +249,21 @@ impl VmMigrator {
             }
         }
 
+        // put idle hosts to sleep -------------------------------------------------------------------------------------
+
+        if self.sleep_idle_hosts {
+            for (host, state) in mon.get_host_states().iter() {
+                if state.vms.is_empty() && !target_hosts.contains(host) {
+                    log_trace!(self.ctx, "request host {} to sleep", host);
+                    self.ctx.emit(
+                        HostSleepRequest {},
+                        *host,
+                        self.sim_config.as_ref().unwrap().message_delay,
+                    );
+                }
+            }
+        }
+
         // schedule the next migration attempt
         self.ctx.emit_self(PerformMigrations {}, self.interval);
     }
@@ -252,6 +275,7 @@ impl CustomComponent for VmMigrator {
             interval: 1.,
             overload_threshold: 0.8,
             underload_threshold: 0.4,
+            sleep_idle_hosts: false,
             monitoring: None,
             vm_api: None,
             sim_config: None,
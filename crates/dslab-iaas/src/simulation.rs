@@ -15,6 +15,8 @@ use simcore::Id;
 
 use crate::core::config::sim_config::SimulationConfig;
 use crate::core::events::allocation::{AllocationRequest, MigrationRequest};
+use crate::core::events::power::{HostSleepRequest, HostWakeUpRequest};
+use crate::core::fragmentation_monitor::{fragmentation, FragmentationMonitor};
 use crate::core::host_manager::HostManager;
 use crate::core::host_manager::SendHostState;
 use crate::core::logger::{Logger, StdoutLogger};
@@ -45,6 +47,7 @@ struct VMSpawnRequest {
 /// It encapsulates all simulation components and provides convenient access to them for the user.
 pub struct CloudSimulation {
     monitoring: Rc<RefCell<Monitoring>>,
+    fragmentation_monitor: Option<Rc<RefCell<FragmentationMonitor>>>,
     vm_api: Rc<RefCell<VmAPI>>,
     placement_store: Rc<RefCell<PlacementStore>>,
     hosts: BTreeMap<u32, Rc<RefCell<HostManager>>>,
@@ -91,6 +94,7 @@ impl CloudSimulation {
         let ctx = sim.create_context("simulation");
         let mut sim = Self {
             monitoring,
+            fragmentation_monitor: None,
             vm_api,
             placement_store,
             hosts: BTreeMap::new(),
@@ -113,12 +117,18 @@ impl CloudSimulation {
             let count = host_config.count.unwrap_or(1);
             if count == 1 {
                 let name = host_config.name.unwrap();
-                sim.add_host(&name, host_config.cpus, host_config.memory);
+                let id = sim.add_host(&name, host_config.cpus, host_config.memory);
+                if let Some(bandwidth) = host_config.bandwidth {
+                    sim.set_host_bandwidth(id, bandwidth);
+                }
             } else {
                 let prefix = host_config.name_prefix.unwrap();
                 for i in 0..count {
                     let name = format!("{}{}", prefix, i + 1);
-                    sim.add_host(&name, host_config.cpus, host_config.memory);
+                    let id = sim.add_host(&name, host_config.cpus, host_config.memory);
+                    if let Some(bandwidth) = host_config.bandwidth {
+                        sim.set_host_bandwidth(id, bandwidth);
+                    }
                 }
             }
         }
@@ -185,6 +195,9 @@ impl CloudSimulation {
             self.sim_config.clone(),
         )));
         let id = self.sim.add_handler(name, host.clone());
+        if let Some(fragmentation_monitor) = &self.fragmentation_monitor {
+            fragmentation_monitor.borrow_mut().add_host(host.clone());
+        }
         self.hosts.insert(id, host);
         // add host to monitoring
         self.monitoring.borrow_mut().add_host(id, cpu_total, memory_total);
@@ -212,6 +225,16 @@ impl CloudSimulation {
         self.add_host_internal(name, cpu_total, memory_total, Some(rack_id))
     }
 
+    /// Sets network bandwidth capacity of the host, which limits the total bandwidth of VMs placed on it.
+    /// By default the host bandwidth is not limited.
+    pub fn set_host_bandwidth(&mut self, host_id: u32, bandwidth: u64) {
+        self.hosts[&host_id].borrow_mut().set_bandwidth(bandwidth);
+        self.placement_store.borrow_mut().set_host_bandwidth(host_id, bandwidth);
+        for scheduler in self.schedulers.values() {
+            scheduler.borrow_mut().set_host_bandwidth(host_id, bandwidth);
+        }
+    }
+
     /// Creates new scheduler with specified name and VM placement algorithm, and returns the scheduler ID.
     pub fn add_scheduler(&mut self, name: &str, vm_placement_algorithm: VMPlacementAlgorithm) -> u32 {
         // create scheduler using current state from placement store
@@ -361,6 +384,20 @@ impl CloudSimulation {
         );
     }
 
+    /// Sends request to put the specified host to sleep.
+    ///
+    /// The request is ignored if the host is not idle when it arrives.
+    pub fn sleep_host(&mut self, host_id: u32) {
+        self.ctx
+            .emit(HostSleepRequest {}, host_id, self.sim_config.message_delay);
+    }
+
+    /// Sends request to wake up the specified host.
+    pub fn wake_up_host(&mut self, host_id: u32) {
+        self.ctx
+            .emit(HostWakeUpRequest {}, host_id, self.sim_config.message_delay);
+    }
+
     /// Creates custom component and adds it to the simulation.
     pub fn build_custom_component<Component: 'static + CustomComponent>(
         &mut self,
@@ -551,6 +588,41 @@ impl CloudSimulation {
         sum_memory_allocated / sum_memory_total
     }
 
+    /// Returns the current resource fragmentation, i.e. the share of free resources located on active hosts
+    /// (the hosts with at least one allocated VM). Free resources on active hosts are scattered and may be unusable
+    /// for large VMs, unlike the resources of idle hosts. The value is averaged over CPU and memory.
+    pub fn fragmentation(&self) -> f64 {
+        fragmentation(self.hosts.values())
+    }
+
+    /// Enables recording of resource fragmentation (see [`fragmentation()`](Self::fragmentation))
+    /// with the specified period starting from the current time.
+    pub fn record_fragmentation(&mut self, period: f64) {
+        assert!(
+            self.fragmentation_monitor.is_none(),
+            "Fragmentation recording is already enabled"
+        );
+        let fragmentation_monitor = rc!(refcell!(FragmentationMonitor::new(
+            period,
+            self.sim.create_context("fragmentation_monitor")
+        )));
+        for host in self.hosts.values() {
+            fragmentation_monitor.borrow_mut().add_host(host.clone());
+        }
+        self.sim
+            .add_handler("fragmentation_monitor", fragmentation_monitor.clone());
+        self.fragmentation_monitor = Some(fragmentation_monitor);
+    }
+
+    /// Returns the recorded resource fragmentation as (time, value) pairs,
+    /// see [`record_fragmentation()`](Self::record_fragmentation).
+    pub fn fragmentation_history(&self) -> Vec<(f64, f64)> {
+        self.fragmentation_monitor
+            .as_ref()
+            .map(|fragmentation_monitor| fragmentation_monitor.borrow().samples().to_vec())
+            .unwrap_or_default()
+    }
+
     pub fn log_error(&mut self, log: String) {
         self.logger.borrow_mut().log_error(self.context(), log);
     }
@@ -170,3 +170,42 @@ fn test_batch_rack_anti_affinity() {
         vec!["h1", "h2", "h3"],
     );
 }
+
+#[test]
+// Tests that RackAntiAffinity algorithm prefers racks with lower network bandwidth load.
+// Rack #1 is loaded by 80% and rack #2 by 30%, so the VMs are placed on the idle rack #3 and then on rack #2.
+fn test_rack_anti_affinity_bandwidth_load() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file("test-configs/config_zero_latency.yaml");
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+
+    let host_ids = vec![
+        cloud_sim.add_host_in_rack("h1", 10, 10, 0),
+        cloud_sim.add_host_in_rack("h2", 10, 10, 1),
+        cloud_sim.add_host_in_rack("h3", 10, 10, 2),
+    ];
+    for &host_id in host_ids.iter() {
+        cloud_sim.set_host_bandwidth(host_id, 1000);
+    }
+
+    let vm = ResourceConsumer::with_full_load(1, 1).with_bandwidth(800);
+    cloud_sim.spawn_vm_on_host(vm, 10.0, None, host_ids[0]);
+    let vm = ResourceConsumer::with_full_load(1, 1).with_bandwidth(300);
+    cloud_sim.spawn_vm_on_host(vm, 10.0, None, host_ids[1]);
+
+    cloud_sim.step_for_duration(1.);
+
+    let scheduler_id = cloud_sim.add_scheduler("s", VMPlacementAlgorithm::multi(RackAntiAffinity::new()));
+    cloud_sim.begin_batch();
+    let vm_ids: Vec<u32> = (0..2)
+        .map(|_| {
+            let vm = ResourceConsumer::with_full_load(1, 1).with_bandwidth(100);
+            cloud_sim.spawn_vm_now(vm, 10.0, None, scheduler_id)
+        })
+        .collect();
+    cloud_sim.spawn_batch();
+
+    cloud_sim.step_for_duration(1.);
+    assert_eq!(cloud_sim.vm_location(vm_ids[0]), Some(host_ids[2]));
+    assert_eq!(cloud_sim.vm_location(vm_ids[1]), Some(host_ids[1]));
+}
@@ -3,8 +3,10 @@ use simcore::simulation::Simulation;
 use dslab_models::power::cpu_models::constant::ConstantCpuPowerModel;
 use dslab_models::power::host::HostPowerModelBuilder;
 
-use dslab_iaas::core::common::Allocation;
+use dslab_iaas::core::admission_controller::{AdmissionController, AdmissionDecision};
+use dslab_iaas::core::common::{Allocation, AllocationVerdict};
 use dslab_iaas::core::config::sim_config::SimulationConfig;
+use dslab_iaas::core::host_manager::HostPowerState;
 use dslab_iaas::core::monitoring::Monitoring;
 use dslab_iaas::core::resource_pool::ResourcePoolState;
 use dslab_iaas::core::slav_metric::OverloadTimeFraction;
@@ -13,6 +15,7 @@ use dslab_iaas::core::vm_placement_algorithm::{SingleVMPlacementAlgorithm, VMPla
 use dslab_iaas::core::vm_placement_algorithms::best_fit::BestFit;
 use dslab_iaas::core::vm_placement_algorithms::best_fit_threshold::BestFitThreshold;
 use dslab_iaas::core::vm_placement_algorithms::first_fit::FirstFit;
+use dslab_iaas::core::vm_placement_algorithms::min_fragmentation::MinFragmentation;
 use dslab_iaas::simulation::CloudSimulation;
 
 fn name_wrapper(file_name: &str) -> String {
@@ -454,3 +457,279 @@ fn test_batch_request() {
     assert_eq!(cloud_sim.vm_location(vm_ids[1]), Some(h));
     assert_eq!(cloud_sim.vm_location(vm_ids[2]), Some(h));
 }
+
+#[test]
+// Idle host is put to sleep at moment 0.2 (after message delay) and consumes 0.1 until a new VM arrives.
+// VM is spawned at moment 10 and arrives at the host at moment 10.4 (scheduler + placement store delays).
+// The host wakes up for 5 seconds consuming the idle power 0.4, then the VM is allocated and starts at moment 16.4.
+// The host is loaded by 1/3 for 3.5 seconds (0.6 power) and stays idle from moment 18.9 until the end at moment 30.
+// Thus, the overall energy consumed is 0.2 * 0.4 + 10.2 * 0.1 + 5 * 0.4 + 3.5 * 0.6 + 11.1 * 0.4 = 9.64.
+fn test_host_sleep() {
+    let sim = Simulation::new(123);
+    let mut sim_config = SimulationConfig::from_file(&name_wrapper("config.yaml"));
+    sim_config.host_wake_up_duration = 5.;
+    sim_config.host_sleep_power = 0.1;
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+
+    let h = cloud_sim.add_host("h", 30, 30);
+    let s = cloud_sim.add_scheduler("s", VMPlacementAlgorithm::single(BestFit::new()));
+
+    cloud_sim.sleep_host(h);
+    cloud_sim.step_for_duration(10.);
+    assert_eq!(cloud_sim.host(h).borrow().power_state(), HostPowerState::Sleep);
+
+    let vm = cloud_sim.spawn_vm_now(ResourceConsumer::with_full_load(10, 10), 2.0, None, s);
+    cloud_sim.step_for_duration(1.);
+    assert_eq!(cloud_sim.host(h).borrow().power_state(), HostPowerState::WakingUp);
+    assert_eq!(cloud_sim.vm_status(vm), VmStatus::Initializing);
+
+    cloud_sim.step_for_duration(7.);
+    assert_eq!(cloud_sim.host(h).borrow().power_state(), HostPowerState::Active);
+    assert_eq!(cloud_sim.vm_status(vm), VmStatus::Running);
+    assert!((cloud_sim.vm(vm).borrow().start_time() - 16.4).abs() < 1e-12);
+
+    cloud_sim.step_for_duration(12.);
+    let end_time = cloud_sim.current_time();
+    assert_eq!(end_time, 30.);
+    assert_eq!(cloud_sim.host(h).borrow().power_state(), HostPowerState::Idle);
+    assert!((cloud_sim.host(h).borrow_mut().get_energy_consumed(end_time) - 9.64).abs() < 1e-12);
+}
+
+#[test]
+// VM arrives at the host at moment 0.4 (scheduler + placement store delays) and boots for 5 seconds instead of
+// the default 1 second, so it starts running at moment 5.4. The host resources are allocated during the boot.
+fn test_vm_start_duration() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+
+    let h = cloud_sim.add_host("h", 30, 30);
+    let s = cloud_sim.add_scheduler("s", VMPlacementAlgorithm::single(BestFit::new()));
+
+    let vm = cloud_sim.spawn_vm_now(
+        ResourceConsumer::with_full_load(10, 10).with_start_duration(5.),
+        2.0,
+        None,
+        s,
+    );
+    let default_vm = cloud_sim.spawn_vm_now(ResourceConsumer::with_full_load(10, 10), 2.0, None, s);
+
+    cloud_sim.step_for_duration(3.);
+    assert_eq!(cloud_sim.vm_status(vm), VmStatus::Initializing);
+    assert_eq!(cloud_sim.vm_status(default_vm), VmStatus::Running);
+    assert_eq!(cloud_sim.host(h).borrow().cpu_allocated(), 20.);
+    assert_eq!(cloud_sim.host(h).borrow().memory_allocated(), 20.);
+
+    cloud_sim.step_for_duration(3.);
+    assert_eq!(cloud_sim.vm_status(vm), VmStatus::Running);
+    assert_eq!(cloud_sim.vm_location(vm), Some(h));
+    assert!((cloud_sim.vm(vm).borrow().start_time() - 5.4).abs() < 1e-12);
+    assert!((cloud_sim.vm(default_vm).borrow().start_time() - 1.4).abs() < 1e-12);
+}
+
+struct UtilizationThreshold {
+    threshold: f64,
+}
+
+impl AdmissionController for UtilizationThreshold {
+    fn admit(
+        &mut self,
+        allocations: &[Allocation],
+        pool_state: &ResourcePoolState,
+        _monitoring: &Monitoring,
+    ) -> AdmissionDecision {
+        let host_ids = pool_state.get_host_ids();
+        let total: u32 = host_ids.iter().map(|&id| pool_state.get_total_cpu(id)).sum();
+        let allocated: u32 = host_ids.iter().map(|&id| pool_state.get_allocated_cpu(id)).sum();
+        let requested: u32 = allocations.iter().map(|alloc| alloc.cpu_usage).sum();
+        if (allocated + requested) as f64 > self.threshold * total as f64 {
+            AdmissionDecision::Reject(format!("utilization would exceed {}", self.threshold))
+        } else {
+            AdmissionDecision::Admit
+        }
+    }
+}
+
+#[test]
+// Admission controller keeps the cluster utilization below 50%, so only the first two VMs are admitted.
+fn test_admission_controller() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+
+    let h = cloud_sim.add_host("h", 100, 100);
+    let s = cloud_sim.add_scheduler("s", VMPlacementAlgorithm::single(FirstFit::new()));
+    cloud_sim
+        .scheduler(s)
+        .borrow_mut()
+        .set_admission_controller(Box::new(UtilizationThreshold { threshold: 0.5 }));
+
+    let mut vms = Vec::new();
+    for _ in 0..4 {
+        vms.push(cloud_sim.spawn_vm_now(ResourceConsumer::with_full_load(20, 20), 100.0, None, s));
+        cloud_sim.step_for_duration(5.);
+    }
+
+    assert_eq!(cloud_sim.vm_status(vms[0]), VmStatus::Running);
+    assert_eq!(cloud_sim.vm_status(vms[1]), VmStatus::Running);
+    assert_eq!(cloud_sim.vm_status(vms[2]), VmStatus::Rejected);
+    assert_eq!(cloud_sim.vm_status(vms[3]), VmStatus::Rejected);
+    assert_eq!(cloud_sim.vm_location(vms[2]), None);
+    assert_eq!(cloud_sim.host(h).borrow().cpu_allocated(), 40.);
+}
+
+#[test]
+// First fit puts the third VM on the first host, so the fourth VM doesn't fit into the first two hosts.
+// Min fragmentation fills the second host with the third VM, which leaves room for the fourth VM on the first host.
+fn test_min_fragmentation() {
+    let run = |alg: VMPlacementAlgorithm| {
+        let sim = Simulation::new(123);
+        let sim_config = SimulationConfig::from_file(&name_wrapper("config.yaml"));
+        let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+
+        let hosts: Vec<u32> = (0..3)
+            .map(|i| cloud_sim.add_host(&format!("h{}", i), 100, 100))
+            .collect();
+        let s = cloud_sim.add_scheduler("s", alg);
+        cloud_sim.record_fragmentation(5.);
+
+        let mut vms = Vec::new();
+        for (cpu, memory) in [(50, 40), (70, 60), (30, 40), (50, 60)] {
+            vms.push(cloud_sim.spawn_vm_now(ResourceConsumer::with_full_load(cpu, memory), 100.0, None, s));
+            cloud_sim.step_for_duration(5.);
+        }
+        // locations are returned as host indices
+        let locations: Vec<usize> = vms
+            .iter()
+            .map(|&vm| {
+                hosts
+                    .iter()
+                    .position(|&h| Some(h) == cloud_sim.vm_location(vm))
+                    .unwrap()
+            })
+            .collect();
+        let active_hosts = hosts
+            .iter()
+            .filter(|&&h| cloud_sim.host(h).borrow().cpu_allocated() > 0.)
+            .count();
+        (
+            locations,
+            active_hosts,
+            cloud_sim.fragmentation(),
+            cloud_sim.fragmentation_history(),
+        )
+    };
+
+    let (locations, active_hosts, fragmentation, history) = run(VMPlacementAlgorithm::single(FirstFit::new()));
+    assert_eq!(locations, vec![0, 1, 0, 2]);
+    assert_eq!(active_hosts, 3);
+    assert_eq!(fragmentation, 1.);
+    // fragmentation is recorded every 5 time units, the last sample is taken after all VMs are allocated
+    let times: Vec<f64> = history.iter().map(|&(time, _)| time).collect();
+    assert_eq!(times, vec![0., 5., 10., 15., 20.]);
+    assert_eq!(history[0].1, 0.);
+    assert_eq!(history[4].1, 1.);
+
+    let (locations, active_hosts, fragmentation, history) = run(VMPlacementAlgorithm::single(MinFragmentation::new()));
+    assert_eq!(locations, vec![0, 1, 1, 0]);
+    assert_eq!(active_hosts, 2);
+    assert_eq!(fragmentation, 0.);
+    assert_eq!(history.len(), 5);
+    assert_eq!(history[4].1, 0.);
+}
+
+#[test]
+fn test_host_bandwidth() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+
+    let hosts: Vec<u32> = (0..2)
+        .map(|i| cloud_sim.add_host_in_rack(&format!("h{}", i), 100, 100, 0))
+        .collect();
+    for &host in hosts.iter() {
+        cloud_sim.set_host_bandwidth(host, 1000);
+    }
+    let s = cloud_sim.add_scheduler("s", VMPlacementAlgorithm::single(FirstFit::new()));
+
+    // the VMs easily fit on a single host by CPU and memory, but not by bandwidth
+    let vms: Vec<u32> = (0..2)
+        .map(|_| {
+            let vm = ResourceConsumer::with_full_load(10, 10).with_bandwidth(600);
+            let id = cloud_sim.spawn_vm_now(vm, 100.0, None, s);
+            cloud_sim.step_for_duration(5.);
+            id
+        })
+        .collect();
+
+    assert_eq!(cloud_sim.vm_location(vms[0]), Some(hosts[0]));
+    assert_eq!(cloud_sim.vm_location(vms[1]), Some(hosts[1]));
+    for &host in hosts.iter() {
+        assert_eq!(cloud_sim.host(host).borrow().bandwidth_allocated(), 600);
+        assert_eq!(cloud_sim.host(host).borrow().bandwidth_share(), 1.);
+    }
+}
+
+#[test]
+// Bandwidth overcommit is allowed, so the VM is migrated to host 1 even though the bandwidth of both VMs exceeds
+// its capacity.
+// The VMs contend for the host bandwidth, so the migration of the VM with memory size 100 over the network
+// with throughput 10 takes 100 / (10 * 1000 / 1200) = 12 seconds instead of 10 seconds (see test_migration_simple).
+fn test_bandwidth_contention() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_bandwidth_overcommit.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+
+    let h1 = cloud_sim.add_host("h1", 200, 200);
+    let h2 = cloud_sim.add_host("h2", 200, 200);
+    cloud_sim.set_host_bandwidth(h1, 1000);
+    cloud_sim.set_host_bandwidth(h2, 1000);
+
+    let vm = ResourceConsumer::with_full_load(10, 10).with_bandwidth(600);
+    cloud_sim.spawn_vm_on_host(vm, 100.0, None, h1);
+    let vm = ResourceConsumer::with_full_load(100, 100).with_bandwidth(600);
+    let vm = cloud_sim.spawn_vm_on_host(vm, 100.0, None, h2);
+
+    cloud_sim.step_for_duration(5.);
+    assert_eq!(cloud_sim.vm_status(vm), VmStatus::Running);
+    assert_eq!(cloud_sim.host(h1).borrow().bandwidth_share(), 1.);
+
+    cloud_sim.migrate_vm_to_host(vm, h1);
+    cloud_sim.step_for_duration(1.);
+    assert_eq!(cloud_sim.vm_status(vm), VmStatus::Migrating);
+    assert_eq!(cloud_sim.host(h1).borrow().bandwidth_allocated(), 1200);
+    assert_eq!(cloud_sim.host(h1).borrow().bandwidth_share(), 1000. / 1200.);
+
+    cloud_sim.step_until_time(18.);
+    assert_eq!(cloud_sim.vm_status(vm), VmStatus::Migrating);
+    assert_eq!(cloud_sim.vm_location(vm), Some(h2));
+
+    cloud_sim.step_until_time(19.);
+    assert_eq!(cloud_sim.vm_status(vm), VmStatus::Running);
+    assert_eq!(cloud_sim.vm_location(vm), Some(h1));
+}
+
+#[test]
+fn test_rack_bandwidth_load() {
+    let mut pool_state = ResourcePoolState::new();
+    for host in 0..3 {
+        pool_state.add_host(host, 100, 100, 100, 100, Some(host / 2));
+        pool_state.set_host_bandwidth(host, 1000);
+    }
+    let alloc = |id: u32| Allocation {
+        id,
+        cpu_usage: 10,
+        memory_usage: 10,
+        bandwidth_usage: 600,
+    };
+    assert!(pool_state.can_allocate(&alloc(1), 0, false, false) == AllocationVerdict::Success);
+    pool_state.allocate(&alloc(1), 0);
+    assert!(pool_state.can_allocate(&alloc(2), 0, false, false) == AllocationVerdict::NotEnoughBandwidth);
+    assert!(pool_state.can_allocate(&alloc(2), 0, true, false) == AllocationVerdict::NotEnoughBandwidth);
+    assert!(pool_state.can_allocate(&alloc(2), 0, false, true) == AllocationVerdict::Success);
+    pool_state.allocate(&alloc(2), 2);
+    assert_eq!(pool_state.get_available_bandwidth(0), 400);
+    assert_eq!(pool_state.get_bandwidth_load(0), 0.6);
+    assert_eq!(pool_state.get_rack_bandwidth_load(0), 0.3);
+    assert_eq!(pool_state.get_rack_bandwidth_load(1), 0.6);
+}
@@ -0,0 +1,158 @@
+//! Generator of synthetic background traffic.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use rand::prelude::*;
+use rand_pcg::Pcg64;
+use serde::Serialize;
+
+use simcore::context::SimulationContext;
+use simcore::event::{Event, EventId};
+use simcore::handler::EventHandler;
+use simcore::{cast, log_debug};
+
+use crate::{DataTransferCompleted, Network, NodeId};
+
+#[derive(Clone, Serialize)]
+struct GenerateTransfer {}
+
+/// Simulation component which injects synthetic data transfers (cross traffic) between the network nodes.
+///
+/// Allows to model the contention caused by other network users without scheduling the competing transfers manually.
+/// Transfers arrive as a Poisson process with the given intensity (mean number of transfers started per unit of time)
+/// and have exponentially distributed sizes with the given mean. The source and destination nodes of each transfer
+/// are sampled from the node pair distribution, which is uniform over all pairs of distinct nodes by default.
+/// The generator uses its own random number generator, so the background traffic is determined by its seed
+/// and doesn't depend on the other components.
+///
+/// The generator must be registered as a simulation handler and started with [`Self::start`].
+pub struct BackgroundTraffic {
+    net: Rc<RefCell<Network>>,
+    intensity: f64,
+    mean_size: f64,
+    node_pairs: Vec<(NodeId, NodeId)>,
+    pair_weights: Vec<f64>,
+    rng: Pcg64,
+    next_transfer: Option<EventId>,
+    started_transfers: u64,
+    completed_transfers: u64,
+    ctx: SimulationContext,
+}
+
+impl BackgroundTraffic {
+    /// Creates a new generator between all pairs of distinct network nodes, which must be added beforehand.
+    pub fn new(net: Rc<RefCell<Network>>, intensity: f64, mean_size: f64, seed: u64, ctx: SimulationContext) -> Self {
+        assert!(intensity > 0., "Background traffic intensity must be positive");
+        assert!(mean_size > 0., "Mean transfer size must be positive");
+        let nodes = net
+            .borrow()
+            .get_nodes()
+            .iter()
+            .map(|name| net.borrow().get_node_id(name))
+            .collect::<Vec<_>>();
+        let node_pairs = nodes
+            .iter()
+            .flat_map(|&src| nodes.iter().filter(move |&&dst| dst != src).map(move |&dst| (src, dst)))
+            .collect::<Vec<_>>();
+        let pair_weights = vec![1.; node_pairs.len()];
+        Self {
+            net,
+            intensity,
+            mean_size,
+            node_pairs,
+            pair_weights,
+            rng: Pcg64::seed_from_u64(seed),
+            next_transfer: None,
+            started_transfers: 0,
+            completed_transfers: 0,
+            ctx,
+        }
+    }
+
+    /// Sets the distribution of transfer endpoints as (source node, destination node, weight) triples.
+    ///
+    /// The probability of choosing a pair is proportional to its weight.
+    pub fn set_node_pairs(&mut self, pairs: &[(&str, &str, f64)]) {
+        assert!(!pairs.is_empty(), "At least one node pair is required");
+        assert!(
+            pairs.iter().all(|&(_, _, weight)| weight >= 0.) && pairs.iter().any(|&(_, _, weight)| weight > 0.),
+            "Node pair weights must be non-negative and not all zero"
+        );
+        let net = self.net.borrow();
+        self.node_pairs = pairs
+            .iter()
+            .map(|&(src, dst, _)| (net.get_node_id(src), net.get_node_id(dst)))
+            .collect();
+        self.pair_weights = pairs.iter().map(|&(_, _, weight)| weight).collect();
+    }
+
+    /// Starts generating the transfers.
+    pub fn start(&mut self) {
+        if self.next_transfer.is_none() {
+            self.schedule_next_transfer();
+        }
+    }
+
+    /// Stops generating new transfers, the transfers in progress are not affected.
+    pub fn stop(&mut self) {
+        if let Some(event_id) = self.next_transfer.take() {
+            self.ctx.cancel_event(event_id);
+        }
+    }
+
+    /// Returns the number of started background transfers.
+    pub fn started_transfers(&self) -> u64 {
+        self.started_transfers
+    }
+
+    /// Returns the number of completed background transfers.
+    pub fn completed_transfers(&self) -> u64 {
+        self.completed_transfers
+    }
+
+    fn schedule_next_transfer(&mut self) {
+        let delay = -(1. - self.rng.gen::<f64>()).ln() / self.intensity;
+        self.next_transfer = Some(self.ctx.emit_self(GenerateTransfer {}, delay));
+    }
+
+    fn generate_transfer(&mut self) {
+        let total_weight = self.pair_weights.iter().sum::<f64>();
+        let mut point = self.rng.gen::<f64>() * total_weight;
+        let mut pair = self.node_pairs.len() - 1;
+        for (i, &weight) in self.pair_weights.iter().enumerate() {
+            if point < weight {
+                pair = i;
+                break;
+            }
+            point -= weight;
+        }
+        let (src, dst) = self.node_pairs[pair];
+        let size = -(1. - self.rng.gen::<f64>()).ln() * self.mean_size;
+        log_debug!(
+            self.ctx,
+            "background transfer from node {} to node {} of size {}",
+            src,
+            dst,
+            size
+        );
+        self.net
+            .borrow_mut()
+            .transfer_data_between_nodes(src, dst, size, self.ctx.id());
+        self.started_transfers += 1;
+    }
+}
+
+impl EventHandler for BackgroundTraffic {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            GenerateTransfer {} => {
+                self.generate_transfer();
+                self.schedule_next_transfer();
+            }
+            DataTransferCompleted { .. } => {
+                self.completed_transfers += 1;
+            }
+        })
+    }
+}
@@ -9,10 +9,18 @@
 //!   where each transfer gets the full network bandwidth, i.e. there is no contention.
 //! - [`SharedBandwidthNetworkModel`](crate::models::SharedBandwidthNetworkModel): Topology-unaware model where the
 //!   network bandwidth is shared fairly among all current transfers.
+//! - [`LatencyMatrixNetworkModel`](crate::models::LatencyMatrixNetworkModel): Topology-unaware model without
+//!   contention where the latency and bandwidth between each pair of nodes are specified by matrices.
 //! - [`TopologyAwareNetworkModel`](crate::models::TopologyAwareNetworkModel): Topology-aware model which uses
 //!   information about the network [`Topology`] (links connecting the nodes) and relies on
 //!   [`RoutingAlgorithm`](crate::routing::RoutingAlgorithm) to compute paths between the nodes. The link's bandwidth is
-//!   shared fairly among the transfers using the link.
+//!   shared fairly among the transfers using the link. With [`EcmpRouting`](crate::routing::EcmpRouting) the transfers
+//!   are distributed among multiple equal-cost paths. Supports multicast transfers over a multicast tree.
+//! - [`PacketSwitchedNetworkModel`](crate::models::PacketSwitchedNetworkModel): Topology-aware model which splits
+//!   the transfers into packets and simulates their store-and-forward transmission with queuing at the links.
+//! - [`AdaptiveBandwidthNetworkModel`](crate::models::AdaptiveBandwidthNetworkModel): Topology-aware model where the
+//!   transfer rates are controlled by AIMD algorithm reacting to link saturation, which approximates TCP congestion
+//!   control without simulating the packets.
 //!
 //! ## Examples
 //!
@@ -26,6 +34,7 @@
 
 #![warn(missing_docs)]
 
+pub mod background;
 pub mod link;
 pub mod model;
 pub mod models;
@@ -33,9 +42,15 @@ pub mod network;
 pub mod node;
 pub mod routing;
 pub mod topology;
+pub mod traffic;
 
-pub use link::{BandwidthSharingPolicy, Link, LinkId};
-pub use model::{DataTransfer, DataTransferCompleted, NetworkModel};
-pub use network::{Message, MessageDelivered, Network};
+pub use background::BackgroundTraffic;
+pub use link::{BandwidthSharingPolicy, LatencyJitter, Link, LinkId};
+pub use model::{
+    DataTransfer, DataTransferCompleted, DataTransferFailed, ModelTick, MulticastCompleted, NetworkModel, Packet,
+    PacketDelivered, TransferFailurePolicy, TransferProgress,
+};
+pub use network::{LinkRecovered, Message, MessageDelivered, Network, NodeRecovered};
 pub use node::{Node, NodeId};
 pub use topology::Topology;
+pub use traffic::HotSpots;
@@ -10,17 +10,56 @@ pub enum BandwidthSharingPolicy {
     Shared,
     /// Each transfer gets the full link bandwidth.
     NonShared,
+    /// The bandwidth is shared among the transfers according to their weights and rate limits
+    /// (see [`DataTransfer`](crate::DataTransfer)) using weighted max-min fair allocation:
+    /// the rate of each transfer is proportional to its weight unless limited by its maximum rate,
+    /// and the bandwidth unused by the limited transfers is shared among the others.
+    ///
+    /// Supported by [`SharedBandwidthNetworkModel`](crate::models::SharedBandwidthNetworkModel),
+    /// the topology-aware models treat such links as [`Self::Shared`].
+    WeightedMaxMin,
 }
 
-/// A link between two nodes in the network.
+/// Random variation of the link latency.
 #[derive(Copy, Clone, Debug)]
+pub enum LatencyJitter {
+    /// Extra delay uniformly distributed in `[0, max]`.
+    Uniform {
+        /// Maximum extra delay.
+        max: f64,
+    },
+    /// Extra delay exponentially distributed with the given mean.
+    Exponential {
+        /// Mean extra delay.
+        mean: f64,
+    },
+}
+
+impl LatencyJitter {
+    /// Converts a uniform random value from `[0, 1)` into the extra delay.
+    pub fn sample(&self, uniform: f64) -> f64 {
+        match *self {
+            LatencyJitter::Uniform { max } => max * uniform,
+            LatencyJitter::Exponential { mean } => -mean * (1. - uniform).ln(),
+        }
+    }
+}
+
+/// A link between two nodes in the network.
+#[derive(Clone, Debug)]
 pub struct Link {
     /// Link bandwidth.
     pub bandwidth: f64,
     /// Link latency.
     pub latency: f64,
+    /// Random variation of the link latency.
+    pub jitter: Option<LatencyJitter>,
     /// Used bandwidth sharing policy.
     pub sharing_policy: BandwidthSharingPolicy,
+    /// Fraction of packets lost on the link, which have to be retransmitted.
+    pub loss_rate: f64,
+    /// Planned changes of the link bandwidth as (time, bandwidth) pairs ordered by time.
+    pub bandwidth_schedule: Vec<(f64, f64)>,
 }
 
 impl Link {
@@ -29,7 +68,10 @@ impl Link {
         Self {
             bandwidth,
             latency,
+            jitter: None,
             sharing_policy: BandwidthSharingPolicy::Shared,
+            loss_rate: 0.,
+            bandwidth_schedule: Vec::new(),
         }
     }
 
@@ -38,7 +80,52 @@ impl Link {
         Self {
             bandwidth,
             latency,
+            jitter: None,
             sharing_policy: BandwidthSharingPolicy::NonShared,
+            loss_rate: 0.,
+            bandwidth_schedule: Vec::new(),
         }
     }
+
+    /// Sets the link latency (propagation delay).
+    pub fn with_latency(mut self, latency: f64) -> Self {
+        assert!(latency >= 0., "Link latency must be >= 0");
+        self.latency = latency;
+        self
+    }
+
+    /// Sets the random variation of the link latency.
+    ///
+    /// The jitter is sampled independently for each transfer or message using the link, and is added to the latency
+    /// of the link. The throughput of the transfers is not affected. The routing uses the latency without jitter.
+    pub fn with_jitter(mut self, jitter: LatencyJitter) -> Self {
+        self.jitter = Some(jitter);
+        self
+    }
+
+    /// Sets the fraction of packets lost on the link.
+    ///
+    /// Lost packets are retransmitted by the transport layer, so the loss doesn't fail the transfers
+    /// but slows them down: each packet is sent `1 / (1 - loss_rate)` times on average, and each loss
+    /// costs an extra round trip before the packet is retransmitted.
+    pub fn set_loss_rate(&mut self, loss_rate: f64) {
+        assert!((0. ..1.).contains(&loss_rate), "Loss rate must be in [0, 1)");
+        self.loss_rate = loss_rate;
+    }
+
+    /// Sets the schedule of link bandwidth changes as (time, bandwidth) pairs ordered by time.
+    ///
+    /// Allows to model the links with time-varying capacity, e.g. diurnal WAN capacity or maintenance windows.
+    /// Each change affects both the transfers in progress and the future ones.
+    pub fn set_bandwidth_schedule(&mut self, schedule: Vec<(f64, f64)>) {
+        assert!(
+            schedule.windows(2).all(|w| w[0].0 <= w[1].0),
+            "Bandwidth schedule must be ordered by time"
+        );
+        assert!(
+            schedule.iter().all(|&(_, bandwidth)| bandwidth > 0.),
+            "Link bandwidth must be > 0"
+        );
+        self.bandwidth_schedule = schedule;
+    }
 }
@@ -24,6 +24,14 @@ pub struct DataTransfer {
     pub size: f64,
     /// Simulation component to notify when the transfer is completed.
     pub notification_dst: Id,
+    /// Weight of the transfer in bandwidth sharing (1 by default).
+    ///
+    /// Used with [`BandwidthSharingPolicy::WeightedMaxMin`](crate::BandwidthSharingPolicy::WeightedMaxMin).
+    pub weight: f64,
+    /// Maximum transfer rate, if limited.
+    ///
+    /// Used with [`BandwidthSharingPolicy::WeightedMaxMin`](crate::BandwidthSharingPolicy::WeightedMaxMin).
+    pub max_rate: Option<f64>,
 }
 
 /// Event signalling the completion of data transfer.
@@ -33,6 +41,109 @@ pub struct DataTransferCompleted {
     pub dt: DataTransfer,
 }
 
+/// Progress of a data transfer processed by a network model, see [`NetworkModel::active_transfers`].
+#[derive(Clone, Debug, Serialize)]
+pub struct TransferProgress {
+    /// Data transfer.
+    pub dt: DataTransfer,
+    /// Amount of data transferred so far.
+    pub transferred: f64,
+    /// Current transfer rate.
+    pub rate: f64,
+    /// Estimated completion time assuming the current rate, or `None` if the transfer is paused
+    /// or its rate is not known yet.
+    pub estimated_finish: Option<f64>,
+    /// Time at which the progress is reported.
+    pub time: f64,
+}
+
+impl TransferProgress {
+    pub(crate) fn new(dt: DataTransfer, transferred: f64, rate: f64, time: f64) -> Self {
+        let transferred = transferred.min(dt.size);
+        let estimated_finish = if transferred >= dt.size {
+            Some(time)
+        } else if rate > 0. {
+            Some(time + (dt.size - transferred) / rate)
+        } else {
+            None
+        };
+        Self {
+            dt,
+            transferred,
+            rate,
+            estimated_finish,
+            time,
+        }
+    }
+
+    /// Returns the progress at the given time assuming the transfer continues at the current rate.
+    pub fn at(&self, time: f64) -> Self {
+        Self::new(
+            self.dt.clone(),
+            self.transferred + self.rate * (time - self.time),
+            self.rate,
+            time,
+        )
+    }
+}
+
+/// Event signalling that data transfer is aborted due to a failure of link or node on its path.
+#[derive(Clone, Serialize)]
+pub struct DataTransferFailed {
+    /// Failed data transfer.
+    pub dt: DataTransfer,
+}
+
+/// Defines what happens with the data transfers whose path becomes unavailable due to link or node failures.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TransferFailurePolicy {
+    /// The transfers are aborted and [`DataTransferFailed`] event is emitted.
+    #[default]
+    Abort,
+    /// The transfers are paused until the failed links are recovered.
+    /// Topology-aware models may also reroute the transfers if there is another path.
+    Pause,
+}
+
+/// Part of a data transfer sent over the network by packet-level models.
+#[derive(Clone, Debug, Serialize)]
+pub struct Packet {
+    /// Id of the data transfer.
+    pub transfer_id: usize,
+    /// Sequence number of the packet in the transfer.
+    pub seq: usize,
+    /// Packet size.
+    pub size: f64,
+    /// Node where the packet is located.
+    pub node: NodeId,
+    /// Number of links on the transfer path passed by the packet.
+    pub hop: usize,
+    /// Whether the packet was dropped and is returned to the source node for retransmission.
+    pub retransmitted: bool,
+}
+
+/// Event signalling the arrival of a packet at the next node of its path.
+#[derive(Clone, Serialize)]
+pub struct PacketDelivered {
+    /// Delivered packet.
+    pub packet: Packet,
+}
+
+/// Event signalling that the data of multicast transfer is sent over the whole multicast tree.
+///
+/// Emitted by the models supporting multicast, see [`NetworkModel::start_multicast`].
+#[derive(Clone, Serialize)]
+pub struct MulticastCompleted {
+    /// Id of the multicast transfer, which is equal to the id of its first data transfer.
+    pub id: usize,
+}
+
+/// Event signalling the periodic update of network model state.
+///
+/// Emitted by the models which update their state periodically, see [`NetworkModel::on_tick`].
+#[derive(Clone, Serialize)]
+pub struct ModelTick {}
+
 /// Network model interface.
 ///
 /// The main functions of the network model:
@@ -51,6 +162,14 @@ pub trait NetworkModel {
     /// Returns the network latency from node `src` to node `dst`.
     fn latency(&self, src: NodeId, dst: NodeId) -> f64;
 
+    /// Returns a sample of the network latency from node `src` to node `dst` for a single transfer or message.
+    ///
+    /// Differs from [`Self::latency`] for the models with random latency variation,
+    /// which must use the passed simulation context as a source of randomness.
+    fn sample_latency(&self, src: NodeId, dst: NodeId, _ctx: &SimulationContext) -> f64 {
+        self.latency(src, dst)
+    }
+
     /// Starts data transfer.
     ///
     /// Must calculate the transfer completion time and emit the [`DataTransferCompleted`] event at this time.
@@ -62,6 +181,58 @@ pub trait NetworkModel {
     /// This is necessary since the model itself does not receive the [`DataTransferCompleted`] event.
     fn on_transfer_completion(&mut self, dt: DataTransfer, ctx: &mut SimulationContext);
 
+    /// Returns the progress of data transfers processed by the model.
+    ///
+    /// The progress of each transfer is reported at the time of the last update of its rate,
+    /// so the current progress is obtained with [`TransferProgress::at`].
+    /// Includes the transfers paused due to failures, but not the transfers which are not yet passed to the model
+    /// (see [`Network::transfer_data`](crate::Network::transfer_data)).
+    ///
+    /// The default implementation reports no transfers.
+    fn active_transfers(&self) -> Vec<TransferProgress> {
+        Vec::new()
+    }
+
+    /// Returns true if the model supports multicast transfers via [`Self::start_multicast`].
+    fn supports_multicast(&self) -> bool {
+        false
+    }
+
+    /// Starts multicast transfer of the same data from a single source node to multiple destination nodes.
+    ///
+    /// The passed data transfers (one per destination) have the same source and size. The model must emit
+    /// the [`DataTransferCompleted`] event for each of them via [`SimulationContext::emit_self`], possibly after
+    /// emitting the [`MulticastCompleted`] event. Note that [`Self::on_transfer_completion`] is called for each
+    /// of these transfers.
+    ///
+    /// Must be implemented for models supporting multicast.
+    fn start_multicast(&mut self, _dts: Vec<DataTransfer>, _ctx: &mut SimulationContext) {
+        panic!("This model doesn't support multicast")
+    }
+
+    /// Callback for notifying the model about the multicast transfer progress signalled with
+    /// [`MulticastCompleted`] event.
+    ///
+    /// Must be implemented for models emitting such events.
+    fn on_multicast_completion(&mut self, _id: usize, _ctx: &mut SimulationContext) {
+        panic!("This model doesn't support multicast")
+    }
+
+    /// Callback for notifying packet-level model about the delivery of a packet emitted
+    /// as a [`PacketDelivered`] event.
+    ///
+    /// Must be implemented for models emitting such events.
+    fn on_packet_delivery(&mut self, _packet: Packet, _ctx: &mut SimulationContext) {
+        panic!("This model doesn't support packets")
+    }
+
+    /// Callback for notifying the model about the [`ModelTick`] event.
+    ///
+    /// Must be implemented for models emitting such events.
+    fn on_tick(&mut self, _ctx: &mut SimulationContext) {
+        panic!("This model doesn't use ticks")
+    }
+
     /// Returns a reference to inner network topology.
     ///
     /// Must be implemented for topology-aware model.
@@ -86,6 +257,10 @@ pub trait NetworkModel {
 
     /// Callback for notifying topology-aware model about the topology change.
     ///
+    /// Topology changes include failures and recoveries of links and nodes (see [`Topology::is_link_up`]).
+    /// The transfers in progress which use the failed links must be handled according to the model's
+    /// [`TransferFailurePolicy`], with [`DataTransferFailed`] events emitted via [`SimulationContext::emit_self`].
+    ///
     /// Must be implemented for topology-aware model.
     fn on_topology_change(&mut self, _ctx: &mut SimulationContext) {
         assert!(
@@ -0,0 +1,298 @@
+//! Topology-aware network model with AIMD congestion control.
+
+use std::collections::BTreeMap;
+
+use simcore::context::SimulationContext;
+use simcore::log_debug;
+
+use crate::routing::{RoutingAlgorithm, ShortestPathFloydWarshall};
+use crate::{
+    BandwidthSharingPolicy, DataTransfer, DataTransferCompleted, DataTransferFailed, LinkId, ModelTick, NetworkModel,
+    NodeId, Topology, TransferFailurePolicy, TransferProgress,
+};
+
+struct AdaptiveTransfer {
+    dt: DataTransfer,
+    path: Vec<LinkId>,
+    size_left: f64,
+    /// Sending rate controlled by AIMD.
+    rate: f64,
+    /// Actual transfer rate, which is lower than the sending rate if some link on the path is overloaded.
+    throughput: f64,
+}
+
+/// Topology-aware model where the transfer rates are controlled by the additive increase/multiplicative decrease
+/// (AIMD) algorithm, which approximates the behavior of TCP congestion control without simulating the packets.
+///
+/// The model uses information about the network [`Topology`] and relies on [`RoutingAlgorithm`] to compute
+/// the transfer paths. Each transfer starts with the sending rate equal to the additive increase value.
+/// The rates are updated periodically: if some link on the transfer path is saturated, i.e. the total sending
+/// rate of its transfers exceeds the link bandwidth, the transfer rate is multiplied by the decrease factor,
+/// otherwise the rate is increased by the additive increase value. When a link is overloaded, the actual
+/// throughput of its transfers is reduced proportionally to fit the link bandwidth.
+///
+/// Over time the rates of transfers sharing a bottleneck link converge to the fair share oscillating around it,
+/// which allows to study the convergence time and fairness between long and short transfers.
+///
+/// When links or nodes fail, the transfers using them are handled according to [`TransferFailurePolicy`].
+/// Paused transfers are rerouted if there is another path, otherwise they wait until a path is available again.
+pub struct AdaptiveBandwidthNetworkModel {
+    topology: Topology,
+    routing: Box<dyn RoutingAlgorithm>,
+    tick_interval: f64,
+    additive_increase: f64,
+    multiplicative_decrease: f64,
+    transfers: BTreeMap<usize, AdaptiveTransfer>,
+    paused_transfers: BTreeMap<usize, AdaptiveTransfer>,
+    failure_policy: TransferFailurePolicy,
+    last_update_time: f64,
+    next_event: Option<u64>,
+    tick_scheduled: bool,
+}
+
+impl AdaptiveBandwidthNetworkModel {
+    /// Creates a new network model with empty topology.
+    ///
+    /// The transfer rates are updated every `tick_interval` by adding `additive_increase` or multiplying by
+    /// `multiplicative_decrease` from `(0, 1)`.
+    ///
+    /// Uses [`ShortestPathFloydWarshall`] as default routing algorithm.
+    pub fn new(tick_interval: f64, additive_increase: f64, multiplicative_decrease: f64) -> Self {
+        assert!(tick_interval > 0., "Tick interval must be > 0");
+        assert!(additive_increase > 0., "Additive increase must be > 0");
+        assert!(
+            multiplicative_decrease > 0. && multiplicative_decrease < 1.,
+            "Multiplicative decrease must be in (0, 1)"
+        );
+        Self {
+            topology: Topology::default(),
+            routing: Box::<ShortestPathFloydWarshall>::default(),
+            tick_interval,
+            additive_increase,
+            multiplicative_decrease,
+            transfers: BTreeMap::new(),
+            paused_transfers: BTreeMap::new(),
+            failure_policy: TransferFailurePolicy::default(),
+            last_update_time: 0.,
+            next_event: None,
+            tick_scheduled: false,
+        }
+    }
+
+    /// Sets the used routing algorithm.
+    pub fn with_routing(mut self, routing: Box<dyn RoutingAlgorithm>) -> Self {
+        self.routing = routing;
+        self
+    }
+
+    /// Sets the policy for handling the transfers affected by link or node failures.
+    pub fn with_failure_policy(mut self, failure_policy: TransferFailurePolicy) -> Self {
+        self.failure_policy = failure_policy;
+        self
+    }
+
+    /// Updates the amount of data left for each transfer.
+    fn update_progress(&mut self, time: f64) {
+        let elapsed = time - self.last_update_time;
+        for transfer in self.transfers.values_mut() {
+            transfer.size_left = (transfer.size_left - transfer.throughput * elapsed).max(0.);
+        }
+        self.last_update_time = time;
+    }
+
+    /// Returns for each link the ratio of the total sending rate of its transfers to the link bandwidth.
+    fn get_link_loads(&self) -> Vec<f64> {
+        let mut loads = vec![0.; self.topology.link_count()];
+        for transfer in self.transfers.values() {
+            for &link_id in transfer.path.iter() {
+                let link = self.topology.link(link_id);
+                let load = transfer.rate / link.bandwidth;
+                loads[link_id] = match link.sharing_policy {
+                    BandwidthSharingPolicy::Shared | BandwidthSharingPolicy::WeightedMaxMin => loads[link_id] + load,
+                    BandwidthSharingPolicy::NonShared => loads[link_id].max(load),
+                };
+            }
+        }
+        loads
+    }
+
+    /// Recalculates the transfer throughputs from their sending rates.
+    fn update_throughput(&mut self) {
+        let loads = self.get_link_loads();
+        for transfer in self.transfers.values_mut() {
+            let max_load = transfer.path.iter().map(|&link_id| loads[link_id]).fold(1., f64::max);
+            transfer.throughput = transfer.rate / max_load;
+        }
+    }
+
+    /// Schedules the completion of the transfer which finishes first and the next rate update.
+    fn update_next_event(&mut self, ctx: &mut SimulationContext) {
+        if let Some(event_id) = self.next_event.take() {
+            ctx.cancel_event(event_id);
+        }
+        if let Some(transfer) = self
+            .transfers
+            .values()
+            .min_by(|a, b| (a.size_left / a.throughput).total_cmp(&(b.size_left / b.throughput)))
+        {
+            self.next_event = Some(ctx.emit_self(
+                DataTransferCompleted {
+                    dt: transfer.dt.clone(),
+                },
+                transfer.size_left / transfer.throughput,
+            ));
+            if !self.tick_scheduled {
+                ctx.emit_self(ModelTick {}, self.tick_interval);
+                self.tick_scheduled = true;
+            }
+        }
+    }
+
+    /// Starts the transfer along the path given by routing, aborts or pauses it if there is no path due to failures.
+    fn route(&mut self, transfer: AdaptiveTransfer, ctx: &mut SimulationContext) {
+        let dt = &transfer.dt;
+        match self
+            .routing
+            .get_transfer_path(dt.src_node_id, dt.dst_node_id, dt.id, &self.topology)
+        {
+            Some(path) => {
+                self.transfers.insert(dt.id, AdaptiveTransfer { path, ..transfer });
+            }
+            None if self.topology.has_failures() => match self.failure_policy {
+                TransferFailurePolicy::Abort => {
+                    ctx.emit_self_now(DataTransferFailed { dt: transfer.dt });
+                }
+                TransferFailurePolicy::Pause => {
+                    self.paused_transfers.insert(dt.id, transfer);
+                }
+            },
+            None => panic!("No path from {} to {}", dt.src_node_id, dt.dst_node_id),
+        }
+    }
+}
+
+impl NetworkModel for AdaptiveBandwidthNetworkModel {
+    fn is_topology_aware(&self) -> bool {
+        true
+    }
+
+    fn bandwidth(&self, src: NodeId, dst: NodeId) -> f64 {
+        let path = self
+            .routing
+            .get_path_iter(src, dst, &self.topology)
+            .unwrap_or_else(|| panic!("No path from {} to {}", src, dst));
+        self.topology.get_path_bandwidth(path)
+    }
+
+    fn latency(&self, src: NodeId, dst: NodeId) -> f64 {
+        let path = self
+            .routing
+            .get_path_iter(src, dst, &self.topology)
+            .unwrap_or_else(|| panic!("No path from {} to {}", src, dst));
+        self.topology.get_path_latency(path)
+    }
+
+    fn sample_latency(&self, src: NodeId, dst: NodeId, ctx: &SimulationContext) -> f64 {
+        let path = self
+            .routing
+            .get_path_iter(src, dst, &self.topology)
+            .unwrap_or_else(|| panic!("No path from {} to {}", src, dst));
+        self.topology.sample_path_latency(path, || ctx.rand())
+    }
+
+    fn start_transfer(&mut self, dt: DataTransfer, ctx: &mut SimulationContext) {
+        assert!(!self.transfers.contains_key(&dt.id) && !self.paused_transfers.contains_key(&dt.id));
+        self.update_progress(ctx.time());
+        let size = dt.size;
+        let transfer = AdaptiveTransfer {
+            dt,
+            path: Vec::new(),
+            size_left: size,
+            rate: self.additive_increase,
+            throughput: 0.,
+        };
+        self.route(transfer, ctx);
+        self.update_throughput();
+        self.update_next_event(ctx);
+    }
+
+    fn on_transfer_completion(&mut self, dt: DataTransfer, ctx: &mut SimulationContext) {
+        self.next_event = None;
+        self.update_progress(ctx.time());
+        self.transfers.remove(&dt.id);
+        self.update_throughput();
+        self.update_next_event(ctx);
+    }
+
+    fn active_transfers(&self) -> Vec<TransferProgress> {
+        self.transfers
+            .values()
+            .chain(self.paused_transfers.values())
+            .map(|transfer| {
+                TransferProgress::new(
+                    transfer.dt.clone(),
+                    transfer.dt.size - transfer.size_left,
+                    transfer.throughput,
+                    self.last_update_time,
+                )
+            })
+            .collect()
+    }
+
+    fn on_tick(&mut self, ctx: &mut SimulationContext) {
+        self.tick_scheduled = false;
+        self.update_progress(ctx.time());
+        let loads = self.get_link_loads();
+        for transfer in self.transfers.values_mut() {
+            if transfer.path.iter().any(|&link_id| loads[link_id] > 1.) {
+                transfer.rate *= self.multiplicative_decrease;
+            } else {
+                transfer.rate += self.additive_increase;
+            }
+        }
+        self.update_throughput();
+        self.update_next_event(ctx);
+    }
+
+    fn topology(&self) -> Option<&Topology> {
+        Some(&self.topology)
+    }
+
+    fn topology_mut(&mut self) -> Option<&mut Topology> {
+        Some(&mut self.topology)
+    }
+
+    fn on_topology_change(&mut self, ctx: &mut SimulationContext) {
+        self.routing.init(&self.topology);
+        self.update_progress(ctx.time());
+
+        let failed_transfers = self
+            .transfers
+            .iter()
+            .filter(|(_, transfer)| transfer.path.iter().any(|&link| !self.topology.is_link_up(link)))
+            .map(|(&id, _)| id)
+            .collect::<Vec<_>>();
+        for id in failed_transfers {
+            let mut transfer = self.transfers.remove(&id).unwrap();
+            match self.failure_policy {
+                TransferFailurePolicy::Abort => {
+                    log_debug!(ctx, "data transfer {} is aborted due to failure", id);
+                    ctx.emit_self_now(DataTransferFailed { dt: transfer.dt });
+                }
+                TransferFailurePolicy::Pause => {
+                    log_debug!(ctx, "data transfer {} is paused due to failure", id);
+                    // the rate control is restarted after resuming
+                    transfer.rate = self.additive_increase;
+                    transfer.throughput = 0.;
+                    self.paused_transfers.insert(id, transfer);
+                }
+            }
+        }
+        for (_, transfer) in std::mem::take(&mut self.paused_transfers) {
+            self.route(transfer, ctx);
+        }
+
+        self.update_throughput();
+        self.update_next_event(ctx);
+    }
+}
@@ -1,19 +1,26 @@
 //! Network model without congestion where each transfer gets the full bandwidth.
 
+use std::collections::BTreeMap;
+
 use simcore::context::SimulationContext;
 
-use crate::{DataTransfer, DataTransferCompleted, NetworkModel, NodeId};
+use crate::{DataTransfer, DataTransferCompleted, NetworkModel, NodeId, TransferProgress};
 
 /// Network model without congestion where each transfer gets the full bandwidth.
 pub struct ConstantBandwidthNetworkModel {
     bandwidth: f64,
     latency: f64,
+    transfers: BTreeMap<usize, (DataTransfer, f64)>,
 }
 
 impl ConstantBandwidthNetworkModel {
     /// Creates a new network model with specified bandwidth and latency.
     pub fn new(bandwidth: f64, latency: f64) -> ConstantBandwidthNetworkModel {
-        ConstantBandwidthNetworkModel { bandwidth, latency }
+        ConstantBandwidthNetworkModel {
+            bandwidth,
+            latency,
+            transfers: BTreeMap::new(),
+        }
     }
 }
 
@@ -32,8 +39,18 @@ impl NetworkModel for ConstantBandwidthNetworkModel {
 
     fn start_transfer(&mut self, dt: DataTransfer, ctx: &mut SimulationContext) {
         let data_transfer_time = dt.size / self.bandwidth;
+        self.transfers.insert(dt.id, (dt.clone(), ctx.time()));
         ctx.emit_self(DataTransferCompleted { dt }, data_transfer_time);
     }
 
-    fn on_transfer_completion(&mut self, _dt: DataTransfer, _ctx: &mut SimulationContext) {}
+    fn on_transfer_completion(&mut self, dt: DataTransfer, _ctx: &mut SimulationContext) {
+        self.transfers.remove(&dt.id);
+    }
+
+    fn active_transfers(&self) -> Vec<TransferProgress> {
+        self.transfers
+            .values()
+            .map(|(dt, start_time)| TransferProgress::new(dt.clone(), 0., self.bandwidth, *start_time))
+            .collect()
+    }
 }
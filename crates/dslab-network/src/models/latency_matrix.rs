@@ -0,0 +1,101 @@
+//! Topology-unaware network model with heterogeneous point-to-point latency and bandwidth.
+
+use std::collections::{BTreeMap, HashMap};
+
+use simcore::context::SimulationContext;
+
+use crate::{DataTransfer, DataTransferCompleted, NetworkModel, NodeId, TransferProgress};
+
+/// Network model without congestion where the latency and bandwidth between each pair of nodes are specified
+/// explicitly, e.g. using the measured values.
+///
+/// The values are stored as N×N matrices indexed by node ids. Missing entries use the default values.
+/// Each transfer gets the full bandwidth between its nodes.
+pub struct LatencyMatrixNetworkModel {
+    default_bandwidth: f64,
+    default_latency: f64,
+    bandwidth: HashMap<(NodeId, NodeId), f64>,
+    latency: HashMap<(NodeId, NodeId), f64>,
+    transfers: BTreeMap<usize, (DataTransfer, f64)>,
+}
+
+impl LatencyMatrixNetworkModel {
+    /// Creates a new network model with specified default bandwidth and latency.
+    pub fn new(default_bandwidth: f64, default_latency: f64) -> LatencyMatrixNetworkModel {
+        LatencyMatrixNetworkModel {
+            default_bandwidth,
+            default_latency,
+            bandwidth: HashMap::new(),
+            latency: HashMap::new(),
+            transfers: BTreeMap::new(),
+        }
+    }
+
+    /// Sets latencies from matrix, where `matrix[src][dst]` is the latency from node `src` to node `dst`.
+    ///
+    /// Rows can be shorter than the number of nodes, the missing entries use the default latency.
+    pub fn with_latency_matrix(mut self, matrix: Vec<Vec<f64>>) -> Self {
+        for (src, row) in matrix.into_iter().enumerate() {
+            for (dst, latency) in row.into_iter().enumerate() {
+                self.latency.insert((src, dst), latency);
+            }
+        }
+        self
+    }
+
+    /// Sets bandwidths from matrix, where `matrix[src][dst]` is the bandwidth from node `src` to node `dst`.
+    ///
+    /// Rows can be shorter than the number of nodes, the missing entries use the default bandwidth.
+    pub fn with_bandwidth_matrix(mut self, matrix: Vec<Vec<f64>>) -> Self {
+        for (src, row) in matrix.into_iter().enumerate() {
+            for (dst, bandwidth) in row.into_iter().enumerate() {
+                self.bandwidth.insert((src, dst), bandwidth);
+            }
+        }
+        self
+    }
+
+    /// Sets the latency from node `src` to node `dst`.
+    pub fn set_latency(&mut self, src: NodeId, dst: NodeId, latency: f64) {
+        self.latency.insert((src, dst), latency);
+    }
+
+    /// Sets the bandwidth from node `src` to node `dst`.
+    pub fn set_bandwidth(&mut self, src: NodeId, dst: NodeId, bandwidth: f64) {
+        self.bandwidth.insert((src, dst), bandwidth);
+    }
+}
+
+impl NetworkModel for LatencyMatrixNetworkModel {
+    fn is_topology_aware(&self) -> bool {
+        false
+    }
+
+    fn bandwidth(&self, src: NodeId, dst: NodeId) -> f64 {
+        *self.bandwidth.get(&(src, dst)).unwrap_or(&self.default_bandwidth)
+    }
+
+    fn latency(&self, src: NodeId, dst: NodeId) -> f64 {
+        *self.latency.get(&(src, dst)).unwrap_or(&self.default_latency)
+    }
+
+    fn start_transfer(&mut self, dt: DataTransfer, ctx: &mut SimulationContext) {
+        let data_transfer_time = dt.size / self.bandwidth(dt.src_node_id, dt.dst_node_id);
+        self.transfers.insert(dt.id, (dt.clone(), ctx.time()));
+        ctx.emit_self(DataTransferCompleted { dt }, data_transfer_time);
+    }
+
+    fn on_transfer_completion(&mut self, dt: DataTransfer, _ctx: &mut SimulationContext) {
+        self.transfers.remove(&dt.id);
+    }
+
+    fn active_transfers(&self) -> Vec<TransferProgress> {
+        self.transfers
+            .values()
+            .map(|(dt, start_time)| {
+                let bandwidth = self.bandwidth(dt.src_node_id, dt.dst_node_id);
+                TransferProgress::new(dt.clone(), 0., bandwidth, *start_time)
+            })
+            .collect()
+    }
+}
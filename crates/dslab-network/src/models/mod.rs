@@ -1,9 +1,15 @@
 //! Network model implementations.
 
+pub mod adaptive;
 pub mod constant;
+pub mod latency_matrix;
+pub mod packet_switched;
 pub mod shared;
 pub mod topology_aware;
 
+pub use adaptive::AdaptiveBandwidthNetworkModel;
 pub use constant::ConstantBandwidthNetworkModel;
+pub use latency_matrix::LatencyMatrixNetworkModel;
+pub use packet_switched::PacketSwitchedNetworkModel;
 pub use shared::SharedBandwidthNetworkModel;
 pub use topology_aware::TopologyAwareNetworkModel;
@@ -0,0 +1,333 @@
+//! Topology-aware packet-level network model.
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use simcore::context::SimulationContext;
+use simcore::log_debug;
+
+use crate::routing::{RoutingAlgorithm, ShortestPathFloydWarshall};
+use crate::{
+    DataTransfer, DataTransferCompleted, DataTransferFailed, LinkId, NetworkModel, NodeId, Packet, PacketDelivered,
+    Topology, TransferFailurePolicy, TransferProgress,
+};
+
+struct PacketTransfer {
+    dt: DataTransfer,
+    path: Vec<LinkId>,
+    packets_left: usize,
+    delivered: f64,
+    start_time: f64,
+    last_delivery_time: f64,
+}
+
+/// Output queue of a link.
+#[derive(Clone, Default)]
+struct LinkQueue {
+    /// Time when the link finishes sending the queued packets.
+    busy_until: f64,
+    /// Start times of sending the queued packets.
+    send_times: VecDeque<f64>,
+    /// Packets which are queued or sent over the link, as (transfer id, sequence number) pairs.
+    in_flight: BTreeSet<(usize, usize)>,
+}
+
+impl LinkQueue {
+    /// Returns the number of packets waiting in the queue at given time.
+    fn len(&mut self, time: f64) -> usize {
+        while self.send_times.front().is_some_and(|&t| t <= time) {
+            self.send_times.pop_front();
+        }
+        self.send_times.len()
+    }
+}
+
+/// Topology-aware model which splits each data transfer into packets and simulates their store-and-forward
+/// transmission along the path computed by [`RoutingAlgorithm`].
+///
+/// The packets have the size of MTU (except the last one) and are sent over each link one after another
+/// at the full link bandwidth, so the transfers sharing a link are interleaved at the packet granularity
+/// and the packets wait in the link queues. Each link has a buffer for at most `buffer_size` waiting packets
+/// at the intermediate nodes. A packet arriving at a full buffer is dropped and retransmitted by the source node
+/// after the round-trip time of the path. The source node buffers are not limited.
+///
+/// The arrival of each packet at the next node of the path is simulated with a [`PacketDelivered`] event.
+/// The [`DataTransferCompleted`] event is emitted when the last packet reaches the destination node.
+/// As with other models, the network latency is added once before the transfer is started,
+/// so the links add only the transmission and queuing delays to the packets.
+///
+/// When links or nodes fail, the transfers using them are handled according to [`TransferFailurePolicy`].
+/// The packets sent over a failed link are lost and sent again when the link is recovered.
+/// The paths of paused transfers are not changed, their packets wait at the failed links until they are recovered.
+pub struct PacketSwitchedNetworkModel {
+    topology: Topology,
+    routing: Box<dyn RoutingAlgorithm>,
+    mtu: f64,
+    buffer_size: usize,
+    transfers: BTreeMap<usize, PacketTransfer>,
+    link_queues: Vec<LinkQueue>,
+    failure_policy: TransferFailurePolicy,
+    /// Packets waiting for the recovery of the next link on their path.
+    held_packets: Vec<Packet>,
+    /// Paused transfers which have no path to start.
+    waiting_transfers: Vec<DataTransfer>,
+}
+
+impl PacketSwitchedNetworkModel {
+    /// Creates a new network model with empty topology, specified MTU and link buffer size (in packets).
+    ///
+    /// Uses [`ShortestPathFloydWarshall`] as default routing algorithm.
+    pub fn new(mtu: f64, buffer_size: usize) -> Self {
+        assert!(mtu > 0., "MTU must be > 0");
+        Self {
+            topology: Topology::default(),
+            routing: Box::<ShortestPathFloydWarshall>::default(),
+            mtu,
+            buffer_size,
+            transfers: BTreeMap::new(),
+            link_queues: Vec::new(),
+            failure_policy: TransferFailurePolicy::default(),
+            held_packets: Vec::new(),
+            waiting_transfers: Vec::new(),
+        }
+    }
+
+    /// Sets the used routing algorithm.
+    pub fn with_routing(mut self, routing: Box<dyn RoutingAlgorithm>) -> Self {
+        self.routing = routing;
+        self
+    }
+
+    /// Sets the policy for handling the transfers affected by link or node failures.
+    pub fn with_failure_policy(mut self, failure_policy: TransferFailurePolicy) -> Self {
+        self.failure_policy = failure_policy;
+        self
+    }
+
+    fn start_packets(&mut self, dt: DataTransfer, path: Vec<LinkId>, ctx: &mut SimulationContext) {
+        let packet_count = ((dt.size / self.mtu).ceil() as usize).max(1);
+        let id = dt.id;
+        let src_node_id = dt.src_node_id;
+        let size = dt.size;
+        self.transfers.insert(
+            id,
+            PacketTransfer {
+                dt,
+                path,
+                packets_left: packet_count,
+                delivered: 0.,
+                start_time: ctx.time(),
+                last_delivery_time: ctx.time(),
+            },
+        );
+        for seq in 0..packet_count {
+            let packet = Packet {
+                transfer_id: id,
+                seq,
+                size: (size - seq as f64 * self.mtu).min(self.mtu),
+                node: src_node_id,
+                hop: 0,
+                retransmitted: false,
+            };
+            self.send_packet(packet, ctx);
+        }
+    }
+
+    /// Puts the packet to the queue of the next link on its path, drops it if the link buffer is full.
+    fn send_packet(&mut self, mut packet: Packet, ctx: &mut SimulationContext) {
+        let transfer = &self.transfers[&packet.transfer_id];
+        let link_id = transfer.path[packet.hop];
+        if !self.topology.is_link_up(link_id) {
+            self.held_packets.push(packet);
+            return;
+        }
+        let link = self.topology.link(link_id);
+        let queue = &mut self.link_queues[link_id];
+        let time = ctx.time();
+        if packet.hop > 0 && queue.len(time) >= self.buffer_size {
+            let path_latency = transfer
+                .path
+                .iter()
+                .map(|&l| self.topology.link(l).latency)
+                .sum::<f64>();
+            log_debug!(
+                ctx,
+                "packet {} of data transfer {} is dropped at node {}",
+                packet.seq,
+                packet.transfer_id,
+                packet.node
+            );
+            packet.node = transfer.dt.src_node_id;
+            packet.hop = 0;
+            packet.retransmitted = true;
+            ctx.emit_self(PacketDelivered { packet }, 2. * path_latency);
+            return;
+        }
+        let send_time = queue.busy_until.max(time);
+        if send_time > time {
+            queue.send_times.push_back(send_time);
+        }
+        queue.busy_until = send_time + packet.size / link.bandwidth;
+        queue.in_flight.insert((packet.transfer_id, packet.seq));
+        let next_node = self.topology.node_links_map()[&packet.node]
+            .iter()
+            .find(|(_, &l)| l == link_id)
+            .map(|(&node, _)| node)
+            .unwrap();
+        packet.node = next_node;
+        packet.hop += 1;
+        packet.retransmitted = false;
+        ctx.emit_self(PacketDelivered { packet }, queue.busy_until - time);
+    }
+}
+
+impl NetworkModel for PacketSwitchedNetworkModel {
+    fn is_topology_aware(&self) -> bool {
+        true
+    }
+
+    fn bandwidth(&self, src: NodeId, dst: NodeId) -> f64 {
+        let path = self
+            .routing
+            .get_path_iter(src, dst, &self.topology)
+            .unwrap_or_else(|| panic!("No path from {} to {}", src, dst));
+        self.topology.get_path_bandwidth(path)
+    }
+
+    fn latency(&self, src: NodeId, dst: NodeId) -> f64 {
+        let path = self
+            .routing
+            .get_path_iter(src, dst, &self.topology)
+            .unwrap_or_else(|| panic!("No path from {} to {}", src, dst));
+        self.topology.get_path_latency(path)
+    }
+
+    fn sample_latency(&self, src: NodeId, dst: NodeId, ctx: &SimulationContext) -> f64 {
+        let path = self
+            .routing
+            .get_path_iter(src, dst, &self.topology)
+            .unwrap_or_else(|| panic!("No path from {} to {}", src, dst));
+        self.topology.sample_path_latency(path, || ctx.rand())
+    }
+
+    fn start_transfer(&mut self, dt: DataTransfer, ctx: &mut SimulationContext) {
+        self.link_queues
+            .resize(self.topology.link_count(), LinkQueue::default());
+        assert!(!self.transfers.contains_key(&dt.id));
+        match self
+            .routing
+            .get_transfer_path(dt.src_node_id, dt.dst_node_id, dt.id, &self.topology)
+        {
+            Some(path) => self.start_packets(dt, path, ctx),
+            None if self.topology.has_failures() => match self.failure_policy {
+                TransferFailurePolicy::Abort => {
+                    ctx.emit_self_now(DataTransferFailed { dt });
+                }
+                TransferFailurePolicy::Pause => {
+                    self.waiting_transfers.push(dt);
+                }
+            },
+            None => panic!("No path from {} to {}", dt.src_node_id, dt.dst_node_id),
+        }
+    }
+
+    fn on_transfer_completion(&mut self, dt: DataTransfer, _ctx: &mut SimulationContext) {
+        self.transfers.remove(&dt.id);
+    }
+
+    fn active_transfers(&self) -> Vec<TransferProgress> {
+        // the rate is estimated as the average rate of data delivery since the transfer start
+        self.transfers
+            .values()
+            .map(|transfer| {
+                let elapsed = transfer.last_delivery_time - transfer.start_time;
+                let rate = if elapsed > 0. { transfer.delivered / elapsed } else { 0. };
+                TransferProgress::new(
+                    transfer.dt.clone(),
+                    transfer.delivered,
+                    rate,
+                    transfer.last_delivery_time,
+                )
+            })
+            .chain(
+                self.waiting_transfers
+                    .iter()
+                    .map(|dt| TransferProgress::new(dt.clone(), 0., 0., 0.)),
+            )
+            .collect()
+    }
+
+    fn on_packet_delivery(&mut self, mut packet: Packet, ctx: &mut SimulationContext) {
+        let Some(transfer) = self.transfers.get_mut(&packet.transfer_id) else {
+            // the transfer is aborted
+            return;
+        };
+        if !packet.retransmitted {
+            let link_id = transfer.path[packet.hop - 1];
+            if !self.link_queues[link_id]
+                .in_flight
+                .remove(&(packet.transfer_id, packet.seq))
+            {
+                // the packet is lost due to the link failure and is sent again by the previous node
+                let (node1, node2) = self.topology.link_nodes(link_id);
+                packet.node = if node2 == packet.node { node1 } else { node2 };
+                packet.hop -= 1;
+                self.send_packet(packet, ctx);
+                return;
+            }
+        }
+        if packet.hop < transfer.path.len() {
+            self.send_packet(packet, ctx);
+            return;
+        }
+        transfer.packets_left -= 1;
+        transfer.delivered += packet.size;
+        transfer.last_delivery_time = ctx.time();
+        if transfer.packets_left == 0 {
+            ctx.emit_self_now(DataTransferCompleted {
+                dt: transfer.dt.clone(),
+            });
+        }
+    }
+
+    fn topology(&self) -> Option<&Topology> {
+        Some(&self.topology)
+    }
+
+    fn topology_mut(&mut self) -> Option<&mut Topology> {
+        Some(&mut self.topology)
+    }
+
+    fn on_topology_change(&mut self, ctx: &mut SimulationContext) {
+        self.routing.init(&self.topology);
+        self.link_queues
+            .resize(self.topology.link_count(), LinkQueue::default());
+        for (link_id, queue) in self.link_queues.iter_mut().enumerate() {
+            if !self.topology.is_link_up(link_id) {
+                *queue = LinkQueue::default();
+            }
+        }
+
+        if self.failure_policy == TransferFailurePolicy::Abort {
+            let failed_transfers = self
+                .transfers
+                .iter()
+                .filter(|(_, transfer)| transfer.path.iter().any(|&link| !self.topology.is_link_up(link)))
+                .map(|(&id, _)| id)
+                .collect::<Vec<_>>();
+            for id in failed_transfers {
+                log_debug!(ctx, "data transfer {} is aborted due to failure", id);
+                let transfer = self.transfers.remove(&id).unwrap();
+                ctx.emit_self_now(DataTransferFailed { dt: transfer.dt });
+            }
+            self.held_packets
+                .retain(|packet| self.transfers.contains_key(&packet.transfer_id));
+        }
+
+        for packet in std::mem::take(&mut self.held_packets) {
+            self.send_packet(packet, ctx);
+        }
+        for dt in std::mem::take(&mut self.waiting_transfers) {
+            self.start_transfer(dt, ctx);
+        }
+    }
+}
@@ -1,15 +1,33 @@
 //! Network model where the bandwidth is shared fairly among all current transfers.
 
+use std::collections::BTreeMap;
+
 use dslab_models::throughput_sharing::{FairThroughputSharingModel, ThroughputSharingModel};
 use simcore::context::SimulationContext;
 
-use crate::{DataTransfer, DataTransferCompleted, NetworkModel, NodeId};
+use crate::{BandwidthSharingPolicy, DataTransfer, DataTransferCompleted, NetworkModel, NodeId, TransferProgress};
+
+struct WeightedTransfer {
+    dt: DataTransfer,
+    size_left: f64,
+    rate: f64,
+}
 
 /// Network model where the bandwidth is shared fairly among all current transfers.
+///
+/// By default the bandwidth is shared equally. With [`BandwidthSharingPolicy::WeightedMaxMin`] policy
+/// the transfer weights and rate limits are taken into account.
 pub struct SharedBandwidthNetworkModel {
     bandwidth: f64,
     latency: f64,
+    sharing_policy: BandwidthSharingPolicy,
     throughput_model: FairThroughputSharingModel<DataTransfer>,
+    // current transfers with equal sharing and the values of fair_share_work at their start
+    fair_transfers: BTreeMap<usize, (DataTransfer, f64)>,
+    // amount of data sent by each transfer with equal sharing since the model creation
+    fair_share_work: f64,
+    weighted_transfers: BTreeMap<usize, WeightedTransfer>,
+    last_update_time: f64,
     next_event: u64,
 }
 
@@ -19,10 +37,82 @@ impl SharedBandwidthNetworkModel {
         SharedBandwidthNetworkModel {
             bandwidth,
             latency,
+            sharing_policy: BandwidthSharingPolicy::Shared,
             throughput_model: FairThroughputSharingModel::with_fixed_throughput(bandwidth),
+            fair_transfers: BTreeMap::new(),
+            fair_share_work: 0.,
+            weighted_transfers: BTreeMap::new(),
+            last_update_time: 0.,
             next_event: 0,
         }
     }
+
+    /// Sets the bandwidth sharing policy, which can be [`BandwidthSharingPolicy::Shared`] (default)
+    /// or [`BandwidthSharingPolicy::WeightedMaxMin`].
+    pub fn with_sharing_policy(mut self, sharing_policy: BandwidthSharingPolicy) -> Self {
+        assert!(
+            !matches!(sharing_policy, BandwidthSharingPolicy::NonShared),
+            "NonShared policy is not supported, use ConstantBandwidthNetworkModel instead"
+        );
+        self.sharing_policy = sharing_policy;
+        self
+    }
+
+    fn is_weighted(&self) -> bool {
+        matches!(self.sharing_policy, BandwidthSharingPolicy::WeightedMaxMin)
+    }
+
+    fn fair_share_rate(&self) -> f64 {
+        self.bandwidth / self.fair_transfers.len() as f64
+    }
+
+    /// Updates the amount of data sent by each transfer with equal sharing.
+    fn update_fair_share_work(&mut self, time: f64) {
+        if !self.fair_transfers.is_empty() {
+            self.fair_share_work += self.fair_share_rate() * (time - self.last_update_time);
+        }
+        self.last_update_time = time;
+    }
+
+    /// Updates the progress of weighted transfers, recalculates their rates and schedules the next completion.
+    fn update_weighted_transfers(&mut self, ctx: &mut SimulationContext) {
+        let elapsed = ctx.time() - self.last_update_time;
+        for transfer in self.weighted_transfers.values_mut() {
+            transfer.size_left = (transfer.size_left - transfer.rate * elapsed).max(0.);
+        }
+        self.last_update_time = ctx.time();
+
+        // weighted max-min fair allocation: the transfers are processed in the order of their limits per unit weight,
+        // the limited transfers get their maximum rate and the rest of bandwidth is shared among the others
+        let mut transfers = self.weighted_transfers.values_mut().collect::<Vec<_>>();
+        transfers.sort_by(|a, b| {
+            let a_limit = a.dt.max_rate.unwrap_or(f64::INFINITY) / a.dt.weight;
+            let b_limit = b.dt.max_rate.unwrap_or(f64::INFINITY) / b.dt.weight;
+            a_limit.total_cmp(&b_limit)
+        });
+        let mut bandwidth_left = self.bandwidth;
+        let mut weight_left = transfers.iter().map(|t| t.dt.weight).sum::<f64>();
+        for transfer in transfers {
+            let share = bandwidth_left * transfer.dt.weight / weight_left;
+            transfer.rate = transfer.dt.max_rate.map_or(share, |max_rate| max_rate.min(share));
+            bandwidth_left = (bandwidth_left - transfer.rate).max(0.);
+            weight_left -= transfer.dt.weight;
+        }
+
+        ctx.cancel_event(self.next_event);
+        if let Some(transfer) = self
+            .weighted_transfers
+            .values()
+            .min_by(|a, b| (a.size_left / a.rate).total_cmp(&(b.size_left / b.rate)))
+        {
+            self.next_event = ctx.emit_self(
+                DataTransferCompleted {
+                    dt: transfer.dt.clone(),
+                },
+                transfer.size_left / transfer.rate,
+            );
+        }
+    }
 }
 
 impl NetworkModel for SharedBandwidthNetworkModel {
@@ -39,7 +129,22 @@ impl NetworkModel for SharedBandwidthNetworkModel {
     }
 
     fn start_transfer(&mut self, dt: DataTransfer, ctx: &mut SimulationContext) {
+        if self.is_weighted() {
+            let size = dt.size;
+            self.weighted_transfers.insert(
+                dt.id,
+                WeightedTransfer {
+                    dt,
+                    size_left: size,
+                    rate: 0.,
+                },
+            );
+            self.update_weighted_transfers(ctx);
+            return;
+        }
         ctx.cancel_event(self.next_event);
+        self.update_fair_share_work(ctx.time());
+        self.fair_transfers.insert(dt.id, (dt.clone(), self.fair_share_work));
         let size = dt.size;
         self.throughput_model.insert(dt, size, ctx);
         if let Some((time, dt)) = self.throughput_model.peek() {
@@ -47,10 +152,43 @@ impl NetworkModel for SharedBandwidthNetworkModel {
         }
     }
 
-    fn on_transfer_completion(&mut self, _dt: DataTransfer, ctx: &mut SimulationContext) {
+    fn on_transfer_completion(&mut self, dt: DataTransfer, ctx: &mut SimulationContext) {
+        if self.is_weighted() {
+            self.weighted_transfers.remove(&dt.id);
+            self.update_weighted_transfers(ctx);
+            return;
+        }
         self.throughput_model.pop().unwrap();
+        self.update_fair_share_work(ctx.time());
+        self.fair_transfers.remove(&dt.id);
         if let Some((time, dt)) = self.throughput_model.peek() {
             self.next_event = ctx.emit_self(DataTransferCompleted { dt: dt.clone() }, time - ctx.time());
         }
     }
+
+    fn active_transfers(&self) -> Vec<TransferProgress> {
+        let time = self.last_update_time;
+        if self.is_weighted() {
+            return self
+                .weighted_transfers
+                .values()
+                .map(|transfer| {
+                    TransferProgress::new(
+                        transfer.dt.clone(),
+                        transfer.dt.size - transfer.size_left,
+                        transfer.rate,
+                        time,
+                    )
+                })
+                .collect();
+        }
+        if self.fair_transfers.is_empty() {
+            return Vec::new();
+        }
+        let rate = self.fair_share_rate();
+        self.fair_transfers
+            .values()
+            .map(|(dt, start_work)| TransferProgress::new(dt.clone(), self.fair_share_work - start_work, rate, time))
+            .collect()
+    }
 }
@@ -1,12 +1,16 @@
 //! Topology-aware network model.
 
 use std::cmp::Ordering;
-use std::collections::{BTreeMap, BinaryHeap, HashSet, VecDeque};
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashSet, VecDeque};
 
 use simcore::context::SimulationContext;
+use simcore::log_debug;
 
 use crate::routing::{RoutingAlgorithm, ShortestPathFloydWarshall};
-use crate::{BandwidthSharingPolicy, DataTransfer, DataTransferCompleted, LinkId, NetworkModel, NodeId, Topology};
+use crate::{
+    BandwidthSharingPolicy, DataTransfer, DataTransferCompleted, DataTransferFailed, LinkId, MulticastCompleted,
+    NetworkModel, NodeId, Topology, TransferFailurePolicy, TransferProgress,
+};
 
 // Link usage ----------------------------------------------------------------------------------------------------------
 
@@ -21,7 +25,9 @@ struct LinkUsage {
 impl LinkUsage {
     fn get_path_bandwidth(&self) -> f64 {
         match self.sharing_policy {
-            BandwidthSharingPolicy::Shared => self.left_bandwidth / self.transfers_count as f64,
+            BandwidthSharingPolicy::Shared | BandwidthSharingPolicy::WeightedMaxMin => {
+                self.left_bandwidth / self.transfers_count as f64
+            }
             BandwidthSharingPolicy::NonShared => self.left_bandwidth,
         }
     }
@@ -63,23 +69,61 @@ impl Eq for LinkUsage {}
 struct TransferInfo {
     dt: DataTransfer,
     path: Vec<LinkId>,
+    loss_rate: f64,
     size_left: f64,
     throughput: f64,
     last_update_time: f64,
+    // data transfers to each destination of multicast transfer and their paths, empty for unicast transfer
+    branches: Vec<DataTransfer>,
+    branch_paths: Vec<Vec<LinkId>>,
 }
 
 impl TransferInfo {
-    fn new(dt: DataTransfer, path: Vec<LinkId>, time: f64) -> TransferInfo {
-        let size = dt.size;
+    fn new(dt: DataTransfer, path: Vec<LinkId>, loss_rate: f64, time: f64) -> TransferInfo {
+        // retransmitted packets occupy the links as well
+        let size = dt.size / (1. - loss_rate);
         TransferInfo {
             dt,
             path,
+            loss_rate,
             size_left: size,
             throughput: 0.0,
             last_update_time: time,
+            branches: Vec::new(),
+            branch_paths: Vec::new(),
+        }
+    }
+
+    fn new_multicast(dts: Vec<DataTransfer>, time: f64) -> TransferInfo {
+        TransferInfo {
+            branches: dts.clone(),
+            ..TransferInfo::new(dts.into_iter().next().unwrap(), Vec::new(), 0., time)
+        }
+    }
+
+    fn is_multicast(&self) -> bool {
+        !self.branches.is_empty()
+    }
+
+    /// Returns the data transfers to all destinations.
+    fn into_transfers(self) -> Vec<DataTransfer> {
+        if self.is_multicast() {
+            self.branches
+        } else {
+            vec![self.dt]
         }
     }
 
+    fn progress(&self, dt: &DataTransfer) -> TransferProgress {
+        let transferred = dt.size - self.size_left * (1. - self.loss_rate);
+        TransferProgress::new(
+            dt.clone(),
+            transferred,
+            self.throughput * (1. - self.loss_rate),
+            self.last_update_time,
+        )
+    }
+
     fn expected_time_left(&self) -> f64 {
         self.size_left / self.throughput
     }
@@ -93,17 +137,34 @@ impl TransferInfo {
 
 /// Topology-aware model which uses information about the network [`Topology`] (links connecting the nodes)
 /// and relies on [`RoutingAlgorithm`] to compute paths between the nodes.
-/// The link's bandwidth is shared fairly among the transfers using the link.  
+/// The link's bandwidth is shared fairly among the transfers using the link.
+///
+/// Packet loss on the links (see [`Link::set_loss_rate`](crate::Link::set_loss_rate)) is handled
+/// by retransmissions: the retransmitted data consumes the link bandwidth, and the expected number of
+/// retransmissions adds extra round trips to the latency.
+///
+/// The latency of each transfer is the sum of latencies of the path links, including their random jitter
+/// (see [`Link::with_jitter`](crate::Link::with_jitter)), which is sampled for each transfer.
+///
+/// When links or nodes fail, the transfers using them are handled according to [`TransferFailurePolicy`].
+/// Paused transfers are rerouted if there is another path, otherwise they wait until a path is available again.
+///
+/// Multicast transfers are sent over a multicast tree formed by the paths to all destinations, which is treated as
+/// a single transfer using each link of the tree once. When the data is sent over the tree, the transfer to each
+/// destination is completed after the latency of its path.
 pub struct TopologyAwareNetworkModel {
     topology: Topology,
     routing: Box<dyn RoutingAlgorithm>,
     current_transfers: BTreeMap<usize, TransferInfo>,
+    paused_transfers: BTreeMap<usize, TransferInfo>,
+    delivering_branches: HashSet<usize>,
     transfers_through_link: Vec<Vec<usize>>,
     tmp_transfers_through_link: Vec<Vec<usize>>,
     next_event: Option<u64>,
     next_event_index: Option<usize>,
     link_data: Vec<Option<LinkUsage>>,
     full_mesh_optimization: bool,
+    failure_policy: TransferFailurePolicy,
 }
 
 #[allow(clippy::derivable_impls)]
@@ -113,12 +174,15 @@ impl Default for TopologyAwareNetworkModel {
             topology: Topology::default(),
             routing: Box::<ShortestPathFloydWarshall>::default(),
             current_transfers: BTreeMap::new(),
+            paused_transfers: BTreeMap::new(),
+            delivering_branches: HashSet::new(),
             transfers_through_link: Vec::new(),
             tmp_transfers_through_link: Vec::new(),
             next_event: None,
             next_event_index: None,
             link_data: Vec::new(),
             full_mesh_optimization: false,
+            failure_policy: TransferFailurePolicy::default(),
         }
     }
 }
@@ -144,6 +208,176 @@ impl TopologyAwareNetworkModel {
         self
     }
 
+    /// Sets the policy for handling the transfers affected by link or node failures.
+    pub fn with_failure_policy(mut self, failure_policy: TransferFailurePolicy) -> Self {
+        self.failure_policy = failure_policy;
+        self
+    }
+
+    /// Aborts or pauses the transfers using failed links and resumes the paused transfers which have a path.
+    fn update_failed_transfers(&mut self, ctx: &mut SimulationContext) {
+        let failed_transfers = self
+            .current_transfers
+            .iter()
+            .filter(|(_, transfer)| transfer.path.iter().any(|&link| !self.topology.is_link_up(link)))
+            .map(|(&id, _)| id)
+            .collect::<Vec<_>>();
+        for id in failed_transfers {
+            let mut transfer = self.current_transfers.remove(&id).unwrap();
+            for &link in transfer.path.iter() {
+                let vec = self.transfers_through_link.get_mut(link).unwrap();
+                vec.remove(vec.iter().position(|&x| x == id).unwrap());
+            }
+            transfer.size_left -= transfer.throughput * (ctx.time() - transfer.last_update_time);
+            transfer.size_left = transfer.size_left.max(0.);
+            transfer.last_update_time = ctx.time();
+            transfer.throughput = 0.;
+            match self.failure_policy {
+                TransferFailurePolicy::Abort => {
+                    log_debug!(ctx, "data transfer {} is aborted due to failure", id);
+                    for dt in transfer.into_transfers() {
+                        ctx.emit_self_now(DataTransferFailed { dt });
+                    }
+                }
+                TransferFailurePolicy::Pause => {
+                    log_debug!(ctx, "data transfer {} is paused due to failure", id);
+                    self.paused_transfers.insert(id, transfer);
+                }
+            }
+        }
+
+        for (id, transfer) in std::mem::take(&mut self.paused_transfers) {
+            let (path, branch_paths) = match self.route(&transfer) {
+                Ok(route) => route,
+                Err(_) => {
+                    self.paused_transfers.insert(id, transfer);
+                    continue;
+                }
+            };
+            log_debug!(ctx, "data transfer {} is resumed", id);
+            let transfer = if transfer.path.is_empty() {
+                // the transfer has not started yet
+                let loss_rate = self.topology.get_path_loss_rate(path.iter().cloned());
+                TransferInfo {
+                    branches: transfer.branches,
+                    branch_paths,
+                    ..TransferInfo::new(transfer.dt, path, loss_rate, ctx.time())
+                }
+            } else {
+                TransferInfo {
+                    path,
+                    branch_paths,
+                    ..transfer
+                }
+            };
+            for &link in transfer.path.iter() {
+                self.transfers_through_link[link].push(id);
+            }
+            self.current_transfers.insert(id, transfer);
+        }
+    }
+
+    /// Returns the path of unicast transfer, or the links of multicast tree and the paths to each destination
+    /// of multicast transfer. If some destination is unreachable, returns it as an error.
+    fn route(&mut self, transfer: &TransferInfo) -> Result<(Vec<LinkId>, Vec<Vec<LinkId>>), NodeId> {
+        let mut branch_paths = Vec::with_capacity(transfer.branches.len());
+        for dt in transfer.branches.iter() {
+            let path = self
+                .routing
+                .get_transfer_path(dt.src_node_id, dt.dst_node_id, dt.id, &self.topology)
+                .ok_or(dt.dst_node_id)?;
+            branch_paths.push(path);
+        }
+        if transfer.is_multicast() {
+            let tree = branch_paths.iter().flatten().cloned().collect::<BTreeSet<_>>();
+            return Ok((tree.into_iter().collect(), branch_paths));
+        }
+        let dt = &transfer.dt;
+        let path = self
+            .routing
+            .get_transfer_path(dt.src_node_id, dt.dst_node_id, dt.id, &self.topology)
+            .ok_or(dt.dst_node_id)?;
+        Ok((path, branch_paths))
+    }
+
+    /// Routes the new transfer and recalculates the throughput of transfers.
+    fn add_transfer(&mut self, transfer: TransferInfo, ctx: &mut SimulationContext) {
+        self.validate_array_lengths();
+        let (path, branch_paths) = match self.route(&transfer) {
+            Ok(route) => route,
+            Err(_) if self.topology.has_failures() => {
+                match self.failure_policy {
+                    TransferFailurePolicy::Abort => {
+                        for dt in transfer.into_transfers() {
+                            ctx.emit_self_now(DataTransferFailed { dt });
+                        }
+                    }
+                    TransferFailurePolicy::Pause => {
+                        self.paused_transfers.insert(transfer.dt.id, transfer);
+                    }
+                }
+                return;
+            }
+            Err(dst) => panic!("No path from {} to {}", transfer.dt.src_node_id, dst),
+        };
+        let id = transfer.dt.id;
+        assert!(!self.current_transfers.contains_key(&id) && !self.paused_transfers.contains_key(&id));
+        for &link in path.iter() {
+            self.transfers_through_link[link].push(id);
+        }
+        let loss_rate = self.topology.get_path_loss_rate(path.iter().cloned());
+        self.current_transfers.insert(
+            id,
+            TransferInfo {
+                branches: transfer.branches,
+                branch_paths,
+                ..TransferInfo::new(transfer.dt, path, loss_rate, ctx.time())
+            },
+        );
+
+        if self.full_mesh_optimization {
+            let affected_transfers = self.get_affected_transfers(id);
+            self.calc(ctx, affected_transfers);
+        } else {
+            self.calc_all(ctx);
+        }
+        self.update_next_event(ctx);
+    }
+
+    /// Removes the completed transfer and recalculates the throughput of remaining transfers.
+    fn remove_completed_transfer(&mut self, ctx: &mut SimulationContext) -> TransferInfo {
+        self.validate_array_lengths();
+        let next_event_index = self.next_event_index.unwrap();
+        let affected_transfers = if self.full_mesh_optimization {
+            let mut transfers = self.get_affected_transfers(next_event_index);
+            assert!(transfers.remove(&next_event_index));
+            transfers
+        } else {
+            HashSet::new()
+        };
+        let transfer = self.current_transfers.remove(&next_event_index).unwrap();
+        for &link in transfer.path.iter() {
+            let vec = self.transfers_through_link.get_mut(link).unwrap();
+            vec.remove(vec.iter().position(|&x| x == next_event_index).unwrap());
+        }
+        self.next_event = None;
+        self.next_event_index = None;
+        if self.full_mesh_optimization {
+            self.calc(ctx, affected_transfers);
+        } else {
+            self.calc_all(ctx);
+        }
+        self.update_next_event(ctx);
+        transfer
+    }
+
+    fn sample_path_latency(&self, path: impl Iterator<Item = LinkId> + Clone, ctx: &SimulationContext) -> f64 {
+        let loss_rate = self.topology.get_path_loss_rate(path.clone());
+        let latency = self.topology.sample_path_latency(path, || ctx.rand());
+        // each loss is detected after a round trip, the expected number of losses is p / (1 - p)
+        latency + 2. * latency * loss_rate / (1. - loss_rate)
+    }
+
     /// Finds the smallest subset of transfers which contains `updated_transfer`
     /// so that the sets of links used by transfers inside and outside this subset don't intersect.
     fn get_affected_transfers(&self, updated_transfer: usize) -> HashSet<usize> {
@@ -187,12 +421,16 @@ impl TopologyAwareNetworkModel {
         if let Some(event_idx) = self.next_event_index {
             let transfer = &self.current_transfers[&event_idx];
             let time = transfer.expected_finish();
-            self.next_event = Some(ctx.emit_self(
-                DataTransferCompleted {
-                    dt: transfer.dt.clone(),
-                },
-                time - ctx.time(),
-            ));
+            self.next_event = Some(if transfer.is_multicast() {
+                ctx.emit_self(MulticastCompleted { id: event_idx }, time - ctx.time())
+            } else {
+                ctx.emit_self(
+                    DataTransferCompleted {
+                        dt: transfer.dt.clone(),
+                    },
+                    time - ctx.time(),
+                )
+            });
         };
     }
 
@@ -295,7 +533,9 @@ impl TopologyAwareNetworkModel {
                         let mut link_usage = self.link_data[link].take().unwrap();
                         link_usage.transfers_count -= 1;
                         match link_usage.sharing_policy {
-                            BandwidthSharingPolicy::Shared => link_usage.left_bandwidth -= bandwidth,
+                            BandwidthSharingPolicy::Shared | BandwidthSharingPolicy::WeightedMaxMin => {
+                                link_usage.left_bandwidth -= bandwidth
+                            }
                             BandwidthSharingPolicy::NonShared => {}
                         }
                         self.link_data[link] = Some(link_usage);
@@ -369,7 +609,9 @@ impl TopologyAwareNetworkModel {
                         let mut link_usage = self.link_data[link].take().unwrap();
                         link_usage.transfers_count -= 1;
                         match link_usage.sharing_policy {
-                            BandwidthSharingPolicy::Shared => link_usage.left_bandwidth -= bandwidth,
+                            BandwidthSharingPolicy::Shared | BandwidthSharingPolicy::WeightedMaxMin => {
+                                link_usage.left_bandwidth -= bandwidth
+                            }
                             BandwidthSharingPolicy::NonShared => {}
                         }
                         self.link_data[link] = Some(link_usage);
@@ -398,7 +640,8 @@ impl NetworkModel for TopologyAwareNetworkModel {
             .routing
             .get_path_iter(src, dst, &self.topology)
             .unwrap_or_else(|| panic!("No path from {} to {}", src, dst));
-        self.topology.get_path_bandwidth(path)
+        let loss_rate = self.topology.get_path_loss_rate(path.clone());
+        self.topology.get_path_bandwidth(path) * (1. - loss_rate)
     }
 
     fn latency(&self, src: NodeId, dst: NodeId) -> f64 {
@@ -406,56 +649,61 @@ impl NetworkModel for TopologyAwareNetworkModel {
             .routing
             .get_path_iter(src, dst, &self.topology)
             .unwrap_or_else(|| panic!("No path from {} to {}", src, dst));
-        self.topology.get_path_latency(path)
+        let loss_rate = self.topology.get_path_loss_rate(path.clone());
+        let latency = self.topology.get_path_latency(path);
+        // each loss is detected after a round trip, the expected number of losses is p / (1 - p)
+        latency + 2. * latency * loss_rate / (1. - loss_rate)
     }
 
-    fn start_transfer(&mut self, dt: DataTransfer, ctx: &mut SimulationContext) {
-        self.validate_array_lengths();
+    fn sample_latency(&self, src: NodeId, dst: NodeId, ctx: &SimulationContext) -> f64 {
         let path = self
             .routing
-            .get_path_iter(dt.src_node_id, dt.dst_node_id, &self.topology)
-            .unwrap_or_else(|| panic!("No path from {} to {}", dt.src_node_id, dt.dst_node_id))
-            .collect::<Vec<_>>();
-        let id = dt.id;
-        assert!(!self.current_transfers.contains_key(&dt.id));
-        for &link in path.iter() {
-            self.transfers_through_link[link].push(id);
+            .get_path_iter(src, dst, &self.topology)
+            .unwrap_or_else(|| panic!("No path from {} to {}", src, dst));
+        self.sample_path_latency(path, ctx)
+    }
+
+    fn start_transfer(&mut self, dt: DataTransfer, ctx: &mut SimulationContext) {
+        self.add_transfer(TransferInfo::new(dt, Vec::new(), 0., ctx.time()), ctx);
+    }
+
+    fn on_transfer_completion(&mut self, dt: DataTransfer, ctx: &mut SimulationContext) {
+        if self.delivering_branches.remove(&dt.id) {
+            return;
         }
+        self.remove_completed_transfer(ctx);
+    }
+
+    fn active_transfers(&self) -> Vec<TransferProgress> {
         self.current_transfers
-            .insert(id, TransferInfo::new(dt, path, ctx.time()));
+            .values()
+            .chain(self.paused_transfers.values())
+            .flat_map(|transfer| {
+                let dts = if transfer.is_multicast() {
+                    transfer.branches.iter().collect()
+                } else {
+                    vec![&transfer.dt]
+                };
+                dts.into_iter().map(|dt| transfer.progress(dt))
+            })
+            .collect()
+    }
 
-        if self.full_mesh_optimization {
-            let affected_transfers = self.get_affected_transfers(id);
-            self.calc(ctx, affected_transfers);
-        } else {
-            self.calc_all(ctx);
-        }
-        self.update_next_event(ctx);
+    fn supports_multicast(&self) -> bool {
+        true
     }
 
-    fn on_transfer_completion(&mut self, _dt: DataTransfer, ctx: &mut SimulationContext) {
-        self.validate_array_lengths();
-        let next_event_index = self.next_event_index.unwrap();
-        let affected_transfers = if self.full_mesh_optimization {
-            let mut transfers = self.get_affected_transfers(next_event_index);
-            assert!(transfers.remove(&next_event_index));
-            transfers
-        } else {
-            HashSet::new()
-        };
-        let transfer = self.current_transfers.remove(&next_event_index).unwrap();
-        for &link in transfer.path.iter() {
-            let vec = self.transfers_through_link.get_mut(link).unwrap();
-            vec.remove(vec.iter().position(|&x| x == next_event_index).unwrap());
-        }
-        self.next_event = None;
-        self.next_event_index = None;
-        if self.full_mesh_optimization {
-            self.calc(ctx, affected_transfers);
-        } else {
-            self.calc_all(ctx);
+    fn start_multicast(&mut self, dts: Vec<DataTransfer>, ctx: &mut SimulationContext) {
+        self.add_transfer(TransferInfo::new_multicast(dts, ctx.time()), ctx);
+    }
+
+    fn on_multicast_completion(&mut self, _id: usize, ctx: &mut SimulationContext) {
+        let transfer = self.remove_completed_transfer(ctx);
+        for (dt, path) in transfer.branches.into_iter().zip(transfer.branch_paths) {
+            let latency = self.sample_path_latency(path.into_iter(), ctx);
+            self.delivering_branches.insert(dt.id);
+            ctx.emit_self(DataTransferCompleted { dt }, latency);
         }
-        self.update_next_event(ctx);
     }
 
     fn topology(&self) -> Option<&Topology> {
@@ -469,6 +717,7 @@ impl NetworkModel for TopologyAwareNetworkModel {
     fn on_topology_change(&mut self, ctx: &mut SimulationContext) {
         self.routing.init(&self.topology);
         self.validate_array_lengths();
+        self.update_failed_transfers(ctx);
         self.calc_all(ctx);
         self.update_next_event(ctx);
     }
@@ -10,9 +10,13 @@ use simcore::component::Id;
 use simcore::context::SimulationContext;
 use simcore::event::{Event, EventData, EventId};
 use simcore::handler::EventHandler;
-use simcore::{cast, log_debug};
+use simcore::{cast, log_debug, log_warn};
 
-use crate::{DataTransfer, DataTransferCompleted, Link, LinkId, NetworkModel, Node, NodeId};
+use crate::traffic::{HotSpots, TrafficMonitor};
+use crate::{
+    DataTransfer, DataTransferCompleted, DataTransferFailed, Link, LinkId, ModelTick, MulticastCompleted, NetworkModel,
+    Node, NodeId, PacketDelivered, Topology, TransferProgress,
+};
 
 /// Represents a message sent between two simulation components over the network.
 #[derive(Clone, Serialize)]
@@ -39,6 +43,31 @@ struct StartDataTransfer {
     dt: DataTransfer,
 }
 
+#[derive(Clone, Serialize)]
+struct StartMulticast {
+    dts: Vec<DataTransfer>,
+}
+
+#[derive(Clone, Serialize)]
+struct LinkBandwidthChange {
+    link_id: LinkId,
+    bandwidth: f64,
+}
+
+/// Event signalling the recovery of a failed link.
+#[derive(Clone, Serialize)]
+pub struct LinkRecovered {
+    /// Recovered link.
+    pub link_id: LinkId,
+}
+
+/// Event signalling the recovery of a failed node.
+#[derive(Clone, Serialize)]
+pub struct NodeRecovered {
+    /// Recovered node.
+    pub node_id: NodeId,
+}
+
 /// Simulation component representing a network.
 ///
 /// This is the main entry point for all network operations, which relies internally on the supplied network model.
@@ -50,6 +79,7 @@ pub struct Network {
     next_dt_id: AtomicUsize,
     next_msg_id: AtomicUsize,
     topology_initialized: bool,
+    traffic: Option<TrafficMonitor>,
     ctx: SimulationContext,
 }
 
@@ -64,6 +94,7 @@ impl Network {
             next_dt_id: AtomicUsize::new(0),
             next_msg_id: AtomicUsize::new(0),
             topology_initialized: false,
+            traffic: None,
             ctx,
         }
     }
@@ -118,6 +149,7 @@ impl Network {
         let node1 = self.get_node_id(node1);
         let node2 = self.get_node_id(node2);
         let link_id = self.network_model.topology_mut().unwrap().add_link(node1, node2, link);
+        self.schedule_bandwidth_changes(link_id);
         if self.topology_initialized {
             self.network_model.on_topology_change(&mut self.ctx);
         }
@@ -137,6 +169,7 @@ impl Network {
             .topology_mut()
             .unwrap()
             .add_unidirectional_link(node_from, node_to, link);
+        self.schedule_bandwidth_changes(link_id);
         if self.topology_initialized {
             self.network_model.on_topology_change(&mut self.ctx);
         }
@@ -158,12 +191,155 @@ impl Network {
             .topology_mut()
             .unwrap()
             .add_full_duplex_link(node1, node2, link);
+        self.schedule_bandwidth_changes(uplink_id);
+        self.schedule_bandwidth_changes(downlink_id);
         if self.topology_initialized {
             self.network_model.on_topology_change(&mut self.ctx);
         }
         (uplink_id, downlink_id)
     }
 
+    /// Adds the nodes and links of the topology, e.g. loaded with [`parsers`](crate::topology::parsers).
+    ///
+    /// The nodes are added with their names, and their intra-node communications are modeled with the models returned
+    /// by `local_model` for each node name.
+    pub fn add_topology<F>(&mut self, topology: &Topology, local_model: F)
+    where
+        F: Fn(&str) -> Box<dyn NetworkModel>,
+    {
+        for node_id in 0..topology.node_count() {
+            let name = &topology.node(node_id).name;
+            self.add_node(name, local_model(name));
+        }
+        for link_id in 0..topology.link_count() {
+            let (node1, node2) = topology.link_nodes(link_id);
+            let node1 = &topology.node(node1).name;
+            let node2 = &topology.node(node2).name;
+            let link = topology.link(link_id).clone();
+            if topology.is_link_bidirectional(link_id) {
+                self.add_link(node1, node2, link);
+            } else {
+                self.add_unidirectional_link(node1, node2, link);
+            }
+        }
+    }
+
+    fn schedule_bandwidth_changes(&mut self, link_id: LinkId) {
+        let schedule = self
+            .network_model
+            .topology()
+            .unwrap()
+            .link(link_id)
+            .bandwidth_schedule
+            .clone();
+        for (time, bandwidth) in schedule {
+            self.ctx.emit_self(
+                LinkBandwidthChange { link_id, bandwidth },
+                (time - self.ctx.time()).max(0.),
+            );
+        }
+    }
+
+    fn on_link_bandwidth_change(&mut self, link_id: LinkId, bandwidth: f64) {
+        log_debug!(self.ctx, "bandwidth of link {} changed to {}", link_id, bandwidth);
+        self.network_model
+            .topology_mut()
+            .unwrap()
+            .set_link_bandwidth(link_id, bandwidth);
+        // throughput of the transfers in progress is recalculated
+        if self.topology_initialized {
+            self.network_model.on_topology_change(&mut self.ctx);
+        }
+    }
+
+    /// Marks the link as failed, so it can't be used by the transfers until it is recovered.
+    ///
+    /// The transfers in progress which use the link are handled according to the [`TransferFailurePolicy`] of the
+    /// network model, the aborted transfers are reported with [`DataTransferFailed`] event.
+    /// If `recover_after` is set, the link is recovered after the specified delay with [`LinkRecovered`] event.
+    ///
+    /// [`TransferFailurePolicy`]: crate::TransferFailurePolicy
+    pub fn fail_link(&mut self, link_id: LinkId, recover_after: Option<f64>) {
+        assert!(
+            self.network_model.is_topology_aware(),
+            "This method requires topology-aware model"
+        );
+        log_debug!(self.ctx, "link {} failed", link_id);
+        self.network_model
+            .topology_mut()
+            .unwrap()
+            .set_link_failed(link_id, true);
+        if self.topology_initialized {
+            self.network_model.on_topology_change(&mut self.ctx);
+        }
+        if let Some(delay) = recover_after {
+            self.ctx.emit_self(LinkRecovered { link_id }, delay);
+        }
+    }
+
+    /// Recovers the failed link.
+    pub fn recover_link(&mut self, link_id: LinkId) {
+        assert!(
+            self.network_model.is_topology_aware(),
+            "This method requires topology-aware model"
+        );
+        log_debug!(self.ctx, "link {} recovered", link_id);
+        self.network_model
+            .topology_mut()
+            .unwrap()
+            .set_link_failed(link_id, false);
+        if self.topology_initialized {
+            self.network_model.on_topology_change(&mut self.ctx);
+        }
+    }
+
+    /// Marks the node as failed, so its links can't be used by the transfers until it is recovered.
+    ///
+    /// Works similar to [`Self::fail_link`] for all links of the node, the recovery is signalled with
+    /// [`NodeRecovered`] event. The intra-node transfers are not affected.
+    pub fn fail_node(&mut self, node_id: NodeId, recover_after: Option<f64>) {
+        assert!(
+            self.network_model.is_topology_aware(),
+            "This method requires topology-aware model"
+        );
+        log_debug!(self.ctx, "node {} failed", node_id);
+        self.network_model
+            .topology_mut()
+            .unwrap()
+            .set_node_failed(node_id, true);
+        if self.topology_initialized {
+            self.network_model.on_topology_change(&mut self.ctx);
+        }
+        if let Some(delay) = recover_after {
+            self.ctx.emit_self(NodeRecovered { node_id }, delay);
+        }
+    }
+
+    /// Recovers the failed node.
+    pub fn recover_node(&mut self, node_id: NodeId) {
+        assert!(
+            self.network_model.is_topology_aware(),
+            "This method requires topology-aware model"
+        );
+        log_debug!(self.ctx, "node {} recovered", node_id);
+        self.network_model
+            .topology_mut()
+            .unwrap()
+            .set_node_failed(node_id, false);
+        if self.topology_initialized {
+            self.network_model.on_topology_change(&mut self.ctx);
+        }
+    }
+
+    /// Checks whether the link can be used, i.e. neither the link nor its nodes are failed.
+    pub fn is_link_up(&self, link_id: LinkId) -> bool {
+        assert!(
+            self.network_model.is_topology_aware(),
+            "This method requires topology-aware model"
+        );
+        self.network_model.topology().unwrap().is_link_up(link_id)
+    }
+
     /// Performs initialization of network topology, such as computing the paths between the nodes.
     ///
     /// Must be called after all links are added and before submitting any operations.
@@ -210,9 +386,13 @@ impl Network {
     }
 
     /// Returns the network latency between two simulation components.
+    ///
+    /// The random variation of latency (if supported by the network model) is not included.
     pub fn latency(&self, src: Id, dst: Id) -> f64 {
-        let src_node_id = self.get_location(src);
-        let dst_node_id = self.get_location(dst);
+        self.node_latency(self.get_location(src), self.get_location(dst))
+    }
+
+    fn node_latency(&self, src_node_id: NodeId, dst_node_id: NodeId) -> f64 {
         if src_node_id == dst_node_id {
             self.local_models[&src_node_id].latency(src_node_id, src_node_id)
         } else {
@@ -220,6 +400,14 @@ impl Network {
         }
     }
 
+    fn sample_node_latency(&self, src_node_id: NodeId, dst_node_id: NodeId) -> f64 {
+        if src_node_id == dst_node_id {
+            self.local_models[&src_node_id].sample_latency(src_node_id, src_node_id, &self.ctx)
+        } else {
+            self.network_model.sample_latency(src_node_id, dst_node_id, &self.ctx)
+        }
+    }
+
     // Operations ------------------------------------------------------------------------------------------------------
 
     /// Starts a data transfer between two simulation components, returns unique transfer id.
@@ -228,8 +416,140 @@ impl Network {
     /// The transfer completion time is calculated by the underlying network model.
     /// The [`DataTransferCompleted`] event is sent to `notification_dst` on the transfer completion.
     pub fn transfer_data(&mut self, src: Id, dst: Id, size: f64, notification_dst: Id) -> usize {
+        self.transfer_data_weighted(src, dst, size, notification_dst, 1., None)
+    }
+
+    /// Same as [`Self::transfer_data`], but also sets the weight and the maximum rate of the transfer.
+    ///
+    /// These parameters are used by the network models with
+    /// [`BandwidthSharingPolicy::WeightedMaxMin`](crate::BandwidthSharingPolicy::WeightedMaxMin) policy.
+    pub fn transfer_data_weighted(
+        &mut self,
+        src: Id,
+        dst: Id,
+        size: f64,
+        notification_dst: Id,
+        weight: f64,
+        max_rate: Option<f64>,
+    ) -> usize {
+        assert!(weight > 0., "Transfer weight must be > 0");
+        assert!(max_rate.is_none_or(|rate| rate > 0.), "Transfer max rate must be > 0");
         let src_node_id = self.get_location(src);
         let dst_node_id = self.get_location(dst);
+        self.start_transfer(
+            src,
+            src_node_id,
+            dst,
+            dst_node_id,
+            size,
+            notification_dst,
+            weight,
+            max_rate,
+        )
+    }
+
+    /// Starts a data transfer between two nodes on behalf of the `requester` component, which is notified
+    /// on the transfer completion.
+    ///
+    /// Used to inject transfers which are not bound to the components located on the nodes, such as background traffic.
+    pub(crate) fn transfer_data_between_nodes(
+        &mut self,
+        src_node_id: NodeId,
+        dst_node_id: NodeId,
+        size: f64,
+        requester: Id,
+    ) -> usize {
+        self.start_transfer(
+            requester,
+            src_node_id,
+            requester,
+            dst_node_id,
+            size,
+            requester,
+            1.,
+            None,
+        )
+    }
+
+    /// Starts a multicast transfer of the same data from the simulation component to multiple nodes,
+    /// returns the ids of data transfers to each node.
+    ///
+    /// If the network model supports multicast (e.g. [`TopologyAwareNetworkModel`]), the data is sent over
+    /// a multicast tree, so that the links shared by the paths to several nodes are used only once.
+    /// Otherwise, a separate transfer is started to each node.
+    /// The [`DataTransferCompleted`] event for each node is sent to `src` when the data reaches this node.
+    ///
+    /// [`TopologyAwareNetworkModel`]: crate::models::TopologyAwareNetworkModel
+    pub fn multicast(&mut self, src: Id, dsts: &[NodeId], size: f64) -> Vec<usize> {
+        let src_node_id = self.get_location(src);
+        if !self.network_model.supports_multicast() {
+            return dsts
+                .iter()
+                .map(|&dst_node_id| self.start_transfer(src, src_node_id, src, dst_node_id, size, src, 1., None))
+                .collect();
+        }
+        let mut transfer_ids = Vec::with_capacity(dsts.len());
+        let mut dts = Vec::new();
+        for &dst_node_id in dsts {
+            if dst_node_id == src_node_id {
+                transfer_ids.push(self.start_transfer(src, src_node_id, src, dst_node_id, size, src, 1., None));
+            } else {
+                let dt = self.create_transfer(src, src_node_id, src, dst_node_id, size, src, 1., None);
+                transfer_ids.push(dt.id);
+                dts.push(dt);
+            }
+        }
+        if !dts.is_empty() {
+            self.record_traffic(src, "Multicast");
+            // The latency to each node is modeled by the network model after the data is sent over the tree.
+            self.ctx.emit_self_now(StartMulticast { dts });
+        }
+        transfer_ids
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn start_transfer(
+        &mut self,
+        src: Id,
+        src_node_id: NodeId,
+        dst: Id,
+        dst_node_id: NodeId,
+        size: f64,
+        notification_dst: Id,
+        weight: f64,
+        max_rate: Option<f64>,
+    ) -> usize {
+        let dt = self.create_transfer(
+            src,
+            src_node_id,
+            dst,
+            dst_node_id,
+            size,
+            notification_dst,
+            weight,
+            max_rate,
+        );
+        let transfer_id = dt.id;
+        // The fixed part of data transfer time (latency) is modeled by the delayed StartDataTransfer event.
+        // The remaining part is calculated by the underlying network model (see handling of StartDataTransfer event).
+        self.record_traffic(src, "DataTransfer");
+        let delay = self.sample_node_latency(src_node_id, dst_node_id);
+        self.ctx.emit_self(StartDataTransfer { dt }, delay);
+        transfer_id
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn create_transfer(
+        &mut self,
+        src: Id,
+        src_node_id: NodeId,
+        dst: Id,
+        dst_node_id: NodeId,
+        size: f64,
+        notification_dst: Id,
+        weight: f64,
+        max_rate: Option<f64>,
+    ) -> DataTransfer {
         let transfer_id = self.next_dt_id.fetch_add(1, Ordering::Relaxed);
         let dt = DataTransfer {
             id: transfer_id,
@@ -239,6 +559,8 @@ impl Network {
             dst_node_id,
             size,
             notification_dst,
+            weight,
+            max_rate,
         };
         log_debug!(
             self.ctx,
@@ -248,11 +570,25 @@ impl Network {
             dt.dst,
             dt.size
         );
-        // The fixed part of data transfer time (latency) is modeled by the delayed StartDataTransfer event.
-        // The remaining part is calculated by the underlying network model (see handling of StartDataTransfer event).
-        let delay = self.latency(src, dst);
-        self.ctx.emit_self(StartDataTransfer { dt }, delay);
-        transfer_id
+        dt
+    }
+
+    /// Returns the progress of data transfers in progress, ordered by transfer id.
+    ///
+    /// The transfers are reported by the network model and the intra-node models after the network latency passes,
+    /// see [`NetworkModel::active_transfers`].
+    pub fn active_transfers(&self) -> Vec<TransferProgress> {
+        let time = self.ctx.time();
+        let mut transfers = self.network_model.active_transfers();
+        for model in self.local_models.values() {
+            transfers.extend(model.active_transfers());
+        }
+        let mut transfers = transfers
+            .into_iter()
+            .map(|progress| progress.at(time))
+            .collect::<Vec<_>>();
+        transfers.sort_by_key(|progress| progress.dt.id);
+        transfers
     }
 
     /// Sends a message between two simulation components, returns unique message id.
@@ -269,7 +605,8 @@ impl Network {
             dst,
             data: message,
         };
-        let delay = self.latency(src, dst);
+        self.record_traffic(src, "Message");
+        let delay = self.sample_node_latency(self.get_location(src), self.get_location(dst));
         self.ctx.emit(MessageDelivered { msg }, dst, delay);
         msg_id
     }
@@ -280,9 +617,51 @@ impl Network {
     /// The event delivery time is equal to the network latency, assuming the event data has a small size.
     pub fn send_event<T: EventData>(&mut self, data: T, src: Id, dst: Id) -> EventId {
         log_debug!(self.ctx, "{} sent event to {}", src, dst);
-        let delay = self.latency(src, dst);
+        self.record_traffic(src, std::any::type_name::<T>().rsplit("::").next().unwrap());
+        let delay = self.sample_node_latency(self.get_location(src), self.get_location(dst));
         self.ctx.emit_as(data, src, dst, delay)
     }
+
+    /// Sets the length of the sliding time window used by [`Self::hot_spots`] (1 by default).
+    ///
+    /// Enables traffic monitoring, which is disabled by default.
+    pub fn set_traffic_window(&mut self, window: f64) {
+        self.traffic_monitor().set_window(window);
+    }
+
+    /// Sets the number of messages per traffic window above which the network is considered flooded.
+    ///
+    /// A warning is logged at the start of each such message storm. Enables traffic monitoring,
+    /// storm detection is disabled by default.
+    pub fn set_storm_threshold(&mut self, threshold: usize) {
+        self.traffic_monitor().set_storm_threshold(threshold);
+    }
+
+    /// Returns the top talkers and message type distribution for the messages, events and data transfers
+    /// sent during the last traffic window, or None if traffic monitoring is not enabled.
+    pub fn hot_spots(&self) -> Option<HotSpots> {
+        self.traffic.as_ref().map(|traffic| traffic.hot_spots(self.ctx.time()))
+    }
+
+    fn traffic_monitor(&mut self) -> &mut TrafficMonitor {
+        self.traffic.get_or_insert_with(|| TrafficMonitor::new(1., usize::MAX))
+    }
+
+    fn record_traffic(&mut self, src: Id, msg_type: &'static str) {
+        let Some(traffic) = self.traffic.as_mut() else {
+            return;
+        };
+        if traffic.record(self.ctx.time(), src, msg_type) {
+            let hot_spots = traffic.hot_spots(self.ctx.time());
+            log_warn!(
+                self.ctx,
+                "message storm detected: {} messages during last {} time units, top talker is {}",
+                hot_spots.total,
+                hot_spots.window,
+                hot_spots.top_talkers[0].0
+            );
+        }
+    }
 }
 
 impl EventHandler for Network {
@@ -296,6 +675,39 @@ impl EventHandler for Network {
                 };
                 model.start_transfer(dt, &mut self.ctx);
             }
+            StartMulticast { dts } => {
+                self.network_model.start_multicast(dts, &mut self.ctx);
+            }
+            MulticastCompleted { id } => {
+                self.network_model.on_multicast_completion(id, &mut self.ctx);
+            }
+            ModelTick {} => {
+                self.network_model.on_tick(&mut self.ctx);
+            }
+            PacketDelivered { packet } => {
+                self.network_model.on_packet_delivery(packet, &mut self.ctx);
+            }
+            LinkBandwidthChange { link_id, bandwidth } => {
+                self.on_link_bandwidth_change(link_id, bandwidth);
+            }
+            LinkRecovered { link_id } => {
+                self.recover_link(link_id);
+            }
+            NodeRecovered { node_id } => {
+                self.recover_node(node_id);
+            }
+            DataTransferFailed { dt } => {
+                log_debug!(
+                    self.ctx,
+                    "failed data transfer {} from {} to {} of size {}",
+                    dt.id,
+                    dt.src,
+                    dt.dst,
+                    dt.size
+                );
+                let notification_dst = dt.notification_dst;
+                self.ctx.emit_now(DataTransferFailed { dt }, notification_dst);
+            }
             DataTransferCompleted { dt } => {
                 log_debug!(
                     self.ctx,
@@ -1,6 +1,8 @@
 //! Routing algorithms.
 
+use std::collections::hash_map::DefaultHasher;
 use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
 
 use crate::topology::NodeLinksMap;
 use crate::{LinkId, NodeId, Topology};
@@ -8,6 +10,8 @@ use crate::{LinkId, NodeId, Topology};
 const INVALID_NODE_ID: usize = usize::MAX;
 
 /// Calculates the paths between pairs of nodes in a network.
+///
+/// The paths must not use the failed links (see [`Topology::is_link_up`]).
 pub trait RoutingAlgorithm {
     /// Performs initialization of the routing algorithm based on the provided network topology.
     fn init(&mut self, topology: &Topology);
@@ -16,9 +20,34 @@ pub trait RoutingAlgorithm {
     ///
     /// Can be used only after calling [`Self::init`].
     fn get_path_iter<'a>(&'a self, src: NodeId, dst: NodeId, topology: &'a Topology) -> Option<PathIterator<'a>>;
+
+    /// Returns all candidate paths from node `src` to node `dst`, which is empty if there is no path.
+    ///
+    /// By default returns the single path given by [`Self::get_path_iter`].
+    fn get_paths(&self, src: NodeId, dst: NodeId, topology: &Topology) -> Vec<Vec<LinkId>> {
+        self.get_path_iter(src, dst, topology)
+            .map(|path| vec![path.collect()])
+            .unwrap_or_default()
+    }
+
+    /// Returns the path for a new data transfer with id `transfer_id` from node `src` to node `dst`,
+    /// or `None` if there is no path.
+    ///
+    /// Allows multipath algorithms to distribute the transfers among the candidate paths.
+    /// By default returns the first of the paths given by [`Self::get_paths`].
+    fn get_transfer_path(
+        &mut self,
+        src: NodeId,
+        dst: NodeId,
+        _transfer_id: usize,
+        topology: &Topology,
+    ) -> Option<Vec<LinkId>> {
+        self.get_paths(src, dst, topology).into_iter().next()
+    }
 }
 
 /// Iterator which returns links on a path.
+#[derive(Clone)]
 pub struct PathIterator<'a> {
     src: NodeId,
     dst: NodeId,
@@ -60,7 +89,7 @@ impl RoutingAlgorithm for ShortestPathFloydWarshall {
         }
 
         for (node1, intermap) in topology.inv_node_links_map() {
-            for (node2, link_id) in intermap {
+            for (node2, link_id) in intermap.iter().filter(|(_, &link_id)| topology.is_link_up(link_id)) {
                 current_paths[*node1][*node2] = topology.link(*link_id).latency;
                 self.parent_path[*node1][*node2] = *node1;
             }
@@ -128,6 +157,9 @@ impl ShortestPathDijkstra {
             }
 
             for (node_to, link_id) in node_links_map.get(&relax_node).unwrap() {
+                if !topology.is_link_up(*link_id) {
+                    continue;
+                }
                 let link = topology.link(*link_id);
                 if latency[&relax_node] + link.latency < latency[node_to] {
                     latency.insert(*node_to, latency[&relax_node] + link.latency);
@@ -161,3 +193,143 @@ impl RoutingAlgorithm for ShortestPathDijkstra {
         }
     }
 }
+
+// Equal-cost multipath (ECMP) -----------------------------------------------------------------------------------------
+
+/// Defines how [`EcmpRouting`] distributes the transfers among the equal-cost paths.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EcmpPathSelection {
+    /// The path is chosen by hash of source and destination nodes, so all transfers between two nodes use the same path.
+    Hash,
+    /// The paths between each pair of nodes are used in turn.
+    RoundRobin,
+}
+
+/// Equal-cost multipath routing algorithm which distributes the transfers among all shortest paths (by latency).
+///
+/// The shortest paths are found with the Floyd–Warshall algorithm. [`RoutingAlgorithm::get_path_iter`] returns
+/// the same single path as [`ShortestPathFloydWarshall`].
+pub struct EcmpRouting {
+    selection: EcmpPathSelection,
+    single_path: ShortestPathFloydWarshall,
+    distances: Vec<Vec<f64>>,
+    paths_cache: HashMap<(NodeId, NodeId), Vec<Vec<LinkId>>>,
+    next_path: HashMap<(NodeId, NodeId), usize>,
+}
+
+impl EcmpRouting {
+    /// Creates a new routing algorithm with given path selection method.
+    pub fn new(selection: EcmpPathSelection) -> Self {
+        Self {
+            selection,
+            single_path: ShortestPathFloydWarshall::default(),
+            distances: Vec::new(),
+            paths_cache: HashMap::new(),
+            next_path: HashMap::new(),
+        }
+    }
+
+    fn collect_paths(
+        &self,
+        node: NodeId,
+        dst: NodeId,
+        topology: &Topology,
+        visited: &mut HashSet<NodeId>,
+        path: &mut Vec<LinkId>,
+        paths: &mut Vec<Vec<LinkId>>,
+    ) {
+        if node == dst {
+            paths.push(path.clone());
+            return;
+        }
+        for (&next, &link_id) in topology.node_links_map()[&node].iter() {
+            if !topology.is_link_up(link_id) || visited.contains(&next) {
+                continue;
+            }
+            // the link must be on a shortest path from the current node
+            let total = self.distances[node][dst];
+            let cost = topology.link(link_id).latency + self.distances[next][dst];
+            if (cost - total).abs() > 1e-12 * total.max(1.) {
+                continue;
+            }
+            visited.insert(next);
+            path.push(link_id);
+            self.collect_paths(next, dst, topology, visited, path, paths);
+            path.pop();
+            visited.remove(&next);
+        }
+    }
+}
+
+impl RoutingAlgorithm for EcmpRouting {
+    fn init(&mut self, topology: &Topology) {
+        self.single_path.init(topology);
+        self.paths_cache.clear();
+        let node_count = topology.node_count();
+        self.distances = vec![vec![f64::INFINITY; node_count]; node_count];
+        #[allow(clippy::needless_range_loop)]
+        for node in 0..node_count {
+            self.distances[node][node] = 0.0;
+        }
+        for (node1, intermap) in topology.node_links_map() {
+            for (node2, link_id) in intermap.iter().filter(|(_, &link_id)| topology.is_link_up(link_id)) {
+                let latency = topology.link(*link_id).latency;
+                self.distances[*node1][*node2] = self.distances[*node1][*node2].min(latency);
+            }
+        }
+        for k in 0..node_count {
+            for i in 0..node_count {
+                for j in 0..node_count {
+                    if self.distances[i][k] + self.distances[k][j] < self.distances[i][j] {
+                        self.distances[i][j] = self.distances[i][k] + self.distances[k][j];
+                    }
+                }
+            }
+        }
+    }
+
+    fn get_path_iter<'a>(&'a self, src: NodeId, dst: NodeId, topology: &'a Topology) -> Option<PathIterator<'a>> {
+        self.single_path.get_path_iter(src, dst, topology)
+    }
+
+    fn get_paths(&self, src: NodeId, dst: NodeId, topology: &Topology) -> Vec<Vec<LinkId>> {
+        if self.distances[src][dst] == f64::INFINITY {
+            return Vec::new();
+        }
+        let mut paths = Vec::new();
+        let mut visited = HashSet::from([src]);
+        self.collect_paths(src, dst, topology, &mut visited, &mut Vec::new(), &mut paths);
+        paths
+    }
+
+    fn get_transfer_path(
+        &mut self,
+        src: NodeId,
+        dst: NodeId,
+        _transfer_id: usize,
+        topology: &Topology,
+    ) -> Option<Vec<LinkId>> {
+        if !self.paths_cache.contains_key(&(src, dst)) {
+            let paths = self.get_paths(src, dst, topology);
+            self.paths_cache.insert((src, dst), paths);
+        }
+        let paths = &self.paths_cache[&(src, dst)];
+        if paths.is_empty() {
+            return None;
+        }
+        let index = match self.selection {
+            EcmpPathSelection::Hash => {
+                let mut hasher = DefaultHasher::new();
+                (src, dst).hash(&mut hasher);
+                hasher.finish() as usize % paths.len()
+            }
+            EcmpPathSelection::RoundRobin => {
+                let next = self.next_path.entry((src, dst)).or_default();
+                let index = *next % paths.len();
+                *next += 1;
+                index
+            }
+        };
+        Some(paths[index].clone())
+    }
+}
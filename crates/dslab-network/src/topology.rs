@@ -1,6 +1,8 @@
 //! Network topology.
 
-use std::collections::BTreeMap;
+pub mod parsers;
+
+use std::collections::{BTreeMap, BTreeSet};
 
 use crate::routing::PathIterator;
 use crate::{Link, LinkId, Node, NodeId};
@@ -13,8 +15,11 @@ pub type NodeLinksMap = BTreeMap<NodeId, BTreeMap<NodeId, LinkId>>;
 pub struct Topology {
     nodes: Vec<Node>,
     links: Vec<Link>,
+    link_nodes: Vec<(NodeId, NodeId)>,
     node_links_map: NodeLinksMap,
     inv_node_links_map: NodeLinksMap,
+    failed_links: BTreeSet<LinkId>,
+    failed_nodes: BTreeSet<NodeId>,
 }
 
 impl Topology {
@@ -32,6 +37,13 @@ impl Topology {
         node_id
     }
 
+    /// Returns the node by its id.
+    pub fn node(&self, node_id: NodeId) -> &Node {
+        self.nodes
+            .get(node_id)
+            .unwrap_or_else(|| panic!("Node {} is not found", node_id))
+    }
+
     /// Returns the number of nodes.
     pub fn node_count(&self) -> usize {
         self.nodes.len()
@@ -50,11 +62,17 @@ impl Topology {
     /// Adds two unidirectional links with the same parameters between two nodes in opposite directions.
     pub fn add_full_duplex_link(&mut self, node1: NodeId, node2: NodeId, link: Link) -> (LinkId, LinkId) {
         (
-            self.add_link_internal(node1, node2, link, false),
+            self.add_link_internal(node1, node2, link.clone(), false),
             self.add_link_internal(node2, node1, link, false),
         )
     }
 
+    /// Checks whether the link can be used in both directions, i.e. it was added with [`Self::add_link`].
+    pub fn is_link_bidirectional(&self, link_id: LinkId) -> bool {
+        let (node1, node2) = self.link_nodes[link_id];
+        self.node_links_map[&node2].get(&node1) == Some(&link_id)
+    }
+
     /// Returns the link by its id.
     pub fn link(&self, link_id: LinkId) -> &Link {
         self.links
@@ -62,6 +80,56 @@ impl Topology {
             .unwrap_or_else(|| panic!("Link {} is not found", link_id))
     }
 
+    /// Changes the bandwidth of the link.
+    pub fn set_link_bandwidth(&mut self, link_id: LinkId, bandwidth: f64) {
+        assert!(bandwidth > 0.0, "Link bandwidth must be > 0");
+        self.links
+            .get_mut(link_id)
+            .unwrap_or_else(|| panic!("Link {} is not found", link_id))
+            .bandwidth = bandwidth;
+    }
+
+    /// Returns the nodes connected by the link (in the link direction for unidirectional links).
+    pub fn link_nodes(&self, link_id: LinkId) -> (NodeId, NodeId) {
+        self.link_nodes[link_id]
+    }
+
+    /// Marks the link as failed or recovered.
+    pub fn set_link_failed(&mut self, link_id: LinkId, failed: bool) {
+        assert!(link_id < self.links.len(), "Link {} is not found", link_id);
+        if failed {
+            self.failed_links.insert(link_id);
+        } else {
+            self.failed_links.remove(&link_id);
+        }
+    }
+
+    /// Marks the node as failed or recovered. The links of a failed node can't be used.
+    pub fn set_node_failed(&mut self, node_id: NodeId, failed: bool) {
+        assert!(node_id < self.nodes.len(), "Node {} is not found", node_id);
+        if failed {
+            self.failed_nodes.insert(node_id);
+        } else {
+            self.failed_nodes.remove(&node_id);
+        }
+    }
+
+    /// Checks whether the node is not failed.
+    pub fn is_node_up(&self, node_id: NodeId) -> bool {
+        !self.failed_nodes.contains(&node_id)
+    }
+
+    /// Checks whether the link can be used, i.e. neither the link nor its nodes are failed.
+    pub fn is_link_up(&self, link_id: LinkId) -> bool {
+        let (node1, node2) = self.link_nodes[link_id];
+        !self.failed_links.contains(&link_id) && self.is_node_up(node1) && self.is_node_up(node2)
+    }
+
+    /// Checks whether there are failed links or nodes.
+    pub fn has_failures(&self) -> bool {
+        !self.failed_links.is_empty() || !self.failed_nodes.is_empty()
+    }
+
     /// Returns the number of links.
     pub fn link_count(&self) -> usize {
         self.links.len()
@@ -82,6 +150,26 @@ impl Topology {
         path_iter.map(|link_id| self.link(link_id).latency).sum()
     }
 
+    /// Returns a sample of the network latency of the given path including the jitter of its links.
+    ///
+    /// The `rand` function must return uniform random values from `[0, 1)`,
+    /// it is called only for the links with jitter.
+    pub fn sample_path_latency(&self, path_iter: impl Iterator<Item = LinkId>, mut rand: impl FnMut() -> f64) -> f64 {
+        path_iter
+            .map(|link_id| {
+                let link = self.link(link_id);
+                link.latency + link.jitter.map_or(0., |jitter| jitter.sample(rand()))
+            })
+            .sum()
+    }
+
+    /// Returns the probability that a packet is lost on the given path.
+    pub fn get_path_loss_rate(&self, path_iter: impl Iterator<Item = LinkId>) -> f64 {
+        1. - path_iter
+            .map(|link_id| 1. - self.link(link_id).loss_rate)
+            .product::<f64>()
+    }
+
     /// Returns the network bandwidth of the given path.
     pub fn get_path_bandwidth(&self, path_iter: PathIterator) -> f64 {
         path_iter
@@ -94,6 +182,7 @@ impl Topology {
         assert!(link.bandwidth > 0.0, "Link bandwidth must be > 0");
         let link_id = self.links.len();
         self.links.push(link);
+        self.link_nodes.push((node1, node2));
         self.node_links_map.get_mut(&node1).unwrap().insert(node2, link_id);
         self.inv_node_links_map.get_mut(&node2).unwrap().insert(node1, link_id);
         if bidirectional {
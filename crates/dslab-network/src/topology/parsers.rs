@@ -0,0 +1,263 @@
+//! Tools for loading network topology from different file formats.
+//!
+//! The loaded [`Topology`] contains the nodes and the links with [`BandwidthSharingPolicy::Shared`] policy.
+//! The link bandwidth and latency are read from the file where present, otherwise [`DEFAULT_LINK_BANDWIDTH`]
+//! and [`DEFAULT_LINK_LATENCY`] are used. The topology can be added to the network with [`Network::add_topology`].
+//!
+//! [`BandwidthSharingPolicy::Shared`]: crate::BandwidthSharingPolicy::Shared
+//! [`Network::add_topology`]: crate::Network::add_topology
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::Deserialize;
+use serde_xml_rs::from_str;
+
+use crate::{Link, Node, NodeId, Topology};
+
+/// Bandwidth of the links without bandwidth information.
+pub const DEFAULT_LINK_BANDWIDTH: f64 = 1000.;
+/// Latency of the links without latency information.
+pub const DEFAULT_LINK_LATENCY: f64 = 0.;
+
+fn read_file<P: AsRef<Path>>(file: &P) -> String {
+    std::fs::read_to_string(file).unwrap_or_else(|_| panic!("Can't read file {}", file.as_ref().display()))
+}
+
+fn parse_value(value: &str, name: &str) -> f64 {
+    value
+        .trim()
+        .parse::<f64>()
+        .unwrap_or_else(|_| panic!("Can't parse {} value {}", name, value))
+}
+
+/// Adds a node to the topology, the node names must be unique.
+fn add_node(topology: &mut Topology, node_ids: &mut HashMap<String, NodeId>, name: &str) {
+    assert!(!node_ids.contains_key(name), "Duplicate node {}", name);
+    let node_id = topology.add_node(Node { name: name.to_string() });
+    node_ids.insert(name.to_string(), node_id);
+}
+
+fn get_node(node_ids: &HashMap<String, NodeId>, name: &str) -> NodeId {
+    *node_ids
+        .get(name)
+        .unwrap_or_else(|| panic!("Node {} is not found", name))
+}
+
+fn add_link(topology: &mut Topology, node1: NodeId, node2: NodeId, link: Link, directed: bool) {
+    if directed {
+        topology.add_unidirectional_link(node1, node2, link);
+    } else {
+        topology.add_link(node1, node2, link);
+    }
+}
+
+// GraphML -------------------------------------------------------------------------------------------------------------
+
+#[derive(Debug, Deserialize)]
+struct GraphMlKey {
+    id: String,
+    #[serde(rename = "for", default)]
+    domain: Option<String>,
+    #[serde(rename = "attr.name", default)]
+    name: Option<String>,
+    default: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphMlData {
+    key: String,
+    #[serde(rename = "$value", default)]
+    value: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphMlNode {
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphMlEdge {
+    source: String,
+    target: String,
+    directed: Option<String>,
+    #[serde(rename = "data", default)]
+    data: Vec<GraphMlData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphMlGraph {
+    edgedefault: Option<String>,
+    #[serde(rename = "node", default)]
+    nodes: Vec<GraphMlNode>,
+    #[serde(rename = "edge", default)]
+    edges: Vec<GraphMlEdge>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename = "graphml")]
+struct GraphMl {
+    #[serde(rename = "key", default)]
+    keys: Vec<GraphMlKey>,
+    graph: GraphMlGraph,
+}
+
+/// Reads topology from a file in [GraphML format](http://graphml.graphdrawing.org/).
+///
+/// The node names are set to the node ids. Each edge is converted to a bidirectional link, or to a unidirectional
+/// one if the edge is directed. The link bandwidth and latency are read from the edge attributes
+/// (`<data>` elements) named `bandwidth` and `latency`, the defaults of the attributes are respected.
+pub fn from_graphml<P: AsRef<Path>>(file: P) -> Topology {
+    let graphml: GraphMl = from_str(&read_file(&file))
+        .unwrap_or_else(|_| panic!("Can't parse GraphML from file {}", file.as_ref().display()));
+
+    // attribute name -> (key id, default value)
+    let mut edge_attrs = HashMap::new();
+    for key in graphml.keys.iter() {
+        if matches!(key.domain.as_deref(), Some("edge") | Some("all") | None) {
+            if let Some(name) = &key.name {
+                edge_attrs.insert(name.as_str(), (key.id.as_str(), key.default.as_deref()));
+            }
+        }
+    }
+    let edge_attr = |edge: &GraphMlEdge, name: &str, default: f64| -> f64 {
+        let Some((key, key_default)) = edge_attrs.get(name) else {
+            return default;
+        };
+        edge.data
+            .iter()
+            .find(|data| data.key == *key)
+            .map(|data| data.value.as_str())
+            .or(*key_default)
+            .map_or(default, |value| parse_value(value, name))
+    };
+
+    let mut topology = Topology::new();
+    let mut node_ids = HashMap::new();
+    for node in graphml.graph.nodes.iter() {
+        add_node(&mut topology, &mut node_ids, &node.id);
+    }
+    let directed_default = graphml.graph.edgedefault.as_deref() == Some("directed");
+    for edge in graphml.graph.edges.iter() {
+        let directed = edge.directed.as_deref().map_or(directed_default, |d| d == "true");
+        let link = Link::shared(
+            edge_attr(edge, "bandwidth", DEFAULT_LINK_BANDWIDTH),
+            edge_attr(edge, "latency", DEFAULT_LINK_LATENCY),
+        );
+        let source = get_node(&node_ids, &edge.source);
+        let target = get_node(&node_ids, &edge.target);
+        add_link(&mut topology, source, target, link, directed);
+    }
+    topology
+}
+
+// BRITE ---------------------------------------------------------------------------------------------------------------
+
+/// Reads topology from a file in the output format of [BRITE](https://www.cs.bu.edu/brite/) topology generator.
+///
+/// The node names are set to the node ids. The edges marked as directed (`D`) are converted to unidirectional links,
+/// other edges are converted to bidirectional links. The edge delay in milliseconds is converted to the link latency
+/// in seconds, and the edge bandwidth in Mbit/s is converted to the link bandwidth in MB/s.
+/// The edges with zero bandwidth get the default one.
+pub fn from_brite<P: AsRef<Path>>(file: P) -> Topology {
+    let content = read_file(&file);
+    let mut topology = Topology::new();
+    let mut node_ids = HashMap::new();
+    let mut section = "";
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            section = "";
+            continue;
+        }
+        if line.starts_with("Nodes:") {
+            section = "nodes";
+            continue;
+        }
+        if line.starts_with("Edges:") {
+            section = "edges";
+            continue;
+        }
+        let fields = line.split_whitespace().collect::<Vec<_>>();
+        match section {
+            "nodes" => add_node(&mut topology, &mut node_ids, fields[0]),
+            "edges" => {
+                // EdgeId From To Length Delay Bandwidth ASFrom ASTo Type Direction
+                assert!(fields.len() >= 6, "Can't parse BRITE edge: {}", line);
+                let delay = parse_value(fields[4], "delay");
+                let bandwidth = parse_value(fields[5], "bandwidth");
+                let link = Link::shared(
+                    if bandwidth > 0. {
+                        bandwidth / 8.
+                    } else {
+                        DEFAULT_LINK_BANDWIDTH
+                    },
+                    delay / 1000.,
+                );
+                let directed = fields.len() >= 10 && fields[9] == "D";
+                let node1 = get_node(&node_ids, fields[1]);
+                let node2 = get_node(&node_ids, fields[2]);
+                add_link(&mut topology, node1, node2, link, directed);
+            }
+            _ => {}
+        }
+    }
+    topology
+}
+
+// CSV -----------------------------------------------------------------------------------------------------------------
+
+#[derive(Debug, Deserialize)]
+struct CsvNode {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CsvLink {
+    node1: String,
+    node2: String,
+    #[serde(default)]
+    bandwidth: Option<f64>,
+    #[serde(default)]
+    latency: Option<f64>,
+    #[serde(default)]
+    directed: Option<bool>,
+}
+
+/// Reads topology from two CSV files with nodes and links.
+///
+/// The nodes file must have the `name` column. The links file must have the `node1` and `node2` columns with
+/// the names of connected nodes, and can have the `bandwidth`, `latency` and `directed` columns.
+/// The links with `directed` set to `true` are unidirectional links from `node1` to `node2`,
+/// other links are bidirectional.
+pub fn from_csv<P: AsRef<Path>, Q: AsRef<Path>>(nodes_file: P, links_file: Q) -> Topology {
+    let mut topology = Topology::new();
+    let mut node_ids = HashMap::new();
+    let content = read_file(&nodes_file);
+    let mut reader = csv::Reader::from_reader(content.as_bytes());
+    for record in reader.deserialize::<CsvNode>() {
+        let node =
+            record.unwrap_or_else(|e| panic!("Can't parse node from file {}: {}", nodes_file.as_ref().display(), e));
+        add_node(&mut topology, &mut node_ids, node.name.trim());
+    }
+
+    let content = read_file(&links_file);
+    let mut reader = csv::Reader::from_reader(content.as_bytes());
+    for record in reader.deserialize::<CsvLink>() {
+        let link =
+            record.unwrap_or_else(|e| panic!("Can't parse link from file {}: {}", links_file.as_ref().display(), e));
+        let node1 = get_node(&node_ids, link.node1.trim());
+        let node2 = get_node(&node_ids, link.node2.trim());
+        add_link(
+            &mut topology,
+            node1,
+            node2,
+            Link::shared(
+                link.bandwidth.unwrap_or(DEFAULT_LINK_BANDWIDTH),
+                link.latency.unwrap_or(DEFAULT_LINK_LATENCY),
+            ),
+            link.directed.unwrap_or(false),
+        );
+    }
+    topology
+}
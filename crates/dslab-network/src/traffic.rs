@@ -0,0 +1,95 @@
+//! Sliding window statistics of network traffic used to detect message storms.
+
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use simcore::component::Id;
+
+/// Summary of the network traffic sent during the last time window.
+///
+/// Helps to diagnose feedback loops, such as broadcast amplification, which flood the network with messages.
+#[derive(Clone, Debug)]
+pub struct HotSpots {
+    /// Length of the time window.
+    pub window: f64,
+    /// Number of messages, events and data transfers sent during the window.
+    pub total: usize,
+    /// Senders ordered by the number of sent messages (most active first).
+    pub top_talkers: Vec<(Id, usize)>,
+    /// Message types ordered by the number of messages (most frequent first).
+    pub message_types: Vec<(String, usize)>,
+    /// Whether the number of messages during the window exceeds the storm threshold.
+    pub storm_detected: bool,
+}
+
+/// Records sent messages and computes [`HotSpots`] over a sliding time window.
+pub(crate) struct TrafficMonitor {
+    window: f64,
+    storm_threshold: usize,
+    storm_reported: bool,
+    records: VecDeque<(f64, Id, &'static str)>,
+}
+
+impl TrafficMonitor {
+    pub fn new(window: f64, storm_threshold: usize) -> Self {
+        Self {
+            window,
+            storm_threshold,
+            storm_reported: false,
+            records: VecDeque::new(),
+        }
+    }
+
+    pub fn set_window(&mut self, window: f64) {
+        assert!(window > 0., "Traffic window must be positive");
+        self.window = window;
+    }
+
+    pub fn set_storm_threshold(&mut self, storm_threshold: usize) {
+        self.storm_threshold = storm_threshold;
+    }
+
+    /// Records a sent message, returns true if this message has started a new storm.
+    pub fn record(&mut self, time: f64, src: Id, msg_type: &'static str) -> bool {
+        while let Some(&(t, _, _)) = self.records.front() {
+            if t > time - self.window {
+                break;
+            }
+            self.records.pop_front();
+        }
+        self.records.push_back((time, src, msg_type));
+        if self.records.len() > self.storm_threshold {
+            let started = !self.storm_reported;
+            self.storm_reported = true;
+            started
+        } else {
+            self.storm_reported = false;
+            false
+        }
+    }
+
+    pub fn hot_spots(&self, time: f64) -> HotSpots {
+        let mut talkers: HashMap<Id, usize> = HashMap::new();
+        let mut types: BTreeMap<&'static str, usize> = BTreeMap::new();
+        let mut total = 0;
+        for &(_, src, msg_type) in self.records.iter().filter(|(t, _, _)| *t > time - self.window) {
+            *talkers.entry(src).or_default() += 1;
+            *types.entry(msg_type).or_default() += 1;
+            total += 1;
+        }
+        let mut top_talkers: Vec<(Id, usize)> = talkers.into_iter().collect();
+        top_talkers.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        let mut message_types: Vec<(String, usize)> = types
+            .into_iter()
+            .map(|(name, count)| (name.to_string(), count))
+            .collect();
+        message_types.sort_by_key(|(_, count)| Reverse(*count));
+        HotSpots {
+            window: self.window,
+            total,
+            top_talkers,
+            message_types,
+            storm_detected: total > self.storm_threshold,
+        }
+    }
+}
@@ -12,9 +12,20 @@ use simcore::handler::EventHandler;
 use simcore::simulation::Simulation;
 use simcore::EPSILON;
 
-use dslab_network::models::{ConstantBandwidthNetworkModel, TopologyAwareNetworkModel};
-use dslab_network::routing::{RoutingAlgorithm, ShortestPathDijkstra, ShortestPathFloydWarshall};
-use dslab_network::{DataTransferCompleted, Link, Network};
+use dslab_network::models::{
+    AdaptiveBandwidthNetworkModel, ConstantBandwidthNetworkModel, LatencyMatrixNetworkModel,
+    PacketSwitchedNetworkModel, SharedBandwidthNetworkModel, TopologyAwareNetworkModel,
+};
+use dslab_network::routing::{
+    EcmpPathSelection, EcmpRouting, RoutingAlgorithm, ShortestPathDijkstra, ShortestPathFloydWarshall,
+};
+use dslab_network::topology::parsers::{
+    from_brite, from_csv, from_graphml, DEFAULT_LINK_BANDWIDTH, DEFAULT_LINK_LATENCY,
+};
+use dslab_network::{
+    BackgroundTraffic, BandwidthSharingPolicy, DataTransferCompleted, DataTransferFailed, LatencyJitter, Link,
+    MessageDelivered, Network, NetworkModel, TransferFailurePolicy,
+};
 
 #[derive(Clone, Copy)]
 enum RoutingImpl {
@@ -187,6 +198,33 @@ fn test_links(
     );
 }
 
+#[rstest]
+fn test_lossy_link(#[values(RoutingImpl::Dijkstra, RoutingImpl::FloydWarshall)] routing: RoutingImpl) {
+    let lossless = run_link_test(Link::shared(100., 1.), true, false, routing, 1, 0);
+    assert_float_eq(lossless, 11., EPSILON);
+
+    let mut link = Link::shared(100., 1.);
+    link.set_loss_rate(0.2);
+    let lossy = run_link_test(link, true, false, routing, 1, 0);
+    // 25% of data is retransmitted and the expected 0.25 losses per packet cost half a second of extra round trips
+    assert_float_eq(lossy, 1.5 + 12.5, EPSILON);
+    assert!(lossy > lossless);
+}
+
+#[rstest]
+fn test_bandwidth_schedule(#[values(RoutingImpl::Dijkstra, RoutingImpl::FloydWarshall)] routing: RoutingImpl) {
+    let mut link = Link::shared(100., 1.);
+    // half of the data is transferred by the moment the bandwidth drops
+    link.set_bandwidth_schedule(vec![(6., 50.)]);
+    let time = run_link_test(link, true, false, routing, 1, 0);
+    assert_float_eq(time, 6. + 500. / 50., EPSILON);
+
+    let mut link = Link::shared(100., 1.);
+    link.set_bandwidth_schedule(vec![(0., 50.), (11., 100.)]);
+    let time = run_link_test(link, false, true, routing, 1, 0);
+    assert_float_eq(time, 11. + 500. / 100., EPSILON);
+}
+
 #[rstest]
 fn test_triangle(#[values(RoutingImpl::Dijkstra, RoutingImpl::FloydWarshall)] routing: RoutingImpl) {
     let mut sim = Simulation::new(123);
@@ -297,3 +335,985 @@ fn test_diamond(#[values(RoutingImpl::Dijkstra, RoutingImpl::FloydWarshall)] rou
 
     assert_float_eq(sim.time(), 10.2, EPSILON);
 }
+
+pub struct Recorder {
+    net: Rc<RefCell<Network>>,
+    completed: Rc<RefCell<Vec<(Id, Id, f64)>>>,
+    ctx: SimulationContext,
+}
+
+impl EventHandler for Recorder {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            Start { size, receiver_id } => {
+                self.net
+                    .borrow_mut()
+                    .transfer_data(self.ctx.id(), receiver_id, size, receiver_id);
+            }
+            DataTransferCompleted { dt } => {
+                self.completed.borrow_mut().push((dt.src, dt.dst, event.time));
+            }
+        })
+    }
+}
+
+#[test]
+fn test_latency_matrix() {
+    let mut sim = Simulation::new(123);
+
+    // the entry from host3 to host2 is missing and uses the default latency
+    let network_model = LatencyMatrixNetworkModel::new(100., 5.)
+        .with_latency_matrix(vec![vec![0., 1., 2.], vec![1., 0., 3.], vec![2.]])
+        .with_bandwidth_matrix(vec![vec![100., 50.]]);
+    let mut network = Network::new(Box::new(network_model), sim.create_context("net"));
+    for host in ["host1", "host2", "host3"] {
+        network.add_node(host, Box::new(ConstantBandwidthNetworkModel::new(100.0, 0.0)));
+    }
+    let network_rc = Rc::new(RefCell::new(network));
+    sim.add_handler("net", network_rc.clone());
+
+    let completed = Rc::new(RefCell::new(Vec::new()));
+    let mut nodes = Vec::new();
+    for i in 1..=3 {
+        let name = format!("node{}", i);
+        let node = Recorder {
+            net: network_rc.clone(),
+            completed: completed.clone(),
+            ctx: sim.create_context(&name),
+        };
+        let node_id = sim.add_handler(&name, Rc::new(RefCell::new(node)));
+        network_rc.borrow_mut().set_location(node_id, &format!("host{}", i));
+        nodes.push(node_id);
+    }
+
+    let client = sim.create_context("client");
+    for &src in nodes.iter() {
+        for &dst in nodes.iter() {
+            if src != dst {
+                client.emit_now(
+                    Start {
+                        size: 1000.0,
+                        receiver_id: dst,
+                    },
+                    src,
+                );
+            }
+        }
+    }
+    sim.step_until_no_events();
+
+    // latency + size / bandwidth
+    let expected = [
+        [0., 1. + 20., 2. + 10.],
+        [1. + 10., 0., 3. + 10.],
+        [2. + 10., 5. + 10., 0.],
+    ];
+    let completed = completed.borrow();
+    assert_eq!(completed.len(), 6);
+    for &(src, dst, time) in completed.iter() {
+        let src_idx = nodes.iter().position(|&n| n == src).unwrap();
+        let dst_idx = nodes.iter().position(|&n| n == dst).unwrap();
+        assert_float_eq(time, expected[src_idx][dst_idx], EPSILON);
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub struct Echo {}
+
+/// Broadcasts two messages to every peer on each received echo, which results in exponential message growth.
+pub struct Amplifier {
+    net: Rc<RefCell<Network>>,
+    peers: Vec<Id>,
+    ctx: SimulationContext,
+}
+
+impl Amplifier {
+    fn broadcast(&mut self) {
+        for &peer in self.peers.iter() {
+            for _ in 0..2 {
+                self.net
+                    .borrow_mut()
+                    .send_msg("gossip".to_string(), self.ctx.id(), peer);
+            }
+        }
+    }
+}
+
+impl EventHandler for Amplifier {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            Start { .. } => {
+                self.broadcast();
+            }
+            Echo {} => {
+                self.broadcast();
+            }
+        })
+    }
+}
+
+/// Replies to each received message with an echo.
+pub struct Echoer {
+    net: Rc<RefCell<Network>>,
+    ctx: SimulationContext,
+}
+
+impl EventHandler for Echoer {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            MessageDelivered { msg } => {
+                self.net.borrow_mut().send_event(Echo {}, self.ctx.id(), msg.src);
+            }
+        })
+    }
+}
+
+#[test]
+fn test_hot_spots() {
+    let mut sim = Simulation::new(123);
+
+    let mut network = Network::new(
+        Box::new(ConstantBandwidthNetworkModel::new(100., 1.)),
+        sim.create_context("net"),
+    );
+    for host in ["host1", "host2", "host3"] {
+        network.add_node(host, Box::new(ConstantBandwidthNetworkModel::new(100.0, 0.0)));
+    }
+    // traffic monitoring is disabled by default
+    assert!(network.hot_spots().is_none());
+    network.set_traffic_window(2.);
+    network.set_storm_threshold(100);
+    let network_rc = Rc::new(RefCell::new(network));
+    sim.add_handler("net", network_rc.clone());
+
+    let mut peers = Vec::new();
+    for i in 2..=3 {
+        let name = format!("echoer{}", i);
+        let echoer = Echoer {
+            net: network_rc.clone(),
+            ctx: sim.create_context(&name),
+        };
+        let id = sim.add_handler(&name, Rc::new(RefCell::new(echoer)));
+        network_rc.borrow_mut().set_location(id, &format!("host{}", i));
+        peers.push(id);
+    }
+    let amplifier = Amplifier {
+        net: network_rc.clone(),
+        peers: peers.clone(),
+        ctx: sim.create_context("amplifier"),
+    };
+    let amplifier_id = sim.add_handler("amplifier", Rc::new(RefCell::new(amplifier)));
+    network_rc.borrow_mut().set_location(amplifier_id, "host1");
+
+    // a single message does not trigger the storm detection
+    let client = sim.create_context("client");
+    client.emit_now(
+        Start {
+            size: 0.,
+            receiver_id: peers[0],
+        },
+        amplifier_id,
+    );
+    sim.step_for_duration(0.5);
+    let hot_spots = network_rc.borrow().hot_spots().unwrap();
+    assert_eq!(hot_spots.total, 4);
+    assert!(!hot_spots.storm_detected);
+
+    sim.step_for_duration(7.);
+    let hot_spots = network_rc.borrow().hot_spots().unwrap();
+    assert!(hot_spots.storm_detected);
+    assert!(hot_spots.total > 100);
+    assert_eq!(hot_spots.top_talkers[0].0, amplifier_id);
+    assert!(hot_spots
+        .top_talkers
+        .iter()
+        .all(|&(id, _)| id == amplifier_id || peers.contains(&id)));
+    // the amplifier sends plain messages, while the peers reply with echo events
+    assert_eq!(hot_spots.message_types.len(), 2);
+    let messages = hot_spots
+        .message_types
+        .iter()
+        .find(|(name, _)| name == "Message")
+        .unwrap();
+    assert_eq!(messages.1, hot_spots.top_talkers[0].1);
+    let total_by_type: usize = hot_spots.message_types.iter().map(|(_, count)| count).sum();
+    assert_eq!(total_by_type, hot_spots.total);
+}
+
+/// Records the completion times of received data transfers.
+pub struct Receiver {
+    completions: Rc<RefCell<Vec<f64>>>,
+}
+
+impl EventHandler for Receiver {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            DataTransferCompleted { dt: _ } => {
+                self.completions.borrow_mut().push(event.time);
+            }
+        })
+    }
+}
+
+fn run_with_background_traffic(seed: Option<u64>) -> (f64, u64) {
+    let mut sim = Simulation::new(123);
+
+    let mut network = Network::new(
+        Box::new(SharedBandwidthNetworkModel::new(100., 0.)),
+        sim.create_context("net"),
+    );
+    for host in ["host1", "host2", "host3"] {
+        network.add_node(host, Box::new(ConstantBandwidthNetworkModel::new(1000., 0.)));
+    }
+    let network_rc = Rc::new(RefCell::new(network));
+    sim.add_handler("net", network_rc.clone());
+
+    let sender = Node::new(network_rc.clone(), sim.create_context("sender"));
+    let sender_id = sim.add_handler("sender", Rc::new(RefCell::new(sender)));
+    network_rc.borrow_mut().set_location(sender_id, "host1");
+    let completions = Rc::new(RefCell::new(Vec::new()));
+    let receiver = Receiver {
+        completions: completions.clone(),
+    };
+    let receiver_id = sim.add_handler("receiver", Rc::new(RefCell::new(receiver)));
+    network_rc.borrow_mut().set_location(receiver_id, "host2");
+
+    let background = seed.map(|seed| {
+        let mut background =
+            BackgroundTraffic::new(network_rc.clone(), 1., 100., seed, sim.create_context("background"));
+        background.set_node_pairs(&[("host1", "host3", 1.), ("host3", "host2", 1.)]);
+        let background = Rc::new(RefCell::new(background));
+        sim.add_handler("background", background.clone());
+        background.borrow_mut().start();
+        background
+    });
+
+    let client = sim.create_context("client");
+    client.emit(
+        Start {
+            size: 1000.,
+            receiver_id,
+        },
+        sender_id,
+        5.,
+    );
+    sim.step_for_duration(100.);
+    if let Some(background) = background.as_ref() {
+        background.borrow_mut().stop();
+    }
+    sim.step_until_no_events();
+
+    assert_eq!(completions.borrow().len(), 1);
+    let finish = completions.borrow()[0];
+    let background_transfers = background.map_or(0, |b| {
+        let b = b.borrow();
+        assert_eq!(b.started_transfers(), b.completed_transfers());
+        b.started_transfers()
+    });
+    (finish, background_transfers)
+}
+
+#[test]
+fn test_background_traffic() {
+    let (idle_finish, _) = run_with_background_traffic(None);
+    assert_float_eq(idle_finish, 15., EPSILON);
+
+    // the foreground transfer shares the bandwidth with the background transfers
+    let (finish, transfers) = run_with_background_traffic(Some(42));
+    assert!(transfers > 50);
+    assert!(finish > idle_finish + 1.);
+
+    // the background traffic is determined by the seed
+    assert_eq!(run_with_background_traffic(Some(42)), (finish, transfers));
+}
+
+/// Runs transfers of 1000 bytes from `senders` to a single receiver connected via a switch.
+fn run_packet_switched(senders: usize, latency: f64, buffer_size: usize) -> Vec<f64> {
+    let mut sim = Simulation::new(123);
+
+    let network_model = PacketSwitchedNetworkModel::new(100., buffer_size);
+    let mut network = Network::new(Box::new(network_model), sim.create_context("net"));
+    network.add_node("switch", Box::new(ConstantBandwidthNetworkModel::new(1000., 0.)));
+    network.add_node("receiver", Box::new(ConstantBandwidthNetworkModel::new(1000., 0.)));
+    network.add_link("switch", "receiver", Link::shared(100., latency));
+    for i in 0..senders {
+        let host = format!("sender{}", i);
+        network.add_node(&host, Box::new(ConstantBandwidthNetworkModel::new(1000., 0.)));
+        network.add_link(&host, "switch", Link::shared(100., latency));
+    }
+    network.init_topology();
+    let network_rc = Rc::new(RefCell::new(network));
+    sim.add_handler("net", network_rc.clone());
+
+    let completions = Rc::new(RefCell::new(Vec::new()));
+    let receiver = Receiver {
+        completions: completions.clone(),
+    };
+    let receiver_id = sim.add_handler("receiver", Rc::new(RefCell::new(receiver)));
+    network_rc.borrow_mut().set_location(receiver_id, "receiver");
+    let client = sim.create_context("client");
+    for i in 0..senders {
+        let name = format!("sender{}", i);
+        let sender = Node::new(network_rc.clone(), sim.create_context(&name));
+        let sender_id = sim.add_handler(&name, Rc::new(RefCell::new(sender)));
+        network_rc.borrow_mut().set_location(sender_id, &name);
+        client.emit_now(
+            Start {
+                size: 1000.,
+                receiver_id,
+            },
+            sender_id,
+        );
+    }
+
+    sim.step_until_no_events();
+    let completions = completions.borrow().clone();
+    assert_eq!(completions.len(), senders);
+    completions
+}
+
+#[test]
+fn test_packet_switched() {
+    // 10 packets are pipelined over two links, the last one is sent over the second link after the first link
+    assert_float_eq(run_packet_switched(1, 0.5, 10)[0], 1. + 10. + 1., EPSILON);
+
+    // the packets of two transfers are interleaved on the bottleneck link and wait in its queue
+    let completions = run_packet_switched(2, 0., 10);
+    assert_float_eq(completions[0], 20., EPSILON);
+    assert_float_eq(completions[1], 21., EPSILON);
+
+    let completions = run_packet_switched(2, 0.5, 10);
+    assert_float_eq(completions[0], 21., EPSILON);
+    assert_float_eq(completions[1], 22., EPSILON);
+
+    // small buffer causes packet drops, the dropped packets of the second transfer are retransmitted
+    // while the first transfer uses the bottleneck link, which stays busy all the time
+    let completions = run_packet_switched(2, 0.5, 2);
+    assert!(completions[0] < 21.);
+    assert_float_eq(completions[1], 22., EPSILON);
+}
+
+/// Records the completion and failure times of received data transfers.
+pub struct TransferObserver {
+    events: Rc<RefCell<Vec<(&'static str, f64)>>>,
+}
+
+impl EventHandler for TransferObserver {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            DataTransferCompleted { dt: _ } => {
+                self.events.borrow_mut().push(("completed", event.time));
+            }
+            DataTransferFailed { dt: _ } => {
+                self.events.borrow_mut().push(("failed", event.time));
+            }
+        })
+    }
+}
+
+/// Runs a transfer of 1000 bytes from host1 to host2 connected with a direct link,
+/// calls `fail` at time 4.5 and returns the transfer outcome.
+fn run_with_failure(
+    network_model: Box<dyn NetworkModel>,
+    alternative_path: bool,
+    fail: impl FnOnce(&mut Network),
+) -> (&'static str, f64) {
+    let mut sim = Simulation::new(123);
+
+    let mut network = Network::new(network_model, sim.create_context("net"));
+    for host in ["host1", "host2", "host3"] {
+        network.add_node(host, Box::new(ConstantBandwidthNetworkModel::new(1000., 0.)));
+    }
+    network.add_link("host1", "host2", Link::shared(100., 0.));
+    if alternative_path {
+        network.add_link("host1", "host3", Link::shared(100., 0.1));
+        network.add_link("host3", "host2", Link::shared(100., 0.1));
+    }
+    network.init_topology();
+    let network_rc = Rc::new(RefCell::new(network));
+    sim.add_handler("net", network_rc.clone());
+
+    let sender = Node::new(network_rc.clone(), sim.create_context("sender"));
+    let sender_id = sim.add_handler("sender", Rc::new(RefCell::new(sender)));
+    network_rc.borrow_mut().set_location(sender_id, "host1");
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let receiver = TransferObserver { events: events.clone() };
+    let receiver_id = sim.add_handler("receiver", Rc::new(RefCell::new(receiver)));
+    network_rc.borrow_mut().set_location(receiver_id, "host2");
+
+    sim.create_context("client").emit_now(
+        Start {
+            size: 1000.,
+            receiver_id,
+        },
+        sender_id,
+    );
+    sim.step_until_time(4.5);
+    fail(&mut network_rc.borrow_mut());
+    sim.step_until_no_events();
+
+    let events = events.borrow();
+    assert_eq!(events.len(), 1);
+    events[0]
+}
+
+#[test]
+fn test_link_failure() {
+    let model = |policy| Box::new(TopologyAwareNetworkModel::new().with_failure_policy(policy));
+
+    let outcome = run_with_failure(model(TransferFailurePolicy::Abort), true, |net| net.fail_link(0, None));
+    assert_eq!(outcome.0, "failed");
+    assert_float_eq(outcome.1, 4.5, EPSILON);
+
+    // the transfer is rerouted via host3
+    let outcome = run_with_failure(model(TransferFailurePolicy::Pause), true, |net| net.fail_link(0, None));
+    assert_eq!(outcome.0, "completed");
+    assert_float_eq(outcome.1, 10., EPSILON);
+
+    // there is no other path, so the transfer waits for the link recovery
+    let outcome = run_with_failure(model(TransferFailurePolicy::Pause), false, |net| {
+        net.fail_link(0, Some(3.));
+        assert!(!net.is_link_up(0));
+    });
+    assert_eq!(outcome.0, "completed");
+    assert_float_eq(outcome.1, 13., EPSILON);
+}
+
+#[test]
+fn test_node_failure() {
+    let model = |policy| Box::new(TopologyAwareNetworkModel::new().with_failure_policy(policy));
+
+    let outcome = run_with_failure(model(TransferFailurePolicy::Abort), true, |net| {
+        let node = net.get_node_id("host2");
+        net.fail_node(node, None);
+        assert!(!net.is_link_up(0));
+    });
+    assert_eq!(outcome.0, "failed");
+    assert_float_eq(outcome.1, 4.5, EPSILON);
+
+    // the alternative path is not used since the failed node is the destination
+    let outcome = run_with_failure(model(TransferFailurePolicy::Pause), true, |net| {
+        let node = net.get_node_id("host2");
+        net.fail_node(node, Some(3.));
+    });
+    assert_eq!(outcome.0, "completed");
+    assert_float_eq(outcome.1, 13., EPSILON);
+}
+
+#[test]
+fn test_packet_switched_link_failure() {
+    let model = |policy| Box::new(PacketSwitchedNetworkModel::new(100., 10).with_failure_policy(policy));
+
+    let outcome = run_with_failure(model(TransferFailurePolicy::Abort), false, |net| net.fail_link(0, None));
+    assert_eq!(outcome.0, "failed");
+    assert_float_eq(outcome.1, 4.5, EPSILON);
+
+    // the packets sent over the link during the failure are lost and sent again after the recovery
+    // or after their loss is detected, whichever comes later
+    let outcome = run_with_failure(model(TransferFailurePolicy::Pause), false, |net| {
+        net.fail_link(0, Some(3.))
+    });
+    assert_eq!(outcome.0, "completed");
+    assert_float_eq(outcome.1, 13.5, EPSILON);
+}
+
+/// Returns the completion times of small transfers sent from host1 to host2 over two links.
+fn run_small_transfers(link: Link, count: usize) -> Vec<f64> {
+    let mut sim = Simulation::new(123);
+
+    let mut network = Network::new(Box::new(TopologyAwareNetworkModel::new()), sim.create_context("net"));
+    for host in ["host1", "switch", "host2"] {
+        network.add_node(host, Box::new(ConstantBandwidthNetworkModel::new(1000., 0.)));
+    }
+    network.add_link("host1", "switch", link.clone());
+    network.add_link("switch", "host2", link);
+    network.init_topology();
+    let network_rc = Rc::new(RefCell::new(network));
+    sim.add_handler("net", network_rc.clone());
+
+    let sender = Node::new(network_rc.clone(), sim.create_context("sender"));
+    let sender_id = sim.add_handler("sender", Rc::new(RefCell::new(sender)));
+    network_rc.borrow_mut().set_location(sender_id, "host1");
+    let completions = Rc::new(RefCell::new(Vec::new()));
+    let receiver = Receiver {
+        completions: completions.clone(),
+    };
+    let receiver_id = sim.add_handler("receiver", Rc::new(RefCell::new(receiver)));
+    network_rc.borrow_mut().set_location(receiver_id, "host2");
+
+    let client = sim.create_context("client");
+    for _ in 0..count {
+        client.emit_now(Start { size: 0., receiver_id }, sender_id);
+    }
+    sim.step_until_no_events();
+    let completions = completions.borrow().clone();
+    assert_eq!(completions.len(), count);
+    completions
+}
+
+#[test]
+fn test_latency_jitter() {
+    // small transfers take the sum of link latencies
+    let completions = run_small_transfers(Link::shared(100., 0.).with_latency(0.5), 10);
+    assert!(completions.iter().all(|&time| (time - 1.).abs() < EPSILON));
+
+    let link = Link::shared(100., 0.5).with_jitter(LatencyJitter::Uniform { max: 0.2 });
+    let completions = run_small_transfers(link, 10);
+    assert!(completions.iter().all(|&time| (1. ..=1.4).contains(&time)));
+    assert!(completions.windows(2).any(|w| w[0] != w[1]));
+}
+
+/// Runs two concurrent transfers between two hosts connected via two switches and returns the completion time.
+fn run_two_paths(routing: Box<dyn RoutingAlgorithm>) -> f64 {
+    let mut sim = Simulation::new(123);
+
+    let network_model = TopologyAwareNetworkModel::new().with_routing(routing);
+    let mut network = Network::new(Box::new(network_model), sim.create_context("net"));
+    for host in ["host1", "switch1", "switch2", "host2"] {
+        network.add_node(host, Box::new(ConstantBandwidthNetworkModel::new(1000., 0.)));
+    }
+    for switch in ["switch1", "switch2"] {
+        network.add_link("host1", switch, Link::shared(100., 1.));
+        network.add_link(switch, "host2", Link::shared(100., 1.));
+    }
+    network.init_topology();
+    let network_rc = Rc::new(RefCell::new(network));
+    sim.add_handler("net", network_rc.clone());
+
+    let sender = Node::new(network_rc.clone(), sim.create_context("sender"));
+    let sender_id = sim.add_handler("sender", Rc::new(RefCell::new(sender)));
+    network_rc.borrow_mut().set_location(sender_id, "host1");
+    let receiver = Node::new(network_rc.clone(), sim.create_context("receiver"));
+    let receiver_id = sim.add_handler("receiver", Rc::new(RefCell::new(receiver)));
+    network_rc.borrow_mut().set_location(receiver_id, "host2");
+
+    let client = sim.create_context("client");
+    for _ in 0..2 {
+        client.emit_now(
+            Start {
+                size: 1000.,
+                receiver_id,
+            },
+            sender_id,
+        );
+    }
+    sim.step_until_no_events();
+    sim.time()
+}
+
+#[test]
+fn test_ecmp_routing() {
+    // the transfers share a single path
+    assert_float_eq(
+        run_two_paths(Box::<ShortestPathFloydWarshall>::default()),
+        2. + 20.,
+        EPSILON,
+    );
+    assert_float_eq(
+        run_two_paths(Box::new(EcmpRouting::new(EcmpPathSelection::Hash))),
+        2. + 20.,
+        EPSILON,
+    );
+    // each transfer gets its own path with full bandwidth
+    assert_float_eq(
+        run_two_paths(Box::new(EcmpRouting::new(EcmpPathSelection::RoundRobin))),
+        2. + 10.,
+        EPSILON,
+    );
+}
+
+#[test]
+fn test_ecmp_paths() {
+    let mut topology = dslab_network::Topology::new();
+    let nodes = (0..5)
+        .map(|i| {
+            topology.add_node(dslab_network::Node {
+                name: format!("node{}", i),
+            })
+        })
+        .collect::<Vec<_>>();
+    // two equal-cost paths via nodes 1 and 2, and a longer path via node 3
+    topology.add_link(nodes[0], nodes[1], Link::shared(100., 1.));
+    topology.add_link(nodes[1], nodes[4], Link::shared(100., 1.));
+    topology.add_link(nodes[0], nodes[2], Link::shared(100., 1.));
+    topology.add_link(nodes[2], nodes[4], Link::shared(100., 1.));
+    topology.add_link(nodes[0], nodes[3], Link::shared(100., 1.));
+    topology.add_link(nodes[3], nodes[4], Link::shared(100., 2.));
+
+    let mut routing = EcmpRouting::new(EcmpPathSelection::RoundRobin);
+    routing.init(&topology);
+    let paths = routing.get_paths(nodes[0], nodes[4], &topology);
+    assert_eq!(paths, vec![vec![0, 1], vec![2, 3]]);
+    let first = routing.get_transfer_path(nodes[0], nodes[4], 0, &topology).unwrap();
+    let second = routing.get_transfer_path(nodes[0], nodes[4], 1, &topology).unwrap();
+    assert_ne!(first, second);
+    assert_eq!(routing.get_paths(nodes[4], nodes[0], &topology).len(), 2);
+}
+
+fn write_temp_file(name: &str, content: &str) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(name);
+    std::fs::write(&path, content).unwrap();
+    path
+}
+
+#[test]
+fn test_graphml_topology() {
+    let path = write_temp_file(
+        "dslab_network_test_topology.graphml",
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <key id="d0" for="edge" attr.name="bandwidth" attr.type="double"/>
+  <key id="d1" for="edge" attr.name="latency" attr.type="double">
+    <default>0.5</default>
+  </key>
+  <graph id="G" edgedefault="undirected">
+    <node id="host1"/>
+    <node id="switch"/>
+    <node id="host2"/>
+    <edge source="host1" target="switch">
+      <data key="d0">100</data>
+      <data key="d1">1</data>
+    </edge>
+    <edge source="switch" target="host2" directed="true">
+      <data key="d0">50</data>
+    </edge>
+    <edge source="host2" target="host1"/>
+  </graph>
+</graphml>
+"#,
+    );
+    let topology = from_graphml(&path);
+    assert_eq!(topology.node_count(), 3);
+    assert_eq!(topology.node(1).name, "switch");
+    assert_eq!(topology.link_count(), 3);
+    assert_eq!(topology.link_nodes(0), (0, 1));
+    assert_float_eq(topology.link(0).bandwidth, 100., EPSILON);
+    assert_float_eq(topology.link(0).latency, 1., EPSILON);
+    assert!(topology.is_link_bidirectional(0));
+    assert_float_eq(topology.link(1).bandwidth, 50., EPSILON);
+    assert_float_eq(topology.link(1).latency, 0.5, EPSILON);
+    assert!(!topology.is_link_bidirectional(1));
+    assert_float_eq(topology.link(2).bandwidth, DEFAULT_LINK_BANDWIDTH, EPSILON);
+    assert!(topology.is_link_bidirectional(2));
+}
+
+#[test]
+fn test_brite_topology() {
+    let path = write_temp_file(
+        "dslab_network_test_topology.brite",
+        "\
+Topology: ( 3 Nodes, 3 Edges )
+Model (1 - RTWaxman):  3 1000 100 1  2  0.15 0.2 1 1 10.0 1024.0
+
+Nodes: ( 3 )
+0\t48\t45\t2\t2\t-1\tRT_NODE
+1\t12\t80\t2\t2\t-1\tRT_NODE
+2\t70\t11\t2\t2\t-1\tRT_NODE
+
+Edges: ( 3 ):
+0\t0\t1\t50.0\t2.0\t800.0\t-1\t-1\tE_RT\tU
+1\t1\t2\t60.0\t4.0\t80.0\t-1\t-1\tE_RT\tU
+2\t2\t0\t70.0\t1.0\t0.0\t-1\t-1\tE_RT\tD
+",
+    );
+    let topology = from_brite(&path);
+    assert_eq!(topology.node_count(), 3);
+    assert_eq!(topology.node(2).name, "2");
+    assert_eq!(topology.link_count(), 3);
+    assert_eq!(topology.link_nodes(1), (1, 2));
+    assert_float_eq(topology.link(0).bandwidth, 100., EPSILON);
+    assert_float_eq(topology.link(0).latency, 0.002, EPSILON);
+    assert_float_eq(topology.link(1).bandwidth, 10., EPSILON);
+    assert!(topology.is_link_bidirectional(1));
+    assert_float_eq(topology.link(2).bandwidth, DEFAULT_LINK_BANDWIDTH, EPSILON);
+    assert!(!topology.is_link_bidirectional(2));
+}
+
+#[test]
+fn test_csv_topology() {
+    let nodes_path = write_temp_file("dslab_network_test_topology_nodes.csv", "name\nhost1\nswitch\nhost2\n");
+    let links_path = write_temp_file(
+        "dslab_network_test_topology_links.csv",
+        "\
+node1,node2,bandwidth,latency,directed
+host1,switch,100,1,false
+switch,host2,50,,
+host2,host1,,,true
+",
+    );
+    let topology = from_csv(&nodes_path, &links_path);
+    assert_eq!(topology.node_count(), 3);
+    assert_eq!(topology.link_count(), 3);
+    assert_float_eq(topology.link(0).latency, 1., EPSILON);
+    assert_float_eq(topology.link(1).bandwidth, 50., EPSILON);
+    assert_float_eq(topology.link(1).latency, DEFAULT_LINK_LATENCY, EPSILON);
+    assert!(topology.is_link_bidirectional(1));
+    assert_float_eq(topology.link(2).bandwidth, DEFAULT_LINK_BANDWIDTH, EPSILON);
+    assert!(!topology.is_link_bidirectional(2));
+
+    // the loaded topology is used in simulation
+    let mut sim = Simulation::new(123);
+    let network_model = TopologyAwareNetworkModel::new();
+    let mut network = Network::new(Box::new(network_model), sim.create_context("net"));
+    network.add_topology(&topology, |_| Box::new(ConstantBandwidthNetworkModel::new(1000., 0.)));
+    network.init_topology();
+    assert_eq!(network.get_nodes(), vec!["host1", "switch", "host2"]);
+    let network_rc = Rc::new(RefCell::new(network));
+    sim.add_handler("net", network_rc.clone());
+
+    let sender = Node::new(network_rc.clone(), sim.create_context("sender"));
+    let sender_id = sim.add_handler("sender", Rc::new(RefCell::new(sender)));
+    network_rc.borrow_mut().set_location(sender_id, "host1");
+    let receiver = Node::new(network_rc.clone(), sim.create_context("receiver"));
+    let receiver_id = sim.add_handler("receiver", Rc::new(RefCell::new(receiver)));
+    network_rc.borrow_mut().set_location(receiver_id, "host2");
+    // host2 -> host1 link is unidirectional, so the transfer goes through the switch
+    assert_float_eq(network_rc.borrow().latency(sender_id, receiver_id), 1., EPSILON);
+    assert_float_eq(network_rc.borrow().latency(receiver_id, sender_id), 0., EPSILON);
+
+    sim.create_context("client").emit_now(
+        Start {
+            size: 1000.,
+            receiver_id,
+        },
+        sender_id,
+    );
+    sim.step_until_no_events();
+    assert_float_eq(sim.time(), 1. + 20., EPSILON);
+}
+
+/// Runs concurrent transfers with given (size, weight, max rate) over a weighted shared network
+/// and returns their completion times.
+fn run_weighted_transfers(transfers: &[(f64, f64, Option<f64>)]) -> Vec<f64> {
+    let mut sim = Simulation::new(123);
+
+    let network_model =
+        SharedBandwidthNetworkModel::new(90., 0.).with_sharing_policy(BandwidthSharingPolicy::WeightedMaxMin);
+    let mut network = Network::new(Box::new(network_model), sim.create_context("net"));
+    for host in ["host1", "host2"] {
+        network.add_node(host, Box::new(ConstantBandwidthNetworkModel::new(1000., 0.)));
+    }
+    let network_rc = Rc::new(RefCell::new(network));
+    sim.add_handler("net", network_rc.clone());
+
+    let completions = Rc::new(RefCell::new(Vec::new()));
+    let sender_id = sim.add_handler(
+        "sender",
+        Rc::new(RefCell::new(Receiver {
+            completions: completions.clone(),
+        })),
+    );
+    network_rc.borrow_mut().set_location(sender_id, "host1");
+    let receiver_id = sim.add_handler(
+        "receiver",
+        Rc::new(RefCell::new(Receiver {
+            completions: completions.clone(),
+        })),
+    );
+    network_rc.borrow_mut().set_location(receiver_id, "host2");
+
+    for &(size, weight, max_rate) in transfers {
+        network_rc
+            .borrow_mut()
+            .transfer_data_weighted(sender_id, receiver_id, size, receiver_id, weight, max_rate);
+    }
+    sim.step_until_no_events();
+    let result = completions.borrow().clone();
+    result
+}
+
+#[test]
+fn test_weighted_max_min_sharing() {
+    // the transfer with weight 2 gets 60, the other one gets 30 and then the full bandwidth
+    let completions = run_weighted_transfers(&[(300., 1., None), (300., 2., None)]);
+    assert_eq!(completions.len(), 2);
+    assert_float_eq(completions[0], 5., EPSILON);
+    assert_float_eq(completions[1], 5. + 150. / 90., EPSILON);
+
+    // the capped transfer gets 10, the rest is shared equally among the others
+    let completions = run_weighted_transfers(&[(100., 1., Some(10.)), (100., 1., None), (100., 1., None)]);
+    assert_eq!(completions.len(), 3);
+    assert_float_eq(completions[0], 2.5, EPSILON);
+    assert_float_eq(completions[1], 2.5, EPSILON);
+    assert_float_eq(completions[2], 10., EPSILON);
+
+    // the cap above the fair share has no effect
+    let completions = run_weighted_transfers(&[(100., 1., Some(50.)), (100., 1., None)]);
+    assert_float_eq(completions[0], 100. / 45., EPSILON);
+    assert_float_eq(completions[1], 100. / 45., EPSILON);
+}
+
+/// Runs a multicast transfer of 1000 bytes from host1 to host2 and host3 over the star topology
+/// (if the model is topology-aware) and returns the completion times.
+fn run_multicast(network_model: Box<dyn NetworkModel>) -> Vec<f64> {
+    let mut sim = Simulation::new(123);
+
+    let topology_aware = network_model.is_topology_aware();
+    let mut network = Network::new(network_model, sim.create_context("net"));
+    for host in ["host1", "host2", "host3", "switch"] {
+        network.add_node(host, Box::new(ConstantBandwidthNetworkModel::new(1000., 0.)));
+    }
+    if topology_aware {
+        network.add_link("host1", "switch", Link::shared(100., 1.));
+        network.add_link("switch", "host2", Link::shared(100., 1.));
+        network.add_link("switch", "host3", Link::shared(100., 2.));
+        network.init_topology();
+    }
+    let network_rc = Rc::new(RefCell::new(network));
+    sim.add_handler("net", network_rc.clone());
+
+    let completions = Rc::new(RefCell::new(Vec::new()));
+    let sender_id = sim.add_handler(
+        "sender",
+        Rc::new(RefCell::new(Receiver {
+            completions: completions.clone(),
+        })),
+    );
+    network_rc.borrow_mut().set_location(sender_id, "host1");
+
+    let dsts = ["host2", "host3"].map(|host| network_rc.borrow().get_node_id(host));
+    let transfer_ids = network_rc.borrow_mut().multicast(sender_id, &dsts, 1000.);
+    assert_eq!(transfer_ids.len(), 2);
+    sim.step_until_no_events();
+    let result = completions.borrow().clone();
+    result
+}
+
+#[test]
+fn test_multicast() {
+    // the shared link is used once, so the data is sent in 10 and then reaches each host after its path latency
+    let completions = run_multicast(Box::new(TopologyAwareNetworkModel::new()));
+    assert_eq!(completions.len(), 2);
+    assert_float_eq(completions[0], 10. + 2., EPSILON);
+    assert_float_eq(completions[1], 10. + 3., EPSILON);
+
+    // the same with separate transfers sharing the link
+    let completions = run_multicast(Box::new(SharedBandwidthNetworkModel::new(100., 1.)));
+    assert_eq!(completions.len(), 2);
+    assert_float_eq(completions[0], 1. + 20., EPSILON);
+    assert_float_eq(completions[1], 1. + 20., EPSILON);
+}
+
+#[rstest]
+fn test_active_transfers(#[values(false, true)] topology_aware: bool) {
+    let mut sim = Simulation::new(123);
+
+    let network_model: Box<dyn NetworkModel> = if topology_aware {
+        Box::new(TopologyAwareNetworkModel::new())
+    } else {
+        Box::new(SharedBandwidthNetworkModel::new(100., 0.))
+    };
+    let mut network = Network::new(network_model, sim.create_context("net"));
+    for host in ["host1", "host2"] {
+        network.add_node(host, Box::new(ConstantBandwidthNetworkModel::new(1000., 0.)));
+    }
+    if topology_aware {
+        network.add_link("host1", "host2", Link::shared(100., 0.));
+        network.init_topology();
+    }
+    let network_rc = Rc::new(RefCell::new(network));
+    sim.add_handler("net", network_rc.clone());
+
+    let completions = Rc::new(RefCell::new(Vec::new()));
+    let sender_id = sim.add_handler(
+        "sender",
+        Rc::new(RefCell::new(Receiver {
+            completions: completions.clone(),
+        })),
+    );
+    network_rc.borrow_mut().set_location(sender_id, "host1");
+    let receiver_id = sim.add_handler(
+        "receiver",
+        Rc::new(RefCell::new(Receiver {
+            completions: completions.clone(),
+        })),
+    );
+    network_rc.borrow_mut().set_location(receiver_id, "host2");
+
+    let first = network_rc
+        .borrow_mut()
+        .transfer_data(sender_id, receiver_id, 1000., receiver_id);
+    sim.step_until_time(5.);
+    let second = network_rc
+        .borrow_mut()
+        .transfer_data(sender_id, receiver_id, 1000., receiver_id);
+    sim.step_until_time(7.);
+
+    // the first transfer got the full bandwidth for 5 time units, then the bandwidth is shared
+    let transfers = network_rc.borrow().active_transfers();
+    assert_eq!(transfers.len(), 2);
+    assert_eq!(transfers[0].dt.id, first);
+    assert_float_eq(transfers[0].time, 7., EPSILON);
+    assert_float_eq(transfers[0].transferred, 600., EPSILON);
+    assert_float_eq(transfers[0].rate, 50., EPSILON);
+    assert_float_eq(transfers[0].estimated_finish.unwrap(), 15., EPSILON);
+    assert_eq!(transfers[1].dt.id, second);
+    assert_float_eq(transfers[1].transferred, 100., EPSILON);
+    assert_float_eq(transfers[1].estimated_finish.unwrap(), 25., EPSILON);
+
+    sim.step_until_no_events();
+    assert!(network_rc.borrow().active_transfers().is_empty());
+    assert_eq!(completions.borrow().len(), 2);
+}
+
+/// Runs long transfers from host1 and host2 to host3 over the links host1-switch (100), host2-switch
+/// (`host2_bandwidth`) and switch-host3 (100), the second transfer starts at `second_start`.
+/// Returns the average rates of transfers during the time window [50, 100].
+fn run_adaptive(host2_bandwidth: f64, second_start: f64) -> (f64, f64) {
+    let mut sim = Simulation::new(123);
+
+    let network_model = AdaptiveBandwidthNetworkModel::new(0.1, 2., 0.8);
+    let mut network = Network::new(Box::new(network_model), sim.create_context("net"));
+    for host in ["host1", "host2", "host3", "switch"] {
+        network.add_node(host, Box::new(ConstantBandwidthNetworkModel::new(1000., 0.)));
+    }
+    network.add_link("host1", "switch", Link::shared(100., 0.));
+    network.add_link("host2", "switch", Link::shared(host2_bandwidth, 0.));
+    network.add_link("switch", "host3", Link::shared(100., 0.));
+    network.init_topology();
+    let network_rc = Rc::new(RefCell::new(network));
+    sim.add_handler("net", network_rc.clone());
+
+    let completions = Rc::new(RefCell::new(Vec::new()));
+    let mut ids = Vec::new();
+    for host in ["host1", "host2", "host3"] {
+        let id = sim.add_handler(
+            host,
+            Rc::new(RefCell::new(Receiver {
+                completions: completions.clone(),
+            })),
+        );
+        network_rc.borrow_mut().set_location(id, host);
+        ids.push(id);
+    }
+
+    network_rc.borrow_mut().transfer_data(ids[0], ids[2], 1e6, ids[2]);
+    sim.step_until_time(second_start);
+    network_rc.borrow_mut().transfer_data(ids[1], ids[2], 1e6, ids[2]);
+    sim.step_until_time(50.);
+    let start = network_rc.borrow().active_transfers();
+    sim.step_until_time(100.);
+    let end = network_rc.borrow().active_transfers();
+    assert_eq!(start.len(), 2);
+    assert_eq!(end.len(), 2);
+    (
+        (end[0].transferred - start[0].transferred) / 50.,
+        (end[1].transferred - start[1].transferred) / 50.,
+    )
+}
+
+#[test]
+fn test_adaptive_bandwidth() {
+    // the rates oscillate between 0.8 and 1 of the max-min fair allocation, so the average is 0.9 of it
+    let utilization = (1. + 0.8) / 2.;
+
+    // the late transfer converges to the fair share of the common bottleneck link
+    let (rate1, rate2) = run_adaptive(100., 10.);
+    assert_float_eq(rate1, 50. * utilization, 0.01);
+    assert_float_eq(rate2, 50. * utilization, 0.01);
+
+    // the max-min fair allocation is 70 and 30 since the second transfer is limited by its first link
+    let (rate1, rate2) = run_adaptive(30., 0.);
+    assert_float_eq(rate1, 70. * utilization, 0.01);
+    assert_float_eq(rate2, 30. * utilization, 0.01);
+}
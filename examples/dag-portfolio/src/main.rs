@@ -159,6 +159,9 @@ fn run_experiments(args: &Args) {
                     speed,
                     cores,
                     memory,
+                    price: 0.,
+                    cost: 0.,
+                    provisioning: None,
                 });
             }
         }
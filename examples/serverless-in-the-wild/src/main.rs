@@ -11,6 +11,7 @@ use serde::{Deserialize, Serialize};
 
 use dslab_faas::coldstart::{ColdStartPolicy, FixedTimeColdStartPolicy};
 use dslab_faas::config::{ConfigParamResolvers, RawConfig};
+use dslab_faas::extra::adaptive_exponential::AdaptiveExponentialPolicy;
 use dslab_faas::extra::azure_trace_2019::{process_azure_2019_trace, AppPreference, Azure2019TraceConfig};
 use dslab_faas::extra::hybrid_histogram::HybridHistogramPolicy;
 use dslab_faas::parallel::parallel_simulation_raw;
@@ -25,6 +26,10 @@ struct ExperimentConfig {
 }
 
 fn policy_resolver(s: &str) -> Box<dyn ColdStartPolicy> {
+    if let Some(smoothing) = s.strip_prefix("Adaptive exponential policy, smoothing ") {
+        let smoothing = smoothing.parse::<f64>().unwrap();
+        return Box::new(AdaptiveExponentialPolicy::new(smoothing, 2.0, 4.0 * 3600.0));
+    }
     match &s[s.len() - 9..] {
         "keepalive" => {
             let s1 = s.split('-').next().unwrap();
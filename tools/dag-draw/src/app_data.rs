@@ -87,6 +87,7 @@ impl AppData {
                 Event::TaskScheduled { task_id, .. }
                 | Event::TaskStarted { task_id, .. }
                 | Event::TaskCompleted { task_id, .. } => tasks.entry(task_id).or_default().push(event),
+                Event::TaskFailed { .. } => {}
             }
         }
 